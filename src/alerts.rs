@@ -0,0 +1,124 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::config::{self, AlertConfig, NotificationConfig};
+use crate::notify;
+use crate::text;
+use crate::twitter::{Tweet, TwitterClient};
+
+/// Posts asked for per search. When more than this come in between two searches, only the
+/// newest are reported.
+const MAX_RESULTS: u32 = 25;
+
+fn state_path() -> Result<PathBuf> {
+    Ok(config::config_dir()?.join("alerts.json"))
+}
+
+/// When each `[[alerts]]` search last ran, and the newest post it has turned up. The newest
+/// posts are saved, so a restarted daemon doesn't report the same matches again.
+#[derive(Debug, Default)]
+pub struct Alerts {
+    last_run: HashMap<String, DateTime<Utc>>,
+    /// Newest match per query; `None` for a query that has run without finding anything.
+    newest: HashMap<String, Option<String>>,
+}
+
+impl Alerts {
+    pub fn load() -> Result<Self> {
+        let path = state_path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(&path).context("Failed to read alerts state")?;
+        let newest = serde_json::from_str(&content).context("Failed to parse alerts state")?;
+        Ok(Self { last_run: HashMap::new(), newest })
+    }
+
+    fn save(&self) -> Result<()> {
+        let json = serde_json::to_string_pretty(&self.newest).context("Failed to serialize alerts state")?;
+        config::write_atomic(&state_path()?, json).context("Failed to write alerts state")
+    }
+
+    pub fn is_due(&self, alert: &AlertConfig, now: DateTime<Utc>) -> bool {
+        self.last_run
+            .get(&alert.query)
+            .is_none_or(|last| now - *last >= alert.interval())
+    }
+
+    /// Runs `alert`'s search and returns the posts it hadn't seen, oldest first. The very
+    /// first search only notes where the results start, rather than reporting the past week.
+    /// After more than a week without a search, the newest matches from the past week count
+    /// as new, since the search can't reach back further.
+    pub async fn check(&mut self, alert: &AlertConfig, client: &TwitterClient) -> Result<Vec<Tweet>> {
+        self.last_run.insert(alert.query.clone(), Utc::now());
+        let first_run = !self.newest.contains_key(&alert.query);
+        let since_id = self.newest.get(&alert.query).cloned().flatten();
+
+        let mut matches = client.search_recent(&alert.query, since_id.as_deref(), MAX_RESULTS).await?;
+        // Post IDs grow over time, so the longest and then greatest is the newest
+        let newest = matches
+            .iter()
+            .map(|tweet| tweet.id.as_str())
+            .max_by_key(|id| (id.len(), *id))
+            .map(str::to_string)
+            .or(since_id);
+        if first_run || newest != self.newest[&alert.query] {
+            self.newest.insert(alert.query.clone(), newest);
+            self.save()?;
+        }
+
+        if first_run {
+            return Ok(Vec::new());
+        }
+        matches.reverse();
+        Ok(matches)
+    }
+}
+
+/// Tells you about new `matches` for `alert`: one desktop notification for the lot, and a
+/// line per post in the alerts file when one is set.
+pub fn report(alert: &AlertConfig, matches: &[Tweet], notifications: &NotificationConfig) -> Result<()> {
+    let Some(latest) = matches.last() else {
+        return Ok(());
+    };
+
+    let summary = match matches.len() {
+        1 => format!("New post for \"{}\"", alert.label()),
+        count => format!("{} new posts for \"{}\"", count, alert.label()),
+    };
+    notify::desktop(notifications, &summary, &describe(latest));
+    notify::bell(notifications);
+
+    let Some(file) = &notifications.alerts_file else {
+        return Ok(());
+    };
+    let path = config::resolve_path(file);
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Failed to open alerts file {}", path.display()))?;
+    let timestamp = Utc::now().format("%Y-%m-%d %H:%M:%S");
+    for tweet in matches {
+        writeln!(file, "[{}] {}: {} {}", timestamp, alert.label(), describe(tweet), url(tweet))
+            .context("Failed to write alerts file")?;
+    }
+    Ok(())
+}
+
+/// "@author: text" on one line.
+fn describe(tweet: &Tweet) -> String {
+    let text = text::preview_line(&tweet.text, 140);
+    match &tweet.author {
+        Some(author) => format!("@{}: {}", author, text),
+        None => text,
+    }
+}
+
+fn url(tweet: &Tweet) -> String {
+    format!("https://x.com/{}/status/{}", tweet.author.as_deref().unwrap_or("user"), tweet.id)
+}
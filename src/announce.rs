@@ -0,0 +1,82 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::Deserialize;
+use std::sync::OnceLock;
+
+use crate::config::AnnounceConfig;
+
+/// Used when `[announce] template` isn't set.
+pub const DEFAULT_TEMPLATE: &str = "🚀 {name} {tag} is out!\n\n{notes}\n\n{url}";
+
+/// Most release note bullets that make it into the post; the rest are a click away.
+const MAX_NOTES: usize = 5;
+
+#[derive(Debug, Deserialize)]
+pub struct Release {
+    pub tag_name: String,
+    pub html_url: String,
+    #[serde(default)]
+    pub body: Option<String>,
+}
+
+/// The release tagged `tag` in `repo` (owner/name), or the latest one. Uses `GITHUB_TOKEN` when
+/// set, for private repositories and a higher rate limit.
+pub async fn fetch_release(repo: &str, tag: Option<&str>) -> Result<Release> {
+    let url = match tag {
+        Some(tag) => format!("https://api.github.com/repos/{}/releases/tags/{}", repo, tag),
+        None => format!("https://api.github.com/repos/{}/releases/latest", repo),
+    };
+    let mut request = reqwest::Client::new()
+        .get(&url)
+        .header("Accept", "application/vnd.github+json")
+        .header("User-Agent", "xpost");
+    if let Ok(token) = std::env::var("GITHUB_TOKEN") {
+        request = request.bearer_auth(token);
+    }
+
+    let response = request.send().await.context("Failed to fetch release")?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        anyhow::bail!("No release {} in {}", tag.unwrap_or("(latest)"), repo);
+    }
+    if !response.status().is_success() {
+        let error_text = response.text().await?;
+        anyhow::bail!("GitHub API Error: {}", error_text);
+    }
+    response.json().await.context("Failed to parse release")
+}
+
+/// The announcement post for `release`, from the configured template or the default one.
+/// `{repo}`, `{name}`, `{tag}`, `{notes}` and `{url}` are filled in.
+pub fn announcement(config: Option<&AnnounceConfig>, repo: &str, release: &Release) -> String {
+    let template = config
+        .and_then(|config| config.template.as_deref())
+        .unwrap_or(DEFAULT_TEMPLATE);
+    let name = repo.rsplit('/').next().unwrap_or(repo);
+    let notes = highlights(release.body.as_deref().unwrap_or(""));
+
+    let text = template
+        .replace("{repo}", repo)
+        .replace("{name}", name)
+        .replace("{tag}", &release.tag_name)
+        .replace("{url}", &release.html_url)
+        .replace("{notes}", &notes);
+    // No notes shouldn't leave a gap where they'd go
+    text.replace("\n\n\n\n", "\n\n").trim().to_string()
+}
+
+/// The first bullet points of the release notes, without GitHub's generated "by @user in
+/// <pull request>" credits.
+fn highlights(body: &str) -> String {
+    static CREDIT: OnceLock<Regex> = OnceLock::new();
+    let credit = CREDIT.get_or_init(|| Regex::new(r"\s+by @[\w-]+(\s+in\s+\S+)?\s*$").unwrap());
+
+    body.lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            line.strip_prefix("- ").or_else(|| line.strip_prefix("* "))
+        })
+        .map(|note| format!("• {}", credit.replace(note.trim(), "")))
+        .take(MAX_NOTES)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,68 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::config::AssistConfig;
+
+/// Built-in instructions offered in the assist picker, before any from the config.
+pub const DEFAULT_PROMPTS: &[&str] = &[
+    "Shorten this to fit in 280 characters",
+    "Turn this into a thread, separating posts with a line containing only ---",
+    "Fix spelling and grammar without changing the tone",
+    "Make this punchier",
+];
+
+const SYSTEM_PROMPT: &str = "You help edit social media posts. Reply with only the rewritten post text, \
+    no quotes, commentary or markdown fences.";
+
+#[derive(Debug, Deserialize)]
+struct ChatResponse {
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatChoice {
+    message: ChatMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatMessage {
+    content: String,
+}
+
+/// Asks the configured OpenAI-compatible endpoint to apply `instruction` to `text`.
+pub async fn suggest(config: &AssistConfig, instruction: &str, text: &str) -> Result<String> {
+    let url = format!("{}/chat/completions", config.endpoint.trim_end_matches('/'));
+    let body = serde_json::json!({
+        "model": config.model,
+        "messages": [
+            { "role": "system", "content": SYSTEM_PROMPT },
+            { "role": "user", "content": format!("{}:\n\n{}", instruction, text) },
+        ],
+    });
+
+    let mut request = reqwest::Client::new().post(&url).json(&body);
+    if let Some(api_key) = &config.api_key {
+        request = request.bearer_auth(api_key);
+    }
+
+    let response = request
+        .send()
+        .await
+        .context("Failed to reach the assist endpoint")?;
+
+    if !response.status().is_success() {
+        let error_text = response.text().await?;
+        anyhow::bail!("Assist request failed: {}", error_text);
+    }
+
+    let chat: ChatResponse = response.json().await?;
+    let suggestion = chat
+        .choices
+        .into_iter()
+        .next()
+        .context("Assist endpoint returned no suggestion")?
+        .message
+        .content;
+
+    Ok(suggestion.trim().to_string())
+}
@@ -0,0 +1,204 @@
+use anyhow::{Context, Result};
+use chrono::Utc;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Component, Path};
+
+use crate::config::{self, Config, DraftsConfig};
+use crate::history::{self, PostRecord};
+use crate::{drafts, lock, queue};
+
+/// First entry of every backup, naming the layout so `restore` can tell it's one of ours.
+const MANIFEST: &str = "xpost-backup.json";
+const VERSION: u32 = 1;
+
+/// The drafts key; drafts saved with `encrypt = true` can't be read without it.
+const DRAFTS_KEY: &str = "drafts.key.age";
+
+/// What `restore` put back.
+#[derive(Debug, Default)]
+pub struct Restored {
+    pub files: usize,
+    /// Posts added to the history; ones it already had are left alone.
+    pub history: usize,
+    /// Whether this machine has credentials to post with, kept from its own config.
+    pub has_credentials: bool,
+}
+
+/// `[drafts]` from a config table, so drafts are read from and restored to where it keeps them.
+fn drafts_settings(table: &toml::Table) -> DraftsConfig {
+    table
+        .get("drafts")
+        .cloned()
+        .and_then(|value| value.try_into().ok())
+        .unwrap_or_default()
+}
+
+/// Writes the drafts (posted and damaged ones too), the drafts key, the post history, the queue
+/// and `config.toml` without its credentials to a gzipped tarball at `path`. Returns how many
+/// files went in.
+pub fn backup(path: &Path) -> Result<usize> {
+    let table = Config::shareable_table()?;
+    drafts::init(&drafts_settings(&table));
+    let config_dir = config::config_dir()?;
+
+    let file = File::create(path).context("Failed to create backup file")?;
+    let mut archive = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+
+    let manifest = serde_json::json!({ "version": VERSION, "created_at": Utc::now() });
+    append(&mut archive, MANIFEST, manifest.to_string().as_bytes())?;
+    let mut files = 0;
+
+    // The key goes before anything it opens, so `restore` can refuse a clash before writing
+    let key_path = config_dir.join(DRAFTS_KEY);
+    if key_path.exists() {
+        archive.append_path_with_name(&key_path, DRAFTS_KEY)
+            .context("Failed to add drafts key to backup")?;
+        files += 1;
+    }
+    let config = toml::to_string(&table).context("Failed to serialize config")?;
+    append(&mut archive, "config.toml", config.as_bytes())?;
+    files += 1;
+
+    {
+        let _lock = lock::lock(drafts::DRAFTS_LOCK)?;
+        files += append_dir(&mut archive, &drafts::drafts_dir()?, "drafts")?;
+    }
+    let history = history::load_history()?;
+    if !history.is_empty() {
+        let json = serde_json::to_string_pretty(&history).context("Failed to serialize post history")?;
+        append(&mut archive, "history.json", json.as_bytes())?;
+        files += 1;
+    }
+    {
+        let _lock = lock::lock(queue::QUEUE_LOCK)?;
+        files += append_dir(&mut archive, &queue::queue_dir()?, "queue")?;
+    }
+
+    archive
+        .into_inner()
+        .and_then(|encoder| encoder.finish())
+        .context("Failed to write backup file")?;
+    Ok(files)
+}
+
+fn append(archive: &mut tar::Builder<GzEncoder<File>>, name: &str, data: &[u8]) -> Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o600);
+    header.set_mtime(Utc::now().timestamp().max(0) as u64);
+    header.set_cksum();
+    archive.append_data(&mut header, name, data)
+        .context("Failed to add file to backup")
+}
+
+/// Adds the files under `dir` as `name/…`, leaving out hidden ones: git metadata and writes
+/// still in progress.
+fn append_dir(archive: &mut tar::Builder<GzEncoder<File>>, dir: &Path, name: &str) -> Result<usize> {
+    let mut files = 0;
+    for entry in fs::read_dir(dir).context("Failed to read directory")? {
+        let path = entry?.path();
+        let file_name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+        if file_name.starts_with('.') {
+            continue;
+        }
+
+        let archived = format!("{}/{}", name, file_name);
+        if path.is_dir() {
+            files += append_dir(archive, &path, &archived)?;
+        } else {
+            archive.append_path_with_name(&path, &archived)
+                .context("Failed to add file to backup")?;
+            files += 1;
+        }
+    }
+    Ok(files)
+}
+
+/// Puts a backup made by `backup` in place. Drafts and queued posts from it are written over
+/// ones with the same ID, its history is merged into this machine's, and its config replaces
+/// `config.toml` (kept as `config.toml.bak`) apart from the credentials, which stay as they are.
+pub fn restore(path: &Path) -> Result<Restored> {
+    let file = File::open(path).context("Failed to open backup file")?;
+    let mut archive = tar::Archive::new(GzDecoder::new(file));
+    let config_dir = config::config_dir()?;
+    let mut restored = Restored::default();
+    let mut checked = false;
+
+    for entry in archive.entries().context("Failed to read backup file")? {
+        let mut entry = entry.context("Failed to read backup file")?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let name = entry.path().context("Failed to read backup file")?.into_owned();
+        if !name.components().all(|component| matches!(component, Component::Normal(_))) {
+            anyhow::bail!("Backup contains an unsafe path: {}", name.display());
+        }
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data).context("Failed to read backup file")?;
+
+        if !checked {
+            check_manifest(path, &name, &data)?;
+            checked = true;
+            continue;
+        }
+
+        if name == Path::new(DRAFTS_KEY) {
+            let key_path = config_dir.join(DRAFTS_KEY);
+            match fs::read(&key_path) {
+                Ok(existing) if existing != data => anyhow::bail!(
+                    "This machine already has a different drafts key; move {} away to restore the backup's",
+                    key_path.display()
+                ),
+                Ok(_) => {}
+                Err(_) => write_file(&key_path, &data)?,
+            }
+        } else if name == Path::new("config.toml") {
+            let table: toml::Table = toml::from_str(&String::from_utf8_lossy(&data))
+                .context("Failed to parse config in backup")?;
+            drafts::init(&drafts_settings(&table));
+            restored.has_credentials = Config::restore_table(table)?;
+        } else if name == Path::new("history.json") {
+            let records: Vec<PostRecord> = serde_json::from_slice(&data)
+                .context("Failed to parse post history in backup")?;
+            restored.history = history::merge(records)?;
+            continue;
+        } else if let Ok(rest) = name.strip_prefix("drafts") {
+            let _lock = lock::lock(drafts::DRAFTS_LOCK)?;
+            write_file(&drafts::drafts_dir()?.join(rest), &data)?;
+        } else if let Ok(rest) = name.strip_prefix("queue") {
+            let _lock = lock::lock(queue::QUEUE_LOCK)?;
+            write_file(&queue::queue_dir()?.join(rest), &data)?;
+        } else {
+            continue;
+        }
+        restored.files += 1;
+    }
+
+    if !checked {
+        anyhow::bail!("{} is empty", path.display());
+    }
+    Ok(restored)
+}
+
+fn check_manifest(path: &Path, name: &Path, data: &[u8]) -> Result<()> {
+    let version = (name == Path::new(MANIFEST))
+        .then(|| serde_json::from_slice::<serde_json::Value>(data).ok())
+        .flatten()
+        .and_then(|manifest| manifest.get("version")?.as_u64());
+    match version {
+        Some(version) if version <= VERSION as u64 => Ok(()),
+        Some(_) => anyhow::bail!("{} was made by a newer xpost; update to restore it", path.display()),
+        None => anyhow::bail!("{} isn't an xpost backup", path.display()),
+    }
+}
+
+fn write_file(path: &Path, data: &[u8]) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).context("Failed to create directory")?;
+    }
+    config::write_atomic(path, data).context("Failed to restore file")
+}
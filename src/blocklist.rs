@@ -0,0 +1,73 @@
+use regex::{Regex, RegexBuilder};
+
+/// Entries of an account's `blocklist` that `text` matches. Plain entries match whole words,
+/// ignoring case; entries written as `/pattern/` are regular expressions.
+pub fn matches(blocklist: &[String], text: &str) -> Vec<String> {
+    blocklist
+        .iter()
+        .filter(|entry| pattern(entry).is_some_and(|pattern| pattern.is_match(text)))
+        .cloned()
+        .collect()
+}
+
+fn pattern(entry: &str) -> Option<Regex> {
+    // An empty pattern would match every post
+    if entry.trim().is_empty() {
+        return None;
+    }
+    let source = match entry.strip_prefix('/').and_then(|rest| rest.strip_suffix('/')) {
+        Some(regex) if !regex.is_empty() => regex.to_string(),
+        _ => {
+            // Word boundaries only make sense next to word characters, e.g. not before "@handle"
+            let word = entry.trim();
+            let boundary = |c: Option<char>| match c {
+                Some(c) if c.is_alphanumeric() || c == '_' => r"\b",
+                _ => "",
+            };
+            format!("{}{}{}", boundary(word.chars().next()), regex::escape(word), boundary(word.chars().last()))
+        }
+    };
+    // A pattern that doesn't compile is matched literally rather than dropped
+    RegexBuilder::new(&source)
+        .case_insensitive(true)
+        .build()
+        .or_else(|_| RegexBuilder::new(&regex::escape(entry)).case_insensitive(true).build())
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blocklist(entries: &[&str]) -> Vec<String> {
+        entries.iter().map(|entry| entry.to_string()).collect()
+    }
+
+    #[test]
+    fn plain_entries_match_whole_words_in_any_case() {
+        let list = blocklist(&["crypto", "NFT"]);
+        assert_eq!(matches(&list, "My Crypto take"), vec!["crypto"]);
+        assert_eq!(matches(&list, "nft drop and crypto"), vec!["crypto", "NFT"]);
+        assert!(matches(&list, "cryptography and nfts").is_empty());
+    }
+
+    #[test]
+    fn entries_starting_or_ending_with_symbols_still_match() {
+        let list = blocklist(&["@rival", "#ad"]);
+        assert_eq!(matches(&list, "thanks @Rival!"), vec!["@rival"]);
+        assert_eq!(matches(&list, "great stuff #ad"), vec!["#ad"]);
+    }
+
+    #[test]
+    fn slashes_make_a_regular_expression() {
+        let list = blocklist(&[r"/launch(ing|ed)?\s+soon/"]);
+        assert_eq!(matches(&list, "Launching  soon!").len(), 1);
+        assert!(matches(&list, "launch later").is_empty());
+    }
+
+    #[test]
+    fn broken_patterns_match_literally_and_blank_entries_never() {
+        assert_eq!(matches(&blocklist(&["/(oops/"]), "so /(oops/ here"), vec!["/(oops/"]);
+        assert!(matches(&blocklist(&["", "  "]), "anything at all").is_empty());
+    }
+}
@@ -0,0 +1,126 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+
+const SEPARATOR: &str = " | ";
+
+/// A "Key: action" segment of an instruction bar, placed on screen.
+struct Button<'a> {
+    text: &'a str,
+    x: u16,
+    y: u16,
+    width: u16,
+}
+
+/// Draws `instructions` ("Ctrl+S: save draft | Esc: exit") into `area` one segment at a
+/// time, wrapping between segments so each one can be clicked.
+pub fn render(f: &mut Frame, instructions: &str, area: Rect, centered: bool) {
+    let buttons = layout(instructions, area, centered);
+    for (i, button) in buttons.iter().enumerate() {
+        let mut spans = match button.text.split_once(": ") {
+            Some((key, action)) => vec![
+                Span::styled(key, Style::default().fg(Color::Gray)),
+                Span::styled(format!(": {}", action), Style::default().fg(Color::DarkGray)),
+            ],
+            None => vec![Span::styled(button.text, Style::default().fg(Color::DarkGray))],
+        };
+        if buttons.get(i + 1).is_some_and(|next| next.y == button.y) {
+            spans.push(Span::styled(SEPARATOR, Style::default().fg(Color::DarkGray)));
+        }
+        let width = spans.iter().map(Span::width).sum::<usize>() as u16;
+        let cell = Rect::new(button.x, button.y, width.min(area.right().saturating_sub(button.x)), 1);
+        f.render_widget(Paragraph::new(Line::from(spans)), cell);
+    }
+}
+
+/// Rows `instructions` wrap to at `width`, so a narrow terminal can give the bar more room.
+pub fn rows(instructions: &str, width: u16) -> u16 {
+    layout(instructions, Rect::new(0, 0, width, u16::MAX), false)
+        .last()
+        .map_or(1, |button| button.y + 1)
+}
+
+/// The key press for the segment at (`column`, `row`), if it names one.
+pub fn key_at(instructions: &str, area: Rect, centered: bool, column: u16, row: u16) -> Option<KeyEvent> {
+    layout(instructions, area, centered)
+        .into_iter()
+        .find(|button| button.y == row && (button.x..button.x + button.width).contains(&column))
+        .and_then(|button| parse_key(button.text))
+}
+
+fn layout(instructions: &str, area: Rect, centered: bool) -> Vec<Button<'_>> {
+    let separator = SEPARATOR.len() as u16;
+    let mut rows: Vec<Vec<(&str, u16)>> = vec![Vec::new()];
+    let mut row_width = 0;
+
+    for text in instructions.split(SEPARATOR) {
+        let width = Span::raw(text).width() as u16;
+        let current = rows.last_mut().expect("at least one row");
+        if !current.is_empty() && row_width + separator + width > area.width {
+            rows.push(vec![(text, width)]);
+            row_width = width;
+        } else {
+            row_width += if current.is_empty() { width } else { separator + width };
+            current.push((text, width));
+        }
+    }
+
+    let mut buttons = Vec::new();
+    for (y, row) in rows.into_iter().enumerate().take(area.height as usize) {
+        let total: u16 = row.iter().map(|(_, width)| width).sum::<u16>() + separator * (row.len() as u16).saturating_sub(1);
+        let mut x = if centered {
+            area.x + area.width.saturating_sub(total) / 2
+        } else {
+            area.x
+        };
+        for (text, width) in row {
+            buttons.push(Button { text, x, y: area.y + y as u16, width });
+            x += width + separator;
+        }
+    }
+    buttons
+}
+
+/// "Ctrl+P: post" → Ctrl+P. Where a segment lists alternatives ("Enter/Esc") the first wins;
+/// arrows and descriptions like "any other key" aren't clickable.
+fn parse_key(text: &str) -> Option<KeyEvent> {
+    let (key, _) = text.split_once(": ")?;
+    let key = key.split('/').next()?.trim();
+
+    let (modifiers, name) = if let Some(name) = key.strip_prefix("Ctrl+") {
+        (KeyModifiers::CONTROL, name)
+    } else if let Some(name) = key.strip_prefix("Alt+") {
+        (KeyModifiers::ALT, name)
+    } else if let Some(name) = key.strip_prefix("Shift+") {
+        (KeyModifiers::SHIFT, name)
+    } else {
+        (KeyModifiers::NONE, key)
+    };
+
+    let code = match name {
+        "Enter" => KeyCode::Enter,
+        "Esc" => KeyCode::Esc,
+        "Space" => KeyCode::Char(' '),
+        "Delete" => KeyCode::Delete,
+        "Home" => KeyCode::Home,
+        "PgUp" => KeyCode::PageUp,
+        "PgDn" => KeyCode::PageDown,
+        _ => {
+            let mut chars = name.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) if c.is_ascii_alphanumeric() && modifiers == KeyModifiers::SHIFT => {
+                    KeyCode::Char(c.to_ascii_uppercase())
+                }
+                (Some(c), None) if c.is_ascii_alphanumeric() => KeyCode::Char(c.to_ascii_lowercase()),
+                _ => return None,
+            }
+        }
+    };
+
+    Some(KeyEvent::new(code, modifiers))
+}
@@ -0,0 +1,159 @@
+use anyhow::{Context, Result};
+use chrono::Local;
+use ratatui::style::Color;
+use resvg::{tiny_skia, usvg};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::thread;
+
+/// Width of the card in pixels; it's as tall as the text needs.
+const CARD_WIDTH: usize = 1200;
+/// Space between the edge of the image and the card, and around the text inside it.
+const MARGIN: usize = 48;
+const PADDING: usize = 56;
+const FONT_SIZE: usize = 34;
+const LINE_HEIGHT: usize = 48;
+/// Characters per line, going by the average width of a sans-serif letter at `FONT_SIZE`.
+const LINE_CHARS: usize = 52;
+
+/// Fonts tried in order, since a generic `sans-serif` only resolves if the system names one.
+const FONT_FAMILY: &str = "Inter, 'Helvetica Neue', Helvetica, Arial, 'DejaVu Sans', 'Noto Sans', sans-serif";
+
+/// Renders `text` as a PNG card in the exports folder, headed with `name` and today's date on
+/// an `accent` background, and returns where it went. The parts of a thread are separated by
+/// a rule.
+pub fn export(text: &str, name: &str, accent: Color) -> Result<PathBuf> {
+    let dir = crate::config::config_dir()?.join("exports");
+    if !dir.exists() {
+        fs::create_dir_all(&dir).context("Failed to create exports directory")?;
+    }
+
+    let svg = to_svg(text, name, accent);
+    let mut options = usvg::Options::default();
+    options.fontdb_mut().load_system_fonts();
+    let tree = usvg::Tree::from_str(&svg, &options).context("Failed to lay out the card")?;
+
+    let size = tree.size().to_int_size();
+    let mut pixmap = tiny_skia::Pixmap::new(size.width(), size.height()).context("Card is too large to render")?;
+    resvg::render(&tree, tiny_skia::Transform::default(), &mut pixmap.as_mut());
+
+    let path = dir.join(format!("card-{}.png", Local::now().format("%Y%m%d-%H%M%S")));
+    pixmap.save_png(&path).context("Failed to write card image")?;
+    Ok(path)
+}
+
+fn to_svg(text: &str, name: &str, accent: Color) -> String {
+    let left = MARGIN + PADDING;
+    let mut body = String::new();
+    let mut y = MARGIN + PADDING + FONT_SIZE;
+
+    body.push_str(&format!(
+        r#"<text x="{}" y="{}" font-size="{}" font-weight="bold" fill="{}">{}</text>"#,
+        left,
+        y,
+        FONT_SIZE,
+        hex(accent),
+        escape(name)
+    ));
+    y += LINE_HEIGHT;
+    body.push_str(&format!(
+        r##"<text x="{}" y="{}" font-size="{}" fill="#8b98a5">{}</text>"##,
+        left,
+        y,
+        FONT_SIZE * 3 / 4,
+        Local::now().format("%B %-d, %Y")
+    ));
+    y += LINE_HEIGHT;
+
+    for (i, part) in thread::split_thread(text).iter().enumerate() {
+        if i > 0 {
+            let rule = y + LINE_HEIGHT / 2;
+            body.push_str(&format!(
+                r##"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="#38444d" stroke-width="2"/>"##,
+                left,
+                rule,
+                CARD_WIDTH - left,
+                rule
+            ));
+            y += LINE_HEIGHT * 3 / 4;
+        }
+        for line in wrap(part) {
+            y += LINE_HEIGHT;
+            body.push_str(&format!(
+                r##"<text x="{}" y="{}" font-size="{}" fill="#e7e9ea" xml:space="preserve">{}</text>"##,
+                left,
+                y,
+                FONT_SIZE,
+                escape(&line)
+            ));
+        }
+    }
+
+    let height = y + PADDING + MARGIN;
+    format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="{font}">
+<rect width="{w}" height="{h}" fill="{accent}"/>
+<rect x="{m}" y="{m}" width="{cw}" height="{ch}" rx="24" fill="#15202b"/>
+{body}
+</svg>"##,
+        w = CARD_WIDTH,
+        h = height,
+        font = FONT_FAMILY,
+        accent = hex(accent),
+        m = MARGIN,
+        cw = CARD_WIDTH - 2 * MARGIN,
+        ch = height - 2 * MARGIN,
+        body = body,
+    )
+}
+
+/// Breaks `text` into lines of at most `LINE_CHARS` between words, keeping its own line
+/// breaks and blank lines. A word longer than a line is cut.
+fn wrap(text: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.trim().lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let mut word: Vec<char> = word.chars().collect();
+            while word.len() > LINE_CHARS {
+                if !line.is_empty() {
+                    lines.push(std::mem::take(&mut line));
+                }
+                lines.push(word.drain(..LINE_CHARS).collect());
+            }
+            let word: String = word.into_iter().collect();
+            if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > LINE_CHARS {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&word);
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// `color` as an SVG colour; the named terminal colours get their usual shades.
+fn hex(color: Color) -> String {
+    let (r, g, b) = match color {
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Red | Color::LightRed => (0xf4, 0x21, 0x2e),
+        Color::Green | Color::LightGreen => (0x00, 0xba, 0x7c),
+        Color::Yellow | Color::LightYellow => (0xff, 0xd4, 0x00),
+        Color::Magenta | Color::LightMagenta => (0xf9, 0x18, 0x80),
+        Color::Blue | Color::LightBlue => (0x1d, 0x9b, 0xf0),
+        Color::Gray | Color::DarkGray | Color::White => (0x8b, 0x98, 0xa5),
+        _ => (0x1d, 0x9b, 0xf0),
+    };
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
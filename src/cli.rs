@@ -0,0 +1,90 @@
+use anyhow::{Context, Result};
+
+/// Parsed command line:
+/// `xpost [--account NAME] [--data-dir DIR] [--config FILE] [--plain] [--demo] [--debug-http] [COMMAND] [ARGS...]`.
+pub struct Cli {
+    pub command: Option<String>,
+    pub args: Vec<String>,
+    pub account: Option<String>,
+    /// Where drafts, history, the queue and the rest are kept instead of `~/.config/xpost`.
+    pub data_dir: Option<String>,
+    /// Config file to read instead of `config.toml` in the data directory.
+    pub config: Option<String>,
+    /// Line-based output for screen readers instead of the TUI.
+    pub plain: bool,
+    /// Answer every API call with made-up data, for trying xpost without an account.
+    pub demo: bool,
+    /// Log every API request and response, minus secrets, to `xpost.log`.
+    pub debug_http: bool,
+}
+
+impl Cli {
+    pub fn parse() -> Result<Self> {
+        let mut command = None;
+        let mut args = Vec::new();
+        let mut account = None;
+        let mut data_dir = None;
+        let mut config = None;
+        let mut plain = false;
+        let mut demo = false;
+        let mut debug_http = false;
+
+        let mut iter = std::env::args().skip(1);
+        while let Some(arg) = iter.next() {
+            if let Some(value) = arg.strip_prefix("--account=") {
+                account = Some(value.to_string());
+                continue;
+            }
+            if let Some(value) = arg.strip_prefix("--data-dir=") {
+                data_dir = Some(value.to_string());
+                continue;
+            }
+            if let Some(value) = arg.strip_prefix("--config=") {
+                config = Some(value.to_string());
+                continue;
+            }
+
+            match arg.as_str() {
+                "--account" | "-a" => {
+                    account = Some(iter.next().context("--account requires an account name")?);
+                }
+                "--data-dir" => {
+                    data_dir = Some(iter.next().context("--data-dir requires a directory")?);
+                }
+                "--config" => {
+                    config = Some(iter.next().context("--config requires a file")?);
+                }
+                "--plain" => plain = true,
+                "--demo" => demo = true,
+                "--debug-http" => debug_http = true,
+                _ if command.is_none() => command = Some(arg),
+                _ => args.push(arg),
+            }
+        }
+
+        Ok(Self {
+            command,
+            args,
+            account,
+            data_dir,
+            config,
+            plain,
+            demo,
+            debug_http,
+        })
+    }
+
+    /// The value of a subcommand flag given as `--flag value` or `--flag=value`.
+    pub fn option(&self, flag: &str) -> Option<String> {
+        let mut iter = self.args.iter();
+        while let Some(arg) = iter.next() {
+            if arg == flag {
+                return iter.next().cloned();
+            }
+            if let Some(value) = arg.strip_prefix(flag).and_then(|rest| rest.strip_prefix('=')) {
+                return Some(value.to_string());
+            }
+        }
+        None
+    }
+}
@@ -1,17 +1,130 @@
 use anyhow::{Context, Result};
 use arboard::Clipboard;
 use arboard::ImageData;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{Mutex, OnceLock};
+
+use crate::config::MediaConfig;
+
+/// `[media]` from the config, set once at startup.
+static SETTINGS: OnceLock<MediaConfig> = OnceLock::new();
+
+/// Width SVGs are rendered at without `svg_width`: sharp on X without being slow to upload.
+const DEFAULT_SVG_WIDTH: u32 = 2048;
+
+pub fn init(config: &MediaConfig) {
+    let _ = SETTINGS.set(config.clone());
+}
+
+/// On X11 and Wayland the copied text is only on offer while the clipboard that set it is
+/// alive, so the one used for copying is kept for the whole session.
+static COPY_CLIPBOARD: Mutex<Option<Clipboard>> = Mutex::new(None);
+
+/// Puts `text` on the clipboard, through wl-copy on Wayland compositors arboard can't reach.
+pub fn copy_text(text: &str) -> Result<()> {
+    let mut clipboard = COPY_CLIPBOARD.lock().map_err(|_| anyhow::anyhow!("Clipboard is unavailable"))?;
+    if clipboard.is_none() {
+        *clipboard = Clipboard::new().ok();
+    }
+    if let Some(clipboard) = clipboard.as_mut() {
+        if clipboard.set_text(text).is_ok() {
+            return Ok(());
+        }
+    }
+
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        let status = Command::new("wl-copy")
+            .arg(text)
+            .status()
+            .context("Clipboard is unavailable, and wl-copy isn't installed (install wl-clipboard)")?;
+        if status.success() {
+            return Ok(());
+        }
+    }
+
+    anyhow::bail!("Clipboard is unavailable")
+}
 
 pub fn get_image_from_clipboard() -> Result<Vec<u8>> {
-    let mut clipboard = Clipboard::new()
-        .context("Failed to access clipboard")?;
-    
-    let img = clipboard.get_image()
-        .context("No image in clipboard. Try Ctrl+U to upload from file instead (clipboard may not work on Wayland)")?;
-    
-    let png_data = rgba_to_png(&img)?;
-    
-    Ok(png_data)
+    let clipboard = Clipboard::new();
+
+    if let Ok(mut clipboard) = clipboard {
+        if let Ok(img) = clipboard.get_image() {
+            return rgba_to_png(&img);
+        }
+
+        // File managers put the copied file's path or file:// URI on the clipboard as text
+        if let Some(path) = clipboard.get_text().ok().as_deref().and_then(path_from_clipboard_text) {
+            return validate_image_file(&path.to_string_lossy());
+        }
+    }
+
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        return wl_paste_image();
+    }
+
+    anyhow::bail!("No image in clipboard. Try Ctrl+U to upload from file instead")
+}
+
+/// arboard can't read images on some Wayland compositors (Sway, Hyprland), so ask wl-paste.
+fn wl_paste_image() -> Result<Vec<u8>> {
+    let output = Command::new("wl-paste")
+        .args(["--no-newline", "--type", "image/png"])
+        .output()
+        .context("No image in clipboard, and wl-paste isn't installed (install wl-clipboard or use Ctrl+U)")?;
+
+    if output.status.success() && !output.stdout.is_empty() {
+        return Ok(output.stdout);
+    }
+
+    // Nothing as PNG; the clipboard may still hold a copied file's URI
+    let output = Command::new("wl-paste")
+        .args(["--no-newline", "--type", "text/uri-list"])
+        .output()
+        .context("Failed to run wl-paste")?;
+    let text = String::from_utf8_lossy(&output.stdout);
+
+    match path_from_clipboard_text(&text) {
+        Some(path) => validate_image_file(&path.to_string_lossy()),
+        None => anyhow::bail!("No image in clipboard. Try Ctrl+U to upload from file instead"),
+    }
+}
+
+/// Finds the first existing file named by clipboard text: a plain path or a `file://` URI,
+/// one per line (GNOME prefixes the list with "copy" or "cut").
+fn path_from_clipboard_text(text: &str) -> Option<PathBuf> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && *line != "copy" && *line != "cut")
+        .map(|line| match line.strip_prefix("file://") {
+            // file://host/path is allowed but the host is always empty or localhost locally
+            Some(uri) => PathBuf::from(percent_decode(uri.trim_start_matches("localhost"))),
+            None => PathBuf::from(line),
+        })
+        .find(|path| Path::new(path).is_file())
+}
+
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        // from_str_radix would also take a sign, as in "%+1"
+        if bytes[i] == b'%' && i + 2 < bytes.len() && bytes[i + 1..i + 3].iter().all(u8::is_ascii_hexdigit) {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or("");
+            if let Ok(byte) = u8::from_str_radix(hex, 16) {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
 }
 
 fn rgba_to_png(img: &ImageData) -> Result<Vec<u8>> {
@@ -33,11 +146,32 @@ fn rgba_to_png(img: &ImageData) -> Result<Vec<u8>> {
     Ok(png_bytes)
 }
 
+/// Reads an image to attach, as PNG. Videos are attached as they are, since X takes MP4s
+/// as-is and there's nothing to convert them to.
 pub fn validate_image_file(path: &str) -> Result<Vec<u8>> {
     use std::io::Cursor;
     
-    let img = image::open(path)
-        .context("Failed to open image file")?;
+    if has_extension(path, &["mp4", "m4v", "mov"]) {
+        let data = std::fs::read(path).context("Failed to open video file")?;
+        if !is_video(&data) {
+            anyhow::bail!("Not an MP4 or QuickTime video");
+        }
+        return Ok(data);
+    }
+    if has_extension(path, &["svg"]) {
+        return rasterize_svg(path);
+    }
+
+    let img = match image::open(path) {
+        Ok(img) => img,
+        // HEIC/HEIF (iPhone photos) and AVIF have no pure-Rust decoder; let a system tool convert them
+        Err(_) if needs_external_decoder(path) => {
+            let png_bytes = convert_with_system_tool(path)?;
+            image::load_from_memory(&png_bytes)
+                .context("Failed to read converted image")?
+        }
+        Err(e) => return Err(e).context("Failed to open image file"),
+    };
     
     let mut png_bytes = Vec::new();
     let mut cursor = Cursor::new(&mut png_bytes);
@@ -47,3 +181,91 @@ pub fn validate_image_file(path: &str) -> Result<Vec<u8>> {
     
     Ok(png_bytes)
 }
+
+/// MP4 and QuickTime files start with an `ftyp` box.
+pub fn is_video(data: &[u8]) -> bool {
+    data.get(4..8) == Some(b"ftyp")
+}
+
+fn needs_external_decoder(path: &str) -> bool {
+    has_extension(path, &["heic", "heif", "avif"])
+}
+
+fn has_extension(path: &str, extensions: &[&str]) -> bool {
+    Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| extensions.iter().any(|candidate| ext.eq_ignore_ascii_case(candidate)))
+}
+
+/// Renders an SVG, e.g. a diagram export, to PNG at `svg_width` pixels wide. X shows
+/// transparency against its own background, so the image gets a white one like most
+/// diagram tools draw on.
+fn rasterize_svg(path: &str) -> Result<Vec<u8>> {
+    use resvg::{tiny_skia, usvg};
+
+    let data = std::fs::read(path).context("Failed to open image file")?;
+    let mut options = usvg::Options {
+        resources_dir: Path::new(path).parent().map(Path::to_path_buf),
+        ..Default::default()
+    };
+    options.fontdb_mut().load_system_fonts();
+    let tree = usvg::Tree::from_data(&data, &options).context("Failed to read SVG")?;
+
+    let width = SETTINGS.get().and_then(|settings| settings.svg_width).unwrap_or(DEFAULT_SVG_WIDTH);
+    let scale = width as f32 / tree.size().width();
+    let height = (tree.size().height() * scale).ceil() as u32;
+    let mut pixmap = tiny_skia::Pixmap::new(width, height.max(1))
+        .context("SVG is too large to render")?;
+    pixmap.fill(tiny_skia::Color::WHITE);
+    resvg::render(&tree, tiny_skia::Transform::from_scale(scale, scale), &mut pixmap.as_mut());
+
+    pixmap.encode_png().context("Failed to encode image as PNG")
+}
+
+/// Converts `path` to PNG with ImageMagick, or `sips` on macOS, returning the PNG bytes.
+fn convert_with_system_tool(path: &str) -> Result<Vec<u8>> {
+    for program in ["magick", "convert"] {
+        if let Ok(output) = Command::new(program).arg(path).arg("png:-").output() {
+            if output.status.success() && !output.stdout.is_empty() {
+                return Ok(output.stdout);
+            }
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        let out_path = std::env::temp_dir().join(format!("xpost-convert-{}.png", std::process::id()));
+        let status = Command::new("sips")
+            .args(["-s", "format", "png", path, "--out"])
+            .arg(&out_path)
+            .output();
+        if matches!(status, Ok(ref output) if output.status.success()) {
+            let png_bytes = std::fs::read(&out_path).context("Failed to read converted image");
+            let _ = std::fs::remove_file(&out_path);
+            return png_bytes;
+        }
+    }
+
+    anyhow::bail!("Can't decode this image format. Install ImageMagick (with libheif) to attach HEIC/AVIF images")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_escaped_bytes() {
+        assert_eq!(percent_decode("/home/me/My%20Pictures/cat.png"), "/home/me/My Pictures/cat.png");
+        assert_eq!(percent_decode("/tmp/caf%C3%A9.png"), "/tmp/café.png");
+        assert_eq!(percent_decode("/tmp/%e6%97%a5.png"), "/tmp/日.png");
+    }
+
+    #[test]
+    fn keeps_percent_signs_that_arent_escapes() {
+        assert_eq!(percent_decode("/tmp/100%.png"), "/tmp/100%.png");
+        assert_eq!(percent_decode("/tmp/50%zz"), "/tmp/50%zz");
+        assert_eq!(percent_decode("/tmp/a%+1b"), "/tmp/a%+1b");
+        assert_eq!(percent_decode("/tmp/ends%2"), "/tmp/ends%2");
+    }
+}
@@ -1,11 +1,351 @@
 use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, NaiveTime};
+use chrono_tz::Tz;
+use ratatui::style::Color;
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use crate::crypto;
+
+/// The data directory and config file picked with `--data-dir` / `--config` or their
+/// environment variables, set once at startup.
+static DATA_DIR: OnceLock<PathBuf> = OnceLock::new();
+static CONFIG_FILE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Config sections that hold secrets and get moved into `credentials.age`.
+const CREDENTIAL_SECTIONS: &[&str] = &["twitter", "accounts", "assist", "targets"];
+/// The keys in those sections that are secret; the rest are ordinary settings.
+const SECRET_KEYS: &[&str] = &[
+    "api_key",
+    "api_secret",
+    "access_token",
+    "access_token_secret",
+    "oauth2_token",
+    "bearer_token",
+    "token",
+];
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct Config {
     pub twitter: TwitterConfig,
+    #[serde(default)]
+    pub profile: ProfileConfig,
+    #[serde(default)]
+    pub accounts: Vec<AccountConfig>,
+    #[serde(default)]
+    pub communities: Vec<CommunityConfig>,
+    #[serde(default)]
+    pub notifications: NotificationConfig,
+    #[serde(default)]
+    pub hooks: HooksConfig,
+    #[serde(default)]
+    pub scripts: ScriptsConfig,
+    pub assist: Option<AssistConfig>,
+    pub grammar: Option<GrammarConfig>,
+    pub shortener: Option<ShortenerConfig>,
+    #[serde(default)]
+    pub display: DisplayConfig,
+    pub quiet_hours: Option<QuietHoursConfig>,
+    #[serde(default)]
+    pub drafts: DraftsConfig,
+    #[serde(default)]
+    pub media: MediaConfig,
+    #[serde(default)]
+    pub paste: PasteConfig,
+    #[serde(default)]
+    pub threads: ThreadsConfig,
+    /// Other networks posts can be cross-posted to, picked per account with `targets`.
+    #[serde(default)]
+    pub targets: Vec<TargetConfig>,
+    pub announce: Option<AnnounceConfig>,
+    /// Searches the daemon runs every so often, telling you about new matches.
+    #[serde(default)]
+    pub alerts: Vec<AlertConfig>,
+}
+
+/// Another network a post goes to as well, adapted to fit it.
+#[derive(Debug, Deserialize, Clone)]
+pub struct TargetConfig {
+    /// What accounts' `targets` and `=== name` overrides in the composer call it.
+    pub name: String,
+    pub kind: TargetKind,
+    /// The server, e.g. "https://fosstodon.org", or for Micropub the endpoint itself. LinkedIn
+    /// doesn't need one.
+    #[serde(default)]
+    pub url: String,
+    pub token: String,
+    /// Longest post the target takes; the usual limit for its kind when unset. Micropub sites
+    /// have none, so a thread goes there as one post.
+    pub char_limit: Option<usize>,
+    /// Leave hashtags out, for networks where they're noise.
+    #[serde(default)]
+    pub strip_hashtags: bool,
+    /// X handles and what to mention instead, e.g. "rustlang" = "rust@social.rust-lang.org".
+    #[serde(default)]
+    pub handles: HashMap<String, String>,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TargetKind {
+    Mastodon,
+    /// An IndieWeb site's Micropub endpoint.
+    Micropub,
+    LinkedIn,
+}
+
+impl TargetConfig {
+    pub fn char_limit(&self) -> usize {
+        self.char_limit.unwrap_or(match self.kind {
+            TargetKind::Mastodon => 500,
+            TargetKind::Micropub => usize::MAX,
+            TargetKind::LinkedIn => 3000,
+        })
+    }
+}
+
+/// Cleanup applied to text pasted into the composer.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct PasteConfig {
+    /// Steps run in order on every paste, e.g. `["quotes", "trailing-whitespace", "newlines"]`.
+    #[serde(default)]
+    pub cleanup: Vec<PasteCleanup>,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum PasteCleanup {
+    /// Curly quotes and apostrophes from word processors become straight ones.
+    Quotes,
+    /// Runs of blank lines become a single blank line.
+    Newlines,
+    /// Spaces and tabs at the ends of lines are removed.
+    TrailingWhitespace,
+}
+
+/// Writing threads in one buffer.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct ThreadsConfig {
+    /// Post the text between lines of just `---` as a thread, each part replying to the one
+    /// before. Off by default, so a horizontal rule in a post stays one post.
+    #[serde(default)]
+    pub split: bool,
+}
+
+/// `xpost announce` settings.
+#[derive(Debug, Deserialize, Clone)]
+pub struct AnnounceConfig {
+    /// The post to start from, with `{repo}`, `{name}`, `{tag}`, `{notes}` and `{url}` filled in.
+    pub template: Option<String>,
+}
+
+/// How attached files are prepared for upload.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct MediaConfig {
+    /// Width in pixels SVG attachments are rendered at; 2048 when unset.
+    pub svg_width: Option<u32>,
+}
+
+/// Where drafts live and how they're kept in sync between machines.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct DraftsConfig {
+    /// Directory to keep drafts in instead of `~/.config/xpost/drafts`, e.g. a Dropbox or
+    /// Syncthing folder.
+    pub dir: Option<String>,
+    /// Keep the drafts directory in a git repository: each change is committed (and pushed when
+    /// a remote is set up) and the remote is pulled when the browser opens.
+    #[serde(default)]
+    pub git: bool,
+    /// Encrypt drafts on disk. They're encrypted to a key kept in `drafts.key.age`, which is
+    /// unlocked with a passphrase when the composer starts.
+    #[serde(default)]
+    pub encrypt: bool,
+    /// Command that prints the passphrase, e.g. "secret-tool lookup service xpost-drafts",
+    /// to keep it in the system keyring instead of typing it.
+    pub passphrase_command: Option<String>,
+}
+
+/// A daily window when Ctrl+P offers to schedule the post for later instead.
+#[derive(Debug, Deserialize, Clone)]
+pub struct QuietHoursConfig {
+    /// "HH:MM" times in the `[display] timezone`; the window may wrap past midnight, e.g. 23:00
+    /// to 07:00.
+    pub start: String,
+    pub end: String,
+    /// When to schedule posts written during quiet hours; defaults to `end`.
+    pub schedule_at: Option<String>,
+}
+
+impl QuietHoursConfig {
+    /// When to post instead, if `now` falls within quiet hours.
+    pub fn deferred_until(&self, now: DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let parse = |time: &str| NaiveTime::parse_from_str(time.trim(), "%H:%M").ok();
+        let (start, end) = (parse(&self.start)?, parse(&self.end)?);
+        let at = match &self.schedule_at {
+            Some(at) => parse(at)?,
+            None => end,
+        };
+
+        let time = now.time();
+        let quiet = if start <= end {
+            time >= start && time < end
+        } else {
+            time >= start || time < end
+        };
+        if !quiet {
+            return None;
+        }
+
+        let today = now.date_naive();
+        let day = if at > time { today } else { today + Duration::days(1) };
+        crate::timezone::to_utc_in(now.timezone(), day.and_time(at)).map(|at| at.with_timezone(&now.timezone()))
+    }
+}
+
+/// A search the daemon keeps running, e.g. for mentions of a project that don't tag you.
+#[derive(Debug, Deserialize, Clone)]
+pub struct AlertConfig {
+    /// X search query, e.g. "xpost -from:yourhandle" or "\"my project\" lang:en".
+    pub query: String,
+    /// What notifications call it; the query when unset.
+    pub name: Option<String>,
+    /// Account whose credentials run the search; "default" when unset.
+    pub account: Option<String>,
+    /// How often to search, e.g. "30m" or "2h"; every 15 minutes when unset.
+    pub every: Option<String>,
+}
+
+impl AlertConfig {
+    pub fn label(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.query)
+    }
+
+    /// Time between searches. Anything under a minute, the daemon's tick, counts as unset.
+    pub fn interval(&self) -> Duration {
+        self.every
+            .as_deref()
+            .and_then(crate::history::parse_duration)
+            .filter(|every| *every >= Duration::minutes(1))
+            .unwrap_or_else(|| Duration::minutes(15))
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct DisplayConfig {
+    /// strftime format for post and draft times, shown in the local timezone, e.g. "%b %d %H:%M".
+    /// Unset shows relative times like "2h ago".
+    pub time_format: Option<String>,
+    /// "monochrome" drops colours and marks selections with bold and underline; so does `NO_COLOR`.
+    pub theme: Option<String>,
+    /// UI language, loaded from `~/.config/xpost/locales/<language>.toml`; English when unset.
+    pub language: Option<String>,
+    /// IANA timezone scheduled times are entered and shown in, e.g. "America/New_York"; the
+    /// system's when unset.
+    pub timezone: Option<String>,
+    /// Remind in the composer when nothing has been posted today.
+    #[serde(default)]
+    pub streak_reminder: bool,
+    /// Width of the writing column in zen mode (Alt+Z); 72 when unset.
+    pub zen_width: Option<u16>,
+    /// Keep the cursor's line in the middle of the screen in zen mode.
+    #[serde(default)]
+    pub typewriter: bool,
+}
+
+/// An OpenAI-compatible chat completions API used for drafting suggestions.
+#[derive(Debug, Deserialize, Clone)]
+pub struct AssistConfig {
+    /// Base URL, e.g. "https://api.openai.com/v1" or "http://localhost:11434/v1".
+    pub endpoint: String,
+    pub api_key: Option<String>,
+    pub model: String,
+    /// Extra instructions offered in the picker after the built-in ones.
+    #[serde(default)]
+    pub prompts: Vec<String>,
+}
+
+/// A LanguageTool server, public or self-hosted, used for grammar checks.
+#[derive(Debug, Deserialize, Clone)]
+pub struct GrammarConfig {
+    /// Base URL, e.g. "https://api.languagetool.org" or "http://localhost:8081".
+    pub server: String,
+    /// Language code such as "en-US"; "auto" lets LanguageTool detect it.
+    #[serde(default = "default_language")]
+    pub language: String,
+}
+
+fn default_language() -> String {
+    "auto".to_string()
+}
+
+/// A URL shortener API that answers with the short link as plain text.
+#[derive(Debug, Deserialize, Clone)]
+pub struct ShortenerConfig {
+    /// Request URL with `{url}` where the long link goes,
+    /// e.g. "https://is.gd/create.php?format=simple&url={url}".
+    pub endpoint: String,
+    /// Links shorter than this are left alone.
+    #[serde(default = "default_min_url_length")]
+    pub min_length: usize,
+}
+
+fn default_min_url_length() -> usize {
+    40
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct ScriptsConfig {
+    /// Rhai scripts run in order on the text right before posting; relative paths are
+    /// resolved against the config directory.
+    #[serde(default)]
+    pub transforms: Vec<String>,
+}
+
+/// Shell commands run around each post. They get the text on stdin and in `XPOST_TEXT`,
+/// plus `XPOST_ACCOUNT`, and `XPOST_URL` or `XPOST_ERROR` afterwards.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct HooksConfig {
+    /// Runs before posting; a non-zero exit cancels the post.
+    pub pre_post: Option<String>,
+    pub post_success: Option<String>,
+    pub post_failure: Option<String>,
+}
+
+/// How to tell you a post finished while you weren't looking.
+#[derive(Debug, Deserialize, Clone)]
+pub struct NotificationConfig {
+    /// Desktop notification when a post finishes while the terminal is unfocused, or from the daemon.
+    #[serde(default = "default_true")]
+    pub desktop: bool,
+    /// Ring the terminal bell when a post finishes; works over ssh.
+    #[serde(default)]
+    pub bell: bool,
+    /// Briefly flash the status bar when a post finishes.
+    #[serde(default)]
+    pub flash: bool,
+    /// File the daemon appends `[[alerts]]` matches to, one line per post; relative paths are
+    /// resolved against the config directory.
+    pub alerts_file: Option<String>,
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            desktop: true,
+            bell: false,
+            flash: false,
+            alerts_file: None,
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -14,13 +354,123 @@ pub struct TwitterConfig {
     pub api_secret: String,
     pub access_token: String,
     pub access_token_secret: String,
+    /// OAuth 2.0 user access token, required by endpoints without OAuth 1.0a support (bookmarks).
+    #[serde(default)]
+    pub oauth2_token: Option<String>,
+    /// App-only bearer token. When set, public lookups (profiles, post lookups, search) use it,
+    /// which leaves the user-context rate limits for posting.
+    #[serde(default)]
+    pub bearer_token: Option<String>,
+    /// The app's X API access tier. When set, xpost sticks to what that tier allows.
+    #[serde(default)]
+    pub tier: Option<ApiTier>,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiTier {
+    Free,
+    Basic,
+    Pro,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum AltTextPolicy {
+    Warn,
+    Block,
+}
+
+impl ApiTier {
+    /// Posts a month the tier allows per user.
+    pub fn monthly_post_cap(self) -> usize {
+        match self {
+            ApiTier::Free => 500,
+            ApiTier::Basic => 3_000,
+            ApiTier::Pro => 300_000,
+        }
+    }
+
+    /// The most results to ask for per lookup, to make the monthly read allowance last.
+    pub fn max_results(self) -> u32 {
+        match self {
+            ApiTier::Free => 10,
+            ApiTier::Basic => 25,
+            ApiTier::Pro => 100,
+        }
+    }
+}
+
+impl TwitterConfig {
+    /// Whether the tier can read beyond `users/me`; the free tier is write-only.
+    pub fn can_read(&self) -> bool {
+        self.tier != Some(ApiTier::Free)
+    }
+}
+
+/// Per-account defaults, applied whenever that account is selected.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct ProfileConfig {
+    /// Accent colour for borders and headers, e.g. "magenta" or "#1d9bf0".
+    pub accent: Option<String>,
+    /// Appended to every post that doesn't already end with it.
+    pub signature: Option<String>,
+    /// Who can reply: "following", "mentionedUsers" or "subscribers".
+    pub reply_settings: Option<String>,
+    /// Default lifetime for new posts, e.g. "24h" or "7d"; the daemon deletes them afterwards.
+    pub delete_after: Option<String>,
+    /// Whether the account can publish long posts. Detected from the API when unset.
+    pub premium: Option<bool>,
+    /// Words (or `/regex/`es) that need confirming before a post containing them goes out.
+    #[serde(default)]
+    pub blocklist: Vec<String>,
+    /// Whether posting images without alt text gets a warning ("warn") or isn't allowed ("block").
+    pub alt_text: Option<AltTextPolicy>,
+    /// Names of cross-post targets used by default for this account.
+    #[serde(default)]
+    pub targets: Vec<String>,
+}
+
+/// An X Community that posts can be sent to. The ID is the number in the community's URL.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct CommunityConfig {
+    pub name: String,
+    pub id: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct AccountConfig {
+    pub name: String,
+    #[serde(flatten)]
+    pub twitter: TwitterConfig,
+    #[serde(flatten)]
+    pub profile: ProfileConfig,
+}
+
+impl ProfileConfig {
+    pub fn accent_color(&self) -> Color {
+        self.accent
+            .as_deref()
+            .and_then(|accent| Color::from_str(accent).ok())
+            .unwrap_or(Color::Cyan)
+    }
+
+    pub fn apply_signature(&self, text: String) -> String {
+        match &self.signature {
+            Some(signature) if !text.trim_end().ends_with(signature.trim()) => {
+                format!("{}{}", text.trim_end(), signature)
+            }
+            _ => text,
+        }
+    }
 }
 
 impl Config {
     pub fn load() -> Result<Self> {
         let config_path = Self::config_path()?;
-        
-        if !config_path.exists() {
+        let credentials_path = Self::credentials_path()?;
+
+        if !config_path.exists() && !credentials_path.exists() {
             anyhow::bail!(
                 "Config file not found at: {}\n\n\
                 Please create this file with your X API credentials:\n\n\
@@ -34,32 +484,564 @@ impl Config {
             );
         }
 
-        let config_str = fs::read_to_string(&config_path)
-            .context("Failed to read config file")?;
-        
-        let config: Config = toml::from_str(&config_str)
+        let mut table = Self::read_table()?;
+
+        if credentials_path.exists() {
+            let passphrase = crypto::prompt_passphrase("Passphrase for xpost credentials: ")?;
+            let encrypted = fs::read(&credentials_path)
+                .context("Failed to read encrypted credentials")?;
+            let decrypted = crypto::decrypt(&encrypted, &passphrase)
+                .context("Failed to decrypt credentials")?;
+            let secrets: toml::Table = toml::from_str(&String::from_utf8_lossy(&decrypted))
+                .context("Failed to parse decrypted credentials")?;
+            table.extend(secrets);
+        }
+
+        let config: Config = toml::Value::Table(table)
+            .try_into()
             .context("Failed to parse config file")?;
 
         //set permissions to 600 (user read/write only)
         #[cfg(unix)]
         {
-            use std::os::unix::fs::PermissionsExt;
-            let metadata = fs::metadata(&config_path)?;
-            let mut permissions = metadata.permissions();
-            permissions.set_mode(0o600);
-            fs::set_permissions(&config_path, permissions)?;
+            if config_path.exists() {
+                set_private_permissions(&config_path)?;
+            }
         }
 
         Ok(config)
     }
 
-    fn config_path() -> Result<PathBuf> {
-        let home = std::env::var("HOME")
-            .context("HOME environment variable not set")?;
-        let config_dir = PathBuf::from(home).join(".config").join("xpost");
-        
-        fs::create_dir_all(&config_dir)?;
-        
-        Ok(config_dir.join("config.toml"))
+    /// All configured accounts. The top-level `[twitter]` and `[profile]` sections form "default".
+    pub fn all_accounts(&self) -> Vec<AccountConfig> {
+        let mut accounts = vec![AccountConfig {
+            name: "default".to_string(),
+            twitter: self.twitter.clone(),
+            profile: self.profile.clone(),
+        }];
+        accounts.extend(self.accounts.iter().cloned());
+        accounts
+    }
+
+    pub fn account(&self, name: Option<&str>) -> Result<AccountConfig> {
+        let mut accounts = self.all_accounts();
+        match name {
+            None => Ok(accounts.remove(0)),
+            Some(name) => accounts
+                .into_iter()
+                .find(|account| account.name == name)
+                .with_context(|| format!("No account named '{}' in config", name)),
+        }
+    }
+
+    /// Uses `twitter`'s keys and tokens for the account called `name` from now on.
+    pub fn set_credentials(&mut self, name: &str, twitter: &TwitterConfig) {
+        let account = self.accounts.iter_mut().find(|account| name != "default" && account.name == name);
+        let current = match account {
+            Some(account) => &mut account.twitter,
+            None => &mut self.twitter,
+        };
+        current.api_key = twitter.api_key.clone();
+        current.api_secret = twitter.api_secret.clone();
+        current.access_token = twitter.access_token.clone();
+        current.access_token_secret = twitter.access_token_secret.clone();
+    }
+
+    /// Writes `twitter`'s keys and tokens into `config.toml` for the account called `name`,
+    /// leaving the rest of the file as it is. Returns false without writing anything when the
+    /// credentials are in `credentials.age`, which would need the passphrase to rewrite.
+    pub fn save_credentials(name: &str, twitter: &TwitterConfig) -> Result<bool> {
+        if Self::credentials_path()?.exists() {
+            return Ok(false);
+        }
+
+        let mut table = Self::read_table()?;
+        let section = match table.get_mut("accounts").and_then(|accounts| accounts.as_array_mut()) {
+            Some(accounts) if name != "default" => accounts
+                .iter_mut()
+                .filter_map(|account| account.as_table_mut())
+                .find(|account| account.get("name").and_then(|value| value.as_str()) == Some(name)),
+            _ => None,
+        };
+        let section = match section {
+            Some(section) => section,
+            None => table
+                .entry("twitter")
+                .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+                .as_table_mut()
+                .context("[twitter] in the config file isn't a table")?,
+        };
+        for (key, value) in [
+            ("api_key", &twitter.api_key),
+            ("api_secret", &twitter.api_secret),
+            ("access_token", &twitter.access_token),
+            ("access_token_secret", &twitter.access_token_secret),
+        ] {
+            section.insert(key.to_string(), toml::Value::String(value.clone()));
+        }
+
+        let config_path = Self::config_path()?;
+        let contents = toml::to_string(&table).context("Failed to serialize config")?;
+        write_atomic(&config_path, contents).context("Failed to write config file")?;
+        #[cfg(unix)]
+        set_private_permissions(&config_path)?;
+        Ok(true)
+    }
+
+    /// Moves the credential sections of `config.toml` into a passphrase-encrypted `credentials.age`.
+    pub fn encrypt_credentials() -> Result<()> {
+        let config_path = Self::config_path()?;
+        let credentials_path = Self::credentials_path()?;
+
+        if credentials_path.exists() {
+            anyhow::bail!(
+                "Encrypted credentials already exist at {}. Run `xpost decrypt-config` first.",
+                credentials_path.display()
+            );
+        }
+
+        let mut table = Self::read_table()?;
+        let mut secrets = toml::Table::new();
+        for section in CREDENTIAL_SECTIONS {
+            if let Some(value) = table.remove(*section) {
+                secrets.insert(section.to_string(), value);
+            }
+        }
+
+        if secrets.is_empty() {
+            anyhow::bail!("No credentials found in {}", config_path.display());
+        }
+
+        let passphrase = crypto::prompt_new_passphrase()?;
+        let plaintext = toml::to_string(&secrets).context("Failed to serialize credentials")?;
+        let encrypted = crypto::encrypt(plaintext.as_bytes(), &passphrase)?;
+
+        write_atomic(&credentials_path, encrypted)
+            .context("Failed to write encrypted credentials")?;
+        #[cfg(unix)]
+        set_private_permissions(&credentials_path)?;
+
+        let remaining = toml::to_string(&table).context("Failed to serialize config")?;
+        write_atomic(&config_path, remaining).context("Failed to write config file")?;
+
+        println!("Credentials encrypted to {}", credentials_path.display());
+        Ok(())
+    }
+
+    /// Restores the credential sections from `credentials.age` back into plaintext `config.toml`.
+    pub fn decrypt_credentials() -> Result<()> {
+        let config_path = Self::config_path()?;
+        let credentials_path = Self::credentials_path()?;
+
+        if !credentials_path.exists() {
+            anyhow::bail!("No encrypted credentials found at {}", credentials_path.display());
+        }
+
+        let passphrase = crypto::prompt_passphrase("Passphrase for xpost credentials: ")?;
+        let encrypted = fs::read(&credentials_path)
+            .context("Failed to read encrypted credentials")?;
+        let decrypted = crypto::decrypt(&encrypted, &passphrase)
+            .context("Failed to decrypt credentials")?;
+        let secrets: toml::Table = toml::from_str(&String::from_utf8_lossy(&decrypted))
+            .context("Failed to parse decrypted credentials")?;
+
+        let mut table = Self::read_table()?;
+        table.extend(secrets);
+
+        let contents = toml::to_string(&table).context("Failed to serialize config")?;
+        write_atomic(&config_path, contents).context("Failed to write config file")?;
+        #[cfg(unix)]
+        set_private_permissions(&config_path)?;
+        fs::remove_file(&credentials_path)
+            .context("Failed to remove encrypted credentials")?;
+
+        println!("Credentials decrypted to {}", config_path.display());
+        Ok(())
+    }
+
+    /// `config.toml` without its keys and tokens, as `xpost backup` saves it.
+    pub fn shareable_table() -> Result<toml::Table> {
+        let mut table = Self::read_table()?;
+        for section in CREDENTIAL_SECTIONS {
+            if let Some(value) = table.get_mut(*section) {
+                strip_secrets(value);
+            }
+        }
+        Ok(table)
+    }
+
+    /// Writes `table` as `config.toml` for `xpost restore`, with the keys and tokens of the
+    /// current file put back into the accounts, targets and assist settings they belong to.
+    /// With `credentials.age`, those sections come from it as before. The current file is
+    /// kept as `config.toml.bak`. Returns whether there are credentials to post with.
+    pub fn restore_table(mut table: toml::Table) -> Result<bool> {
+        let config_path = Self::config_path()?;
+        let encrypted = Self::credentials_path()?.exists();
+        let current = Self::read_table()?;
+        for section in CREDENTIAL_SECTIONS {
+            let restored = table.remove(*section).filter(|_| !encrypted);
+            if let Some(value) = merge_secrets(restored, current.get(*section)) {
+                table.insert(section.to_string(), value);
+            }
+        }
+
+        if config_path.exists() {
+            let backup_path = config_path.with_extension("toml.bak");
+            fs::copy(&config_path, &backup_path)
+                .context("Failed to back up config file")?;
+            #[cfg(unix)]
+            set_private_permissions(&backup_path)?;
+        }
+        let contents = toml::to_string(&table).context("Failed to serialize config")?;
+        write_atomic(&config_path, contents).context("Failed to write config file")?;
+        #[cfg(unix)]
+        set_private_permissions(&config_path)?;
+
+        let has_keys = table.get("twitter").and_then(|twitter| twitter.get("access_token")).is_some();
+        Ok(has_keys || encrypted)
+    }
+
+    fn read_table() -> Result<toml::Table> {
+        let config_path = Self::config_path()?;
+        if !config_path.exists() {
+            return Ok(toml::Table::new());
+        }
+
+        let config_str = fs::read_to_string(&config_path)
+            .context("Failed to read config file")?;
+
+        toml::from_str(&config_str).context("Failed to parse config file")
+    }
+
+    /// `config.toml` in the data directory, unless `--config` names another file.
+    pub fn config_path() -> Result<PathBuf> {
+        match CONFIG_FILE.get() {
+            Some(path) => Ok(path.clone()),
+            None => Ok(config_dir()?.join("config.toml")),
+        }
+    }
+
+    /// Encrypted credentials sit beside the config file they were taken from.
+    pub fn credentials_path() -> Result<PathBuf> {
+        Ok(Self::config_path()?.with_file_name("credentials.age"))
+    }
+}
+
+/// Points xpost at another data directory (drafts, history, queue and the rest) and config
+/// file, so separate profiles can run side by side. The flags win over `XPOST_DATA_DIR` and
+/// `XPOST_CONFIG`. Call before anything is read.
+pub fn set_paths(data_dir: Option<String>, config_file: Option<String>) {
+    if let Some(dir) = data_dir.or_else(|| std::env::var("XPOST_DATA_DIR").ok()) {
+        let _ = DATA_DIR.set(expand_home(&dir));
+    }
+    if let Some(file) = config_file.or_else(|| std::env::var("XPOST_CONFIG").ok()) {
+        let _ = CONFIG_FILE.set(expand_home(&file));
+    }
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), std::env::var("HOME")) {
+        (Some(rest), Ok(home)) => PathBuf::from(home).join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Returns the data directory, `~/.config/xpost` unless `set_paths` chose another, creating it
+/// if needed.
+pub fn config_dir() -> Result<PathBuf> {
+    let config_dir = match DATA_DIR.get() {
+        Some(dir) => dir.clone(),
+        None => {
+            let home = std::env::var("HOME")
+                .context("HOME environment variable not set")?;
+            PathBuf::from(home).join(".config").join("xpost")
+        }
+    };
+
+    fs::create_dir_all(&config_dir)?;
+
+    Ok(config_dir)
+}
+
+/// Resolves `~/` and paths relative to the config directory.
+/// Takes the `SECRET_KEYS` out of a credential section, or out of each entry of one.
+fn strip_secrets(value: &mut toml::Value) {
+    match value {
+        toml::Value::Table(table) => table.retain(|key, _| !SECRET_KEYS.contains(&key)),
+        toml::Value::Array(entries) => entries.iter_mut().for_each(strip_secrets),
+        _ => {}
+    }
+}
+
+/// A credential section as restored from a backup, with the secrets from `current` put back:
+/// into the same table, or for `[[accounts]]` and `[[targets]]` into the entry with the same
+/// name. Entries only `current` has are kept, so no credentials are lost.
+fn merge_secrets(restored: Option<toml::Value>, current: Option<&toml::Value>) -> Option<toml::Value> {
+    let secrets = |table: &toml::Table| -> Vec<(String, toml::Value)> {
+        table
+            .iter()
+            .filter(|(key, _)| SECRET_KEYS.contains(&key.as_str()))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    };
+    let name = |value: &toml::Value| value.get("name").and_then(|name| name.as_str()).map(str::to_string);
+
+    let Some(mut restored) = restored else {
+        return current.cloned();
+    };
+    strip_secrets(&mut restored);
+    match (&mut restored, current) {
+        (toml::Value::Table(table), Some(toml::Value::Table(current))) => table.extend(secrets(current)),
+        (toml::Value::Array(entries), Some(toml::Value::Array(current))) => {
+            for entry in current {
+                let found = entries.iter_mut().find(|restored| name(restored).is_some() && name(restored) == name(entry));
+                match (found, entry) {
+                    (Some(toml::Value::Table(table)), toml::Value::Table(entry)) => table.extend(secrets(entry)),
+                    (None, _) => entries.push(entry.clone()),
+                    _ => {}
+                }
+            }
+        }
+        _ => {}
+    }
+    Some(restored)
+}
+
+pub fn resolve_path(path: &str) -> PathBuf {
+    let path = expand_home(path);
+    if path.is_absolute() {
+        return path;
+    }
+
+    config_dir()
+        .map(|dir| dir.join(&path))
+        .unwrap_or(path)
+}
+
+/// Writes `contents` to a temporary file next to `path` and renames it over `path`, so a crash
+/// mid-write leaves the old file or the new one, never half of one. The file keeps its
+/// permissions; a new one is readable only by you, as it may hold secrets.
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> std::io::Result<()> {
+    let name = path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+    let temp_path = path.with_file_name(format!(".{}.tmp", name));
+
+    // One left over from a crash would keep its mode, so start from a fresh file
+    let _ = fs::remove_file(&temp_path);
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(&temp_path)?;
+    if let Ok(metadata) = fs::metadata(path) {
+        file.set_permissions(metadata.permissions())?;
+    }
+    file.write_all(contents.as_ref())?;
+    file.sync_all()?;
+    drop(file);
+
+    fs::rename(&temp_path, path).inspect_err(|_| {
+        let _ = fs::remove_file(&temp_path);
+    })
+}
+
+#[cfg(unix)]
+pub fn set_private_permissions(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    let metadata = fs::metadata(path)?;
+    let mut permissions = metadata.permissions();
+    permissions.set_mode(0o600);
+    fs::set_permissions(path, permissions)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn quiet_hours(start: &str, end: &str, schedule_at: Option<&str>) -> QuietHoursConfig {
+        QuietHoursConfig {
+            start: start.to_string(),
+            end: end.to_string(),
+            schedule_at: schedule_at.map(str::to_string),
+        }
+    }
+
+    fn berlin(day: u32, hour: u32, minute: u32) -> DateTime<Tz> {
+        chrono_tz::Europe::Berlin.with_ymd_and_hms(2025, 3, day, hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn defers_to_the_end_of_a_window_past_midnight() {
+        let night = quiet_hours("23:00", "07:00", None);
+        assert_eq!(night.deferred_until(berlin(5, 23, 30)), Some(berlin(6, 7, 0)));
+        assert_eq!(night.deferred_until(berlin(6, 2, 0)), Some(berlin(6, 7, 0)));
+        assert_eq!(night.deferred_until(berlin(6, 7, 0)), None);
+        assert_eq!(night.deferred_until(berlin(6, 22, 59)), None);
+    }
+
+    #[test]
+    fn defers_within_a_daytime_window() {
+        let focus = quiet_hours("09:00", "12:00", Some("18:30"));
+        assert_eq!(focus.deferred_until(berlin(5, 9, 0)), Some(berlin(5, 18, 30)));
+        assert_eq!(focus.deferred_until(berlin(5, 12, 0)), None);
+        assert_eq!(focus.deferred_until(berlin(5, 8, 59)), None);
+    }
+
+    #[test]
+    fn schedules_a_time_already_passed_today_for_tomorrow() {
+        let night = quiet_hours("22:00", "06:00", Some("08:00"));
+        assert_eq!(night.deferred_until(berlin(5, 22, 15)), Some(berlin(6, 8, 0)));
+        assert_eq!(night.deferred_until(berlin(6, 1, 0)), Some(berlin(6, 8, 0)));
+    }
+
+    #[test]
+    fn unreadable_times_turn_quiet_hours_off() {
+        assert_eq!(quiet_hours("11pm", "07:00", None).deferred_until(berlin(5, 23, 30)), None);
+        assert_eq!(quiet_hours("23:00", "07:00", Some("later")).deferred_until(berlin(5, 23, 30)), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn writes_new_files_readable_only_by_their_owner() {
+        use std::os::unix::fs::PermissionsExt;
+        let path = std::env::temp_dir().join(format!("xpost-write-atomic-{}.toml", std::process::id()));
+        let _ = fs::remove_file(&path);
+        write_atomic(&path, "api_key = \"k\"").unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "api_key = \"k\"");
+        fs::remove_file(&path).unwrap();
+    }
+
+    fn toml(source: &str) -> toml::Value {
+        toml::Value::Table(toml::from_str(source).unwrap())
+    }
+
+    #[test]
+    fn strips_only_secret_keys() {
+        let mut config = toml(
+            r#"
+            [twitter]
+            api_key = "k"
+            access_token = "t"
+            tier = "basic"
+
+            [[accounts]]
+            name = "project"
+            access_token_secret = "s"
+            signature = " — via xpost"
+            blocklist = ["crypto"]
+
+            [[targets]]
+            name = "mastodon"
+            token = "m"
+            url = "https://fosstodon.org"
+
+            [assist]
+            api_key = "a"
+            model = "gpt"
+            "#,
+        );
+        for section in CREDENTIAL_SECTIONS {
+            strip_secrets(config.get_mut(*section).unwrap());
+        }
+        assert_eq!(
+            config,
+            toml(
+                r#"
+                [twitter]
+                tier = "basic"
+
+                [[accounts]]
+                name = "project"
+                signature = " — via xpost"
+                blocklist = ["crypto"]
+
+                [[targets]]
+                name = "mastodon"
+                url = "https://fosstodon.org"
+
+                [assist]
+                model = "gpt"
+                "#,
+            )
+        );
+    }
+
+    #[test]
+    fn puts_secrets_back_entry_by_entry() {
+        let restored = toml(
+            r#"
+            [[accounts]]
+            name = "project"
+            signature = " — new"
+
+            [[accounts]]
+            name = "old"
+            "#,
+        );
+        let current = toml(
+            r#"
+            [[accounts]]
+            name = "project"
+            access_token = "t1"
+            signature = " — current"
+
+            [[accounts]]
+            name = "work"
+            access_token = "t2"
+            "#,
+        );
+        let merged = merge_secrets(restored.get("accounts").cloned(), current.get("accounts")).unwrap();
+        assert_eq!(
+            toml::Value::Table(toml::Table::from_iter([("accounts".to_string(), merged)])),
+            toml(
+                r#"
+                [[accounts]]
+                name = "project"
+                signature = " — new"
+                access_token = "t1"
+
+                [[accounts]]
+                name = "old"
+
+                [[accounts]]
+                name = "work"
+                access_token = "t2"
+                "#,
+            )
+        );
+    }
+
+    #[test]
+    fn keeps_the_current_section_when_the_backup_has_none() {
+        let current = toml("[assist]\napi_key = \"a\"\nmodel = \"gpt\"");
+        assert_eq!(merge_secrets(None, current.get("assist")), current.get("assist").cloned());
+        let restored = toml("[assist]\napi_key = \"stale\"\nmodel = \"llama\"");
+        assert_eq!(
+            merge_secrets(restored.get("assist").cloned(), current.get("assist")),
+            toml("api_key = \"a\"\nmodel = \"llama\"").into()
+        );
+    }
+
+    fn signed(signature: Option<&str>) -> ProfileConfig {
+        ProfileConfig {
+            signature: signature.map(str::to_string),
+            ..ProfileConfig::default()
+        }
+    }
+
+    #[test]
+    fn appends_the_signature_once() {
+        let profile = signed(Some(" — via xpost"));
+        assert_eq!(profile.apply_signature("hello".to_string()), "hello — via xpost");
+        assert_eq!(profile.apply_signature("hello \n".to_string()), "hello — via xpost");
+        assert_eq!(profile.apply_signature("hello — via xpost".to_string()), "hello — via xpost");
+        assert_eq!(profile.apply_signature("hello — via xpost\n".to_string()), "hello — via xpost\n");
+    }
+
+    #[test]
+    fn leaves_text_alone_without_a_signature() {
+        assert_eq!(signed(None).apply_signature("hello \n".to_string()), "hello \n");
     }
 }
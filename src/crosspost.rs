@@ -0,0 +1,422 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use reqwest::multipart::{Form, Part};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use crate::config::{TargetConfig, TargetKind};
+use crate::thread;
+
+/// A line starting with this and a target's name begins text only that target gets, in place
+/// of the post above; X gets everything before the first such line.
+pub const OVERRIDE_MARKER: &str = "===";
+
+/// The target named on an override line (`=== mastodon`), if `line` is one.
+pub fn override_target(line: &str) -> Option<&str> {
+    let name = line.trim().strip_prefix(OVERRIDE_MARKER)?.trim();
+    (!name.is_empty() && !name.starts_with('=')).then_some(name)
+}
+
+/// The composed text before any overrides, and each override by target name.
+pub fn split_overrides(text: &str) -> (String, HashMap<String, String>) {
+    let mut base = Vec::new();
+    let mut overrides: HashMap<String, Vec<&str>> = HashMap::new();
+    let mut current: Option<String> = None;
+
+    for line in text.lines() {
+        if let Some(name) = override_target(line) {
+            current = Some(name.to_string());
+            overrides.entry(name.to_string()).or_default();
+        } else if let Some(name) = &current {
+            overrides.entry(name.clone()).or_default().push(line);
+        } else {
+            base.push(line);
+        }
+    }
+
+    let overrides = overrides
+        .into_iter()
+        .map(|(name, lines)| (name, lines.join("\n").trim().to_string()))
+        .filter(|(_, text)| !text.is_empty())
+        .collect();
+    (base.join("\n"), overrides)
+}
+
+/// The posts `target` gets for the composed `text`: its override if it has one, otherwise the
+/// X version with handles mapped and hashtags stripped as configured. Either way the parts are
+/// repacked to fit the target's length limit.
+pub fn adapt(target: &TargetConfig, text: &str) -> Vec<String> {
+    let (base, overrides) = split_overrides(text);
+    let text = match overrides.get(&target.name) {
+        Some(text) => text.clone(),
+        None => {
+            let text = map_handles(&base, &target.handles);
+            if target.strip_hashtags {
+                strip_hashtags(&text)
+            } else {
+                text
+            }
+        }
+    };
+    repack(thread::split_thread(&text), target.char_limit())
+}
+
+/// Swaps X @handles for the target's own, e.g. @rustlang → @rust@social.rust-lang.org.
+fn map_handles(text: &str, handles: &HashMap<String, String>) -> String {
+    static HANDLE: OnceLock<Regex> = OnceLock::new();
+    let handle = HANDLE.get_or_init(|| Regex::new(r"(^|[^\w@/])@(\w{1,15})\b").unwrap());
+
+    let handles: HashMap<String, &String> = handles
+        .iter()
+        .map(|(x, other)| (x.trim_start_matches('@').to_lowercase(), other))
+        .collect();
+    handle
+        .replace_all(text, |caps: &regex::Captures| match handles.get(&caps[2].to_lowercase()) {
+            Some(other) => format!("{}@{}", &caps[1], other.trim_start_matches('@')),
+            None => caps[0].to_string(),
+        })
+        .into_owned()
+}
+
+/// Removes #hashtags, then any lines they leave empty at the end.
+fn strip_hashtags(text: &str) -> String {
+    static HASHTAG: OnceLock<Regex> = OnceLock::new();
+    let hashtag = HASHTAG.get_or_init(|| Regex::new(r"(^|\s)#\w*[\p{L}_]\w*").unwrap());
+
+    text.lines()
+        .map(|line| match hashtag.replace_all(line, "$1") {
+            stripped if stripped != line => stripped.split_whitespace().collect::<Vec<_>>().join(" "),
+            _ => line.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim_end()
+        .to_string()
+}
+
+/// Joins consecutive parts that fit in one post under `limit`, and splits any part over it at
+/// word boundaries, so a thread written for X reads naturally where posts can be longer or
+/// must be shorter.
+fn repack(parts: Vec<String>, limit: usize) -> Vec<String> {
+    let mut posts: Vec<String> = Vec::new();
+    for part in parts.iter().flat_map(|part| split_long(part, limit)) {
+        match posts.last_mut() {
+            Some(last) if last.chars().count() + 2 + part.chars().count() <= limit => {
+                last.push_str("\n\n");
+                last.push_str(&part);
+            }
+            _ => posts.push(part),
+        }
+    }
+    posts
+}
+
+fn split_long(text: &str, limit: usize) -> Vec<String> {
+    if text.chars().count() <= limit {
+        return vec![text.to_string()];
+    }
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    for word in text.split_inclusive(char::is_whitespace) {
+        if !current.is_empty() && current.chars().count() + word.trim_end().chars().count() > limit {
+            chunks.push(current.trim_end().to_string());
+            current.clear();
+        }
+        current.push_str(word);
+    }
+    if !current.trim().is_empty() {
+        chunks.push(current.trim_end().to_string());
+    }
+    chunks
+}
+
+/// Publishes `parts` as a thread on `target`, with the images on the first, and returns the
+/// URL of the first post.
+pub async fn publish(target: &TargetConfig, parts: Vec<String>, images: &[Vec<u8>], alt_texts: &[String]) -> Result<String> {
+    match target.kind {
+        TargetKind::Mastodon => publish_mastodon(target, parts, images, alt_texts).await,
+        TargetKind::Micropub => publish_micropub(target, parts, images, alt_texts).await,
+        TargetKind::LinkedIn => publish_linkedin(target, parts, images, alt_texts).await,
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct MastodonMedia {
+    id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct MastodonStatus {
+    id: String,
+    url: Option<String>,
+}
+
+async fn publish_mastodon(
+    target: &TargetConfig,
+    parts: Vec<String>,
+    images: &[Vec<u8>],
+    alt_texts: &[String],
+) -> Result<String> {
+    let client = reqwest::Client::new();
+    let base = target.url.trim_end_matches('/');
+
+    let mut media_ids = Vec::new();
+    for (i, image) in images.iter().enumerate() {
+        let file = Part::bytes(image.clone()).file_name("image.png").mime_str("image/png")?;
+        let mut form = Form::new().part("file", file);
+        if let Some(alt_text) = alt_texts.get(i).filter(|text| !text.trim().is_empty()) {
+            form = form.text("description", alt_text.clone());
+        }
+        let response = client
+            .post(format!("{}/api/v2/media", base))
+            .bearer_auth(&target.token)
+            .multipart(form)
+            .send()
+            .await
+            .context("Failed to upload media")?;
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            anyhow::bail!("Media upload failed: {}", error_text);
+        }
+        let media: MastodonMedia = response.json().await?;
+        media_ids.push(media.id);
+    }
+
+    let mut reply_to: Option<String> = None;
+    let mut first_url = None;
+    for part in parts {
+        let body = serde_json::json!({
+            "status": part,
+            "media_ids": std::mem::take(&mut media_ids),
+            "in_reply_to_id": reply_to,
+        });
+        let response = client
+            .post(format!("{}/api/v1/statuses", base))
+            .bearer_auth(&target.token)
+            .json(&body)
+            .send()
+            .await
+            .context("Failed to post")?;
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            anyhow::bail!("API Error: {}", error_text);
+        }
+        let status: MastodonStatus = response.json().await?;
+        first_url.get_or_insert_with(|| status.url.clone().unwrap_or_else(|| format!("{}/statuses/{}", base, status.id)));
+        reply_to = Some(status.id);
+    }
+
+    first_url.context("Nothing to post")
+}
+
+#[derive(Debug, Deserialize)]
+struct MicropubConfig {
+    #[serde(rename = "media-endpoint")]
+    media_endpoint: Option<String>,
+}
+
+/// The `Location` a Micropub server answers with: the new post, or the uploaded file.
+fn location(response: &reqwest::Response) -> Option<String> {
+    response
+        .headers()
+        .get(reqwest::header::LOCATION)
+        .and_then(|location| location.to_str().ok())
+        .map(str::to_string)
+}
+
+/// Creates an h-entry with the parts as its content. Images go to the endpoint's media endpoint
+/// first so they can carry alt text.
+async fn publish_micropub(
+    target: &TargetConfig,
+    parts: Vec<String>,
+    images: &[Vec<u8>],
+    alt_texts: &[String],
+) -> Result<String> {
+    let client = reqwest::Client::new();
+
+    let mut photos = Vec::new();
+    if !images.is_empty() {
+        let config: MicropubConfig = client
+            .get(&target.url)
+            .query(&[("q", "config")])
+            .bearer_auth(&target.token)
+            .send()
+            .await
+            .context("Failed to query Micropub config")?
+            .json()
+            .await
+            .context("Failed to parse Micropub config")?;
+        let media_endpoint = config
+            .media_endpoint
+            .context("The Micropub server has no media endpoint for images")?;
+
+        for (i, image) in images.iter().enumerate() {
+            let file = Part::bytes(image.clone()).file_name("image.png").mime_str("image/png")?;
+            let response = client
+                .post(&media_endpoint)
+                .bearer_auth(&target.token)
+                .multipart(Form::new().part("file", file))
+                .send()
+                .await
+                .context("Failed to upload media")?;
+            if !response.status().is_success() {
+                let error_text = response.text().await?;
+                anyhow::bail!("Media upload failed: {}", error_text);
+            }
+            let url = location(&response).context("Media upload returned no Location")?;
+            let alt = alt_texts.get(i).map(|text| text.trim()).unwrap_or("");
+            photos.push(serde_json::json!({ "value": url, "alt": alt }));
+        }
+    }
+
+    let mut properties = serde_json::json!({ "content": [parts.join("\n\n")] });
+    if !photos.is_empty() {
+        properties["photo"] = photos.into();
+    }
+    let response = client
+        .post(&target.url)
+        .bearer_auth(&target.token)
+        .json(&serde_json::json!({ "type": ["h-entry"], "properties": properties }))
+        .send()
+        .await
+        .context("Failed to post")?;
+    if !response.status().is_success() {
+        let error_text = response.text().await?;
+        anyhow::bail!("API Error: {}", error_text);
+    }
+    location(&response).context("Micropub server returned no Location")
+}
+
+const LINKEDIN_API: &str = "https://api.linkedin.com/v2";
+
+#[derive(Debug, Deserialize)]
+struct LinkedInUser {
+    sub: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct LinkedInUploadResponse {
+    value: LinkedInUpload,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LinkedInUpload {
+    asset: String,
+    upload_mechanism: LinkedInUploadMechanism,
+}
+
+#[derive(Debug, Deserialize)]
+struct LinkedInUploadMechanism {
+    #[serde(rename = "com.linkedin.digitalmedia.uploading.MediaUploadHttpRequest")]
+    request: LinkedInUploadRequest,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LinkedInUploadRequest {
+    upload_url: String,
+}
+
+/// Shares the post publicly as the token's member. LinkedIn has no threads, so the post must
+/// fit in one share.
+async fn publish_linkedin(
+    target: &TargetConfig,
+    parts: Vec<String>,
+    images: &[Vec<u8>],
+    alt_texts: &[String],
+) -> Result<String> {
+    let [text] = parts.as_slice() else {
+        anyhow::bail!("Too long for one LinkedIn post ({} characters)", target.char_limit());
+    };
+    let client = reqwest::Client::new();
+
+    let user: LinkedInUser = client
+        .get(format!("{}/userinfo", LINKEDIN_API))
+        .bearer_auth(&target.token)
+        .send()
+        .await
+        .context("Failed to fetch LinkedIn member")?
+        .error_for_status()
+        .context("Failed to fetch LinkedIn member")?
+        .json()
+        .await?;
+    let author = format!("urn:li:person:{}", user.sub);
+
+    let mut media = Vec::new();
+    for (i, image) in images.iter().enumerate() {
+        let body = serde_json::json!({
+            "registerUploadRequest": {
+                "recipes": ["urn:li:digitalmediaRecipe:feedshare-image"],
+                "owner": author,
+                "serviceRelationships": [{
+                    "relationshipType": "OWNER",
+                    "identifier": "urn:li:userGeneratedContent",
+                }],
+            }
+        });
+        let response = client
+            .post(format!("{}/assets?action=registerUpload", LINKEDIN_API))
+            .bearer_auth(&target.token)
+            .json(&body)
+            .send()
+            .await
+            .context("Failed to upload media")?;
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            anyhow::bail!("Media upload failed: {}", error_text);
+        }
+        let upload: LinkedInUploadResponse = response.json().await?;
+
+        let response = client
+            .put(&upload.value.upload_mechanism.request.upload_url)
+            .bearer_auth(&target.token)
+            .body(image.clone())
+            .send()
+            .await
+            .context("Failed to upload media")?;
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            anyhow::bail!("Media upload failed: {}", error_text);
+        }
+
+        let alt = alt_texts.get(i).map(|text| text.trim()).unwrap_or("");
+        media.push(serde_json::json!({
+            "status": "READY",
+            "media": upload.value.asset,
+            "description": { "text": alt },
+        }));
+    }
+
+    let body = serde_json::json!({
+        "author": author,
+        "lifecycleState": "PUBLISHED",
+        "specificContent": {
+            "com.linkedin.ugc.ShareContent": {
+                "shareCommentary": { "text": text },
+                "shareMediaCategory": if media.is_empty() { "NONE" } else { "IMAGE" },
+                "media": media,
+            }
+        },
+        "visibility": { "com.linkedin.ugc.MemberNetworkVisibility": "PUBLIC" },
+    });
+    let response = client
+        .post(format!("{}/ugcPosts", LINKEDIN_API))
+        .bearer_auth(&target.token)
+        .header("X-Restli-Protocol-Version", "2.0.0")
+        .json(&body)
+        .send()
+        .await
+        .context("Failed to post")?;
+    if !response.status().is_success() {
+        let error_text = response.text().await?;
+        anyhow::bail!("API Error: {}", error_text);
+    }
+    let id = response
+        .headers()
+        .get("x-restli-id")
+        .and_then(|id| id.to_str().ok())
+        .context("LinkedIn returned no post ID")?;
+    Ok(format!("https://www.linkedin.com/feed/update/{}", id))
+}
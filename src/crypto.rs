@@ -0,0 +1,117 @@
+use age::secrecy::Secret;
+use age::x25519;
+use anyhow::{Context, Result};
+use std::io::{Read, Write};
+use std::process::Command;
+
+/// Reads a passphrase from `XPOST_PASSPHRASE` or prompts for it on the terminal.
+pub fn prompt_passphrase(prompt: &str) -> Result<String> {
+    if let Ok(passphrase) = std::env::var("XPOST_PASSPHRASE") {
+        return Ok(passphrase);
+    }
+
+    rpassword::prompt_password(prompt).context("Failed to read passphrase")
+}
+
+/// Runs `command` through the shell and takes the first line it prints as the passphrase,
+/// e.g. from a keyring or password manager.
+pub fn passphrase_from_command(command: &str) -> Result<String> {
+    let output = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .output()
+        .with_context(|| format!("Failed to run '{}'", command))?;
+    if !output.status.success() {
+        anyhow::bail!("'{}' failed: {}", command, String::from_utf8_lossy(&output.stderr).trim());
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let passphrase = stdout.lines().next().unwrap_or_default().to_string();
+    if passphrase.is_empty() {
+        anyhow::bail!("'{}' didn't print a passphrase", command);
+    }
+    Ok(passphrase)
+}
+
+/// Prompts twice and makes sure both entries match.
+pub fn prompt_new_passphrase() -> Result<String> {
+    let passphrase = prompt_passphrase("New passphrase: ")?;
+    if passphrase.is_empty() {
+        anyhow::bail!("Passphrase must not be empty");
+    }
+
+    if std::env::var("XPOST_PASSPHRASE").is_err() {
+        let confirm = prompt_passphrase("Confirm passphrase: ")?;
+        if confirm != passphrase {
+            anyhow::bail!("Passphrases did not match");
+        }
+    }
+
+    Ok(passphrase)
+}
+
+pub fn encrypt(plaintext: &[u8], passphrase: &str) -> Result<Vec<u8>> {
+    let encryptor = age::Encryptor::with_user_passphrase(Secret::new(passphrase.to_owned()));
+
+    let mut encrypted = Vec::new();
+    let mut writer = encryptor
+        .wrap_output(&mut encrypted)
+        .context("Failed to start encryption")?;
+    writer.write_all(plaintext).context("Failed to encrypt data")?;
+    writer.finish().context("Failed to finish encryption")?;
+
+    Ok(encrypted)
+}
+
+pub fn decrypt(ciphertext: &[u8], passphrase: &str) -> Result<Vec<u8>> {
+    let decryptor = match age::Decryptor::new(ciphertext).context("Failed to read encrypted data")? {
+        age::Decryptor::Passphrase(d) => d,
+        _ => anyhow::bail!("Data was not encrypted with a passphrase"),
+    };
+
+    let mut reader = decryptor
+        .decrypt(&Secret::new(passphrase.to_owned()), None)
+        .map_err(|_| anyhow::anyhow!("Wrong passphrase or corrupted data"))?;
+
+    let mut plaintext = Vec::new();
+    reader.read_to_end(&mut plaintext).context("Failed to decrypt data")?;
+
+    Ok(plaintext)
+}
+
+/// Whether `data` is an age file rather than plaintext.
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(b"age-encryption.org/")
+}
+
+/// Encrypts to a key rather than a passphrase, which is quick enough to do for every file.
+pub fn encrypt_to(plaintext: &[u8], identity: &x25519::Identity) -> Result<Vec<u8>> {
+    let recipient: Box<dyn age::Recipient + Send> = Box::new(identity.to_public());
+    let encryptor = age::Encryptor::with_recipients(vec![recipient])
+        .context("Failed to start encryption")?;
+
+    let mut encrypted = Vec::new();
+    let mut writer = encryptor
+        .wrap_output(&mut encrypted)
+        .context("Failed to start encryption")?;
+    writer.write_all(plaintext).context("Failed to encrypt data")?;
+    writer.finish().context("Failed to finish encryption")?;
+
+    Ok(encrypted)
+}
+
+pub fn decrypt_with(ciphertext: &[u8], identity: &x25519::Identity) -> Result<Vec<u8>> {
+    let decryptor = match age::Decryptor::new(ciphertext).context("Failed to read encrypted data")? {
+        age::Decryptor::Recipients(d) => d,
+        _ => anyhow::bail!("Data was encrypted with a passphrase, not a key"),
+    };
+
+    let mut reader = decryptor
+        .decrypt(std::iter::once(identity as &dyn age::Identity))
+        .map_err(|_| anyhow::anyhow!("Wrong key or corrupted data"))?;
+
+    let mut plaintext = Vec::new();
+    reader.read_to_end(&mut plaintext).context("Failed to decrypt data")?;
+
+    Ok(plaintext)
+}
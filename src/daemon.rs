@@ -0,0 +1,214 @@
+use anyhow::Result;
+use chrono::Utc;
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::alerts::{self, Alerts};
+use crate::config::{AccountConfig, Config};
+use crate::history;
+use crate::lock;
+use crate::notify;
+use crate::posting;
+use crate::queue::{self, QueueStatus};
+use crate::twitter::{DeleteRefused, TwitterClient};
+
+type Accounts = HashMap<String, (AccountConfig, TwitterClient)>;
+
+/// How often the daemon looks for due work.
+const TICK: Duration = Duration::from_secs(60);
+
+/// Held while publishing and deleting, so the daemon, `xpost flush` runs and retries from the
+/// queue tab never overlap.
+pub const PUBLISH_LOCK: &str = "publish";
+
+/// Exit codes of `xpost flush`, for systemd timers and launchd jobs to act on.
+pub const EXIT_POST_FAILED: i32 = 3;
+/// Another flush or the daemon was already publishing (EX_TEMPFAIL); nothing was done.
+pub const EXIT_BUSY: i32 = 75;
+
+/// What one pass over the outbox did.
+#[derive(Debug, Default)]
+pub struct Flushed {
+    pub posted: usize,
+    pub failed: usize,
+}
+
+/// Runs in the foreground until interrupted, doing any due background work each tick.
+pub async fn run(config: Config) -> Result<()> {
+    let accounts = load_accounts(&config);
+    let mut alerts = Alerts::load().unwrap_or_else(|e| {
+        log(format!("{:#}; alerts start over", e));
+        Alerts::default()
+    });
+
+    println!("xpost daemon running ({} account(s)). Ctrl+C to stop.", accounts.len());
+    if !config.alerts.is_empty() {
+        println!("Watching {} search(es) for alerts", config.alerts.len());
+    }
+
+    let mut interval = tokio::time::interval(TICK);
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {
+                if let Err(e) = tick(&accounts, &config).await {
+                    eprintln!("[{}] {}", Utc::now().format("%Y-%m-%d %H:%M:%S"), e);
+                }
+                check_alerts(&mut alerts, &accounts, &config).await;
+            }
+            _ = tokio::signal::ctrl_c() => {
+                println!("Stopping xpost daemon");
+                return Ok(());
+            }
+        }
+    }
+}
+
+fn load_accounts(config: &Config) -> Accounts {
+    config
+        .all_accounts()
+        .into_iter()
+        .map(|account| {
+            let client = TwitterClient::new(account.twitter.clone());
+            (account.name.clone(), (account, client))
+        })
+        .collect()
+}
+
+async fn tick(accounts: &Accounts, config: &Config) -> Result<()> {
+    let Some(_lock) = lock::try_lock(PUBLISH_LOCK)? else {
+        log("Another xpost is publishing; skipping this tick".to_string());
+        return Ok(());
+    };
+    publish_due_posts(accounts, config).await?;
+    delete_expired_posts(accounts).await
+}
+
+/// Does one tick's work and returns, for `xpost flush`. Safe to run as often as you like and
+/// from overlapping timers: without the publish lock it does nothing and returns `None`.
+pub async fn flush(config: &Config) -> Result<Option<Flushed>> {
+    let Some(_lock) = lock::try_lock(PUBLISH_LOCK)? else {
+        return Ok(None);
+    };
+    let accounts = load_accounts(config);
+    let flushed = publish_due_posts(&accounts, config).await?;
+    delete_expired_posts(&accounts).await?;
+    Ok(Some(flushed))
+}
+
+fn log(message: String) {
+    println!("[{}] {}", Utc::now().format("%Y-%m-%d %H:%M:%S"), message);
+}
+
+/// Publishes every due post. Call with the publish lock held.
+async fn publish_due_posts(accounts: &Accounts, config: &Config) -> Result<Flushed> {
+    let notifications = &config.notifications;
+    let now = Utc::now();
+    let mut flushed = Flushed::default();
+
+    // Still marked as posting from a run that never finished. Posting again could duplicate
+    // it, so it waits for someone to check
+    for post in queue::load_queue()?.into_iter().filter(|post| post.status == QueueStatus::Posting) {
+        log(format!("Queued post {} was interrupted while posting", post.id));
+        queue::update_post(&post.id, |post| {
+            post.status = QueueStatus::Failed("Interrupted while posting; check X before retrying".to_string());
+        })?;
+        flushed.failed += 1;
+    }
+
+    for post in queue::load_queue()?.into_iter().filter(|post| post.is_due(now)) {
+        // Edited, paused or deleted since the queue was read
+        let Some(post) = queue::claim(&post.id, |post| post.is_due(now))? else {
+            continue;
+        };
+
+        let result = match accounts.get(&post.account) {
+            Some((account, client)) => posting::publish_queued(&post, account, client, config).await,
+            None => Err(format!("No account named '{}' in config", post.account).into()),
+        };
+
+        match result {
+            Ok((url, crossposts)) => {
+                log(format!("Posted queued post {}", url));
+                for (target, result) in crossposts {
+                    match result {
+                        Ok(target_url) => log(format!("Cross-posted to {}: {}", target, target_url)),
+                        Err(e) => log(format!("Cross-posting to {} failed: {}", target, e)),
+                    }
+                }
+                notify::desktop(notifications, "Scheduled post published", &url);
+                notify::bell(notifications);
+                if let Some(recurrence) = &post.recurrence {
+                    log(format!("Post {} repeats {}", post.id, recurrence.describe()));
+                }
+                queue::published(&post)?;
+                flushed.posted += 1;
+            }
+            Err(e) => {
+                log(format!("Queued post {} failed: {}", post.id, e));
+                notify::desktop(notifications, "Scheduled post failed", &e.message);
+                notify::bell(notifications);
+                queue::update_post(&post.id, |post| post.fail(&e))?;
+                flushed.failed += 1;
+            }
+        }
+    }
+
+    Ok(flushed)
+}
+
+/// Runs the `[[alerts]]` searches that are due and reports what's new. A failing search is
+/// logged and tried again at its next interval.
+async fn check_alerts(alerts: &mut Alerts, accounts: &Accounts, config: &Config) {
+    let now = Utc::now();
+    for alert in &config.alerts {
+        if !alerts.is_due(alert, now) {
+            continue;
+        }
+        let account = alert.account.as_deref().unwrap_or("default");
+        let result = match accounts.get(account) {
+            Some((_, client)) if !client.can_read() => {
+                Err(anyhow::anyhow!("The free API tier can't search; alerts need basic or above"))
+            }
+            Some((_, client)) => alerts.check(alert, client).await,
+            None => Err(anyhow::anyhow!("No account named '{}' in config", account)),
+        };
+
+        let reported = result.and_then(|matches| {
+            if !matches.is_empty() {
+                log(format!("{} new post(s) for alert \"{}\"", matches.len(), alert.label()));
+            }
+            alerts::report(alert, &matches, &config.notifications)
+        });
+        if let Err(e) = reported {
+            log(format!("Alert \"{}\" failed: {:#}", alert.label(), e));
+        }
+    }
+}
+
+async fn delete_expired_posts(accounts: &Accounts) -> Result<()> {
+    let now = Utc::now();
+    let due: Vec<_> = history::load_history()?
+        .into_iter()
+        .filter(|record| record.is_due_for_deletion(now))
+        .collect();
+
+    for record in due {
+        let result = match accounts.get(&record.account) {
+            Some((_, client)) => client.delete_tweet(&record.id).await,
+            None => Err(anyhow::anyhow!("No account named '{}' in config", record.account)),
+        };
+
+        let timestamp = Utc::now();
+        match &result {
+            Ok(()) => log(format!("Deleted expired post {}", record.id)),
+            Err(e) => log(format!("Failed to delete post {}: {}", record.id, e)),
+        }
+
+        history::update_record(&record.id, |record| match result {
+            Ok(()) => record.deleted_at = Some(timestamp),
+            Err(e) => record.delete_failed(e.to_string(), e.is::<DeleteRefused>(), timestamp),
+        })?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,248 @@
+use anyhow::{Context, Result};
+use chrono::{Duration, Utc};
+use std::fs;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+
+use crate::config::{self, Config};
+use crate::twitter::{PublicMetrics, ReferencedTweet, Tweet, TweetData, UserData, UserMetrics, UserProfile};
+
+/// Set by `xpost --demo`: every API call is answered from memory instead of X.
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// What the fake account has done this session.
+static STATE: OnceLock<Mutex<State>> = OnceLock::new();
+
+/// Written as the demo profile's config the first time, with keys that never leave the machine.
+const DEMO_CONFIG: &str = r#"[twitter]
+api_key = "demo"
+api_secret = "demo"
+access_token = "demo"
+access_token_secret = "demo"
+
+[threads]
+split = true
+"#;
+
+const USER_ID: &str = "1000";
+const USERNAME: &str = "xpost_demo";
+
+struct State {
+    next_id: u64,
+    posts: Vec<Tweet>,
+    bookmarks: Vec<String>,
+}
+
+/// Turns demo mode on and points xpost at a profile of its own in the temp directory, so
+/// trying it out never touches real drafts, history or the queue.
+pub fn enable() -> Result<()> {
+    ENABLED.store(true, Ordering::Relaxed);
+    let dir = std::env::temp_dir().join("xpost-demo");
+    config::set_paths(Some(dir.to_string_lossy().into_owned()), None);
+
+    let path = Config::config_path()?;
+    if !path.exists() {
+        fs::write(&path, DEMO_CONFIG).context("Failed to write demo config")?;
+    }
+    Ok(())
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+fn state() -> MutexGuard<'static, State> {
+    let state = STATE.get_or_init(|| {
+        Mutex::new(State {
+            next_id: 1_900_000_000_000_000_000,
+            posts: canned_posts(),
+            bookmarks: Vec::new(),
+        })
+    });
+    state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn tweet(id: &str, author: &str, text: &str, hours_ago: i64, likes: u32) -> Tweet {
+    Tweet {
+        id: id.to_string(),
+        text: text.to_string(),
+        created_at: Some((Utc::now() - Duration::hours(hours_ago)).to_rfc3339()),
+        public_metrics: Some(PublicMetrics {
+            retweet_count: likes / 8,
+            reply_count: likes / 12,
+            like_count: likes,
+            quote_count: likes / 40,
+            impression_count: likes * 37,
+        }),
+        author_id: Some(author_id(author).to_string()),
+        author: Some(author.to_string()),
+        author_name: Some(display_name(author).to_string()),
+        conversation_id: None,
+        referenced_tweets: Vec::new(),
+    }
+}
+
+fn author_id(username: &str) -> &str {
+    match username {
+        USERNAME => USER_ID,
+        "terminal_tips" => "2101",
+        "ratatui_rs" => "2102",
+        _ => "2103",
+    }
+}
+
+fn display_name(username: &str) -> &str {
+    match username {
+        USERNAME => "xpost demo",
+        "rustlang" => "Rust Language",
+        "ratatui_rs" => "Ratatui",
+        "terminal_tips" => "Terminal Tips",
+        other => other,
+    }
+}
+
+fn canned_posts() -> Vec<Tweet> {
+    vec![
+        tweet("1800000000000000003", USERNAME, "shipped a terminal client for posting to X. drafts, threads, scheduling, all without leaving the shell", 5, 212),
+        tweet("1800000000000000002", USERNAME, "hot take: the best editor is the one already open", 30, 48),
+        tweet("1800000000000000001", USERNAME, "writing threads in a text file and splitting them on --- is underrated\n\n---\n\nit also makes them easy to review", 76, 131),
+    ]
+}
+
+fn timeline() -> Vec<Tweet> {
+    vec![
+        tweet("1800000000000000103", "rustlang", "Rust 1.90 is out! Faster builds, new lints, and more. Read the release notes on the blog", 2, 5_400),
+        tweet("1800000000000000102", "ratatui_rs", "ratatui 0.30 lands with a new layout engine. show us what you build", 9, 880),
+        tweet("1800000000000000101", "terminal_tips", "tip: ctrl+r searches your shell history. you're welcome", 20, 1_240),
+    ]
+}
+
+fn user(id: &str, username: &str, followers: u64) -> UserData {
+    UserData {
+        id: id.to_string(),
+        username: username.to_string(),
+        name: Some(display_name(username).to_string()),
+        public_metrics: Some(metrics(followers)),
+        subscription_type: None,
+    }
+}
+
+fn metrics(followers: u64) -> UserMetrics {
+    UserMetrics {
+        followers_count: followers,
+        following_count: followers / 3,
+        tweet_count: followers * 2,
+        listed_count: followers / 50,
+    }
+}
+
+pub fn current_user() -> UserData {
+    UserData {
+        subscription_type: Some("None".to_string()),
+        ..user(USER_ID, USERNAME, 1_337)
+    }
+}
+
+pub fn profile(username: &str) -> UserProfile {
+    let username = username.trim_start_matches('@');
+    UserProfile {
+        id: "2000".to_string(),
+        name: username.to_string(),
+        username: username.to_string(),
+        description: Some("A made-up account for xpost's demo mode".to_string()),
+        public_metrics: Some(metrics(4_200)),
+    }
+}
+
+/// "Publishes" a post straight away and lists it with the account's posts.
+pub fn post(text: String) -> TweetData {
+    let mut state = state();
+    state.next_id += 1;
+    let id = state.next_id.to_string();
+    state.posts.insert(0, tweet(&id, USERNAME, &text, 0, 0));
+    TweetData { id, text }
+}
+
+/// A fresh ID for uploaded media or a sent message.
+pub fn next_id() -> String {
+    let mut state = state();
+    state.next_id += 1;
+    state.next_id.to_string()
+}
+
+pub fn user_tweets(user_id: &str, max_results: u32) -> Vec<Tweet> {
+    let posts = match user_id {
+        USER_ID => state().posts.clone(),
+        _ => timeline(),
+    };
+    posts.into_iter().take(max_results as usize).collect()
+}
+
+pub fn home_timeline(max_results: u32) -> Vec<Tweet> {
+    let mut tweets: Vec<Tweet> = timeline().into_iter().chain(state().posts.clone()).collect();
+    tweets.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    tweets.truncate(max_results as usize);
+    tweets
+}
+
+fn find(tweet_id: &str) -> Option<Tweet> {
+    state().posts.iter().cloned().chain(timeline()).find(|tweet| tweet.id == tweet_id)
+}
+
+pub fn bookmarks(max_results: u32) -> Vec<Tweet> {
+    let ids = state().bookmarks.clone();
+    ids.iter().filter_map(|id| find(id)).take(max_results as usize).collect()
+}
+
+pub fn set_bookmark(tweet_id: &str, bookmarked: bool) {
+    let mut state = state();
+    state.bookmarks.retain(|id| id != tweet_id);
+    if bookmarked {
+        state.bookmarks.insert(0, tweet_id.to_string());
+    }
+}
+
+pub fn delete(tweet_id: &str) {
+    state().posts.retain(|tweet| tweet.id != tweet_id);
+}
+
+pub fn participants() -> Vec<UserData> {
+    vec![user("2101", "terminal_tips", 9_800), user("2102", "ratatui_rs", 14_000)]
+}
+
+/// Canned posts newer than `since_id` containing any of the plain words in `query`; operators
+/// like `-from:` or `lang:` are ignored.
+pub fn search(query: &str, since_id: Option<&str>, max_results: u32) -> Vec<Tweet> {
+    let words: Vec<String> = query
+        .split_whitespace()
+        .filter(|word| !word.starts_with('-') && !word.contains(':') && *word != "OR")
+        .map(|word| word.trim_matches(|c: char| c == '"' || c == '(' || c == ')').to_lowercase())
+        .filter(|word| !word.is_empty())
+        .collect();
+    timeline()
+        .into_iter()
+        .chain(state().posts.clone())
+        .filter(|tweet| since_id.is_none_or(|since_id| (tweet.id.len(), tweet.id.as_str()) > (since_id.len(), since_id)))
+        .filter(|tweet| {
+            let text = tweet.text.to_lowercase();
+            words.iter().any(|word| text.contains(word.as_str()))
+        })
+        .take(max_results as usize)
+        .collect()
+}
+
+pub fn replies(max_results: u32) -> Vec<Tweet> {
+    let mut answer = tweet("1800000000000000203", USERNAME, "it does, put --- between the posts", 0, 4);
+    answer.referenced_tweets.push(ReferencedTweet {
+        kind: "replied_to".to_string(),
+        id: "1800000000000000201".to_string(),
+    });
+    vec![
+        answer,
+        tweet("1800000000000000201", "terminal_tips", "this is great, does it do threads?", 1, 12),
+        tweet("1800000000000000202", "ratatui_rs", "love seeing ratatui in the wild 🦀", 2, 30),
+    ]
+    .into_iter()
+    .take(max_results as usize)
+    .collect()
+}
@@ -0,0 +1,296 @@
+use std::path::Path;
+use std::time::Duration;
+
+use crate::config::{self, Config};
+use crate::twitter::TwitterClient;
+use crate::ui::{MIN_HEIGHT, MIN_WIDTH};
+
+/// Hosts xpost talks to on X: the API and media uploads.
+const X_HOSTS: &[&str] = &["api.twitter.com", "upload.twitter.com"];
+
+/// How long a host gets to accept a connection before it counts as unreachable.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+enum Status {
+    Ok,
+    Warn,
+    Fail,
+    /// Worth knowing, but nothing to fix.
+    Info,
+}
+
+#[derive(Default)]
+struct Report {
+    failures: usize,
+    warnings: usize,
+}
+
+impl Report {
+    fn line(&mut self, status: Status, label: &str, detail: impl std::fmt::Display) {
+        let mark = match status {
+            Status::Ok => "✓",
+            Status::Warn => {
+                self.warnings += 1;
+                "!"
+            }
+            Status::Fail => {
+                self.failures += 1;
+                "✗"
+            }
+            Status::Info => "·",
+        };
+        println!("{} {}: {}", mark, label, detail);
+    }
+}
+
+/// `xpost doctor` prints what works and what doesn't: config file permissions, the credentials
+/// of each account (or just `account`), the network, the clipboard and the terminal. Returns
+/// whether everything needed to post passed; warnings don't count against it.
+pub async fn run(account: Option<&str>) -> bool {
+    let mut report = Report::default();
+
+    // Loading the config would quietly tighten its permissions, so look at them first
+    check_files(&mut report);
+    let config = match Config::load() {
+        Ok(config) => {
+            report.line(Status::Ok, "Config", "parsed");
+            Some(config)
+        }
+        Err(e) => {
+            report.line(Status::Fail, "Config", format!("{:#}", e));
+            None
+        }
+    };
+
+    let x_reachable = check_network(&mut report, config.as_ref()).await;
+    match &config {
+        Some(config) if x_reachable => check_credentials(&mut report, config, account).await,
+        Some(_) => report.line(Status::Info, "Accounts", "not checked, since X can't be reached"),
+        None => {}
+    }
+    check_clipboard(&mut report);
+    check_terminal(&mut report);
+
+    println!();
+    match (report.failures, report.warnings) {
+        (0, 0) => println!("Everything looks good"),
+        (0, warnings) => println!("Ready to post, with {} warning{}", warnings, plural(warnings)),
+        (failures, _) => println!("{} problem{} to fix before posting", failures, plural(failures)),
+    }
+    report.failures == 0
+}
+
+fn plural(count: usize) -> &'static str {
+    if count == 1 { "" } else { "s" }
+}
+
+fn check_files(report: &mut Report) {
+    let config_path = match Config::config_path() {
+        Ok(path) => path,
+        Err(e) => {
+            report.line(Status::Fail, "Config file", format!("{:#}", e));
+            return;
+        }
+    };
+    let credentials_path = Config::credentials_path().unwrap_or_default();
+
+    if !config_path.exists() && !credentials_path.exists() {
+        report.line(Status::Fail, "Config file", format!("not found at {}", config_path.display()));
+        return;
+    }
+    for path in [&config_path, &credentials_path] {
+        if path.exists() {
+            check_permissions(report, path);
+        }
+    }
+    if credentials_path.exists() {
+        report.line(Status::Info, "Credentials", "encrypted; you'll be asked for the passphrase");
+    }
+    if let Ok(dir) = config::config_dir() {
+        report.line(Status::Info, "Data directory", dir.display());
+    }
+}
+
+#[cfg(unix)]
+fn check_permissions(report: &mut Report, path: &Path) {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = match std::fs::metadata(path) {
+        Ok(metadata) => metadata.permissions().mode() & 0o777,
+        Err(e) => {
+            report.line(Status::Fail, "Config file", format!("can't read {}: {}", path.display(), e));
+            return;
+        }
+    };
+    if mode & 0o077 == 0 {
+        report.line(Status::Ok, "Config file", format!("{} ({:o})", path.display(), mode));
+    } else if config::set_private_permissions(path).is_ok() {
+        report.line(
+            Status::Warn,
+            "Config file",
+            format!("{} was readable by other users ({:o}); it's 600 now", path.display(), mode),
+        );
+    } else {
+        report.line(
+            Status::Fail,
+            "Config file",
+            format!("{} is readable by other users ({:o}); run `chmod 600 {}`", path.display(), mode, path.display()),
+        );
+    }
+}
+
+#[cfg(not(unix))]
+fn check_permissions(report: &mut Report, path: &Path) {
+    report.line(Status::Ok, "Config file", path.display());
+}
+
+/// Connects to each host xpost posts to (X and any cross-posting targets) without sending
+/// anything, so a network problem shows up apart from a credentials one. Returns whether the
+/// X API answered.
+async fn check_network(report: &mut Report, config: Option<&Config>) -> bool {
+    let mut hosts: Vec<String> = X_HOSTS.iter().map(|host| host.to_string()).collect();
+    for target in config.map_or(&[][..], |config| &config.targets[..]) {
+        let host = reqwest::Url::parse(&target.url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string));
+        if let Some(host) = host.filter(|host| !hosts.contains(host)) {
+            hosts.push(host);
+        }
+    }
+
+    let mut x_reachable = true;
+    for host in hosts {
+        let connect = tokio::net::TcpStream::connect((host.as_str(), 443));
+        let reachable = match tokio::time::timeout(CONNECT_TIMEOUT, connect).await {
+            Ok(Ok(_)) => {
+                report.line(Status::Ok, "Network", format!("{} reachable", host));
+                true
+            }
+            Ok(Err(e)) => {
+                report.line(Status::Fail, "Network", format!("can't reach {}: {}", host, e));
+                false
+            }
+            Err(_) => {
+                report.line(
+                    Status::Fail,
+                    "Network",
+                    format!("{} didn't answer within {}s", host, CONNECT_TIMEOUT.as_secs()),
+                );
+                false
+            }
+        };
+        if host == X_HOSTS[0] {
+            x_reachable = reachable;
+        }
+    }
+    x_reachable
+}
+
+async fn check_credentials(report: &mut Report, config: &Config, account: Option<&str>) {
+    let accounts = match account {
+        Some(name) => match config.account(Some(name)) {
+            Ok(account) => vec![account],
+            Err(e) => {
+                report.line(Status::Fail, "Account", format!("{:#}", e));
+                return;
+            }
+        },
+        None => config.all_accounts(),
+    };
+
+    for account in accounts {
+        let label = format!("Account {}", account.name);
+        let client = TwitterClient::new(account.twitter.clone());
+        match client.get_current_user().await {
+            Ok(user) => report.line(Status::Ok, &label, format!("signed in as @{}", user.username)),
+            Err(e) => match client.auth_problem() {
+                Some(problem) => report.line(Status::Fail, &label, problem.message()),
+                None => report.line(Status::Fail, &label, format!("couldn't sign in: {:#}", e)),
+            },
+        }
+    }
+}
+
+fn check_clipboard(report: &mut Report) {
+    let wayland = std::env::var_os("WAYLAND_DISPLAY").is_some();
+    let wl_paste = || {
+        std::process::Command::new("wl-paste")
+            .arg("--version")
+            .output()
+            .is_ok_and(|output| output.status.success())
+    };
+
+    match arboard::Clipboard::new() {
+        Ok(_) if wayland && !wl_paste() => report.line(
+            Status::Warn,
+            "Clipboard",
+            "available, but some Wayland compositors need wl-paste for images (install wl-clipboard)",
+        ),
+        Ok(_) => report.line(Status::Ok, "Clipboard", "available"),
+        Err(_) if wayland && wl_paste() => report.line(Status::Ok, "Clipboard", "available through wl-paste"),
+        Err(e) => report.line(
+            Status::Warn,
+            "Clipboard",
+            format!("unavailable ({}); attach images with Ctrl+U instead", e),
+        ),
+    }
+}
+
+fn check_terminal(report: &mut Report) {
+    use crossterm::tty::IsTty;
+
+    if !std::io::stdout().is_tty() {
+        report.line(Status::Info, "Terminal", "output isn't a terminal, so its size and colours weren't checked");
+        return;
+    }
+
+    match crossterm::terminal::size() {
+        Ok((width, height)) if width >= MIN_WIDTH && height >= MIN_HEIGHT => {
+            report.line(Status::Ok, "Terminal size", format!("{}×{}", width, height));
+        }
+        Ok((width, height)) => report.line(
+            Status::Warn,
+            "Terminal size",
+            format!("{}×{}; the TUI needs at least {}×{}", width, height, MIN_WIDTH, MIN_HEIGHT),
+        ),
+        Err(e) => report.line(Status::Warn, "Terminal size", format!("unknown ({})", e)),
+    }
+
+    let colorterm = std::env::var("COLORTERM").unwrap_or_default();
+    if std::env::var_os("NO_COLOR").is_some() {
+        report.line(Status::Info, "Colour", "NO_COLOR is set, so everything is drawn without colour");
+    } else if colorterm == "truecolor" || colorterm == "24bit" {
+        report.line(Status::Ok, "Colour", "truecolor");
+    } else {
+        report.line(
+            Status::Warn,
+            "Colour",
+            "truecolor not advertised (COLORTERM isn't set); image thumbnails and the heatmap may look off",
+        );
+    }
+
+    let protocol = graphics_protocol();
+    report.line(
+        Status::Info,
+        "Graphics protocol",
+        format!("{} (thumbnails are drawn with text blocks, so any terminal shows them)", protocol.unwrap_or("none detected")),
+    );
+}
+
+/// The inline image protocol the terminal says it speaks, going by what it puts in the
+/// environment; terminals that only answer a query (most sixel ones) aren't detected.
+fn graphics_protocol() -> Option<&'static str> {
+    let var = |name: &str| std::env::var(name).unwrap_or_default();
+    let term = var("TERM");
+    let program = var("TERM_PROGRAM");
+
+    if std::env::var_os("KITTY_WINDOW_ID").is_some() || term == "xterm-kitty" || program == "ghostty" {
+        Some("kitty")
+    } else if program == "iTerm.app" || program == "WezTerm" {
+        Some("iTerm2 inline images")
+    } else if term.contains("sixel") || term == "foot" || term.starts_with("mlterm") {
+        Some("sixel")
+    } else {
+        None
+    }
+}
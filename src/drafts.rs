@@ -1,8 +1,191 @@
+use age::secrecy::ExposeSecret;
+use age::x25519;
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use uuid::Uuid;
+
+use crate::config::DraftsConfig;
+use crate::crypto;
+use crate::lock;
+use crate::text;
+
+/// Held while draft files are written or removed, so the daemon and an interactive session
+/// never interleave them.
+pub const DRAFTS_LOCK: &str = "drafts";
+
+/// `[drafts]` from the config, set once at startup.
+static SETTINGS: OnceLock<DraftsConfig> = OnceLock::new();
+
+pub fn init(config: &DraftsConfig) {
+    let _ = SETTINGS.set(config.clone());
+}
+
+/// The key drafts are encrypted to, once `unlock` has run.
+static KEY: OnceLock<x25519::Identity> = OnceLock::new();
+
+/// With `encrypt` on, unlocks the drafts key in `~/.config/xpost/drafts.key.age`, creating it
+/// the first time. The key file is encrypted with the passphrase; each draft with the key.
+pub fn unlock() -> Result<()> {
+    let Some(settings) = SETTINGS.get().filter(|settings| settings.encrypt) else {
+        return Ok(());
+    };
+    let path = crate::config::config_dir()?.join("drafts.key.age");
+
+    let identity = if path.exists() {
+        let passphrase = match &settings.passphrase_command {
+            Some(command) => crypto::passphrase_from_command(command)?,
+            None => crypto::prompt_passphrase("Passphrase for xpost drafts: ")?,
+        };
+        let encrypted = fs::read(&path)
+            .context("Failed to read drafts key")?;
+        let key = crypto::decrypt(&encrypted, &passphrase)
+            .context("Failed to unlock drafts key")?;
+        String::from_utf8_lossy(&key)
+            .trim()
+            .parse::<x25519::Identity>()
+            .map_err(|e| anyhow::anyhow!("Failed to parse drafts key: {}", e))?
+    } else {
+        let passphrase = match &settings.passphrase_command {
+            Some(command) => crypto::passphrase_from_command(command)?,
+            None => {
+                println!("Choose a passphrase for encrypting drafts.");
+                crypto::prompt_new_passphrase()?
+            }
+        };
+        let identity = x25519::Identity::generate();
+        let encrypted = crypto::encrypt(identity.to_string().expose_secret().as_bytes(), &passphrase)?;
+        crate::config::write_atomic(&path, encrypted)
+            .context("Failed to write drafts key")?;
+        #[cfg(unix)]
+        crate::config::set_private_permissions(&path)?;
+        identity
+    };
+
+    let _ = KEY.set(identity);
+    Ok(())
+}
+
+/// Reads a draft file, decrypting it if it was saved encrypted.
+fn read_draft(path: &Path) -> Result<Draft> {
+    let data = fs::read(path)
+        .context("Failed to read draft file")?;
+    let data = if crypto::is_encrypted(&data) {
+        let key = KEY
+            .get()
+            .context("Drafts are encrypted; set `encrypt = true` under [drafts] to unlock them")?;
+        crypto::decrypt_with(&data, key)?
+    } else {
+        data
+    };
+    serde_json::from_slice(&data).context("Failed to parse draft")
+}
+
+/// A file in the drafts directory that couldn't be read as a draft, e.g. cut short by a crash.
+#[derive(Debug, Clone)]
+pub struct DamagedDraft {
+    pub path: PathBuf,
+    pub error: String,
+}
+
+impl DamagedDraft {
+    pub fn file_name(&self) -> String {
+        self.path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default()
+    }
+
+    /// What can be read of the file, decrypted if it was saved encrypted and still decrypts.
+    pub fn raw_text(&self) -> Result<String> {
+        let data = fs::read(&self.path)
+            .context("Failed to read draft file")?;
+        let data = match KEY.get() {
+            Some(key) if crypto::is_encrypted(&data) => crypto::decrypt_with(&data, key)?,
+            _ => data,
+        };
+        Ok(String::from_utf8_lossy(&data).into_owned())
+    }
+}
+
+/// The text of a damaged draft, if any of it survived: its `content` when the JSON still
+/// parses, otherwise as much of the `"content": "…"` string as was written.
+pub fn recover_content(raw: &str) -> Option<String> {
+    if let Ok(value) = serde_json::from_str::<serde_json::Value>(raw) {
+        return value.get("content")?.as_str().map(str::to_string);
+    }
+
+    static CONTENT: OnceLock<Regex> = OnceLock::new();
+    let content = CONTENT.get_or_init(|| Regex::new(r#""content"\s*:\s*"((?:[^"\\]|\\.)*)"#).unwrap());
+    let escaped = content.captures(raw)?.get(1)?.as_str();
+    serde_json::from_str::<String>(&format!("\"{}\"", escaped))
+        .ok()
+        .filter(|text| !text.trim().is_empty())
+}
+
+/// Saves what can be recovered of a damaged draft as a new draft and sets the file aside.
+/// Returns where the file went, or `None` (leaving it where it is) if nothing was recoverable.
+pub fn repair(damaged: &DamagedDraft) -> Result<Option<PathBuf>> {
+    let Some(content) = recover_content(&damaged.raw_text()?) else {
+        return Ok(None);
+    };
+    save_draft(&Draft::new(content))?;
+    set_aside(damaged).map(Some)
+}
+
+/// Moves a damaged draft file into the drafts' `damaged` folder, out of the list but not lost.
+/// Returns where it went.
+pub fn set_aside(damaged: &DamagedDraft) -> Result<PathBuf> {
+    let _lock = lock::lock(DRAFTS_LOCK)?;
+    let dir = drafts_dir()?.join("damaged");
+    if !dir.exists() {
+        fs::create_dir_all(&dir)
+            .context("Failed to create damaged drafts directory")?;
+    }
+
+    let name = damaged.file_name();
+    let mut target = dir.join(&name);
+    if target.exists() {
+        target = dir.join(format!("{}.{}", name, Utc::now().timestamp_millis()));
+    }
+    fs::rename(&damaged.path, &target)
+        .context("Failed to move damaged draft file")?;
+    commit("Set aside damaged draft")?;
+    Ok(target)
+}
+
+fn git_enabled() -> bool {
+    SETTINGS.get().is_some_and(|settings| settings.git)
+}
+
+/// How many earlier versions of a draft are kept.
+pub const MAX_VERSIONS: usize = 10;
+
+/// Wording a draft had before a later save replaced it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DraftVersion {
+    pub content: String,
+    pub saved_at: DateTime<Utc>,
+}
+
+impl DraftVersion {
+    pub fn preview(&self, time_format: Option<&str>) -> String {
+        let preview = text::preview_line(&self.content, 60);
+        let date = text::format_time(self.saved_at, time_format);
+        format!("{} | {}", date, preview)
+    }
+}
+
+/// Where an archived draft was posted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Posted {
+    pub account: String,
+    pub tweet_id: String,
+    pub posted_at: DateTime<Utc>,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Draft {
@@ -10,40 +193,72 @@ pub struct Draft {
     pub content: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Pinned drafts are listed before all others.
+    #[serde(default)]
+    pub pinned: bool,
+    /// Position set by reordering in the browser; drafts never moved have none.
+    #[serde(default)]
+    pub sort_order: Option<u32>,
+    /// Up to `MAX_VERSIONS` earlier versions, oldest first.
+    #[serde(default)]
+    pub versions: Vec<DraftVersion>,
+    /// Labels for finding related drafts, shown as #tags in the browser.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Set once the draft has been posted and moved to the archive.
+    #[serde(default)]
+    pub posted: Option<Posted>,
 }
 
 impl Draft {
     pub fn new(content: String) -> Self {
         let now = Utc::now();
         Self {
-            id: now.timestamp_millis().to_string(),
+            id: Uuid::new_v4().to_string(),
             content,
             created_at: now,
             updated_at: now,
+            pinned: false,
+            sort_order: None,
+            versions: Vec::new(),
+            tags: Vec::new(),
+            posted: None,
         }
     }
 
+    /// Replaces the content, keeping what it replaces as a version.
     pub fn update_content(&mut self, content: String) {
-        self.content = content;
+        if content != self.content {
+            let previous = std::mem::replace(&mut self.content, content);
+            self.versions.push(DraftVersion { content: previous, saved_at: self.updated_at });
+            let excess = self.versions.len().saturating_sub(MAX_VERSIONS);
+            self.versions.drain(..excess);
+        }
         self.updated_at = Utc::now();
     }
 
-    pub fn preview(&self) -> String {
-        let first_line = self.content.lines().next().unwrap_or("");
-        let preview = if first_line.len() > 60 {
-            format!("{}...", &first_line[..60])
-        } else {
-            first_line.to_string()
-        };
-        
-        let date = self.updated_at.format("%Y-%m-%d %H:%M").to_string();
-        format!("{} | {}", date, preview)
+    pub fn preview(&self, time_format: Option<&str>) -> String {
+        let tags: String = self.tags.iter().map(|tag| format!("#{} ", tag)).collect();
+        let preview = format!("{}{}", tags, text::preview_line(&self.content, 60));
+        if let Some(posted) = &self.posted {
+            let date = text::format_time(posted.posted_at, time_format);
+            return format!("✓ {} | {} | {}", date, posted.account, preview);
+        }
+        let date = text::format_time(self.updated_at, time_format);
+        let pin = if self.pinned { "📌 " } else { "" };
+        format!("{}{} | {}", pin, date, preview)
     }
 }
 
-fn drafts_dir() -> Result<PathBuf> {
+pub fn drafts_dir() -> Result<PathBuf> {
     let home = std::env::var("HOME").context("HOME environment variable not set")?;
-    let config_dir = Path::new(&home).join(".config").join("xpost").join("drafts");
+    let config_dir = match SETTINGS.get().and_then(|settings| settings.dir.as_deref()) {
+        Some(dir) => match dir.strip_prefix("~/") {
+            Some(rest) => Path::new(&home).join(rest),
+            None => PathBuf::from(dir),
+        },
+        None => crate::config::config_dir()?.join("drafts"),
+    };
     
     if !config_dir.exists() {
         fs::create_dir_all(&config_dir)
@@ -53,54 +268,192 @@ fn drafts_dir() -> Result<PathBuf> {
     Ok(config_dir)
 }
 
+/// Posted drafts, kept apart so the browser only lists unpublished work.
+fn archive_dir() -> Result<PathBuf> {
+    let dir = drafts_dir()?.join("archive");
+
+    if !dir.exists() {
+        fs::create_dir_all(&dir)
+            .context("Failed to create drafts archive directory")?;
+    }
+
+    Ok(dir)
+}
+
 pub fn save_draft(draft: &Draft) -> Result<()> {
-    let dir = drafts_dir()?;
+    let _lock = lock::lock(DRAFTS_LOCK)?;
+    write_draft(draft)
+}
+
+/// Writes the draft's file. Call with the drafts lock held.
+fn write_draft(draft: &Draft) -> Result<()> {
+    let dir = if draft.posted.is_some() { archive_dir()? } else { drafts_dir()? };
     let file_path = dir.join(format!("{}.json", draft.id));
     
     let json = serde_json::to_string_pretty(draft)
         .context("Failed to serialize draft")?;
+    let data = match KEY.get() {
+        Some(key) => crypto::encrypt_to(json.as_bytes(), key)?,
+        None => json.into_bytes(),
+    };
     
-    fs::write(&file_path, json)
+    crate::config::write_atomic(&file_path, data)
         .context("Failed to write draft file")?;
     
     Ok(())
 }
 
-pub fn load_drafts() -> Result<Vec<Draft>> {
-    let dir = drafts_dir()?;
-    
-    if !dir.exists() {
-        return Ok(Vec::new());
-    }
-    
+/// The drafts, in browser order, and the files that couldn't be read as one.
+pub fn load_drafts() -> Result<(Vec<Draft>, Vec<DamagedDraft>)> {
+    let (mut drafts, damaged) = read_drafts(&drafts_dir()?)?;
+    sort_drafts(&mut drafts);
+    Ok((drafts, damaged))
+}
+
+/// Posted drafts, most recently posted first, and the files that couldn't be read as one.
+pub fn load_archive() -> Result<(Vec<Draft>, Vec<DamagedDraft>)> {
+    let (mut drafts, damaged) = read_drafts(&archive_dir()?)?;
+    drafts.sort_by_key(|draft| Reverse(draft.posted.as_ref().map(|posted| posted.posted_at)));
+    Ok((drafts, damaged))
+}
+
+/// Records where the draft went and moves it to the archive, with `content` as what was posted.
+pub fn archive_draft(draft_id: &str, content: String, account: &str, tweet_id: &str) -> Result<()> {
+    let _lock = lock::lock(DRAFTS_LOCK)?;
+    let path = drafts_dir()?.join(format!("{}.json", draft_id));
+    let mut draft = read_draft(&path)?;
+    draft.update_content(content);
+    draft.posted = Some(Posted {
+        account: account.to_string(),
+        tweet_id: tweet_id.to_string(),
+        posted_at: Utc::now(),
+    });
+
+    write_draft(&draft)?;
+    fs::remove_file(&path)
+        .context("Failed to remove archived draft file")?;
+    commit("Archive posted draft")
+}
+
+fn read_drafts(dir: &Path) -> Result<(Vec<Draft>, Vec<DamagedDraft>)> {
     let mut drafts = Vec::new();
+    let mut damaged = Vec::new();
     
     for entry in fs::read_dir(dir).context("Failed to read drafts directory")? {
         let entry = entry?;
         let path = entry.path();
         
         if path.extension().and_then(|s| s.to_str()) == Some("json") {
-            if let Ok(content) = fs::read_to_string(&path) {
-                if let Ok(draft) = serde_json::from_str::<Draft>(&content) {
+            match read_draft(&path) {
+                Ok(draft) => {
+                    // Drafts saved before encryption was turned on get encrypted now
+                    if KEY.get().is_some() && fs::read(&path).is_ok_and(|data| !crypto::is_encrypted(&data)) {
+                        let _ = save_draft(&draft);
+                    }
                     drafts.push(draft);
                 }
+                Err(e) => damaged.push(DamagedDraft { path, error: format!("{:#}", e) }),
             }
         }
     }
+    damaged.sort_by(|a, b| a.path.cmp(&b.path));
     
-    // Sort by updated_at descending (most recent first)
-    drafts.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
-    
-    Ok(drafts)
+    Ok((drafts, damaged))
 }
 
-pub fn delete_draft(draft_id: &str) -> Result<()> {
+/// Whether the draft's file was saved since `draft` was loaded, e.g. from another machine
+/// sharing the drafts directory.
+pub fn changed_elsewhere(draft: &Draft) -> bool {
+    let Ok(path) = drafts_dir().map(|dir| dir.join(format!("{}.json", draft.id))) else {
+        return false;
+    };
+    read_draft(&path).is_ok_and(|on_disk| on_disk.updated_at > draft.updated_at)
+}
+
+/// Brings the drafts directory up to date before it's listed: pulls from the git remote when
+/// `git` is on, and turns copies a sync client made of conflicting edits into drafts of their
+/// own. Returns how many such copies there were.
+pub fn sync() -> Result<usize> {
     let dir = drafts_dir()?;
-    let file_path = dir.join(format!("{}.json", draft_id));
-    
-    if file_path.exists() {
-        fs::remove_file(&file_path)
-            .context("Failed to delete draft file")?;
+    if git_enabled() {
+        crate::sync::pull(&dir)?;
+    }
+
+    let _lock = lock::lock(DRAFTS_LOCK)?;
+    let copies = crate::sync::conflicted_copies(&dir)?;
+    for name in &copies {
+        let path = dir.join(name);
+        let Ok(mut draft) = read_draft(&path) else {
+            continue;
+        };
+        draft.id = Uuid::new_v4().to_string();
+        write_draft(&draft)?;
+        fs::remove_file(&path)
+            .context("Failed to remove conflicting draft file")?;
+    }
+    if !copies.is_empty() {
+        commit("Keep conflicting drafts")?;
+    }
+
+    Ok(copies.len())
+}
+
+/// Commits the drafts directory when `git` is on.
+pub fn commit(message: &str) -> Result<()> {
+    if !git_enabled() {
+        return Ok(());
+    }
+    crate::sync::commit(&drafts_dir()?, message)
+}
+
+/// Gives drafts saved with the old millisecond-timestamp IDs a UUID, renaming their files,
+/// and returns the old ID → new ID of each so references can follow.
+pub fn migrate_ids() -> Result<HashMap<String, String>> {
+    let dir = drafts_dir()?;
+    let _lock = lock::lock(DRAFTS_LOCK)?;
+    let mut renamed = HashMap::new();
+
+    for entry in fs::read_dir(&dir).context("Failed to read drafts directory")? {
+        let path = entry?.path();
+        if path.extension().and_then(|s| s.to_str()) != Some("json") {
+            continue;
+        }
+        let Ok(mut draft) = read_draft(&path) else {
+            continue;
+        };
+        if Uuid::parse_str(&draft.id).is_ok() {
+            continue;
+        }
+
+        let old_id = std::mem::replace(&mut draft.id, Uuid::new_v4().to_string());
+        // Write the new file before removing the old one so a failure can't lose the draft
+        write_draft(&draft)?;
+        fs::remove_file(&path)
+            .context("Failed to remove migrated draft file")?;
+        renamed.insert(old_id, draft.id);
+    }
+    if !renamed.is_empty() {
+        commit("Give drafts UUIDs")?;
+    }
+
+    Ok(renamed)
+}
+
+/// Browser order: pinned drafts first, then within each group drafts that were never moved
+/// (most recently updated first) followed by the reordered ones in their set order.
+pub fn sort_drafts(drafts: &mut [Draft]) {
+    drafts.sort_by_key(|draft| (!draft.pinned, draft.sort_order, Reverse(draft.updated_at)));
+}
+
+pub fn delete_draft(draft_id: &str) -> Result<()> {
+    let _lock = lock::lock(DRAFTS_LOCK)?;
+    for dir in [drafts_dir()?, archive_dir()?] {
+        let file_path = dir.join(format!("{}.json", draft_id));
+        
+        if file_path.exists() {
+            fs::remove_file(&file_path)
+                .context("Failed to delete draft file")?;
+        }
     }
     
     Ok(())
@@ -0,0 +1,150 @@
+use anyhow::{Context, Result};
+use chrono::Utc;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::history::{self, PostRecord};
+use crate::text;
+
+/// Syndication formats `xpost export-feed` can write.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FeedFormat {
+    Atom,
+    Rss,
+    Json,
+}
+
+impl FeedFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            FeedFormat::Atom | FeedFormat::Rss => "xml",
+            FeedFormat::Json => "json",
+        }
+    }
+}
+
+fn post_url(record: &PostRecord) -> String {
+    format!("https://x.com/user/status/{}", record.id)
+}
+
+fn feed_title(account: Option<&str>) -> String {
+    match account {
+        Some(account) => format!("{} on X", account),
+        None => "Posts on X".to_string(),
+    }
+}
+
+/// Posts still up, newest first, optionally only those from `account`.
+fn feed_posts(account: Option<&str>) -> Result<Vec<PostRecord>> {
+    Ok(history::load_history()?
+        .into_iter()
+        .rev()
+        .filter(|record| record.deleted_at.is_none())
+        .filter(|record| account.is_none_or(|account| record.account == account))
+        .collect())
+}
+
+/// Writes the post history as a feed, to `path` or `feed.xml` / `feed.json` in the exports
+/// directory. The default name stays the same between runs so a website can keep pulling it.
+pub fn export(path: Option<&Path>, format: FeedFormat, account: Option<&str>) -> Result<PathBuf> {
+    let posts = feed_posts(account)?;
+    let title = feed_title(account);
+    let feed = match format {
+        FeedFormat::Atom => to_atom(&posts, &title, account.unwrap_or("all")),
+        FeedFormat::Rss => to_rss(&posts, &title),
+        FeedFormat::Json => to_json_feed(&posts, &title)?,
+    };
+
+    let path = match path {
+        Some(path) => path.to_path_buf(),
+        None => {
+            let dir = crate::config::config_dir()?.join("exports");
+            if !dir.exists() {
+                fs::create_dir_all(&dir)
+                    .context("Failed to create exports directory")?;
+            }
+            dir.join(format!("feed.{}", format.extension()))
+        }
+    };
+    fs::write(&path, feed)
+        .context("Failed to write feed")?;
+
+    Ok(path)
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn to_atom(posts: &[PostRecord], title: &str, id: &str) -> String {
+    let updated = posts.first().map_or_else(Utc::now, |record| record.posted_at);
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    xml.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+    xml.push_str(&format!("  <title>{}</title>\n", escape_xml(title)));
+    xml.push_str("  <link href=\"https://x.com\"/>\n");
+    xml.push_str(&format!("  <id>urn:xpost:{}</id>\n", escape_xml(&id.replace(' ', "-"))));
+    xml.push_str(&format!("  <updated>{}</updated>\n", updated.to_rfc3339()));
+    for record in posts {
+        let url = escape_xml(&post_url(record));
+        xml.push_str("  <entry>\n");
+        xml.push_str(&format!("    <title>{}</title>\n", escape_xml(&text::preview_line(&record.text, 80))));
+        xml.push_str(&format!("    <link href=\"{}\"/>\n", url));
+        xml.push_str(&format!("    <id>{}</id>\n", url));
+        xml.push_str(&format!("    <updated>{}</updated>\n", record.posted_at.to_rfc3339()));
+        xml.push_str(&format!("    <author><name>{}</name></author>\n", escape_xml(&record.account)));
+        xml.push_str(&format!("    <content type=\"text\">{}</content>\n", escape_xml(&record.text)));
+        xml.push_str("  </entry>\n");
+    }
+    xml.push_str("</feed>\n");
+    xml
+}
+
+fn to_rss(posts: &[PostRecord], title: &str) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    xml.push_str("<rss version=\"2.0\">\n  <channel>\n");
+    xml.push_str(&format!("    <title>{}</title>\n", escape_xml(title)));
+    xml.push_str("    <link>https://x.com</link>\n");
+    xml.push_str(&format!("    <description>{}</description>\n", escape_xml(title)));
+    if let Some(latest) = posts.first() {
+        xml.push_str(&format!("    <lastBuildDate>{}</lastBuildDate>\n", latest.posted_at.to_rfc2822()));
+    }
+    for record in posts {
+        let url = escape_xml(&post_url(record));
+        xml.push_str("    <item>\n");
+        xml.push_str(&format!("      <title>{}</title>\n", escape_xml(&text::preview_line(&record.text, 80))));
+        xml.push_str(&format!("      <link>{}</link>\n", url));
+        xml.push_str(&format!("      <guid isPermaLink=\"true\">{}</guid>\n", url));
+        xml.push_str(&format!("      <pubDate>{}</pubDate>\n", record.posted_at.to_rfc2822()));
+        xml.push_str(&format!("      <description>{}</description>\n", escape_xml(&record.text)));
+        xml.push_str("    </item>\n");
+    }
+    xml.push_str("  </channel>\n</rss>\n");
+    xml
+}
+
+fn to_json_feed(posts: &[PostRecord], title: &str) -> Result<String> {
+    let items: Vec<serde_json::Value> = posts
+        .iter()
+        .map(|record| {
+            serde_json::json!({
+                "id": record.id,
+                "url": post_url(record),
+                "title": text::preview_line(&record.text, 80),
+                "content_text": record.text,
+                "date_published": record.posted_at.to_rfc3339(),
+                "authors": [{ "name": record.account }],
+            })
+        })
+        .collect();
+    let feed = serde_json::json!({
+        "version": "https://jsonfeed.org/version/1.1",
+        "title": title,
+        "home_page_url": "https://x.com",
+        "items": items,
+    });
+    serde_json::to_string_pretty(&feed).context("Failed to serialize feed")
+}
@@ -0,0 +1,61 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::twitter::UserMetrics;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FollowerSnapshot {
+    pub taken_at: DateTime<Utc>,
+    pub followers: u64,
+    pub following: u64,
+}
+
+fn followers_path(username: &str) -> Result<PathBuf> {
+    let dir = crate::config::config_dir()?.join("followers");
+
+    if !dir.exists() {
+        fs::create_dir_all(&dir)
+            .context("Failed to create followers directory")?;
+    }
+
+    Ok(dir.join(format!("{}.json", username)))
+}
+
+pub fn load_snapshots(username: &str) -> Result<Vec<FollowerSnapshot>> {
+    let path = followers_path(username)?;
+
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(&path)
+        .context("Failed to read follower history")?;
+    serde_json::from_str(&content).context("Failed to parse follower history")
+}
+
+/// Appends the current counts and returns the full history, oldest first.
+/// Runs within the same clock hour replace the previous snapshot instead of piling up.
+pub fn record_snapshot(username: &str, metrics: &UserMetrics) -> Result<Vec<FollowerSnapshot>> {
+    let mut snapshots = load_snapshots(username)?;
+    let now = Utc::now();
+
+    if snapshots.last().is_some_and(|last| crate::metrics::same_hour(last.taken_at, now)) {
+        snapshots.pop();
+    }
+
+    snapshots.push(FollowerSnapshot {
+        taken_at: now,
+        followers: metrics.followers_count,
+        following: metrics.following_count,
+    });
+
+    let json = serde_json::to_string_pretty(&snapshots)
+        .context("Failed to serialize follower history")?;
+    fs::write(followers_path(username)?, json)
+        .context("Failed to write follower history")?;
+
+    Ok(snapshots)
+}
@@ -0,0 +1,110 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::config::GrammarConfig;
+
+/// A problem LanguageTool found, with `offset` and `length` counted in characters.
+#[derive(Debug, Clone)]
+pub struct GrammarIssue {
+    pub message: String,
+    pub offset: usize,
+    pub length: usize,
+    pub replacements: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CheckResponse {
+    matches: Vec<CheckMatch>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CheckMatch {
+    message: String,
+    offset: usize,
+    length: usize,
+    replacements: Vec<Replacement>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Replacement {
+    value: String,
+}
+
+// Only the first few replacements are worth showing in the popup
+const MAX_REPLACEMENTS: usize = 3;
+
+/// Runs `text` through the configured LanguageTool server's `/v2/check`.
+pub async fn check(config: &GrammarConfig, text: &str) -> Result<Vec<GrammarIssue>> {
+    let url = format!("{}/v2/check", config.server.trim_end_matches('/'));
+    let response = reqwest::Client::new()
+        .post(&url)
+        .form(&[("text", text), ("language", config.language.as_str())])
+        .send()
+        .await
+        .context("Failed to reach the LanguageTool server")?;
+
+    if !response.status().is_success() {
+        let error_text = response.text().await?;
+        anyhow::bail!("Grammar check failed: {}", error_text);
+    }
+
+    let check: CheckResponse = response.json().await?;
+    let issues = check
+        .matches
+        .into_iter()
+        .map(|m| {
+            // LanguageTool counts UTF-16 code units; the editor works in characters
+            let offset = utf16_to_char_offset(text, m.offset);
+            let end = utf16_to_char_offset(text, m.offset + m.length);
+            GrammarIssue {
+                message: m.message,
+                offset,
+                length: end - offset,
+                replacements: m
+                    .replacements
+                    .into_iter()
+                    .take(MAX_REPLACEMENTS)
+                    .map(|r| r.value)
+                    .collect(),
+            }
+        })
+        .collect();
+
+    Ok(issues)
+}
+
+fn utf16_to_char_offset(text: &str, utf16_offset: usize) -> usize {
+    let mut units = 0;
+    for (i, c) in text.chars().enumerate() {
+        if units >= utf16_offset {
+            return i;
+        }
+        units += c.len_utf16();
+    }
+    text.chars().count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ascii_offsets_are_unchanged() {
+        assert_eq!(utf16_to_char_offset("their is", 0), 0);
+        assert_eq!(utf16_to_char_offset("their is", 6), 6);
+    }
+
+    #[test]
+    fn emoji_outside_the_bmp_take_two_units() {
+        // "😀 teh" in UTF-16: 😀 is two units, so "teh" starts at unit 3 and character 2
+        assert_eq!(utf16_to_char_offset("😀 teh", 3), 2);
+        assert_eq!(utf16_to_char_offset("😀 teh", 6), 5);
+        assert_eq!(utf16_to_char_offset("naïve 日本 teh", 9), 9);
+    }
+
+    #[test]
+    fn offsets_past_the_end_stop_at_the_end() {
+        assert_eq!(utf16_to_char_offset("😀", 10), 1);
+        assert_eq!(utf16_to_char_offset("", 3), 0);
+    }
+}
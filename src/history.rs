@@ -0,0 +1,325 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::lock;
+
+/// Held from reading the history to writing it back, so an update made by the daemon and one
+/// made by an interactive session can't overwrite each other.
+const HISTORY_LOCK: &str = "history";
+
+/// Failed attempts at deleting an ephemeral post before the daemon gives up on it.
+pub const MAX_DELETE_ATTEMPTS: u32 = 8;
+/// The wait after the first failed deletion, doubled after each one after that.
+const DELETE_BACKOFF_MINUTES: i64 = 5;
+
+/// A post published from xpost, one per post ID (each part of a thread gets its own).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostRecord {
+    pub id: String,
+    pub account: String,
+    pub text: String,
+    pub posted_at: DateTime<Utc>,
+    /// When the daemon should delete this post, for ephemeral posts; pushed back after a
+    /// failed attempt.
+    #[serde(default)]
+    pub delete_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub deleted_at: Option<DateTime<Utc>>,
+    /// Why the last attempt to delete the post failed.
+    #[serde(default)]
+    pub delete_error: Option<String>,
+    #[serde(default)]
+    pub delete_attempts: u32,
+}
+
+impl PostRecord {
+    pub fn new(id: String, account: &str, text: String, delete_after: Option<Duration>) -> Self {
+        let now = Utc::now();
+        Self {
+            id,
+            account: account.to_string(),
+            text,
+            posted_at: now,
+            delete_at: delete_after.map(|after| now + after),
+            deleted_at: None,
+            delete_error: None,
+            delete_attempts: 0,
+        }
+    }
+
+    pub fn is_due_for_deletion(&self, now: DateTime<Utc>) -> bool {
+        self.deleted_at.is_none()
+            && self.delete_attempts < MAX_DELETE_ATTEMPTS
+            && self.delete_at.is_some_and(|at| at <= now)
+    }
+
+    /// Notes a failed attempt at deleting the post and puts the next one off, for longer
+    /// each time. When X `refused`, there's no next attempt.
+    pub fn delete_failed(&mut self, error: String, refused: bool, now: DateTime<Utc>) {
+        self.delete_error = Some(error);
+        if refused {
+            self.delete_attempts = MAX_DELETE_ATTEMPTS;
+            return;
+        }
+        let backoff = DELETE_BACKOFF_MINUTES << self.delete_attempts.min(MAX_DELETE_ATTEMPTS);
+        self.delete_attempts += 1;
+        self.delete_at = Some(now + Duration::minutes(backoff));
+    }
+}
+
+fn history_path() -> Result<PathBuf> {
+    Ok(crate::config::config_dir()?.join("history.json"))
+}
+
+/// All recorded posts, oldest first.
+pub fn load_history() -> Result<Vec<PostRecord>> {
+    let path = history_path()?;
+
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(&path)
+        .context("Failed to read post history")?;
+    serde_json::from_str(&content).context("Failed to parse post history")
+}
+
+fn save_history(records: &[PostRecord]) -> Result<()> {
+    let json = serde_json::to_string_pretty(records)
+        .context("Failed to serialize post history")?;
+    crate::config::write_atomic(&history_path()?, json)
+        .context("Failed to write post history")
+}
+
+pub fn record_posts(records: Vec<PostRecord>) -> Result<()> {
+    let _lock = lock::lock(HISTORY_LOCK)?;
+    let mut history = load_history()?;
+    history.extend(records);
+    save_history(&history)
+}
+
+/// Adds the records for posts the history doesn't have yet, keeping it oldest first. Returns
+/// how many were added.
+pub fn merge(records: Vec<PostRecord>) -> Result<usize> {
+    let _lock = lock::lock(HISTORY_LOCK)?;
+    let mut history = load_history()?;
+    let known: HashSet<String> = history.iter().map(|record| record.id.clone()).collect();
+    let before = history.len();
+    history.extend(records.into_iter().filter(|record| !known.contains(&record.id)));
+    let added = history.len() - before;
+    if added > 0 {
+        history.sort_by_key(|record| record.posted_at);
+        save_history(&history)?;
+    }
+    Ok(added)
+}
+
+/// Posts `account` has published from xpost since the start of this month.
+pub fn posts_this_month(account: &str) -> usize {
+    let month_start = Local::now()
+        .date_naive()
+        .with_day(1)
+        .and_then(|day| day.and_hms_opt(0, 0, 0))
+        .and_then(|midnight| Local.from_local_datetime(&midnight).earliest());
+    let Some(month_start) = month_start else {
+        return 0;
+    };
+
+    load_history()
+        .unwrap_or_default()
+        .iter()
+        .filter(|record| record.account == account && record.posted_at >= month_start)
+        .count()
+}
+
+/// How many posts `account` published from xpost on each local day.
+pub fn posts_per_day(account: &str) -> BTreeMap<NaiveDate, usize> {
+    let mut days = BTreeMap::new();
+    for record in load_history().unwrap_or_default() {
+        if record.account == account {
+            *days.entry(record.posted_at.with_timezone(&Local).date_naive()).or_insert(0) += 1;
+        }
+    }
+    days
+}
+
+/// Posting streaks and pace worked out from posts per day.
+#[derive(Debug, Clone, Copy)]
+pub struct Cadence {
+    /// Consecutive days with a post, up to today; a streak isn't broken until today ends.
+    pub streak: usize,
+    pub longest_streak: usize,
+    pub posted_today: bool,
+    /// Average posts per week since the first recorded post.
+    pub per_week: f64,
+}
+
+pub fn cadence(days: &BTreeMap<NaiveDate, usize>, today: NaiveDate) -> Cadence {
+    let mut longest_streak = 0;
+    let mut run = 0;
+    let mut previous: Option<NaiveDate> = None;
+    for day in days.keys() {
+        run = match previous {
+            Some(previous) if *day - previous == Duration::days(1) => run + 1,
+            _ => 1,
+        };
+        longest_streak = longest_streak.max(run);
+        previous = Some(*day);
+    }
+
+    let posted_today = days.contains_key(&today);
+    let mut day = if posted_today { today } else { today - Duration::days(1) };
+    let mut streak = 0;
+    while days.contains_key(&day) {
+        streak += 1;
+        day -= Duration::days(1);
+    }
+
+    let per_week = match days.keys().next() {
+        Some(first) => {
+            let weeks = ((today - *first).num_days() + 1).max(7) as f64 / 7.0;
+            days.values().sum::<usize>() as f64 / weeks
+        }
+        None => 0.0,
+    };
+
+    Cadence {
+        streak,
+        longest_streak,
+        posted_today,
+        per_week,
+    }
+}
+
+/// Applies `update` to the record with `id`, if there is one.
+pub fn update_record<F>(id: &str, update: F) -> Result<()>
+where
+    F: FnOnce(&mut PostRecord),
+{
+    let _lock = lock::lock(HISTORY_LOCK)?;
+    let mut history = load_history()?;
+    if let Some(record) = history.iter_mut().find(|record| record.id == id) {
+        update(record);
+        save_history(&history)?;
+    }
+    Ok(())
+}
+
+/// Parses a lifetime like "30m", "24h" or "7d".
+pub fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let unit = value.chars().last()?;
+    let amount: u32 = value[..value.len() - unit.len_utf8()].parse().ok()?;
+    let amount = amount as i64;
+    match unit {
+        'm' => Some(Duration::minutes(amount)),
+        'h' => Some(Duration::hours(amount)),
+        'd' => Some(Duration::days(amount)),
+        _ => None,
+    }
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    if duration.num_days() > 0 && duration.num_hours() % 24 == 0 {
+        format!("{}d", duration.num_days())
+    } else if duration.num_hours() > 0 && duration.num_minutes() % 60 == 0 {
+        format!("{}h", duration.num_hours())
+    } else {
+        format!("{}m", duration.num_minutes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("30m"), Some(Duration::minutes(30)));
+        assert_eq!(parse_duration(" 24h "), Some(Duration::hours(24)));
+        assert_eq!(parse_duration("7d"), Some(Duration::days(7)));
+        assert_eq!(parse_duration("7"), None);
+        assert_eq!(parse_duration("d"), None);
+        assert_eq!(parse_duration("-5m"), None);
+        assert_eq!(parse_duration("2w"), None);
+        assert_eq!(parse_duration("5é"), None);
+        assert_eq!(parse_duration(""), None);
+    }
+
+    #[test]
+    fn formats_durations_in_the_largest_whole_unit() {
+        assert_eq!(format_duration(Duration::days(2)), "2d");
+        assert_eq!(format_duration(Duration::hours(36)), "36h");
+        assert_eq!(format_duration(Duration::minutes(90)), "90m");
+    }
+
+    #[test]
+    fn backs_off_after_failed_deletions_until_giving_up() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 5, 12, 0, 0).unwrap();
+        let mut record = PostRecord::new("1".to_string(), "main", "gone soon".to_string(), Some(Duration::hours(1)));
+        record.delete_at = Some(now);
+        assert!(record.is_due_for_deletion(now));
+
+        record.delete_failed("timed out".to_string(), false, now);
+        assert_eq!(record.delete_at, Some(now + Duration::minutes(5)));
+        record.delete_failed("timed out".to_string(), false, now);
+        assert_eq!(record.delete_at, Some(now + Duration::minutes(10)));
+        assert!(!record.is_due_for_deletion(now));
+        assert!(record.is_due_for_deletion(now + Duration::minutes(10)));
+
+        for _ in 2..MAX_DELETE_ATTEMPTS {
+            record.delete_failed("timed out".to_string(), false, now);
+        }
+        assert_eq!(record.delete_error.as_deref(), Some("timed out"));
+        assert!(!record.is_due_for_deletion(now + Duration::days(30)));
+    }
+
+    #[test]
+    fn gives_up_at_once_when_x_refuses() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 5, 12, 0, 0).unwrap();
+        let mut record = PostRecord::new("1".to_string(), "main", "gone soon".to_string(), Some(Duration::hours(1)));
+        record.delete_failed("not found".to_string(), true, now);
+        assert_eq!(record.delete_attempts, MAX_DELETE_ATTEMPTS);
+        assert!(!record.is_due_for_deletion(now + Duration::days(30)));
+    }
+
+    fn posting_days(days: &[(u32, usize)]) -> BTreeMap<NaiveDate, usize> {
+        days.iter()
+            .map(|(day, posts)| (NaiveDate::from_ymd_opt(2025, 3, *day).unwrap(), *posts))
+            .collect()
+    }
+
+    #[test]
+    fn counts_the_streak_up_to_today() {
+        let today = NaiveDate::from_ymd_opt(2025, 3, 10).unwrap();
+        let pace = cadence(&posting_days(&[(1, 1), (2, 2), (3, 1), (4, 1), (8, 1), (9, 1), (10, 3)]), today);
+        assert_eq!(pace.streak, 3);
+        assert_eq!(pace.longest_streak, 4);
+        assert!(pace.posted_today);
+        // 10 posts over 10 days
+        assert!((pace.per_week - 7.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn keeps_yesterdays_streak_until_today_ends() {
+        let today = NaiveDate::from_ymd_opt(2025, 3, 10).unwrap();
+        let pace = cadence(&posting_days(&[(8, 1), (9, 1)]), today);
+        assert_eq!(pace.streak, 2);
+        assert!(!pace.posted_today);
+
+        let pace = cadence(&posting_days(&[(7, 1), (8, 1)]), today);
+        assert_eq!(pace.streak, 0);
+        assert_eq!(pace.longest_streak, 2);
+    }
+
+    #[test]
+    fn averages_at_least_over_a_week() {
+        let today = NaiveDate::from_ymd_opt(2025, 3, 10).unwrap();
+        assert_eq!(cadence(&posting_days(&[(9, 2), (10, 1)]), today).per_week, 3.0);
+        assert_eq!(cadence(&BTreeMap::new(), today).per_week, 0.0);
+    }
+}
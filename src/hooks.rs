@@ -0,0 +1,60 @@
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use crate::config::HooksConfig;
+
+/// How long a hook may run before it's stopped. A `pre_post` hook that runs out of time
+/// cancels the post.
+const HOOK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Runs `pre_post`; a non-zero exit cancels the post, with the hook's stderr as the reason.
+pub async fn pre_post(hooks: &HooksConfig, account: &str, text: &str) -> Result<(), String> {
+    let Some(command) = &hooks.pre_post else {
+        return Ok(());
+    };
+
+    let output = run(command, text, &[("XPOST_ACCOUNT", account)]).await?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(format!("pre_post hook rejected the post: {}", stderr.trim()))
+    }
+}
+
+pub async fn post_success(hooks: &HooksConfig, account: &str, text: &str, url: &str) {
+    if let Some(command) = &hooks.post_success {
+        let _ = run(command, text, &[("XPOST_ACCOUNT", account), ("XPOST_URL", url)]).await;
+    }
+}
+
+pub async fn post_failure(hooks: &HooksConfig, account: &str, text: &str, error: &str) {
+    if let Some(command) = &hooks.post_failure {
+        let _ = run(command, text, &[("XPOST_ACCOUNT", account), ("XPOST_ERROR", error)]).await;
+    }
+}
+
+/// Runs `command` through the shell with the post text on stdin and in `XPOST_TEXT`.
+async fn run(command: &str, text: &str, env: &[(&str, &str)]) -> Result<std::process::Output, String> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .env("XPOST_TEXT", text)
+        .envs(env.iter().copied())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("Failed to run hook '{}': {}", command, e))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(text.as_bytes()).await;
+    }
+
+    tokio::time::timeout(HOOK_TIMEOUT, child.wait_with_output())
+        .await
+        .map_err(|_| format!("Hook '{}' didn't finish within {} seconds", command, HOOK_TIMEOUT.as_secs()))?
+        .map_err(|e| format!("Hook '{}' failed: {}", command, e))
+}
@@ -0,0 +1,122 @@
+use chrono::Utc;
+use reqwest::header::HeaderMap;
+use reqwest::{Request, Response};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use crate::config;
+
+/// Set by `xpost --debug-http`: every API call is written to the log file.
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Appended to in the data directory, since the TUI has the terminal.
+const LOG_FILE: &str = "xpost.log";
+
+/// OAuth parameters that are secret or could be replayed. Keys and tokens keep their last
+/// characters so it's clear which ones were sent; the nonce and timestamp stay as they are,
+/// since a signature mismatch is usually down to one of them.
+const OAUTH_SECRETS: &[&str] = &["oauth_consumer_key", "oauth_token", "oauth_signature"];
+
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Logs the method, URL and headers of a request about to be sent. Bodies are left out: they
+/// hold the posts themselves and uploaded media.
+pub fn request(request: &Request) {
+    if !enabled() {
+        return;
+    }
+    let mut entry = format!("→ {} {}\n", request.method(), request.url());
+    entry.push_str(&headers(request.headers()));
+    write(entry);
+}
+
+/// Logs how the request for `method` `url` went, `elapsed` after it was sent.
+pub fn response(method: &str, url: &str, response: &anyhow::Result<Response>, elapsed: Duration) {
+    if !enabled() {
+        return;
+    }
+    let entry = match response {
+        Ok(response) => format!(
+            "← {} {} {} ({} ms)\n{}",
+            response.status(),
+            method,
+            url,
+            elapsed.as_millis(),
+            headers(response.headers())
+        ),
+        Err(e) => format!("← {} {} failed after {} ms: {:#}\n", method, url, elapsed.as_millis(), e),
+    };
+    write(entry);
+}
+
+fn headers(headers: &HeaderMap) -> String {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value = String::from_utf8_lossy(value.as_bytes());
+            format!("    {}: {}\n", name, sanitize(name.as_str(), &value))
+        })
+        .collect()
+}
+
+fn sanitize(name: &str, value: &str) -> String {
+    match name {
+        "authorization" => match value.split_once(' ') {
+            Some(("OAuth", fields)) => format!("OAuth {}", sanitize_oauth(fields)),
+            Some((scheme, token)) => format!("{} {}", scheme, redact(token)),
+            None => redact(value),
+        },
+        "cookie" | "set-cookie" => "[redacted]".to_string(),
+        _ => value.to_string(),
+    }
+}
+
+fn sanitize_oauth(fields: &str) -> String {
+    fields
+        .split(", ")
+        .map(|field| match field.split_once('=') {
+            Some((key, value)) if OAUTH_SECRETS.contains(&key) => {
+                let value = value.trim_matches('"');
+                let shown = if key == "oauth_signature" { "[redacted]".to_string() } else { redact(value) };
+                format!("{}=\"{}\"", key, shown)
+            }
+            _ => field.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The last four characters of a secret, or nothing of a short one.
+fn redact(secret: &str) -> String {
+    let chars: Vec<char> = secret.chars().collect();
+    match chars.len() {
+        0..=11 => "[redacted]".to_string(),
+        len => format!("[redacted]…{}", chars[len - 4..].iter().collect::<String>()),
+    }
+}
+
+/// Appends `entry` with a timestamp. A log that can't be written is skipped rather than
+/// failing the request it describes.
+fn write(entry: String) {
+    let Ok(path) = config::config_dir().map(|dir| dir.join(LOG_FILE)) else {
+        return;
+    };
+    let created = !path.exists();
+    let Ok(mut file) = OpenOptions::new().create(true).append(true).open(&path) else {
+        return;
+    };
+    #[cfg(unix)]
+    if created {
+        let _ = config::set_private_permissions(&path);
+    }
+    let entry = format!("[{}] {}", Utc::now().format("%Y-%m-%d %H:%M:%S%.3f"), entry);
+    let _ = file.write_all(entry.as_bytes());
+}
@@ -0,0 +1,237 @@
+use anyhow::{Context, Result};
+use image::RgbImage;
+use regex::Regex;
+use std::collections::HashMap;
+
+use crate::config::ShortenerConfig;
+
+/// What X is likely to show for a link: the page's Open Graph tags.
+#[derive(Debug, Clone)]
+pub struct LinkCard {
+    pub url: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub site_name: Option<String>,
+    pub image: Option<RgbImage>,
+}
+
+// Enough of the page to reach the <head> on any reasonable site
+const MAX_HTML_BYTES: usize = 512 * 1024;
+// Bigger than any og:image worth a terminal thumbnail
+const MAX_IMAGE_BYTES: usize = 5 * 1024 * 1024;
+
+/// The first http(s) URL in `text`, without trailing punctuation.
+pub fn first_url(text: &str) -> Option<String> {
+    find_urls(text).into_iter().next()
+}
+
+/// Every http(s) URL in `text`, without trailing punctuation.
+pub fn find_urls(text: &str) -> Vec<String> {
+    let url_pattern = Regex::new(r"https?://\S+").expect("valid URL regex");
+    url_pattern
+        .find_iter(text)
+        .map(|m| m.as_str().trim_end_matches(['.', ',', ';', ':', '!', '?', ')', '"', '\'']).to_string())
+        .collect()
+}
+
+/// Opens `url` in the default browser, without waiting for it.
+pub fn open_in_browser(url: &str) -> Result<()> {
+    #[cfg(target_os = "macos")]
+    let mut command = std::process::Command::new("open");
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = std::process::Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    };
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let mut command = std::process::Command::new("xdg-open");
+
+    // Keep whatever the browser prints off the TUI
+    command
+        .arg(url)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .context("Failed to start a browser")?;
+    Ok(())
+}
+
+/// Links in `text` long enough to be worth shortening.
+pub fn long_urls(config: &ShortenerConfig, text: &str) -> Vec<String> {
+    let mut urls: Vec<String> = find_urls(text)
+        .into_iter()
+        .filter(|url| url.chars().count() >= config.min_length)
+        .collect();
+    urls.dedup();
+    urls
+}
+
+/// Asks the configured shortener for a short form of `url`.
+pub async fn shorten(config: &ShortenerConfig, url: &str) -> Result<String> {
+    let request_url = config.endpoint.replace("{url}", &urlencoding::encode(url));
+    let response = reqwest::get(&request_url)
+        .await
+        .context("Failed to reach the URL shortener")?;
+
+    if !response.status().is_success() {
+        let error_text = response.text().await?;
+        anyhow::bail!("Failed to shorten {}: {}", url, error_text);
+    }
+
+    let short = response.text().await?.trim().to_string();
+    if !short.starts_with("http") {
+        anyhow::bail!("Failed to shorten {}: unexpected reply {}", url, short);
+    }
+    Ok(short)
+}
+
+/// Replaces each long link in `text` with its short form. Links the shortener can't
+/// handle are left as they are rather than holding up the post.
+pub async fn shorten_urls(config: &ShortenerConfig, text: &str) -> String {
+    let mut short_urls = HashMap::new();
+    for url in long_urls(config, text) {
+        if let Ok(short) = shorten(config, &url).await {
+            short_urls.insert(url, short);
+        }
+    }
+    substitute_urls(text, &short_urls)
+}
+
+pub fn substitute_urls(text: &str, short_urls: &HashMap<String, String>) -> String {
+    short_urls
+        .iter()
+        .fold(text.to_string(), |text, (long, short)| text.replace(long.as_str(), short))
+}
+
+/// Fetches `url` and reads its og: (or twitter:) tags, downloading og:image as a thumbnail
+/// of at most `width` x `height` pixels.
+pub async fn fetch_card(url: &str, width: u32, height: u32) -> Result<LinkCard> {
+    let client = reqwest::Client::builder()
+        .user_agent("Mozilla/5.0 (compatible; xpost link preview)")
+        .timeout(std::time::Duration::from_secs(10))
+        .build()?;
+
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .context("Failed to fetch link")?;
+
+    if !response.status().is_success() {
+        anyhow::bail!("Failed to fetch link: {}", response.status());
+    }
+    if !has_content_type(&response, &["text/html", "application/xhtml"]) {
+        anyhow::bail!("Link isn't a web page");
+    }
+
+    let page_url = response.url().clone();
+    let (bytes, _) = read_capped(response, MAX_HTML_BYTES).await?;
+    let html = String::from_utf8_lossy(&bytes);
+    let tags = meta_tags(&html);
+    let tag = |names: &[&str]| {
+        names
+            .iter()
+            .find_map(|name| tags.iter().find(|(key, _)| key == name))
+            .map(|(_, value)| value.clone())
+    };
+
+    let title = tag(&["og:title", "twitter:title"]).or_else(|| page_title(&html));
+    let description = tag(&["og:description", "twitter:description", "description"]);
+    let site_name = tag(&["og:site_name"]);
+
+    // A missing or broken image shouldn't hide the rest of the card
+    let image = match tag(&["og:image", "twitter:image"]).and_then(|src| page_url.join(&src).ok()) {
+        Some(image_url) => fetch_thumbnail(&client, image_url.as_str(), width, height).await.ok(),
+        None => None,
+    };
+
+    Ok(LinkCard {
+        url: url.to_string(),
+        title,
+        description,
+        site_name,
+        image,
+    })
+}
+
+async fn fetch_thumbnail(client: &reqwest::Client, url: &str, width: u32, height: u32) -> Result<RgbImage> {
+    let response = client.get(url).send().await?.error_for_status()?;
+    if !has_content_type(&response, &["image/"]) {
+        anyhow::bail!("Link image isn't an image");
+    }
+    if response.content_length().is_some_and(|length| length > MAX_IMAGE_BYTES as u64) {
+        anyhow::bail!("Link image is too big");
+    }
+    let (bytes, complete) = read_capped(response, MAX_IMAGE_BYTES).await?;
+    if !complete {
+        anyhow::bail!("Link image is too big");
+    }
+    let img = image::load_from_memory(&bytes).context("Failed to decode link image")?;
+    Ok(img.thumbnail(width, height).to_rgb8())
+}
+
+/// Whether the response's `content-type` starts with one of `types`. A response without one
+/// gets the benefit of the doubt.
+fn has_content_type(response: &reqwest::Response, types: &[&str]) -> bool {
+    let Some(content_type) = response.headers().get(reqwest::header::CONTENT_TYPE) else {
+        return true;
+    };
+    let content_type = content_type.to_str().unwrap_or("").to_ascii_lowercase();
+    types.iter().any(|kind| content_type.starts_with(kind))
+}
+
+/// Reads the body up to `max` bytes, stopping the download there, and says whether that was
+/// all of it.
+async fn read_capped(mut response: reqwest::Response, max: usize) -> Result<(Vec<u8>, bool)> {
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        let room = max - bytes.len();
+        if chunk.len() > room {
+            bytes.extend_from_slice(&chunk[..room]);
+            return Ok((bytes, false));
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok((bytes, true))
+}
+
+/// (property or name, content) for each <meta> tag, keys lowercased.
+fn meta_tags(html: &str) -> Vec<(String, String)> {
+    let meta = Regex::new(r"(?is)<meta\s[^>]*>").expect("valid meta regex");
+    let attribute = Regex::new(r#"(?is)([a-z:-]+)\s*=\s*(?:"([^"]*)"|'([^']*)')"#).expect("valid attribute regex");
+
+    meta.find_iter(html)
+        .filter_map(|tag| {
+            let mut key = None;
+            let mut content = None;
+            for caps in attribute.captures_iter(tag.as_str()) {
+                let value = caps.get(2).or(caps.get(3)).map(|v| v.as_str()).unwrap_or("");
+                match caps[1].to_lowercase().as_str() {
+                    "property" | "name" => key = Some(value.to_lowercase()),
+                    "content" => content = Some(decode_entities(value)),
+                    _ => {}
+                }
+            }
+            Some((key?, content?))
+        })
+        .collect()
+}
+
+fn page_title(html: &str) -> Option<String> {
+    let title = Regex::new(r"(?is)<title[^>]*>(.*?)</title>").expect("valid title regex");
+    title
+        .captures(html)
+        .map(|caps| decode_entities(caps[1].trim()))
+        .filter(|title| !title.is_empty())
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
@@ -0,0 +1,367 @@
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fs;
+use std::sync::OnceLock;
+
+/// Strings from the translation picked with `[display] language`, loaded once at startup.
+static TRANSLATIONS: OnceLock<HashMap<String, String>> = OnceLock::new();
+
+/// The built-in English text of every UI string. A translation file uses the same keys, and
+/// anything it leaves out stays English. `{}` marks where values go, in order.
+/// Key hints keep their "Key: action" shape so they stay clickable.
+const ENGLISH: &[(&str, &str)] = &[
+    // Every screen
+    ("ui.too_small", "The terminal is {}×{}; please resize it to at least {}×{}"),
+    // Sign-in problems
+    ("auth.revoked", "X no longer accepts this account's access token; it was revoked or regenerated"),
+    ("auth.clock", "X turned down the request's timestamp; check that this computer's clock is right"),
+    ("auth.rejected", "X didn't accept the API keys or access token"),
+    ("reauth.api_key", "API key"),
+    ("reauth.api_secret", "API secret"),
+    ("reauth.access_token", "Access token"),
+    ("reauth.access_token_secret", "Access token secret"),
+    // Composer
+    ("compose.title", "Compose your post"),
+    ("compose.sending", "Sending..."),
+    ("compose.posting", "Posting..."),
+    ("compose.message_title", "Message @{}"),
+    ("compose.reply_title", "Reply to {}"),
+    ("compose.preview_title", "Preview"),
+    ("compose.link_card_title", "Link card"),
+    ("compose.file_prompt_title", "Enter image file path"),
+    ("compose.alt_text_title", "Alt text for image {} of {}"),
+    ("compose.reauth_title", "{} for {} ({} of {})"),
+    ("compose.schedule_prompt_title", "Schedule for ({})"),
+    ("compose.drafts_title", "Saved Drafts"),
+    ("compose.archive_title", "Posted Drafts"),
+    ("compose.tag_prompt_title", "Tag {} drafts (again to remove the tag)"),
+    ("compose.versions_title", "Earlier Versions"),
+    ("compose.search_title", "Search drafts and posts"),
+    ("compose.results_title", "Results ({})"),
+    ("compose.accounts_title", "Post to accounts"),
+    ("compose.no_community", "No community (your timeline)"),
+    ("compose.community_title", "Post to community"),
+    ("compose.participants_title", "Mention in reply"),
+    ("compose.media_title", "Recent Images"),
+    ("compose.assist_title", "Assist"),
+    ("compose.suggestion_title", "Suggestion"),
+    ("compose.grammar_title", "Grammar ({})"),
+    ("compose.no_suggestion", "no suggestion"),
+    ("compose.format_title", "Format selection"),
+    ("format.bold", "Bold"),
+    ("format.italic", "Italic"),
+    ("format.bold_italic", "Bold italic"),
+    ("format.monospace", "Monospace"),
+    ("format.plain", "Plain"),
+    ("schedule.type_hint", "Type a time (\"tomorrow 9am\", \"in 2 hours\", 2025-06-01 09:00) or pick one below"),
+    ("schedule.weekdays", "Mo Tu We Th Fr Sa Su"),
+    ("schedule.resolved", "→ {} ({})"),
+    ("schedule.in", "in {}"),
+    ("schedule.past", "already passed"),
+    ("schedule.repeats", "↻ {}, first on {}"),
+    ("schedule.unreadable", "Keep typing: e.g. \"tomorrow 9am\", \"friday 14:30\" or \"in 2 hours\""),
+    // Composer status bar
+    ("status.title", "Status"),
+    ("status.characters", "Characters: {}/{}"),
+    ("status.writing", " | Words: {} | Lines: {} | Ln {}, Col {}"),
+    ("status.thread", " | 🧵 {} posts"),
+    ("status.image", " | 📎 Image attached"),
+    ("status.images", " | 📎 {} images attached"),
+    ("status.no_alt_text", " ({} without alt text)"),
+    ("status.draft", " | 📝 Draft loaded"),
+    ("status.deletes_after", " | ⏳ Deletes after {}"),
+    ("status.targets", " | 🔀 {}"),
+    ("status.target_fits", "{} {}/{}"),
+    ("status.target_thread", "{} {} posts"),
+    ("status.target_too_long", "{} ✗ {}/{}"),
+    ("status.quota", " | {}📮 {}{} of {} posts left this month"),
+    ("status.streak", " | 🔥 Post today to keep your {}-day streak"),
+    ("status.no_post_today", " | 🔥 Nothing posted today"),
+    ("status.reply", " | ↩ Reply"),
+    ("status.reply_excluded", " | ↩ Reply ({} excluded)"),
+    ("status.file_prompt", "Enter the path to your image file"),
+    ("status.alt_text_prompt", "{}/{} characters; describe what the image shows for people using screen readers"),
+    ("status.schedule_prompt", "The post is added to the outbox; `xpost daemon` publishes it when due"),
+    ("status.assist_pending", "Asking for a suggestion..."),
+    ("status.grammar_pending", "Checking grammar..."),
+    ("status.no_grammar_issues", "No grammar issues"),
+    ("status.format_picker", "⚠ Screen readers spell styled letters out one by one or skip them, and search can't find them; keep them to a word or two"),
+    ("status.assist_picker", "Sends the whole buffer to your assist endpoint"),
+    ("status.suggestion", "Suggestion: {} characters (now {})"),
+    ("status.sending_message", "Sending message..."),
+    ("status.uploading", "Uploading images ({}/{})..."),
+    ("status.posting", "Posting to X..."),
+    ("status.message_sent", "✓ Message sent to @{}"),
+    ("status.error", "✗ Error: {}"),
+    ("status.reauth_prompt", "{}. Paste the new value from your app's Keys and tokens page on developer.x.com"),
+    ("status.reauth_checking", "Checking the new credentials with X..."),
+    ("status.reauth_failed", "✗ X didn't accept those either: {}"),
+    ("status.reauthed", "✓ Signed in as @{} and saved to {}; retry the failed post from the queue (F4)"),
+    ("status.reauthed_session", "✓ Signed in as @{} for this session. Your credentials are encrypted: run `xpost decrypt-config`, update them and encrypt again to keep them"),
+    ("background.title", "Posting in the background"),
+    ("background.posting", "⏳ {}"),
+    ("background.uploading", "⏳ {}: uploading images ({}/{})"),
+    ("background.posted", "✓ {}: {}"),
+    ("background.sent", "✓ {}: sent"),
+    ("background.failed", "✗ {}: {} (kept in the queue, F4)"),
+    ("background.message_failed", "✗ {}: {}"),
+    ("status.link_copied", "✓ Copied {}"),
+    ("status.copy_failed", "✗ Couldn't copy the link: {}"),
+    ("status.open_failed", "✗ Couldn't open a browser: {}"),
+    ("success.title", "Posted"),
+    ("success.image", "1 image"),
+    ("success.images", "{} images"),
+    ("success.thread", "thread of {}"),
+    ("success.links", "Links"),
+    ("status.post_cancelled", "Cancelled; nothing was posted"),
+    ("status.post_sent", "Already on its way to X, so it can't be called back"),
+    ("status.drafts", "Drafts: {} saved"),
+    ("status.archived", "Posted drafts: {}"),
+    ("status.marked", "{} of {} drafts marked"),
+    ("status.conflicting_drafts", "{} drafts were edited in two places at once; both versions are kept"),
+    ("status.sync_failed", "✗ Draft sync failed: {}"),
+    ("status.damaged_drafts", "⚠ {} draft files couldn't be read; they're listed in red at the end"),
+    ("status.damaged_draft_inspect", "Showing {} as it is on disk; saving keeps it as a new draft"),
+    ("status.damaged_draft_repaired", "✓ Recovered {} into a new draft; the damaged file is now {}"),
+    ("status.damaged_draft_unrecoverable", "✗ No draft text is left in {}; Enter shows the file as it is"),
+    ("status.damaged_draft_set_aside", "Moved {} to {}"),
+    ("status.search", "Searching {} drafts and posts; 📝 draft, ✓ posted draft, ↗ post history"),
+    ("status.no_versions", "This draft has no earlier versions yet"),
+    ("status.version", "{} characters; Enter loads this wording into the composer, and saving keeps the current one as a version"),
+    ("status.no_media", "No recent images yet. Attach one with Ctrl+V or Ctrl+U"),
+    ("status.accounts", "Posting to {} of {} accounts"),
+    ("status.lines", "Characters: {}/{} | Lines: {}"),
+    ("status.shortening", " | 🔗 Shortening links..."),
+    ("status.loading_card", " | 🔗 Loading link card..."),
+    ("status.excluding", "Excluding {} of {} mentioned users"),
+    ("status.community", "Posting to community: {}"),
+    ("status.timeline", "Posting to your timeline"),
+    ("status.finish_first", "Finish what you're doing in the composer first"),
+    ("status.failed", "✗ {}"),
+    ("status.post_timed_out", "No answer from X after {} minutes; it may have gone out, so check X before retrying"),
+    ("status.post_gave_up", "Gave up after {} minutes without posting anything"),
+    ("status.reauth_save_failed", "✗ Couldn't save the new credentials: {}"),
+    ("status.premium_unknown", "Couldn't tell whether this account has premium; assuming {} characters (set premium under [profile])"),
+    ("status.conversation_failed", "Couldn't load the conversation: {}"),
+    ("status.no_grammar_issues_found", "✓ No grammar issues found"),
+    ("status.card_failed", "✗ Couldn't load link card: {}"),
+    ("status.archive_failed", "✗ Couldn't archive the draft: {}"),
+    ("status.image_error", "Image error: {}"),
+    ("status.too_many_images", "Posts can have at most {} images"),
+    ("status.draft_forked", "This draft changed elsewhere since you opened it, so yours was saved as a new draft"),
+    ("status.save_failed", "Failed to save draft: {}"),
+    ("status.select_text", "Select some text with Shift+arrows first"),
+    ("status.card_saved", "🖼 Card saved to {}"),
+    ("status.card_export_failed", "✗ Card export failed: {}"),
+    ("status.exported", "📄 Exported to {}"),
+    ("status.exported_drafts", "📄 Exported {} drafts to {}"),
+    ("status.export_failed", "✗ Export failed: {}"),
+    ("status.no_accounts", "Select at least one account (Ctrl+T)"),
+    ("status.over_limit", "Post is {} characters, over the {} limit"),
+    ("status.alt_text_required", "✗ {} doesn't post images without alt text; add it to image {} with Alt+T"),
+    ("status.alt_text_missing", "⚠ {} of {} images have no alt text (Alt+T); Ctrl+P again to post anyway"),
+    ("status.blocklist", "{}'s blocklist ({})"),
+    ("status.blocklist_and", " and "),
+    ("status.blocklist_warning", "⚠ Matches {}; {}"),
+    ("status.post_anyway", "Ctrl+P again to post anyway"),
+    ("status.schedule_anyway", "Alt+S again to schedule anyway"),
+    ("status.cap_warning", "⚠ {} has used {} of its {} posts this month; Ctrl+P again to post anyway"),
+    ("status.quiet_hours", "🌙 Quiet hours: Enter schedules the post for {}, or Esc and Ctrl+P again to post now"),
+    ("status.scheduled", "🗓 Scheduled for {} (see `xpost queue`)"),
+    ("status.repeats_scheduled", "🗓 Repeats {}, first on {} (see `xpost queue`)"),
+    ("status.schedule_failed", "Failed to schedule: {}"),
+    ("status.time_skipped", "That time doesn't exist here (it's skipped by a clock change)"),
+    ("status.time_unreadable", "Couldn't read '{}' as a time"),
+    ("status.delete_marked", "Delete again to remove the {} marked drafts"),
+    ("status.no_draft_accounts", "Select at least one account (Ctrl+T in the composer)"),
+    ("status.draft_over_limit", "✗ This draft is over the {} character limit"),
+    ("confirm.draft_post", "Post this draft as {}? p again to post"),
+    ("confirm.warnings", "⚠ {}. {}"),
+    ("confirm.blocklist", "matches {}'s blocklist ({})"),
+    ("confirm.cap", "{} has used {} of its {} posts this month"),
+    ("confirm.quiet_hours", "it's quiet hours"),
+    ("notify.posted", "Posted"),
+    ("notify.failed", "Post failed"),
+    ("notify.partly_posted", "Posted with errors"),
+    ("notify.message_sent", "Message sent to @{}"),
+    // Composer key hints
+    ("keys.dm", "Ctrl+S: save draft | Ctrl+D: drafts | Ctrl+P: send message | Esc: exit"),
+    ("keys.upload_image", "Ctrl+U: upload image"),
+    ("keys.recent_images", "Ctrl+L: recent images"),
+    ("keys.alt_text", "Alt+T: alt text"),
+    ("keys.remove_images", "Alt+I: remove images"),
+    ("keys.save_draft", "Ctrl+S: save draft"),
+    ("keys.drafts", "Ctrl+D: drafts"),
+    ("keys.accounts", "Ctrl+T: accounts"),
+    ("keys.community", "Ctrl+G: community"),
+    ("keys.exclude", "Alt+E: exclude from reply"),
+    ("keys.assist", "Alt+A: assist"),
+    ("keys.grammar", "Alt+G: grammar"),
+    ("keys.format", "Alt+F: format"),
+    ("keys.schedule", "Alt+S: schedule"),
+    ("keys.delete_after", "Alt+X: delete after"),
+    ("keys.zen", "Alt+Z: zen"),
+    ("keys.preview", "Ctrl+O: preview"),
+    ("keys.post", "Ctrl+P: post"),
+    ("keys.exit", "Esc: exit"),
+    ("keys.prompt", "Enter: confirm | Esc: cancel"),
+    ("keys.schedule_picker_date", "←/→/↑/↓: Day | PgUp/PgDn: Month | Tab: Time | Enter: Schedule | Esc: Cancel"),
+    ("keys.schedule_picker_time", "↑/↓: Change | ←/→/Tab: Switch field | PgUp/PgDn: Month | Enter: Schedule | Esc: Cancel"),
+    ("keys.wait", "Please wait..."),
+    ("keys.posting", "Ctrl+N: write the next one meanwhile | Esc: cancel (until it reaches X)"),
+    ("keys.format_picker", "↑/↓: navigate | Enter: apply style | Esc: back to editor"),
+    ("keys.grammar_popup", "↑/↓: navigate | Enter: apply suggestion | Esc: back to editor"),
+    ("keys.assist_picker", "↑/↓: navigate | Enter: ask | Esc: back"),
+    ("keys.assist_diff", "Enter: accept suggestion | Esc: keep your text"),
+    ("keys.message_sent", "any key: write another | Esc: exit"),
+    ("keys.success", "r: reply to it | o: open | c: copy link | s: stats | m: export as markdown | any other key: post again | Esc: exit"),
+    ("keys.error", "Press any key to post again, or Esc to exit"),
+    ("keys.error_reauth", "r: re-enter credentials | any other key: post again | Esc: exit"),
+    ("keys.reauth_prompt", "Enter: next | Esc: cancel"),
+    ("keys.draft_browser", "↑/↓: navigate | Space: mark | Shift+↑/↓: move | *: pin | v: versions | Enter: load draft | p: post | t: tag | Delete: remove | m: export markdown | a: posted | /: search | Esc: back"),
+    ("keys.damaged_draft", "↑/↓: navigate | Enter: inspect | r: repair | Delete: set aside | a: switch list | Esc: back"),
+    ("keys.archive_browser", "↑/↓: navigate | Space: mark | Enter: load a copy | t: tag | Delete: remove | m: export markdown | a: drafts | /: search | Esc: back"),
+    ("keys.search", "↑/↓: navigate | Enter: load into composer | Esc: back to drafts"),
+    ("keys.version_picker", "↑/↓: navigate | Enter: restore version | Esc: back to drafts"),
+    ("keys.media_picker", "↑/↓: navigate | Enter: attach image | Delete: remove from library | Esc: back"),
+    ("keys.account_picker", "↑/↓: navigate | Space: toggle account | Enter/Esc: back"),
+    ("keys.community_picker", "↑/↓: navigate | Enter: select | Esc: back"),
+    ("keys.participant_picker", "↑/↓: navigate | Space: include/exclude user | Enter/Esc: back"),
+    ("keys.preview_scroll", "↑/↓/PgUp/PgDn: scroll | Ctrl+O/Esc: back to editor"),
+    // Direct messages
+    ("dm.lookup_failed", "Failed to look up @{}: {}"),
+    ("dm.cancelled", "Cancelled"),
+    ("dm.send_failed", "Failed to send message: {}"),
+    // Stats
+    ("stats.feed_mine", "Your Recent Posts"),
+    ("stats.feed_timeline", "Home Timeline"),
+    ("stats.feed_bookmarks", "Bookmarks"),
+    ("stats.feed_user", "@{}'s Recent Posts"),
+    ("stats.loading", "Fetching tweets..."),
+    ("stats.loading_account", "Fetching tweets for {}..."),
+    ("stats.no_tweets", "No tweets found"),
+    ("stats.unknown_date", "Unknown date"),
+    ("stats.posts_title", "Posts"),
+    ("stats.keys_detail", "↑/↓: Replies | Enter: Fold replies | PgUp/PgDn: Scroll post | L: Like | Shift+L: Unlike | T: Repost | Shift+T: Undo repost | B: Bookmark | F: Follow author | U: Unfollow author | M: Mute author | X: Block author | Esc: Back to List | Q: Exit"),
+    ("stats.keys_bookmarks", "↑/↓: Navigate | Enter: View Stats | L: Like | Shift+L: Unlike | T: Repost | Shift+T: Undo repost | X: Remove bookmark"),
+    ("stats.keys_list", "↑/↓: Navigate | Enter: View Stats | L: Like | Shift+L: Unlike | T: Repost | Shift+T: Undo repost | B: Bookmark"),
+    ("stats.keys_switch_account", "A: Switch account"),
+    ("stats.keys_exit", "Esc: Exit"),
+    ("stats.audience_title", "Audience"),
+    ("stats.followers", " Followers: "),
+    ("stats.following", " Following: "),
+    ("stats.change", " Change: "),
+    ("stats.since", " Since: "),
+    ("stats.growth_title", "Follower Growth"),
+    ("stats.activity_title", "Posting Activity ({})"),
+    ("stats.weekdays", "Mon,,Wed,,Fri,,"),
+    ("stats.cadence", " 🔥 {}d · best {}d · {}/wk "),
+    ("stats.impressions_title", "Impressions"),
+    ("stats.reads_remaining", " {} of {} reads remaining, resets in {} min "),
+    ("stats.detail_title", "Post Statistics"),
+    ("stats.post_by", "Post by @{}"),
+    ("stats.post_content", "Post Content"),
+    ("stats.likes", "  Likes: "),
+    ("stats.retweets", "  Retweets: "),
+    ("stats.replies", "  Replies: "),
+    ("stats.quotes", "  Quotes: "),
+    ("stats.impressions", "  Impressions: "),
+    ("stats.metrics_title", "Metrics"),
+    ("stats.no_metrics", "No metrics available"),
+    ("stats.replies_title", "Replies ({})"),
+    ("stats.no_read_access", "Post stats need read access, which the free API tier doesn't include"),
+    ("stats.free_tier", "{} is on the free API tier, which can't read posts"),
+    ("stats.load_failed", "Failed to load tweets: {}"),
+    ("stats.replies_failed", "Replies unavailable: {}"),
+    ("stats.save_followers_failed", "Couldn't save follower counts: {}"),
+    ("stats.save_metrics_failed", "Couldn't save post metrics: {}"),
+    ("stats.liked", "Liked"),
+    ("stats.reposted", "Reposted"),
+    ("stats.unliked", "Unliked"),
+    ("stats.unreposted", "Repost undone"),
+    ("stats.bookmarked", "Bookmarked"),
+    ("stats.bookmark_removed", "Bookmark removed"),
+    ("stats.followed", "Followed {}"),
+    ("stats.follow_requested", "Asked to follow {}; they have to approve it"),
+    ("stats.unfollowed", "Unfollowed {}"),
+    ("stats.muted", "Muted {}"),
+    ("stats.blocked", "Blocked {}"),
+    ("stats.block_confirm", "Press x again to block {}"),
+    ("stats.no_reply", "✗ No reply selected"),
+    ("stats.reply_author_unknown", "✗ X didn't say who wrote this reply"),
+    ("stats.own_reply", "✗ That reply is yours"),
+    ("stats.user_id", "user {}"),
+    // Outbox
+    ("queue.keys", "↑/↓: Navigate | r: Retry now | e: Edit time | p: Pause | d: Delete | Esc: Exit"),
+    ("queue.repeats", "↻ Repeats {} (e and a new rule changes it)"),
+    ("queue.paused_note", "⏸ Paused: the daemon skips it until p resumes it"),
+    ("queue.posting", "Posting..."),
+    ("queue.title", "Outbox ({})"),
+    ("queue.failed", "✗ {} (attempt {})"),
+    ("queue.empty", "Nothing queued. Schedule a post with Alt+S in the composer."),
+    ("queue.post_title", "Post"),
+    ("queue.edit_time_title", "New time ({})"),
+    ("queue.posted", "✓ Posted {}"),
+    ("queue.partly_posted", "✓ Posted {} (✗ {})"),
+    ("queue.unknown_account", "✗ No account named '{}' in config"),
+    ("queue.daemon_busy", "✗ The daemon is publishing; try again in a moment"),
+    ("queue.gone", "✗ That post was published, deleted or interrupted meanwhile"),
+    ("queue.deleted", "Deleted"),
+    ("queue.paused", "Paused"),
+    ("queue.resumed", "Resumed"),
+    ("queue.rescheduled", "Rescheduled"),
+    ("queue.time_unreadable", "✗ Couldn't read that time"),
+    // Profile lookup
+    ("profile.lookup_title", "Look up user"),
+    ("profile.lookup_keys", "Enter: look up | Esc: Exit"),
+    ("profile.bio_title", "Bio"),
+    ("profile.followers", " Followers: "),
+    ("profile.following", "  Following: "),
+    ("profile.posts", "  Posts: "),
+    ("profile.listed", "  Listed: "),
+    ("profile.recent_posts_title", "Recent Posts"),
+    ("profile.keys", "↑/↓: Navigate | L: Like | Shift+L: Unlike | T: Repost | Shift+T: Undo repost | F: Follow | U: Unfollow | /: New lookup | Esc: Exit"),
+    ("profile.looking_up", "Looking up @{}..."),
+    ("profile.lookup_failed", "Lookup failed: {}"),
+    ("profile.own_profile", "That's you"),
+];
+
+/// Loads `~/.config/xpost/locales/<language>.toml`; English needs no file.
+pub fn load(language: Option<&str>) -> Result<()> {
+    let translations = match language {
+        None | Some("en") => HashMap::new(),
+        Some(language) => {
+            let path = crate::config::config_dir()?.join("locales").join(format!("{}.toml", language));
+            let content = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read translation {}", path.display()))?;
+            toml::from_str(&content).context("Failed to parse translation")?
+        }
+    };
+    let _ = TRANSLATIONS.set(translations);
+    Ok(())
+}
+
+/// The UI text for `key` in the configured language.
+pub fn t(key: &'static str) -> &'static str {
+    TRANSLATIONS
+        .get()
+        .and_then(|translations| translations.get(key))
+        .map(String::as_str)
+        .or_else(|| ENGLISH.iter().find(|(english_key, _)| *english_key == key).map(|(_, text)| *text))
+        .unwrap_or(key)
+}
+
+/// `t(key)` with each `{}` filled in from `args`, in order.
+pub fn tf(key: &'static str, args: &[&dyn Display]) -> String {
+    let mut args = args.iter();
+    let mut parts = t(key).split("{}");
+    let mut text = parts.next().unwrap_or_default().to_string();
+    for part in parts {
+        if let Some(arg) = args.next() {
+            text.push_str(&arg.to_string());
+        }
+        text.push_str(part);
+    }
+    text
+}
@@ -0,0 +1,36 @@
+use anyhow::{Context, Result};
+use std::fs::{File, OpenOptions, TryLockError};
+
+/// An advisory lock on a file in the config directory. It's released when dropped, or by the
+/// OS when the process exits however it exits, so a crash never leaves it stuck.
+pub struct Lock {
+    _file: File,
+}
+
+fn lock_file(name: &str) -> Result<File> {
+    let path = crate::config::config_dir()?.join(format!("{}.lock", name));
+    OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&path)
+        .context("Failed to open lock file")
+}
+
+/// Takes the lock called `name`, or returns `None` straight away if another process has it.
+pub fn try_lock(name: &str) -> Result<Option<Lock>> {
+    let file = lock_file(name)?;
+    match file.try_lock() {
+        Ok(()) => Ok(Some(Lock { _file: file })),
+        Err(TryLockError::WouldBlock) => Ok(None),
+        Err(TryLockError::Error(e)) => Err(e).context("Failed to take lock"),
+    }
+}
+
+/// Takes the lock called `name`, waiting for another process to let go of it first. Locks
+/// aren't re-entrant: taking one the process already holds waits forever.
+pub fn lock(name: &str) -> Result<Lock> {
+    let file = lock_file(name)?;
+    file.lock().context("Failed to take lock")?;
+    Ok(Lock { _file: file })
+}
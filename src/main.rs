@@ -1,319 +1,2826 @@
+mod alerts;
+mod announce;
+mod assist;
+mod backup;
+mod blocklist;
+mod buttons;
+mod card;
 mod config;
 mod twitter;
 mod clipboard;
 mod ui;
 mod stats_ui;
+mod text;
+mod theme;
+mod timezone;
+mod profile_ui;
 mod drafts;
+mod grammar;
+mod media;
+mod thread;
+mod followers;
+mod metrics;
+mod crypto;
+mod daemon;
+mod demo;
+mod doctor;
+mod fixtures;
+mod notify;
+mod oauth;
+mod history;
+mod hooks;
+mod http_log;
+mod links;
+mod locale;
+mod lock;
+mod plain;
+mod posting;
+mod scripting;
+mod queue;
+mod queue_ui;
+mod reauth;
+mod uploads;
+mod cli;
+mod crosspost;
+mod feed;
+mod schedule_picker;
+mod search;
+mod styled;
+mod sync;
 
 use anyhow::Result;
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers},
+    event::{
+        self, DisableBracketedPaste, DisableFocusChange, DisableMouseCapture, EnableBracketedPaste,
+        EnableFocusChange, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent,
+        MouseEventKind,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
-use ratatui::{backend::CrosstermBackend, Terminal};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Constraint, Direction, Layout, Margin, Position, Rect},
+    Terminal,
+};
+use std::collections::BTreeMap;
 use std::io;
 use tokio::sync::mpsc;
 
-use config::Config;
-use twitter::TwitterClient;
-use ui::{App, AppState};
+use cli::Cli;
+use config::{AccountConfig, AltTextPolicy, Config, NotificationConfig, ShortenerConfig, TwitterConfig};
+use locale::{t, tf};
+use reauth::Reauth;
+use schedule_picker::{PickerField, SchedulePicker};
+use twitter::{PostOptions, ReplyTo, TwitterClient, UserData};
+use ui::{App, AppState, PostOutcome};
+
+/// Posts and messages carry an ID that their results and upload progress come back with,
+/// since several can be on their way at once.
+enum PostCommand {
+    Post {
+        id: u64,
+        text: String,
+        images: Vec<Vec<u8>>,
+        alt_texts: Vec<String>,
+        accounts: Vec<String>,
+        community_id: Option<String>,
+        reply: Option<ReplyTo>,
+        delete_after: Option<chrono::Duration>,
+        cancel: posting::Cancel,
+    },
+    DirectMessage {
+        id: u64,
+        username: String,
+        text: String,
+        cancel: posting::Cancel,
+    },
+    /// Credentials re-entered for an account, to post with from now on.
+    Reauth {
+        account: String,
+        twitter: TwitterConfig,
+    },
+}
+
+/// What Ctrl+P does with the composed text.
+enum ComposeTarget {
+    Post,
+    DirectMessage(String),
+    Reply(String),
+    /// A new post that starts out with this text.
+    Text(String),
+}
+
+/// Background results delivered to the compose loop.
+enum ComposeEvent {
+    /// Whether the account has premium; `None` when asking failed.
+    Premium(Option<bool>),
+    Participants(Result<Vec<UserData>>),
+    Assist(Result<String>),
+    Grammar(Result<Vec<grammar::GrammarIssue>>),
+    LinkCard(Result<links::LinkCard>),
+    ShortUrls(Vec<(String, Result<String>)>),
+    UploadProgress(u64, usize, usize),
+    /// Whether X accepted re-entered credentials, and as whom.
+    Reauth(TwitterConfig, Result<UserData, String>),
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let cli = match Cli::parse() {
+        Ok(cli) => cli,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+    };
+
+    if cli.demo {
+        demo::enable()?;
+    } else {
+        config::set_paths(cli.data_dir.clone(), cli.config.clone());
+    }
+    if cli.debug_http {
+        http_log::enable();
+    }
+
+    match cli.command.as_deref() {
+        Some("encrypt-config") => return config::Config::encrypt_credentials(),
+        Some("decrypt-config") => return config::Config::decrypt_credentials(),
+        Some("export-metrics") => return export_metrics(cli.args.first()),
+        Some("export-feed") => return export_feed(&cli.args, cli.account.as_deref()),
+        Some("backup") => return run_backup(cli.args.first()),
+        Some("restore") => return run_restore(cli.args.first()),
+        // Before the config is loaded, which would quietly fix the permissions it checks
+        Some("doctor") => {
+            if !doctor::run(cli.account.as_deref()).await {
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+        _ => {}
+    }
+    
+    let (config, account) = match Config::load().and_then(|cfg| {
+        let account = cfg.account(cli.account.as_deref())?;
+        Ok((cfg, account))
+    }) {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    theme::init(config.display.theme.as_deref());
+    thread::init(&config.threads);
+    drafts::init(&config.drafts);
+    clipboard::init(&config.media);
+    if let Err(e) = locale::load(config.display.language.as_deref()) {
+        eprintln!("{:#}", e);
+        std::process::exit(1);
+    }
+    if let Err(e) = timezone::init(config.display.timezone.as_deref()) {
+        eprintln!("{:#}", e);
+        std::process::exit(1);
+    }
+
+    if cli.command.as_deref() == Some("search-local") {
+        return search_local(&config, &cli.args);
+    }
+    if let Some(file) = cli.option("--file").filter(|_| cli.command.as_deref() == Some("queue")) {
+        return import_queue(&config, &account, &file);
+    }
+
+    if let Some(command @ ("stats" | "timeline" | "bookmarks" | "profile" | "dm" | "snapshot")) = cli.command.as_deref() {
+        if !account.twitter.can_read() {
+            eprintln!("`xpost {}` needs read access, which the free API tier doesn't include", command);
+            std::process::exit(1);
+        }
+    }
+
+    if cli.plain {
+        match cli.command.as_deref() {
+            None | Some("stats" | "timeline" | "bookmarks" | "queue") => {
+                let user = cli.option("--user").map(|handle| handle.trim_start_matches('@').to_string());
+                return plain::run(&config, &account, cli.command.as_deref(), user).await;
+            }
+            Some(command @ ("profile" | "reply")) => {
+                eprintln!("`xpost {}` isn't available with --plain yet", command);
+                std::process::exit(2);
+            }
+            Some("dm") if cli.args.len() < 2 => {
+                eprintln!("With --plain, give the message too: xpost --plain dm @user \"message\"");
+                std::process::exit(2);
+            }
+            _ => {}
+        }
+    }
+
+    // Subcommands that open the session start on the matching tab
+    let (tab, feed) = match cli.command.as_deref() {
+        Some("stats") => match cli.option("--user") {
+            Some(handle) => (Tab::Stats, stats_ui::Feed::User(handle.trim_start_matches('@').to_string())),
+            None => (Tab::Stats, stats_ui::Feed::Mine),
+        },
+        Some("timeline") => (Tab::Stats, stats_ui::Feed::Timeline),
+        Some("bookmarks") => (Tab::Stats, stats_ui::Feed::Bookmarks),
+        Some("queue") => (Tab::Queue, stats_ui::Feed::Mine),
+        Some("profile") => return run_profile_mode(&config, account, cli.args.first().cloned()).await,
+        Some("dm") => return run_dm_command(config, account, &cli.args).await,
+        Some("snapshot") => return run_snapshot_command(account, &cli.args).await,
+        Some("daemon") => return daemon::run(config).await,
+        Some("flush") => return run_flush(&config).await,
+        Some("announce") => {
+            let Some(repo) = cli.option("--repo") else {
+                eprintln!("Usage: xpost announce --repo owner/name [--tag v1.2.3]");
+                std::process::exit(2);
+            };
+            let release = announce::fetch_release(&repo, cli.option("--tag").as_deref()).await?;
+            let target = ComposeTarget::Text(announce::announcement(config.announce.as_ref(), &repo, &release));
+            return run_session(config, account, target, Tab::Compose, stats_ui::Feed::Mine).await;
+        }
+        Some("reply") => {
+            let Some(tweet_id) = cli.args.first().map(|arg| parse_tweet_id(arg)) else {
+                eprintln!("Usage: xpost reply <post id or url>");
+                std::process::exit(2);
+            };
+            let target = ComposeTarget::Reply(tweet_id);
+            return run_session(config, account, target, Tab::Compose, stats_ui::Feed::Mine).await;
+        }
+        _ => (Tab::Compose, stats_ui::Feed::Mine),
+    };
+
+    run_session(config, account, ComposeTarget::Post, tab, feed).await
+}
+
+/// `xpost export-metrics [file.csv]` writes the snapshot history out as CSV.
+fn export_metrics(path: Option<&String>) -> Result<()> {
+    if path.is_some_and(|path| path.ends_with(".parquet")) {
+        eprintln!("Only CSV export is supported; DuckDB and pandas both read it directly");
+        std::process::exit(2);
+    }
+
+    let path = metrics::export_csv(path.map(std::path::Path::new))?;
+    println!("✓ Exported metrics history to {}", path.display());
+    Ok(())
+}
+
+/// `xpost export-feed [feed.xml] [--rss] [--json]` writes the post history as an Atom feed, or
+/// RSS with `--rss`, plus a JSON Feed beside it with `--json` (or only that for a .json path).
+fn export_feed(args: &[String], account: Option<&str>) -> Result<()> {
+    let path = args.iter().find(|arg| !arg.starts_with("--")).map(std::path::Path::new);
+    let json = args.iter().any(|arg| arg == "--json");
+
+    if path.is_some_and(|path| path.extension().is_some_and(|ext| ext == "json")) {
+        let path = feed::export(path, feed::FeedFormat::Json, account)?;
+        println!("✓ Exported JSON Feed to {}", path.display());
+        return Ok(());
+    }
+
+    let format = if args.iter().any(|arg| arg == "--rss") { feed::FeedFormat::Rss } else { feed::FeedFormat::Atom };
+    let written = feed::export(path, format, account)?;
+    println!("✓ Exported feed to {}", written.display());
+    if json {
+        let json_path = path.map(|path| path.with_extension("json"));
+        let written = feed::export(json_path.as_deref(), feed::FeedFormat::Json, account)?;
+        println!("✓ Exported JSON Feed to {}", written.display());
+    }
+    Ok(())
+}
+
+/// `xpost backup [file.tar.gz]` archives drafts, history, the queue and the config without its
+/// credentials, to move them to another machine.
+fn run_backup(path: Option<&String>) -> Result<()> {
+    let path = match path {
+        Some(path) => std::path::PathBuf::from(path),
+        None => std::path::PathBuf::from(format!("xpost-backup-{}.tar.gz", chrono::Local::now().format("%Y-%m-%d"))),
+    };
+    let files = backup::backup(&path)?;
+    println!("✓ Backed up {} files to {}", files, path.display());
+    println!("It holds no credentials; on the new machine run: xpost restore {}", path.display());
+    Ok(())
+}
+
+/// `xpost restore file.tar.gz` puts a backup from `xpost backup` in place.
+fn run_restore(path: Option<&String>) -> Result<()> {
+    let Some(path) = path else {
+        eprintln!("Usage: xpost restore <backup.tar.gz>");
+        std::process::exit(2);
+    };
+    let restored = backup::restore(std::path::Path::new(path))?;
+    println!("✓ Restored {} files and {} posts of history from {}", restored.files, restored.history, path);
+    if !restored.has_credentials {
+        println!("Add your API keys under [twitter] in {} before posting", Config::config_path()?.display());
+    }
+    Ok(())
+}
+
+/// `xpost queue --file posts.csv` schedules every post in a batch file.
+fn import_queue(config: &Config, account: &AccountConfig, file: &str) -> Result<()> {
+    let accounts: Vec<String> = config.all_accounts().into_iter().map(|account| account.name).collect();
+    match queue::import_batch(std::path::Path::new(file), &account.name, &accounts) {
+        Ok(count) => {
+            println!("✓ Queued {} posts from {} (see `xpost queue`)", count, file);
+            Ok(())
+        }
+        Err(e) => {
+            eprintln!("{:#}", e);
+            eprintln!("Nothing was queued");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// `xpost flush` publishes whatever is due once and exits: 0 when nothing failed,
+/// `daemon::EXIT_POST_FAILED` when a post did, `daemon::EXIT_BUSY` when another run had it.
+async fn run_flush(config: &Config) -> Result<()> {
+    match daemon::flush(config).await? {
+        None => {
+            eprintln!("Another xpost flush or daemon is publishing; nothing done");
+            std::process::exit(daemon::EXIT_BUSY);
+        }
+        Some(flushed) if flushed.failed > 0 => {
+            eprintln!("{} posted, {} failed", flushed.posted, flushed.failed);
+            std::process::exit(daemon::EXIT_POST_FAILED);
+        }
+        Some(flushed) => {
+            println!("{} posted", flushed.posted);
+            Ok(())
+        }
+    }
+}
+
+/// `xpost search-local "query"` lists the drafts, posted drafts and posts that match, best first.
+fn search_local(config: &Config, args: &[String]) -> Result<()> {
+    let query = args.join(" ");
+    if query.trim().is_empty() {
+        eprintln!("Usage: xpost search-local \"query\"");
+        std::process::exit(2);
+    }
+
+    drafts::unlock()?;
+    let index = search::Index::build()?;
+    let results = index.search(&query);
+    if results.is_empty() {
+        println!("Nothing matches \"{}\"", query);
+    }
+    for i in results {
+        println!("{}", index.entries[i].preview(config.display.time_format.as_deref(), 100));
+    }
+    Ok(())
+}
+
+/// Accepts a bare post ID or a link like https://x.com/user/status/123?s=20.
+fn parse_tweet_id(arg: &str) -> String {
+    let arg = arg.trim();
+    let id = arg.rsplit("/status/").next().unwrap_or(arg);
+    id.split(['?', '#', '/']).next().unwrap_or(id).to_string()
+}
+
+/// `xpost dm @user "text"` sends right away; `xpost dm @user` opens the composer in DM mode.
+async fn run_dm_command(config: Config, account: AccountConfig, args: &[String]) -> Result<()> {
+    let Some(recipient) = args.first() else {
+        eprintln!("Usage: xpost dm @user [\"message\"]");
+        std::process::exit(2);
+    };
+    let username = recipient.trim_start_matches('@').to_string();
+
+    if args.len() == 1 {
+        let target = ComposeTarget::DirectMessage(username);
+        return run_session(config, account, target, Tab::Compose, stats_ui::Feed::Mine).await;
+    }
+
+    let client = TwitterClient::new(account.twitter.clone());
+    match send_direct_message(&client, &username, args[1..].join(" "), &posting::Cancel::default()).await {
+        Ok(_) => {
+            println!("✓ Message sent to @{}", username);
+            Ok(())
+        }
+        Err(e) => {
+            eprintln!("✗ {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// `xpost snapshot [count]` records the metrics of your recent posts, for running from cron.
+async fn run_snapshot_command(account: AccountConfig, args: &[String]) -> Result<()> {
+    let count = match args.first().map(|arg| arg.parse::<u32>()) {
+        None => 20,
+        Some(Ok(count)) if (5..=100).contains(&count) => count,
+        Some(_) => {
+            eprintln!("Usage: xpost snapshot [count, 5-100]");
+            std::process::exit(2);
+        }
+    };
+
+    let client = TwitterClient::new(account.twitter.clone());
+    let result = async {
+        let user = client.get_current_user().await?;
+        let tweets = client.get_user_tweets(&user.id, count).await?;
+        let recorded = metrics::record_snapshot(&user.username, &tweets)?;
+        anyhow::Ok((user.username, recorded))
+    }.await;
+
+    match result {
+        Ok((username, recorded)) => {
+            println!("✓ Recorded metrics for {} posts by @{}", recorded, username);
+            Ok(())
+        }
+        Err(e) => {
+            eprintln!("✗ {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// A screen of the interactive session; F1-F4 switch between them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Tab {
+    Compose,
+    Stats,
+    Drafts,
+    Queue,
+}
+
+impl Tab {
+    const ALL: [Tab; 4] = [Tab::Compose, Tab::Stats, Tab::Drafts, Tab::Queue];
+
+    fn from_function_key(n: u8) -> Option<Tab> {
+        Tab::ALL.get(usize::from(n).checked_sub(1)?).copied()
+    }
+
+    /// The tab whose title the tab bar draws at `column`, given ratatui's one-cell padding
+    /// on each side of a title and a one-cell divider.
+    fn at_column(column: u16) -> Option<Tab> {
+        let mut x = 0;
+        for tab in Tab::ALL {
+            let width = tab.title().chars().count() as u16 + 2;
+            if (x..x + width).contains(&column) {
+                return Some(tab);
+            }
+            x += width + 1;
+        }
+        None
+    }
+
+    fn title(&self) -> &'static str {
+        match self {
+            Tab::Compose => "F1 Compose",
+            Tab::Stats => "F2 Stats",
+            Tab::Drafts => "F3 Drafts",
+            Tab::Queue => "F4 Queue",
+        }
+    }
+}
+
+/// The composer plus the channels to its posting task and background lookups.
+struct ComposeTab {
+    app: App<'static>,
+    images: Vec<Vec<u8>>,
+    post_tx: mpsc::Sender<PostCommand>,
+    result_rx: mpsc::Receiver<(u64, Vec<PostOutcome>)>,
+    events_tx: mpsc::Sender<ComposeEvent>,
+    events_rx: mpsc::Receiver<ComposeEvent>,
+    /// The posting task, waited for on exit so a post on its way isn't cut off.
+    post_task: tokio::task::JoinHandle<()>,
+    /// Calls off the post in progress, if it hasn't reached X yet.
+    cancel: posting::Cancel,
+    /// A post to open on the stats tab (account and post ID), asked for from the success screen.
+    show_in_stats: Option<(String, String)>,
+}
+
+struct StatsTab {
+    app: stats_ui::StatsApp,
+    /// Client for the account being shown, which `a` switches between `accounts`.
+    client: TwitterClient,
+    accounts: Vec<AccountConfig>,
+    events_tx: mpsc::Sender<StatsEvent>,
+    events_rx: mpsc::Receiver<StatsEvent>,
+    /// The feed is fetched the first time the tab is opened.
+    loaded: bool,
+}
+
+type QueueResult = (queue::QueuedPost, Result<(String, posting::Crossposts), posting::PostError>);
+
+struct QueueTab {
+    app: queue_ui::QueueApp,
+    result_tx: mpsc::Sender<QueueResult>,
+    result_rx: mpsc::Receiver<QueueResult>,
+    /// A failed post being retried, waited for on exit like the composer's.
+    publishing: Option<tokio::task::JoinHandle<()>>,
+}
+
+/// Everything on screen in one terminal session, sharing the active account's client.
+struct Session {
+    tab: Tab,
+    compose: ComposeTab,
+    stats: StatsTab,
+    queue: QueueTab,
+}
+
+impl Session {
+    /// Points the stats tab at credentials re-entered for `account`; the composer's posting
+    /// task has already been told.
+    fn use_credentials(&mut self, account: &str, twitter: &TwitterConfig) {
+        let stats = &mut self.stats;
+        if let Some(stats_account) = stats.accounts.iter_mut().find(|stats_account| stats_account.name == account) {
+            stats_account.twitter = twitter.clone();
+        }
+        if stats.app.account == account {
+            stats.client = TwitterClient::new(twitter.clone());
+        }
+    }
+
+    /// Opens `account`'s own posts on the stats tab with `tweet_id` selected once they load.
+    /// They're fetched again so a post just made is in the list.
+    fn show_post(&mut self, account: &str, tweet_id: String) {
+        let stats = &mut self.stats;
+        if stats.app.account != account {
+            if let Some(config) = stats.accounts.iter().find(|config| config.name == account) {
+                stats.client = TwitterClient::new(config.twitter.clone());
+            }
+        }
+        stats.app.feed = stats_ui::Feed::Mine;
+        stats.app.switch_account(account.to_string());
+        stats.app.focus_tweet = Some(tweet_id);
+        stats.loaded = false;
+        self.switch_to(Tab::Stats);
+    }
+
+    /// The drafts tab is the composer's draft browser, so it follows the compose state.
+    fn current_tab(&self) -> Tab {
+        match self.tab {
+            Tab::Compose | Tab::Drafts
+                if matches!(
+                    self.compose.app.state,
+                    AppState::DraftBrowser | AppState::VersionPicker | AppState::TagPrompt | AppState::Search
+                ) =>
+            {
+                Tab::Drafts
+            }
+            Tab::Compose | Tab::Drafts => Tab::Compose,
+            tab => tab,
+        }
+    }
+
+    fn switch_to(&mut self, tab: Tab) {
+        let compose = &mut self.compose.app;
+        match tab {
+            Tab::Compose
+                if matches!(
+                    compose.state,
+                    AppState::DraftBrowser | AppState::VersionPicker | AppState::TagPrompt | AppState::Search
+                ) =>
+            {
+                compose.state = AppState::Composing;
+            }
+            Tab::Drafts
+                if matches!(
+                    compose.state,
+                    AppState::Composing | AppState::DraftBrowser | AppState::VersionPicker | AppState::Search
+                ) =>
+            {
+                compose.load_drafts();
+                compose.state = AppState::DraftBrowser;
+            }
+            // Leave pickers and prompts alone rather than losing what's in them
+            Tab::Drafts => {
+                compose.notice = Some(t("status.finish_first").to_string());
+                self.tab = Tab::Compose;
+                return;
+            }
+            Tab::Stats if !self.stats.loaded && !self.stats.client.can_read() => {
+                self.stats.app.state = stats_ui::StatsState::Error(t("stats.no_read_access").to_string());
+                self.stats.loaded = true;
+            }
+            Tab::Stats if !self.stats.loaded => {
+                load_stats(&self.stats);
+                self.stats.loaded = true;
+            }
+            Tab::Queue => self.queue.app.reload(),
+            _ => {}
+        }
+        self.tab = tab;
+    }
+}
+
+fn setup_terminal() -> Result<Terminal<CrosstermBackend<io::Stdout>>> {
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture, EnableFocusChange, EnableBracketedPaste)?;
+    Ok(Terminal::new(CrosstermBackend::new(stdout))?)
+}
+
+fn restore_terminal(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<()> {
+    disable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture,
+        DisableFocusChange,
+        DisableBracketedPaste
+    )?;
+    terminal.show_cursor()?;
+    Ok(())
+}
+
+/// Runs the tabbed session, starting on `tab`. `target` decides what the composer posts
+/// and `feed` what the stats tab lists.
+async fn run_session(
+    mut config: Config,
+    account: AccountConfig,
+    target: ComposeTarget,
+    tab: Tab,
+    feed: stats_ui::Feed,
+) -> Result<()> {
+    // Before the terminal switches screens, so the passphrase prompt is visible
+    drafts::unlock()?;
+
+    let client = TwitterClient::new(account.twitter.clone());
+    let accent = account.profile.accent_color();
+
+    let (stats_tx, stats_rx) = mpsc::channel::<StatsEvent>(16);
+    let (queue_tx, queue_rx) = mpsc::channel::<QueueResult>(1);
+    let mut session = Session {
+        tab: Tab::Compose,
+        compose: start_compose(&config, &account, &client, target),
+        stats: StatsTab {
+            app: stats_ui::StatsApp::new(accent, feed),
+            client,
+            accounts: config.all_accounts(),
+            events_tx: stats_tx,
+            events_rx: stats_rx,
+            loaded: false,
+        },
+        queue: QueueTab {
+            app: queue_ui::QueueApp::new(accent),
+            result_tx: queue_tx,
+            result_rx: queue_rx,
+            publishing: None,
+        },
+    };
+    session.stats.app.time_format = config.display.time_format.clone();
+    session.stats.app.account = account.name.clone();
+    session.stats.app.accounts = config.all_accounts().into_iter().map(|account| account.name).collect();
+    session.switch_to(tab);
+
+    let mut terminal = setup_terminal()?;
+    let result = run_session_loop(&mut terminal, &mut session, &mut config).await;
+    restore_terminal(&mut terminal)?;
+
+    if let Err(err) = result {
+        eprintln!("Error: {}", err);
+    }
+    finish_posting(&mut session, &config).await
+}
+
+/// Lets posts still on their way when the session ends finish instead of being dropped
+/// halfway, then prints how they went.
+async fn finish_posting(session: &mut Session, config: &Config) -> Result<()> {
+    let compose = &mut session.compose;
+    let unfinished: Vec<u64> = compose
+        .app
+        .background_posts
+        .iter()
+        .filter(|post| post.outcomes.is_none())
+        .map(|post| post.id)
+        .collect();
+    let posting = compose.app.state == AppState::Posting;
+    match unfinished.len() + posting as usize {
+        0 => {}
+        1 => println!("Waiting for the post in progress to finish..."),
+        n => println!("Waiting for the {} posts in progress to finish...", n),
+    }
+    // Dropping the sender lets the task end once it's through with what it was given
+    compose.post_tx = mpsc::channel(1).0;
+    let _ = (&mut compose.post_task).await;
+    drain_compose_events(compose, &config.notifications);
+
+    let print_outcomes = |outcomes: &[PostOutcome]| {
+        for outcome in outcomes {
+            match &outcome.result {
+                Ok(_) => println!("✓ {}: {}", outcome.account, outcome.url().unwrap_or_default()),
+                Err(e) => println!("✗ {}: {}", outcome.account, e),
+            }
+        }
+    };
+    for post in compose.app.background_posts.iter().filter(|post| unfinished.contains(&post.id)) {
+        print_outcomes(post.outcomes.as_deref().unwrap_or_default());
+    }
+    if posting {
+        match &compose.app.state {
+            AppState::Success(outcomes) => print_outcomes(outcomes),
+            AppState::Error(e) => println!("✗ {}", e),
+            _ => {}
+        }
+    }
+
+    if let Some(publishing) = session.queue.publishing.take() {
+        if !publishing.is_finished() {
+            println!("Waiting for the queued post being retried to finish...");
+        }
+        let _ = publishing.await;
+        drain_queue_results(&mut session.queue)?;
+        if let Some(status) = session.queue.app.status.take() {
+            println!("{}", status);
+        }
+    }
+    Ok(())
+}
+
+async fn run_session_loop(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    session: &mut Session,
+    config: &mut Config,
+) -> Result<()> {
+    loop {
+        let current = session.current_tab();
+        terminal.draw(|f| {
+            if ui::draw_too_small(f) {
+                theme::finish(f);
+                return;
+            }
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(1), Constraint::Min(0)])
+                .split(f.area());
+            let titles: Vec<&str> = Tab::ALL.iter().map(Tab::title).collect();
+            let selected = Tab::ALL.iter().position(|tab| *tab == current).unwrap_or(0);
+            ui::draw_tab_bar(f, &titles, selected, session.compose.app.accent, chunks[0]);
+
+            match current {
+                Tab::Compose | Tab::Drafts => ui::draw(f, &mut session.compose.app, chunks[1]),
+                Tab::Stats => stats_ui::draw(f, &mut session.stats.app, chunks[1]),
+                Tab::Queue => queue_ui::draw(f, &mut session.queue.app, chunks[1]),
+            }
+            theme::finish(f);
+        })?;
+
+        if let Some((account, twitter)) = drain_compose_events(&mut session.compose, &config.notifications) {
+            config.set_credentials(&account, &twitter);
+            session.use_credentials(&account, &twitter);
+        }
+        drain_stats_events(&mut session.stats);
+        drain_queue_results(&mut session.queue)?;
+
+        if event::poll(std::time::Duration::from_millis(100))? {
+            let event = event::read()?;
+            if let Event::Key(KeyEvent { code: KeyCode::F(n), .. }) = event {
+                if let Some(tab) = Tab::from_function_key(n) {
+                    session.switch_to(tab);
+                    continue;
+                }
+            }
+
+            // Clicks and scrolling stand in for the key presses they mean on the current screen
+            let key = match event {
+                Event::Key(key) => Some(key),
+                Event::Mouse(mouse) if mouse.row == 0 => {
+                    if mouse.kind == MouseEventKind::Down(MouseButton::Left) {
+                        if let Some(tab) = Tab::at_column(mouse.column) {
+                            session.switch_to(tab);
+                        }
+                    }
+                    None
+                }
+                Event::Mouse(mouse) => match current {
+                    Tab::Compose | Tab::Drafts => compose_mouse_key(&mut session.compose.app, mouse),
+                    Tab::Stats => stats_mouse_key(&mut session.stats.app, mouse),
+                    Tab::Queue => queue_mouse_key(&mut session.queue.app, mouse),
+                },
+                Event::Paste(text) => {
+                    if matches!(current, Tab::Compose | Tab::Drafts) {
+                        compose_paste(&mut session.compose.app, &text);
+                    }
+                    None
+                }
+                Event::FocusLost => {
+                    session.compose.app.focused = false;
+                    None
+                }
+                Event::FocusGained => {
+                    session.compose.app.focused = true;
+                    None
+                }
+                _ => None,
+            };
+
+            if let Some(key) = key {
+                let quit = match current {
+                    Tab::Compose | Tab::Drafts => handle_compose_key(&mut session.compose, key, config).await?,
+                    Tab::Stats => handle_stats_key(&mut session.stats, key),
+                    Tab::Queue => handle_queue_key(&mut session.queue, key, config)?,
+                };
+                if quit {
+                    return Ok(());
+                }
+                if let Some((account, tweet_id)) = session.compose.show_in_stats.take() {
+                    session.show_post(&account, tweet_id);
+                }
+            }
+        }
+    }
+}
+
+/// Sets up the composer for `target` and spawns the task that posts for it.
+fn start_compose(
+    config: &Config,
+    account: &AccountConfig,
+    client: &TwitterClient,
+    target: ComposeTarget,
+) -> ComposeTab {
+    let mut app = App::new(account.profile.accent_color());
+    match target {
+        ComposeTarget::Post => {}
+        ComposeTarget::DirectMessage(username) => app.dm_recipient = Some(username),
+        ComposeTarget::Reply(tweet_id) => app.reply_to = Some(tweet_id),
+        ComposeTarget::Text(text) => app.set_text(text),
+    }
+
+    let mut accounts: Vec<(AccountConfig, TwitterClient)> = config
+        .all_accounts()
+        .into_iter()
+        .map(|other| {
+            let other_client = if other.name == account.name {
+                client.clone()
+            } else {
+                TwitterClient::new(other.twitter.clone())
+            };
+            (other, other_client)
+        })
+        .collect();
+    app.set_accounts(
+        accounts.iter().map(|(account, _)| account.name.clone()).collect(),
+        &account.name,
+    );
+    app.set_communities(config.communities.clone());
+
+    if let Some(assist) = &config.assist {
+        app.assist_prompts = assist::DEFAULT_PROMPTS
+            .iter()
+            .map(|prompt| prompt.to_string())
+            .chain(assist.prompts.iter().cloned())
+            .collect();
+        app.assist_list_state.select(Some(0));
+    }
+
+    app.grammar_enabled = config.grammar.is_some();
+    app.time_format = config.display.time_format.clone();
+    app.post_caps = config
+        .all_accounts()
+        .into_iter()
+        .filter_map(|account| Some((account.name, account.twitter.tier?.monthly_post_cap())))
+        .collect();
+    app.crosspost_targets = config
+        .all_accounts()
+        .into_iter()
+        .map(|account| {
+            let targets = config
+                .targets
+                .iter()
+                .filter(|target| account.profile.targets.contains(&target.name))
+                .cloned()
+                .collect();
+            (account.name, targets)
+        })
+        .collect();
+    app.alt_text_policies = config
+        .all_accounts()
+        .into_iter()
+        .filter_map(|account| Some((account.name, account.profile.alt_text?)))
+        .collect();
+    app.paste_cleanup = config.paste.cleanup.clone();
+    app.streak_reminder = config.display.streak_reminder;
+    app.zen_width = config.display.zen_width.unwrap_or(ui::ZEN_WIDTH);
+    app.typewriter = config.display.typewriter;
+    app.refresh_post_counts();
+
+    if app.dm_recipient.is_none() {
+        app.delete_after = account.profile.delete_after.as_deref().and_then(history::parse_duration);
+    }
+
+    let (events_tx, events_rx) = mpsc::channel::<ComposeEvent>(10);
+
+    // Long posts need a paid tier; ask the API unless the profile says which it is
+    match account.profile.premium {
+        _ if app.dm_recipient.is_some() => app.char_limit = ui::DM_CHAR_LIMIT,
+        Some(premium) => app.set_premium(premium),
+        None => {
+            app.char_limit_unsure = true;
+            let client = client.clone();
+            let events_tx = events_tx.clone();
+            tokio::spawn(async move {
+                let premium = client.get_current_user().await.ok().map(|user| user.is_premium());
+                let _ = events_tx.send(ComposeEvent::Premium(premium)).await;
+            });
+        }
+    }
+
+    if let Some(tweet_id) = app.reply_to.clone().filter(|_| client.can_read()) {
+        let client = client.clone();
+        let events_tx = events_tx.clone();
+        tokio::spawn(async move {
+            let participants = client.get_reply_participants(&tweet_id).await;
+            let _ = events_tx.send(ComposeEvent::Participants(participants)).await;
+        });
+    }
+
+    let active_account = account.name.clone();
+    let posting_config = config.clone();
+
+    let (post_tx, mut post_rx) = mpsc::channel::<PostCommand>(10);
+    let (result_tx, result_rx) = mpsc::channel::<(u64, Vec<PostOutcome>)>(10);
+    let progress_tx = events_tx.clone();
+
+    let post_task = tokio::spawn(async move {
+        while let Some(cmd) = post_rx.recv().await {
+            match cmd {
+                PostCommand::DirectMessage { id, username, text, cancel } => {
+                    let Some((_, client)) = accounts.iter().find(|(a, _)| a.name == active_account) else {
+                        continue;
+                    };
+                    let send = send_direct_message(client, &username, text, &cancel);
+                    let Some(result) = until_done(send, &cancel).await else {
+                        continue;
+                    };
+                    let result = result.map(|id| vec![id]);
+                    let (result, auth_problem) = posting::explain_auth_failure(result, client);
+                    let _ = result_tx.send((id, vec![PostOutcome {
+                        account: active_account.clone(),
+                        result,
+                        crossposts: Vec::new(),
+                        auth_problem,
+                        username: None,
+                    }])).await;
+                }
+                PostCommand::Reauth { account, twitter } => {
+                    if let Some((config, client)) = accounts.iter_mut().find(|(a, _)| a.name == account) {
+                        config.twitter = twitter;
+                        *client = TwitterClient::new(config.twitter.clone());
+                    }
+                }
+                PostCommand::Post { id, text, images, alt_texts, accounts: targets, community_id, reply, delete_after, cancel } => {
+                    let progress = |done, total| {
+                        let _ = progress_tx.try_send(ComposeEvent::UploadProgress(id, done, total));
+                    };
+                    let mut outcomes = Vec::new();
+                    for (account, client) in accounts.iter().filter(|(a, _)| targets.contains(&a.name)) {
+                        let composed = text.clone();
+                        let (text, _) = crosspost::split_overrides(&composed);
+                        let text = account.profile.apply_signature(text);
+                        let options = PostOptions {
+                            reply_settings: account.profile.reply_settings.clone(),
+                            community_id: community_id.clone(),
+                            reply: reply.clone(),
+                            alt_texts: alt_texts.clone(),
+                        };
+                        let publish = posting::publish(
+                            account,
+                            client,
+                            text.clone(),
+                            images.clone(),
+                            &options,
+                            delete_after,
+                            &posting_config,
+                            &progress,
+                            &cancel,
+                        );
+                        let Some(result) = until_done(publish, &cancel).await else {
+                            break;
+                        };
+                        let failure = result.as_ref().err().filter(|e| !e.vetoed).cloned();
+                        let (result, auth_problem) = posting::explain_auth_failure(result.map_err(|e| e.message), client);
+                        // Keep failed posts in the outbox so they can be retried from `xpost queue`,
+                        // unless the pre_post hook turned them down
+                        if let (Some(mut failure), Err(e)) = (failure, &result) {
+                            failure.message = e.clone();
+                            let mut post = queue::QueuedPost::new(&account.name, text.clone(), chrono::Utc::now());
+                            post.fail(&failure);
+                            post.image_count = images.len();
+                            post.alt_texts = alt_texts.clone();
+                            post.community_id = community_id.clone();
+                            post.reply = reply.clone();
+                            post.delete_after_secs = delete_after.map(|after| after.num_seconds());
+                            let _ = queue::save_post(&post, &images);
+                        }
+                        let (crossposts, username) = match &result {
+                            Ok(_) => (
+                                posting::crosspost(account, &composed, &images, &alt_texts, &posting_config).await,
+                                client.username().await,
+                            ),
+                            Err(_) => (Vec::new(), None),
+                        };
+                        outcomes.push(PostOutcome {
+                            account: account.name.clone(),
+                            result,
+                            crossposts,
+                            auth_problem,
+                            username,
+                        });
+                    }
+                    // The composer went back to editing when it was called off
+                    if !cancel.is_cancelled() {
+                        let _ = result_tx.send((id, outcomes)).await;
+                    }
+                }
+            }
+        }
+    });
+
+    ComposeTab {
+        app,
+        images: Vec::new(),
+        post_tx,
+        result_rx,
+        events_tx,
+        events_rx,
+        post_task,
+        cancel: posting::Cancel::default(),
+        show_in_stats: None,
+    }
+}
+
+/// Runs a post or message from the composer to the end, unless it's called off first (`None`)
+/// or X takes longer than `POST_TIMEOUT`, which fails it.
+async fn until_done<T, E: From<String>>(
+    post: impl std::future::Future<Output = Result<T, E>>,
+    cancel: &posting::Cancel,
+) -> Option<Result<T, E>> {
+    let result = tokio::select! {
+        result = tokio::time::timeout(posting::POST_TIMEOUT, post) => result,
+        _ = cancel.cancelled() => return None,
+    };
+    // Called off just as it finished: it never reached X
+    if cancel.is_cancelled() {
+        return None;
+    }
+    let minutes = posting::POST_TIMEOUT.as_secs() / 60;
+    Some(result.unwrap_or_else(|_| {
+        Err(if cancel.is_sent() {
+            tf("status.post_timed_out", &[&minutes])
+        } else {
+            tf("status.post_gave_up", &[&minutes])
+        }
+        .into())
+    }))
+}
+
+fn clicked_in(mouse: &MouseEvent, area: Rect) -> bool {
+    mouse.kind == MouseEventKind::Down(MouseButton::Left) && area.contains(Position::new(mouse.column, mouse.row))
+}
+
+/// A bracketed paste arrives as one event, so a long article goes in as a single edit
+/// rather than being replayed a key at a time.
+fn compose_paste(app: &mut App, text: &str) {
+    // Terminals send line breaks in pastes as carriage returns
+    let text = text.replace("\r\n", "\n").replace('\r', "\n");
+    match app.state {
+        AppState::Composing => {
+            let text = text::clean_paste(&text, &app.paste_cleanup);
+            // Like typing, the edit invalidates the grammar markers
+            let edited = app.textarea.insert_str(&text);
+            if edited {
+                app.clear_grammar_issues();
+            }
+        }
+        AppState::FilePrompt => app.file_path_input.push_str(text.trim()),
+        AppState::SchedulePrompt => {
+            app.schedule_input.push_str(text.trim());
+            app.schedule_picker.follow_typed(&app.schedule_input);
+        }
+        AppState::AltTextPrompt(_) => app.alt_text_input.push_str(&text.replace('\n', " ")),
+        AppState::ReauthPrompt => {
+            if let Some(reauth) = app.reauth.as_mut().filter(|reauth| !reauth.checking) {
+                reauth.input.push_str(text.trim());
+            }
+        }
+        _ => {}
+    }
+}
+
+fn compose_mouse_key(app: &mut App, mouse: MouseEvent) -> Option<KeyEvent> {
+    if clicked_in(&mouse, app.help_area) {
+        let inner = app.help_area.inner(Margin::new(1, 1));
+        return buttons::key_at(&ui::instructions(app), inner, false, mouse.column, mouse.row);
+    }
+
+    if app.state == AppState::Composing {
+        // Pass mouse events to TextArea for click-to-position and drag-to-select
+        app.textarea.input(Event::Mouse(mouse));
+        return None;
+    }
+
+    // Only lists and the preview scroll; elsewhere a stray key would dismiss the screen
+    let scrollable = app.state == AppState::Preview || app.selected_list_item().is_some();
+    match mouse.kind {
+        MouseEventKind::ScrollDown if scrollable => Some(KeyEvent::from(KeyCode::Down)),
+        MouseEventKind::ScrollUp if scrollable => Some(KeyEvent::from(KeyCode::Up)),
+        MouseEventKind::Down(MouseButton::Left) => {
+            let i = app.list_item_at(mouse.column, mouse.row)?;
+            let toggles = matches!(app.state, AppState::AccountPicker | AppState::ParticipantPicker);
+            if toggles {
+                app.select_list_item(i);
+                Some(KeyEvent::from(KeyCode::Char(' ')))
+            } else if app.selected_list_item() == Some(i) {
+                // Clicking the highlighted item opens it
+                Some(KeyEvent::from(KeyCode::Enter))
+            } else {
+                app.select_list_item(i);
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Applies background results to the composer. Returns the account and credentials X
+/// accepted after they were re-entered, for the other tabs to use too.
+fn drain_compose_events(tab: &mut ComposeTab, notifications: &NotificationConfig) -> Option<(String, TwitterConfig)> {
+    let app = &mut tab.app;
+    let mut reauthed = None;
+    while let Ok(event) = tab.events_rx.try_recv() {
+        match event {
+            ComposeEvent::Reauth(twitter, Ok(user)) => {
+                let Some(reauth) = app.reauth.take() else {
+                    continue;
+                };
+                app.notice = Some(match Config::save_credentials(&reauth.account, &twitter) {
+                    Ok(true) => {
+                        let path = Config::config_path().map(|path| path.display().to_string()).unwrap_or_default();
+                        tf("status.reauthed", &[&user.username, &path])
+                    }
+                    Ok(false) => tf("status.reauthed_session", &[&user.username]),
+                    Err(e) => tf("status.reauth_save_failed", &[&format!("{:#}", e)]),
+                });
+                let _ = tab.post_tx.try_send(PostCommand::Reauth {
+                    account: reauth.account.clone(),
+                    twitter: twitter.clone(),
+                });
+                app.reauth_offer = None;
+                app.reset();
+                tab.images.clear();
+                reauthed = Some((reauth.account, twitter));
+            }
+            ComposeEvent::Reauth(_, Err(e)) => {
+                if let Some(reauth) = app.reauth.as_mut() {
+                    reauth.restart();
+                    app.notice = Some(tf("status.reauth_failed", &[&e]));
+                }
+            }
+            ComposeEvent::Premium(Some(premium)) => app.set_premium(premium),
+            ComposeEvent::Premium(None) => {
+                app.notice = Some(tf("status.premium_unknown", &[&app.char_limit]));
+            }
+            ComposeEvent::Participants(Ok(participants)) => app.set_participants(participants),
+            ComposeEvent::Participants(Err(e)) => {
+                app.state = AppState::Error(tf("status.conversation_failed", &[&e]));
+            }
+            // Ignore a late reply if the request was cancelled with Esc
+            ComposeEvent::Assist(_) if app.state != AppState::AssistPending => {}
+            ComposeEvent::Assist(Ok(suggestion)) => {
+                app.assist_suggestion = Some(suggestion);
+                app.state = AppState::AssistDiff;
+            }
+            ComposeEvent::Assist(Err(e)) => {
+                app.state = AppState::Composing;
+                app.notice = Some(tf("status.failed", &[&e]));
+            }
+            ComposeEvent::Grammar(_) if app.state != AppState::GrammarPending => {}
+            ComposeEvent::Grammar(Ok(issues)) if issues.is_empty() => {
+                app.state = AppState::Composing;
+                app.notice = Some(t("status.no_grammar_issues_found").to_string());
+            }
+            ComposeEvent::Grammar(Ok(issues)) => {
+                app.set_grammar_issues(issues);
+                app.state = AppState::GrammarPopup;
+            }
+            ComposeEvent::Grammar(Err(e)) => {
+                app.state = AppState::Composing;
+                app.notice = Some(tf("status.failed", &[&e]));
+            }
+            ComposeEvent::ShortUrls(results) => {
+                app.shortening = false;
+                for (url, result) in results {
+                    match result {
+                        Ok(short) => {
+                            app.short_urls.insert(url, short);
+                        }
+                        Err(e) => app.notice = Some(tf("status.failed", &[&e])),
+                    }
+                }
+            }
+            ComposeEvent::UploadProgress(id, done, total) if id == app.posting_id => {
+                app.upload_progress = Some((done, total));
+            }
+            ComposeEvent::UploadProgress(id, done, total) => {
+                if let Some(post) = app.background_posts.iter_mut().find(|post| post.id == id) {
+                    post.upload_progress = Some((done, total));
+                }
+            }
+            ComposeEvent::LinkCard(result) => {
+                app.link_card_pending = None;
+                match result {
+                    Ok(card) => app.link_card = Some(card),
+                    Err(e) => app.notice = Some(tf("status.card_failed", &[&e])),
+                }
+            }
+        }
+    }
+
+    app.prune_background_posts();
+    while let Ok((id, outcomes)) = tab.result_rx.try_recv() {
+        if !app.focused {
+            notify_outcomes(notifications, &outcomes, app.dm_recipient.as_deref());
+        }
+        notify::bell(notifications);
+        if notifications.flash {
+            app.flash_until = Some(std::time::Instant::now() + std::time::Duration::from_millis(800));
+        }
+        app.refresh_post_counts();
+
+        if let Some(index) = app.background_posts.iter().position(|post| post.id == id) {
+            let draft = app.background_posts[index].draft.clone();
+            archive_posted(app, draft, &outcomes);
+            let post = &mut app.background_posts[index];
+            post.outcomes = Some(outcomes);
+            post.finished_at = Some(std::time::Instant::now());
+            continue;
+        }
+
+        let draft = match &app.posting_draft {
+            Some(draft) => Some((draft.id.clone(), draft.content.clone())),
+            None => app.current_draft_id.clone().map(|id| (id, app.get_text())),
+        };
+        if archive_posted(app, draft, &outcomes) && app.posting_draft.is_none() {
+            app.current_draft_id = None;
+        }
+        app.reauth_offer = outcomes.iter().find_map(|outcome| {
+            let problem = outcome.auth_problem.filter(|problem| problem.needs_credentials())?;
+            Some((outcome.account.clone(), problem))
+        });
+        if outcomes.iter().all(|outcome| outcome.result.is_err()) {
+            let msg = match outcomes.as_slice() {
+                [PostOutcome { result: Err(e), .. }] => e.clone(),
+                _ => outcomes
+                    .iter()
+                    .filter_map(|outcome| {
+                        outcome.result.as_ref().err().map(|e| format!("{}: {}", outcome.account, e))
+                    })
+                    .collect::<Vec<_>>()
+                    .join("; "),
+            };
+            app.state = AppState::Error(msg);
+        } else {
+            app.state = AppState::Success(outcomes);
+        }
+    }
+    reauthed
+}
+
+/// Moves the draft a post was made from (ID and text) to the archive once it's out, so the
+/// browser only lists unpublished work. Returns whether it did.
+fn archive_posted(app: &mut App, draft: Option<(String, String)>, outcomes: &[PostOutcome]) -> bool {
+    let posted = outcomes
+        .iter()
+        .find_map(|outcome| Some((&outcome.account, outcome.result.as_ref().ok()?.first()?)))
+        .filter(|_| app.dm_recipient.is_none());
+    let (Some((draft_id, text)), Some((account, tweet_id))) = (draft, posted) else {
+        return false;
+    };
+    match drafts::archive_draft(&draft_id, text, account, tweet_id) {
+        Ok(()) => true,
+        Err(e) => {
+            app.notice = Some(tf("status.archive_failed", &[&format!("{:#}", e)]));
+            false
+        }
+    }
+}
+
+/// Starts a post from the composer: the ID its results come back with, and a new handle to
+/// cancel it with.
+fn next_post(app: &mut App, cancel: &mut posting::Cancel) -> (u64, posting::Cancel) {
+    app.posting_id += 1;
+    app.upload_progress = None;
+    app.state = AppState::Posting;
+    *cancel = posting::Cancel::default();
+    (app.posting_id, cancel.clone())
+}
+
+/// Handles a key on the compose and drafts tabs; returns true to quit.
+async fn handle_compose_key(tab: &mut ComposeTab, key: KeyEvent, config: &Config) -> Result<bool> {
+    let ComposeTab { app, images, post_tx, events_tx, cancel, show_in_stats, .. } = tab;
+    app.notice = None;
+    match app.state {
+        AppState::Composing => {
+            match (key.code, key.modifiers) {
+                (KeyCode::Esc, _) if app.zen => {
+                    app.zen = false;
+                }
+                (KeyCode::Esc, _) => {
+                    return Ok(true);
+                }
+                (KeyCode::Char('c'), m) if m == KeyModifiers::CONTROL => {
+                    return Ok(true);
+                }
+                (KeyCode::Char('c'), m) if m == (KeyModifiers::CONTROL | KeyModifiers::SHIFT) => {
+                    // Copy text - handled by TextArea
+                    app.textarea.input(key);
+                }
+                (KeyCode::Char('v'), m) if m == (KeyModifiers::CONTROL | KeyModifiers::SHIFT) => {
+                    // Paste text - handled by TextArea
+                    app.textarea.input(key);
+                }
+                (KeyCode::Char('v'), KeyModifiers::CONTROL) => {
+                    match clipboard::get_image_from_clipboard() {
+                        Ok(img_data) => {
+                            let name = format!("clipboard {}", chrono::Local::now().format("%Y-%m-%d %H:%M"));
+                            let _ = media::remember(&name, &img_data);
+                            attach_image(app, images, img_data);
+                        }
+                        Err(e) => {
+                            app.state = AppState::Error(tf("status.image_error", &[&e]));
+                        }
+                    }
+                }
+                (KeyCode::Char('i'), KeyModifiers::ALT) => {
+                    images.clear();
+                    app.image_count = 0;
+                    app.alt_texts.clear();
+                }
+                (KeyCode::Char('t'), KeyModifiers::ALT) if app.image_count > 0 => {
+                    app.edit_alt_text(0);
+                }
+                (KeyCode::Char('u'), KeyModifiers::CONTROL) => {
+                    app.state = AppState::FilePrompt;
+                    app.file_path_input.clear();
+                }
+                (KeyCode::Char('l'), KeyModifiers::CONTROL) => {
+                    app.load_media();
+                    app.state = AppState::MediaPicker;
+                }
+                (KeyCode::Char('s'), KeyModifiers::CONTROL) => {
+                    let text = app.get_text();
+                    if !text.trim().is_empty() {
+                        let draft = if let Some(draft_id) = &app.current_draft_id {
+                            // Update existing draft
+                            match app.drafts.iter_mut().find(|d| &d.id == draft_id) {
+                                // Edited on another machine since it was loaded: keep both
+                                Some(existing) if drafts::changed_elsewhere(existing) => {
+                                    app.notice = Some(t("status.draft_forked").to_string());
+                                    drafts::Draft::new(text)
+                                }
+                                Some(existing) => {
+                                    existing.update_content(text.clone());
+                                    existing.clone()
+                                }
+                                None => drafts::Draft::new(text),
+                            }
+                        } else {
+                            // Create new draft
+                            drafts::Draft::new(text)
+                        };
+
+                        if let Err(e) = drafts::save_draft(&draft) {
+                            app.state = AppState::Error(tf("status.save_failed", &[&e]));
+                        } else {
+                            app.current_draft_id = Some(draft.id.clone());
+                            if let Err(e) = drafts::commit("Save draft") {
+                                app.notice = Some(tf("status.sync_failed", &[&format!("{:#}", e)]));
+                            }
+                        }
+                    }
+                }
+                (KeyCode::Char('d'), KeyModifiers::CONTROL) => {
+                    app.load_drafts();
+                    app.state = AppState::DraftBrowser;
+                }
+                (KeyCode::Char('t'), KeyModifiers::CONTROL) if app.accounts.len() > 1 => {
+                    app.state = AppState::AccountPicker;
+                }
+                (KeyCode::Char('g'), KeyModifiers::CONTROL) if !app.communities.is_empty() => {
+                    app.state = AppState::CommunityPicker;
+                }
+                (KeyCode::Char('e'), KeyModifiers::ALT) if !app.participants.is_empty() => {
+                    app.state = AppState::ParticipantPicker;
+                }
+                (KeyCode::Char('a'), KeyModifiers::ALT) if !app.assist_prompts.is_empty() => {
+                    if !app.get_text().trim().is_empty() {
+                        app.state = AppState::AssistPicker;
+                    }
+                }
+                (KeyCode::Char('f'), KeyModifiers::ALT) => {
+                    if app.textarea.selection_range().is_some() {
+                        app.format_list_state.select(Some(0));
+                        app.state = AppState::FormatPicker;
+                    } else {
+                        app.notice = Some(t("status.select_text").to_string());
+                    }
+                }
+                (KeyCode::Char('g'), KeyModifiers::ALT) if app.grammar_enabled => {
+                    if !app.grammar_issues.is_empty() {
+                        app.state = AppState::GrammarPopup;
+                    } else if let Some(grammar_config) = config.grammar.clone() {
+                        app.state = AppState::GrammarPending;
+                        let text = app.get_text();
+                        let events_tx = events_tx.clone();
+                        tokio::spawn(async move {
+                            let issues = grammar::check(&grammar_config, &text).await;
+                            let _ = events_tx.send(ComposeEvent::Grammar(issues)).await;
+                        });
+                    }
+                }
+                (KeyCode::Char('s'), KeyModifiers::ALT) if app.dm_recipient.is_none() && app.reply_to.is_none() => {
+                    let text = app.post_text();
+                    let accounts = app.selected_accounts();
+                    if !text.trim().is_empty() {
+                        if let Some(warning) = blocklist_warning(app, config, &accounts, &text, t("status.schedule_anyway")) {
+                            app.notice = Some(warning);
+                        } else {
+                            app.schedule_input.clear();
+                            app.schedule_picker = SchedulePicker::default();
+                            app.state = AppState::SchedulePrompt;
+                        }
+                    }
+                }
+                (KeyCode::Char('x'), KeyModifiers::ALT) if app.dm_recipient.is_none() => {
+                    app.cycle_delete_after();
+                }
+                (KeyCode::Char('z'), KeyModifiers::ALT) => {
+                    app.zen = !app.zen;
+                }
+                (KeyCode::Char('m'), KeyModifiers::ALT) => {
+                    export_thread(app, &app.get_text(), None);
+                }
+                (KeyCode::Char('c'), KeyModifiers::ALT) if !app.get_text().trim().is_empty() => {
+                    // X's version, without the overrides for other networks
+                    let (text, _) = crosspost::split_overrides(&app.post_text());
+                    let name = app.selected_accounts().first().cloned().unwrap_or_default();
+                    app.notice = Some(match card::export(&text, &name, app.accent) {
+                        Ok(path) => tf("status.card_saved", &[&path.display()]),
+                        Err(e) => tf("status.card_export_failed", &[&format!("{:#}", e)]),
+                    });
+                }
+                (KeyCode::Char('o'), KeyModifiers::CONTROL) => {
+                    app.preview_scroll = 0;
+                    app.state = AppState::Preview;
+                    fetch_link_card(app, events_tx);
+                    if let Some(shortener) = &config.shortener {
+                        shorten_links(app, shortener, events_tx);
+                    }
+                }
+                (KeyCode::Char('p'), KeyModifiers::CONTROL) if app.dm_recipient.is_some() => {
+                    let text = app.get_text();
+                    if !text.trim().is_empty() {
+                        let username = app.dm_recipient.clone().unwrap_or_default();
+                        let (id, cancel) = next_post(app, cancel);
+                        let _ = post_tx.send(PostCommand::DirectMessage { id, username, text, cancel }).await;
+                    }
+                }
+                (KeyCode::Char('p'), KeyModifiers::CONTROL) => {
+                    let text = app.post_text();
+                    let accounts = app.selected_accounts();
+                    if accounts.is_empty() {
+                        app.state = AppState::Error(t("status.no_accounts").to_string());
+                    } else if app.is_over_limit() {
+                        app.state = AppState::Error(tf("status.over_limit", &[&app.char_count(), &app.char_limit]));
+                    } else if let Some(message) = alt_text_blocked(app, &accounts) {
+                        app.notice = Some(message);
+                    } else if let Some(warning) = alt_text_warning(app, &accounts) {
+                        app.notice = Some(warning);
+                        app.alt_text_warned = true;
+                    } else if let Some(warning) = blocklist_warning(app, config, &accounts, &text, t("status.post_anyway")) {
+                        app.notice = Some(warning);
+                    } else if let Some(warning) = monthly_cap_warning(config, &accounts, &text).filter(|_| !app.cap_warned) {
+                        app.notice = Some(warning);
+                        app.cap_warned = true;
+                    } else if let Some(at) = quiet_hours_slot(app, config).filter(|_| !text.trim().is_empty()) {
+                        app.quiet_warned = true;
+                        app.schedule_input.clear();
+                        app.schedule_picker = SchedulePicker::new(at.with_timezone(&chrono::Utc));
+                        app.state = AppState::SchedulePrompt;
+                        app.notice = Some(tf("status.quiet_hours", &[&at.format("%H:%M")]));
+                    } else if !text.trim().is_empty() {
+                        let (id, cancel) = next_post(app, cancel);
+                        let _ = post_tx.send(PostCommand::Post {
+                            id,
+                            text,
+                            images: images.clone(),
+                            alt_texts: app.alt_texts.clone(),
+                            accounts,
+                            community_id: app.community().map(|c| c.id.clone()),
+                            reply: app.reply_to.clone().map(|tweet_id| ReplyTo {
+                                in_reply_to_tweet_id: tweet_id,
+                                exclude_reply_user_ids: app.excluded_user_ids(),
+                            }),
+                            delete_after: app.delete_after,
+                            cancel,
+                        }).await;
+                    }
+                }
+                _ => {
+                    // Pass all other events to TextArea; edits invalidate the grammar markers
+                    if app.textarea.input(key) {
+                        app.clear_grammar_issues();
+                    }
+                }
+            }
+        }
+        AppState::DraftBrowser => {
+            let post_confirmed = std::mem::take(&mut app.post_draft_warned);
+            let delete_confirmed = std::mem::take(&mut app.delete_drafts_warned);
+            match key.code {
+                KeyCode::Esc => {
+                    app.state = AppState::Composing;
+                }
+                KeyCode::Down if key.modifiers.contains(KeyModifiers::SHIFT) && !app.show_archive => {
+                    app.move_selected_draft(false);
+                }
+                KeyCode::Up if key.modifiers.contains(KeyModifiers::SHIFT) && !app.show_archive => {
+                    app.move_selected_draft(true);
+                }
+                KeyCode::Down => {
+                    app.next_draft();
+                }
+                KeyCode::Up => {
+                    app.previous_draft();
+                }
+                KeyCode::Enter => {
+                    app.select_current_draft();
+                }
+                KeyCode::Delete if app.marked_drafts.is_empty() => {
+                    app.delete_selected_draft();
+                }
+                KeyCode::Delete if delete_confirmed => {
+                    app.delete_marked_drafts();
+                }
+                KeyCode::Delete => {
+                    app.notice = Some(tf("status.delete_marked", &[&app.marked_drafts.len()]));
+                    app.delete_drafts_warned = true;
+                }
+                KeyCode::Char(' ') => {
+                    app.toggle_mark_selected_draft();
+                }
+                KeyCode::Char('t') if app.selected_draft().is_some() => {
+                    app.tag_input.clear();
+                    app.state = AppState::TagPrompt;
+                }
+                KeyCode::Char('r') if app.selected_damaged_draft().is_some() => {
+                    app.repair_selected_draft();
+                }
+                KeyCode::Char('*') if !app.show_archive => {
+                    app.toggle_pin_selected_draft();
+                }
+                KeyCode::Char('p') if !app.show_archive => {
+                    if let Some(draft) = app.selected_draft().cloned() {
+                        let accounts = app.selected_accounts();
+                        let over_limit = thread::split_thread(&draft.content)
+                            .iter()
+                            .any(|part| text::weighted_len(part) > app.char_limit);
+                        if accounts.is_empty() {
+                            app.notice = Some(t("status.no_draft_accounts").to_string());
+                        } else if over_limit {
+                            app.notice = Some(tf("status.draft_over_limit", &[&app.char_limit]));
+                        } else if !post_confirmed {
+                            app.notice = Some(draft_post_confirmation(&accounts, &draft.content, config));
+                            app.post_draft_warned = true;
+                        } else {
+                            let (id, cancel) = next_post(app, cancel);
+                            let _ = post_tx.send(PostCommand::Post {
+                                id,
+                                text: draft.content.clone(),
+                                images: Vec::new(),
+                                alt_texts: Vec::new(),
+                                accounts,
+                                community_id: app.community().map(|c| c.id.clone()),
+                                reply: None,
+                                delete_after: app.delete_after,
+                                cancel,
+                            }).await;
+                            app.posting_draft = Some(draft);
+                        }
+                    }
+                }
+                KeyCode::Char('v') if !app.show_archive => {
+                    app.open_versions();
+                }
+                KeyCode::Char('a') => {
+                    app.toggle_archive();
+                }
+                KeyCode::Char('/') => {
+                    app.open_search();
+                }
+                KeyCode::Char('m') if app.marked_drafts.is_empty() => {
+                    let content = app
+                        .draft_list_state
+                        .selected()
+                        .and_then(|i| app.drafts.get(i))
+                        .map(|draft| draft.content.clone());
+                    if let Some(content) = content {
+                        export_thread(app, &content, None);
+                    }
+                }
+                KeyCode::Char('m') => {
+                    let texts: Vec<String> = app.bulk_drafts().iter().map(|draft| draft.content.clone()).collect();
+                    match thread::export_drafts_markdown(&texts) {
+                        Ok(path) => app.notice = Some(tf("status.exported_drafts", &[&texts.len(), &path.display()])),
+                        Err(e) => app.notice = Some(tf("status.export_failed", &[&e])),
+                    }
+                }
+                _ => {}
+            }
+        }
+        AppState::TagPrompt => {
+            match key.code {
+                KeyCode::Esc => {
+                    app.state = AppState::DraftBrowser;
+                }
+                KeyCode::Enter => {
+                    let tag = std::mem::take(&mut app.tag_input);
+                    app.tag_marked_drafts(&tag);
+                    app.state = AppState::DraftBrowser;
+                }
+                KeyCode::Char(c) => {
+                    app.tag_input.push(c);
+                }
+                KeyCode::Backspace => {
+                    app.tag_input.pop();
+                }
+                _ => {}
+            }
+        }
+        AppState::Search => {
+            match key.code {
+                KeyCode::Esc => {
+                    app.state = AppState::DraftBrowser;
+                }
+                KeyCode::Down => {
+                    app.next_search_result();
+                }
+                KeyCode::Up => {
+                    app.previous_search_result();
+                }
+                KeyCode::Enter => {
+                    app.select_search_result();
+                }
+                KeyCode::Char(c) => {
+                    app.search_input.push(c);
+                    app.update_search();
+                }
+                KeyCode::Backspace => {
+                    app.search_input.pop();
+                    app.update_search();
+                }
+                _ => {}
+            }
+        }
+        AppState::VersionPicker => {
+            match key.code {
+                KeyCode::Esc => {
+                    app.state = AppState::DraftBrowser;
+                }
+                KeyCode::Down => {
+                    app.next_version();
+                }
+                KeyCode::Up => {
+                    app.previous_version();
+                }
+                KeyCode::Enter => {
+                    app.restore_selected_version();
+                }
+                _ => {}
+            }
+        }
+        AppState::MediaPicker => {
+            match key.code {
+                KeyCode::Esc => {
+                    app.state = AppState::Composing;
+                }
+                KeyCode::Down => {
+                    app.next_media();
+                }
+                KeyCode::Up => {
+                    app.previous_media();
+                }
+                KeyCode::Enter => {
+                    if let Some(item) = app.selected_media().cloned() {
+                        match item.load() {
+                            Ok(img_data) => {
+                                let _ = media::touch(&item.id);
+                                app.state = AppState::Composing;
+                                attach_image(app, images, img_data);
+                            }
+                            Err(e) => {
+                                app.state = AppState::Error(tf("status.image_error", &[&e]));
+                            }
+                        }
+                    }
+                }
+                KeyCode::Delete => {
+                    if let Some(item) = app.selected_media().cloned() {
+                        let _ = media::forget(&item.id);
+                        app.load_media();
+                    }
+                }
+                _ => {}
+            }
+        }
+        AppState::AccountPicker => {
+            match key.code {
+                KeyCode::Esc | KeyCode::Enter => {
+                    app.state = AppState::Composing;
+                }
+                KeyCode::Down => {
+                    app.next_account();
+                }
+                KeyCode::Up => {
+                    app.previous_account();
+                }
+                KeyCode::Char(' ') => {
+                    app.toggle_selected_account();
+                }
+                _ => {}
+            }
+        }
+        AppState::Preview => {
+            match (key.code, key.modifiers) {
+                (KeyCode::Esc, _) | (KeyCode::Char('o'), KeyModifiers::CONTROL) => {
+                    app.state = AppState::Composing;
+                }
+                (KeyCode::Down, _) => app.scroll_preview_down(1),
+                (KeyCode::Up, _) => app.scroll_preview_up(1),
+                (KeyCode::PageDown, _) => app.scroll_preview_down(10),
+                (KeyCode::PageUp, _) => app.scroll_preview_up(10),
+                (KeyCode::Home, _) => app.preview_scroll = 0,
+                _ => {}
+            }
+        }
+        AppState::AssistPicker => {
+            match key.code {
+                KeyCode::Esc => {
+                    app.state = AppState::Composing;
+                }
+                KeyCode::Down => {
+                    app.next_assist_prompt();
+                }
+                KeyCode::Up => {
+                    app.previous_assist_prompt();
+                }
+                KeyCode::Enter => {
+                    if let (Some(assist), Some(prompt)) = (config.assist.clone(), app.selected_assist_prompt().cloned()) {
+                        app.state = AppState::AssistPending;
+                        let text = app.get_text();
+                        let events_tx = events_tx.clone();
+                        tokio::spawn(async move {
+                            let suggestion = assist::suggest(&assist, &prompt, &text).await;
+                            let _ = events_tx.send(ComposeEvent::Assist(suggestion)).await;
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+        AppState::GrammarPopup => {
+            match key.code {
+                KeyCode::Esc => {
+                    app.state = AppState::Composing;
+                }
+                KeyCode::Down => {
+                    app.next_grammar_issue();
+                }
+                KeyCode::Up => {
+                    app.previous_grammar_issue();
+                }
+                KeyCode::Enter => {
+                    app.apply_grammar_suggestion();
+                }
+                _ => {}
+            }
+        }
+        AppState::FormatPicker => {
+            match key.code {
+                KeyCode::Esc => {
+                    app.state = AppState::Composing;
+                }
+                KeyCode::Down => {
+                    app.next_format();
+                }
+                KeyCode::Up => {
+                    app.previous_format();
+                }
+                KeyCode::Enter => {
+                    app.apply_format();
+                }
+                _ => {}
+            }
+        }
+        AppState::GrammarPending => {
+            if key.code == KeyCode::Esc {
+                app.state = AppState::Composing;
+            }
+        }
+        AppState::AssistPending => {
+            if key.code == KeyCode::Esc {
+                app.state = AppState::Composing;
+            }
+        }
+        AppState::AssistDiff => {
+            match key.code {
+                KeyCode::Enter => {
+                    app.accept_suggestion();
+                }
+                KeyCode::Esc => {
+                    app.assist_suggestion = None;
+                    app.state = AppState::Composing;
+                }
+                _ => {}
+            }
+        }
+        AppState::ParticipantPicker => {
+            match key.code {
+                KeyCode::Esc | KeyCode::Enter => {
+                    app.state = AppState::Composing;
+                }
+                KeyCode::Down => {
+                    app.next_participant();
+                }
+                KeyCode::Up => {
+                    app.previous_participant();
+                }
+                KeyCode::Char(' ') => {
+                    app.toggle_selected_participant();
+                }
+                _ => {}
+            }
+        }
+        AppState::CommunityPicker => {
+            match key.code {
+                KeyCode::Esc => {
+                    app.state = AppState::Composing;
+                }
+                KeyCode::Down => {
+                    app.next_community();
+                }
+                KeyCode::Up => {
+                    app.previous_community();
+                }
+                KeyCode::Enter => {
+                    app.select_current_community();
+                }
+                _ => {}
+            }
+        }
+        AppState::FilePrompt => {
+            match key.code {
+                KeyCode::Esc => {
+                    app.state = AppState::Composing;
+                    app.file_path_input.clear();
+                }
+                KeyCode::Enter => {
+                    let path = app.file_path_input.trim();
+                    if !path.is_empty() {
+                        match clipboard::validate_image_file(path) {
+                            Ok(img_data) => {
+                                let name = std::path::Path::new(path)
+                                    .file_name()
+                                    .map_or_else(|| path.to_string(), |name| name.to_string_lossy().into_owned());
+                                let _ = media::remember(&name, &img_data);
+                                app.state = AppState::Composing;
+                                app.file_path_input.clear();
+                                attach_image(app, images, img_data);
+                            }
+                            Err(e) => {
+                                app.state = AppState::Error(tf("status.image_error", &[&e]));
+                            }
+                        }
+                    } else {
+                        app.state = AppState::Composing;
+                    }
+                }
+                KeyCode::Char(c) => {
+                    app.file_path_input.push(c);
+                }
+                KeyCode::Backspace => {
+                    app.file_path_input.pop();
+                }
+                _ => {}
+            }
+        }
+        AppState::SchedulePrompt => {
+            match key.code {
+                KeyCode::Esc => {
+                    app.state = AppState::Composing;
+                }
+                // Picking a time replaces anything typed
+                _ if move_schedule_picker(&mut app.schedule_picker, key.code) => app.schedule_input.clear(),
+                KeyCode::Enter => {
+                    let recurrence = queue::parse_recurrence(&app.schedule_input);
+                    let at = match (app.schedule_input.as_str(), &recurrence) {
+                        (_, Some(recurrence)) => recurrence.next_after(chrono::Utc::now()),
+                        ("", None) => app.schedule_picker.to_utc(),
+                        (input, None) => queue::parse_schedule_time(input),
+                    };
+                    match at {
+                        Some(at) => {
+                            match schedule_post(app, images, at, recurrence.clone()) {
+                                Ok(()) => {
+                                    app.reset();
+                                    images.clear();
+                                    let when = timezone::from_utc(at).format("%Y-%m-%d %H:%M %Z");
+                                    app.notice = Some(match recurrence {
+                                        Some(recurrence) => tf("status.repeats_scheduled", &[&recurrence.describe(), &when]),
+                                        None => tf("status.scheduled", &[&when]),
+                                    });
+                                }
+                                Err(e) => {
+                                    app.state = AppState::Error(tf("status.schedule_failed", &[&e]));
+                                }
+                            }
+                        }
+                        None if app.schedule_input.is_empty() => {
+                            app.state = AppState::Error(t("status.time_skipped").to_string());
+                        }
+                        None => {
+                            app.state = AppState::Error(tf("status.time_unreadable", &[&app.schedule_input]));
+                        }
+                    }
+                }
+                KeyCode::Char(c) => {
+                    app.schedule_input.push(c);
+                    app.schedule_picker.follow_typed(&app.schedule_input);
+                }
+                KeyCode::Backspace => {
+                    app.schedule_input.pop();
+                    app.schedule_picker.follow_typed(&app.schedule_input);
+                }
+                _ => {}
+            }
+        }
+        AppState::AltTextPrompt(image) => {
+            match key.code {
+                KeyCode::Esc => {
+                    app.state = AppState::Composing;
+                }
+                // Each image in turn, then back to the editor
+                KeyCode::Enter => {
+                    if let Some(alt_text) = app.alt_texts.get_mut(image) {
+                        *alt_text = app.alt_text_input.trim().to_string();
+                    }
+                    if image + 1 < app.image_count {
+                        app.edit_alt_text(image + 1);
+                    } else {
+                        app.state = AppState::Composing;
+                    }
+                }
+                KeyCode::Char(c) if app.alt_text_input.chars().count() < ui::ALT_TEXT_LIMIT => {
+                    app.alt_text_input.push(c);
+                }
+                KeyCode::Backspace => {
+                    app.alt_text_input.pop();
+                }
+                _ => {}
+            }
+        }
+        AppState::Posting if key.code == KeyCode::Esc => {
+            if cancel.cancel() {
+                app.upload_progress = None;
+                app.state = match app.posting_draft.take() {
+                    Some(_) => {
+                        app.load_drafts();
+                        AppState::DraftBrowser
+                    }
+                    None => AppState::Composing,
+                };
+                app.notice = Some(t("status.post_cancelled").to_string());
+            } else {
+                app.notice = Some(t("status.post_sent").to_string());
+            }
+        }
+        // Leave it to finish in the background and get on with the next one
+        AppState::Posting if key.code == KeyCode::Char('n') && key.modifiers.contains(KeyModifiers::CONTROL) => {
+            let text = app.posting_draft.as_ref().map_or_else(|| app.get_text(), |draft| draft.content.clone());
+            let mut post = ui::BackgroundPost::new(app.posting_id, &text);
+            post.upload_progress = app.upload_progress.take();
+            match app.posting_draft.take() {
+                Some(draft) => {
+                    post.draft = Some((draft.id, draft.content));
+                    app.load_drafts();
+                    app.state = AppState::DraftBrowser;
+                }
+                None => {
+                    post.draft = app.current_draft_id.clone().map(|id| (id, app.get_text()));
+                    app.reset();
+                    images.clear();
+                }
+            }
+            app.background_posts.push(post);
+        }
+        AppState::Posting => {}
+        AppState::Success(ref outcomes) if matches!(key.code, KeyCode::Char('o' | 'c' | 's' | 'r')) && app.dm_recipient.is_none() => {
+            let Some(outcome) = outcomes.iter().find(|outcome| outcome.result.is_ok()) else {
+                return Ok(false);
+            };
+            let url = outcome.url().unwrap_or_default();
+            match key.code {
+                KeyCode::Char('o') => {
+                    if let Err(e) = links::open_in_browser(&url) {
+                        app.notice = Some(tf("status.open_failed", &[&e]));
+                    }
+                }
+                KeyCode::Char('c') => {
+                    app.notice = Some(match clipboard::copy_text(&url) {
+                        Ok(()) => tf("status.link_copied", &[&url]),
+                        Err(e) => tf("status.copy_failed", &[&e]),
+                    });
+                }
+                // Under the last part, so a thread grows from its end
+                KeyCode::Char('r') => {
+                    let account = outcome.account.clone();
+                    let tweet_id = outcome.result.as_ref().ok().and_then(|ids| ids.last()).cloned().unwrap_or_default();
+                    app.reply_from(&account, tweet_id);
+                    images.clear();
+                }
+                _ => {
+                    let tweet_id = outcome.result.as_ref().ok().and_then(|ids| ids.first()).cloned().unwrap_or_default();
+                    *show_in_stats = Some((outcome.account.clone(), tweet_id));
+                }
+            }
+        }
+        AppState::Success(ref outcomes) if key.code == KeyCode::Char('m') => {
+            let ids = outcomes.iter().find_map(|outcome| outcome.result.clone().ok());
+            let text = app.posting_draft.as_ref().map_or_else(|| app.get_text(), |draft| draft.content.clone());
+            export_thread(app, &text, ids.as_deref());
+        }
+        AppState::Error(_) if key.code == KeyCode::Char('r') && app.reauth_offer.is_some() => {
+            if let Some((name, problem)) = app.reauth_offer.take() {
+                if let Ok(account) = config.account(Some(&name)) {
+                    app.reauth = Some(Reauth::new(name, problem, account.twitter));
+                    app.state = AppState::ReauthPrompt;
+                }
+            }
+        }
+        AppState::ReauthPrompt => {
+            let Some(reauth) = app.reauth.as_mut().filter(|reauth| !reauth.checking) else {
+                return Ok(false);
+            };
+            match key.code {
+                KeyCode::Esc => {
+                    app.reauth = None;
+                    app.reset();
+                    images.clear();
+                }
+                KeyCode::Enter => {
+                    if let Some(twitter) = reauth.submit() {
+                        reauth.checking = true;
+                        let events_tx = events_tx.clone();
+                        tokio::spawn(async move {
+                            let client = TwitterClient::new(twitter.clone());
+                            let result = client.get_current_user().await.map_err(|e| match client.auth_problem() {
+                                Some(problem) => problem.message().to_string(),
+                                None => format!("{:#}", e),
+                            });
+                            let _ = events_tx.send(ComposeEvent::Reauth(twitter, result)).await;
+                        });
+                    }
+                }
+                KeyCode::Char(c) => reauth.input.push(c),
+                KeyCode::Backspace => {
+                    reauth.input.pop();
+                }
+                _ => {}
+            }
+        }
+        AppState::Success(_) | AppState::Error(_) => {
+            match key.code {
+                KeyCode::Esc => {
+                    return Ok(true);
+                }
+                // Posted from the browser: back to it, with the composer as it was
+                _ if app.posting_draft.take().is_some() => {
+                    app.load_drafts();
+                    app.state = AppState::DraftBrowser;
+                }
+                _ => {
+                    app.reset();
+                    images.clear();
+                }
+            }
+        }
+    }
+    Ok(false)
+}
+
+fn notify_outcomes(notifications: &NotificationConfig, outcomes: &[PostOutcome], dm_recipient: Option<&str>) {
+    let failed = outcomes.iter().filter(|outcome| outcome.result.is_err()).count();
+    let summary = match failed {
+        0 => t("notify.posted"),
+        n if n == outcomes.len() => t("notify.failed"),
+        _ => t("notify.partly_posted"),
+    };
+    let body = outcomes
+        .iter()
+        .map(|outcome| match &outcome.result {
+            Ok(_) if dm_recipient.is_some() => tf("notify.message_sent", &[&dm_recipient.unwrap_or_default()]),
+            Ok(_) => format!("{}: {}", outcome.account, outcome.url().unwrap_or_default()),
+            Err(e) => format!("{}: {}", outcome.account, e),
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    notify::desktop(notifications, summary, &body);
+}
+
+/// Fetches the card for the first link in the buffer unless it's loaded or on its way.
+fn fetch_link_card(app: &mut App, events_tx: &mpsc::Sender<ComposeEvent>) {
+    let Some(url) = links::first_url(&app.get_text()) else {
+        return;
+    };
+    if app.current_link_card().is_some() || app.link_card_pending.as_ref() == Some(&url) {
+        return;
+    }
+
+    app.link_card_pending = Some(url.clone());
+    let events_tx = events_tx.clone();
+    tokio::spawn(async move {
+        let card = links::fetch_card(&url, ui::LINK_CARD_WIDTH, ui::LINK_CARD_HEIGHT).await;
+        let _ = events_tx.send(ComposeEvent::LinkCard(card)).await;
+    });
+}
+
+/// Shortens long links in the buffer that haven't been shortened yet, so the preview
+/// shows what will be posted.
+fn shorten_links(app: &mut App, shortener: &ShortenerConfig, events_tx: &mpsc::Sender<ComposeEvent>) {
+    let urls: Vec<String> = links::long_urls(shortener, &app.get_text())
+        .into_iter()
+        .filter(|url| !app.short_urls.contains_key(url))
+        .collect();
+    if urls.is_empty() || app.shortening {
+        return;
+    }
+
+    app.shortening = true;
+    let shortener = shortener.clone();
+    let events_tx = events_tx.clone();
+    tokio::spawn(async move {
+        let mut results = Vec::new();
+        for url in urls {
+            let short = links::shorten(&shortener, &url).await;
+            results.push((url, short));
+        }
+        let _ = events_tx.send(ComposeEvent::ShortUrls(results)).await;
+    });
+}
+
+/// Adds the composed post to the outbox once per selected account.
+fn schedule_post(
+    app: &App,
+    images: &[Vec<u8>],
+    at: chrono::DateTime<chrono::Utc>,
+    recurrence: Option<queue::Recurrence>,
+) -> Result<()> {
+    for account in app.selected_accounts() {
+        let mut post = queue::QueuedPost::new(&account, app.post_text(), at);
+        post.image_count = images.len();
+        post.alt_texts = app.alt_texts.clone();
+        post.community_id = app.community().map(|c| c.id.clone());
+        post.delete_after_secs = app.delete_after.map(|after| after.num_seconds());
+        post.recurrence = recurrence.clone();
+        queue::save_post(&post, images)?;
+    }
+    Ok(())
+}
+
+/// The configs of the accounts named in `accounts`.
+fn account_configs(config: &Config, accounts: &[String]) -> Vec<AccountConfig> {
+    config.all_accounts().into_iter().filter(|account| accounts.contains(&account.name)).collect()
+}
+
+/// Warns when posting would take one of `accounts` past its API tier's monthly post cap.
+fn monthly_cap_warning(config: &Config, accounts: &[String], text: &str) -> Option<String> {
+    account_configs(config, accounts).iter().find_map(|account| {
+        let (used, cap) = posting::over_monthly_cap(account, text)?;
+        Some(tf("status.cap_warning", &[&account.name, &used, &cap]))
+    })
+}
+
+/// Refuses to post images without alt text as an account whose `alt_text` policy is "block".
+fn alt_text_blocked(app: &App, accounts: &[String]) -> Option<String> {
+    let missing = app.images_without_alt_text();
+    let account = accounts
+        .iter()
+        .find(|account| app.alt_text_policies.get(*account) == Some(&AltTextPolicy::Block))?;
+    let image = missing.first()?;
+    Some(tf("status.alt_text_required", &[account, &(image + 1)]))
+}
+
+/// Asks for confirmation before posting images without alt text as an account set to "warn".
+fn alt_text_warning(app: &App, accounts: &[String]) -> Option<String> {
+    let missing = app.images_without_alt_text();
+    if app.alt_text_warned || missing.is_empty() {
+        return None;
+    }
+    accounts.iter().find(|account| app.alt_text_policies.contains_key(*account)).map(|_| {
+        tf("status.alt_text_missing", &[&missing.len(), &app.image_count])
+    })
+}
+
+/// Asks for confirmation when the text matches the blocklist of one of `accounts` in a way
+/// that hasn't been confirmed yet, and counts the matches as confirmed from then on. `again`
+/// says how to go ahead anyway.
+fn blocklist_warning(app: &mut App, config: &Config, accounts: &[String], text: &str, again: &str) -> Option<String> {
+    let mut unconfirmed = Vec::new();
+    for account in account_configs(config, accounts) {
+        let new: Vec<String> = posting::blocklisted(&account, text)
+            .into_iter()
+            .filter(|entry| app.blocklist_confirmed.insert((account.name.clone(), entry.clone())))
+            .collect();
+        if !new.is_empty() {
+            unconfirmed.push(tf("status.blocklist", &[&account.name, &new.join(", ")]));
+        }
+    }
+    (!unconfirmed.is_empty()).then(|| tf("status.blocklist_warning", &[&unconfirmed.join(t("status.blocklist_and")), &again]))
+}
+
+/// What to confirm before posting a draft straight from the browser, with the warnings
+/// Ctrl+P would give.
+fn draft_post_confirmation(accounts: &[String], text: &str, config: &Config) -> String {
+    let mut warnings = Vec::new();
+    for account in account_configs(config, accounts) {
+        let matched = posting::blocklisted(&account, text);
+        if !matched.is_empty() {
+            warnings.push(tf("confirm.blocklist", &[&account.name, &matched.join(", ")]));
+        }
+        if let Some((used, cap)) = posting::over_monthly_cap(&account, text) {
+            warnings.push(tf("confirm.cap", &[&account.name, &used, &cap]));
+        }
+    }
+    if posting::quiet_until(config).is_some() {
+        warnings.push(t("confirm.quiet_hours").to_string());
+    }
+
+    let prompt = tf("confirm.draft_post", &[&accounts.join(", ")]);
+    if warnings.is_empty() {
+        prompt
+    } else {
+        tf("confirm.warnings", &[&warnings.join("; "), &prompt])
+    }
+}
+
+/// Arrow keys, PgUp/PgDn and Tab move the schedule picker; returns whether `code` was one of them.
+fn move_schedule_picker(picker: &mut SchedulePicker, code: KeyCode) -> bool {
+    match (code, picker.field) {
+        (KeyCode::Tab, _) | (KeyCode::Right, PickerField::Hour | PickerField::Minute) => picker.next_field(),
+        (KeyCode::BackTab, _) | (KeyCode::Left, PickerField::Hour | PickerField::Minute) => picker.previous_field(),
+        (KeyCode::PageUp, _) => picker.move_months(-1),
+        (KeyCode::PageDown, _) => picker.move_months(1),
+        (KeyCode::Left, PickerField::Date) => picker.move_days(-1),
+        (KeyCode::Right, PickerField::Date) => picker.move_days(1),
+        (KeyCode::Up, PickerField::Date) => picker.move_days(-7),
+        (KeyCode::Down, PickerField::Date) => picker.move_days(7),
+        (KeyCode::Up, _) => picker.spin(1),
+        (KeyCode::Down, _) => picker.spin(-1),
+        _ => return false,
+    }
+    true
+}
+
+/// When to schedule the post instead, if it's being sent during `[quiet_hours]`.
+/// Replies can't be scheduled, so they go out regardless.
+fn quiet_hours_slot(app: &App, config: &Config) -> Option<chrono::DateTime<chrono_tz::Tz>> {
+    if app.quiet_warned || app.reply_to.is_some() {
+        return None;
+    }
+    posting::quiet_until(config)
+}
+
+/// Adds an image to the post, up to the most X allows.
+fn attach_image(app: &mut App, images: &mut Vec<Vec<u8>>, img_data: Vec<u8>) {
+    if images.len() >= posting::MAX_IMAGES {
+        app.state = AppState::Error(tf("status.too_many_images", &[&posting::MAX_IMAGES]));
+        return;
+    }
+    images.push(img_data);
+    app.image_count = images.len();
+    app.alt_texts.push(String::new());
+}
+
+fn export_thread(app: &mut App, text: &str, post_ids: Option<&[String]>) {
+    match thread::export_markdown(text, post_ids) {
+        Ok(path) => app.notice = Some(tf("status.exported", &[&path.display()])),
+        Err(e) => app.notice = Some(tf("status.export_failed", &[&e])),
+    }
+}
+
+struct LoadedStats {
+    /// Which account these are for, in case it was switched while they loaded.
+    account: String,
+    user_id: String,
+    tweets: Vec<twitter::Tweet>,
+    followers: Vec<followers::FollowerSnapshot>,
+    metrics: metrics::MetricsHistory,
+    posting_days: BTreeMap<chrono::NaiveDate, usize>,
+    /// Why the follower or metrics snapshot wasn't saved, if it wasn't.
+    warning: Option<String>,
+}
+
+/// Background results delivered to the stats event loop.
+enum StatsEvent {
+    Loaded(Result<LoadedStats>),
+    Replies(String, Result<Vec<twitter::Tweet>>), // Tweet ID and its replies
+    Action(Result<String>),                       // Status message for the footer
+    Engagement(String, stats_ui::Engagement, bool, Result<()>), // Tweet ID, action, whether it was undone, outcome
+    Unbookmarked(String, usize, twitter::Tweet, Result<()>), // Account, where the post was, the post, outcome
+}
+
+fn spawn_stats_action<F>(events_tx: &mpsc::Sender<StatsEvent>, action: F)
+where
+    F: std::future::Future<Output = Result<String>> + Send + 'static,
+{
+    let events_tx = events_tx.clone();
+    tokio::spawn(async move {
+        let _ = events_tx.send(StatsEvent::Action(action.await)).await;
+    });
+}
+
+/// What a post list's engagement key does: l likes and t reposts, and with Shift they take
+/// the like or repost back.
+fn engagement_key(key: char) -> (stats_ui::Engagement, bool) {
+    let engagement = match key.to_ascii_lowercase() {
+        'l' => stats_ui::Engagement::Like,
+        _ => stats_ui::Engagement::Retweet,
+    };
+    (engagement, key.is_ascii_uppercase())
+}
+
+/// Likes or reposts `tweet_id` as `user_id`, or takes that back when `undo` is set.
+async fn engage(client: &TwitterClient, user_id: &str, tweet_id: &str, engagement: stats_ui::Engagement, undo: bool) -> Result<()> {
+    match (engagement, undo) {
+        (stats_ui::Engagement::Like, false) => client.like_tweet(user_id, tweet_id).await,
+        (stats_ui::Engagement::Retweet, false) => client.retweet(user_id, tweet_id).await,
+        (stats_ui::Engagement::Like, true) => client.unlike_tweet(user_id, tweet_id).await,
+        (stats_ui::Engagement::Retweet, true) => client.unretweet(user_id, tweet_id).await,
+    }
+}
+
+fn engaged_message(engagement: stats_ui::Engagement, undo: bool) -> &'static str {
+    match (engagement, undo) {
+        (stats_ui::Engagement::Like, false) => t("stats.liked"),
+        (stats_ui::Engagement::Retweet, false) => t("stats.reposted"),
+        (stats_ui::Engagement::Like, true) => t("stats.unliked"),
+        (stats_ui::Engagement::Retweet, true) => t("stats.unreposted"),
+    }
+}
+
+/// Changes the displayed count right away and sends the like/repost, or its undoing, in the
+/// background.
+fn spawn_engagement(
+    app: &mut stats_ui::StatsApp,
+    client: &TwitterClient,
+    tweet_id: String,
+    (engagement, undo): (stats_ui::Engagement, bool),
+    events_tx: &mpsc::Sender<StatsEvent>,
+) {
+    let Some(user_id) = app.user_id.clone() else {
+        return;
+    };
+    app.adjust_engagement(&tweet_id, engagement, if undo { -1 } else { 1 });
 
-enum PostCommand {
-    Post { text: String, image_data: Option<Vec<u8>> },
+    let client = client.clone();
+    let events_tx = events_tx.clone();
+    tokio::spawn(async move {
+        let result = engage(&client, &user_id, &tweet_id, engagement, undo).await;
+        let _ = events_tx.send(StatsEvent::Engagement(tweet_id, engagement, undo, result)).await;
+    });
 }
 
-enum PostResult {
-    Success(String),
-    Error(String),
+fn spawn_replies_fetch(client: &TwitterClient, tweet_id: String, events_tx: &mpsc::Sender<StatsEvent>) {
+    let client = client.clone();
+    let events_tx = events_tx.clone();
+    tokio::spawn(async move {
+        let result = client.get_tweet_replies(&tweet_id, 50).await;
+        let _ = events_tx.send(StatsEvent::Replies(tweet_id, result)).await;
+    });
 }
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    let args: Vec<String> = std::env::args().collect();
-    
-    let config = match Config::load() {
-        Ok(cfg) => cfg,
-        Err(e) => {
-            eprintln!("{}", e);
-            std::process::exit(1);
-        }
-    };
+async fn send_direct_message(
+    client: &TwitterClient,
+    username: &str,
+    text: String,
+    cancel: &posting::Cancel,
+) -> Result<String, String> {
+    let user = client
+        .get_user_by_username(username)
+        .await
+        .map_err(|e| tf("dm.lookup_failed", &[&username, &e]))?;
 
-    // Check if stats mode is requested
-    if args.len() > 1 && args[1] == "stats" {
-        return run_stats_mode(config).await;
+    if !cancel.send() {
+        return Err(t("dm.cancelled").to_string());
     }
+    client
+        .send_direct_message(&user.id, text)
+        .await
+        .map_err(|e| tf("dm.send_failed", &[&e]))
+}
 
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+fn load_stats(tab: &StatsTab) {
+    let client = tab.client.clone();
+    let account = tab.app.account.clone();
+    let feed = tab.app.feed.clone();
+    let loaded_tx = tab.events_tx.clone();
 
-    let mut app = App::new();
-    let mut image_data: Option<Vec<u8>> = None;
+    tokio::spawn(async move {
+        let result = async {
+            let user = client.get_current_user().await?;
+            let (tweets, profile) = match &feed {
+                stats_ui::Feed::Mine => {
+                    let tweets = client.get_user_tweets(&user.id, 20).await?;
+                    (tweets, Some((user.username.clone(), user.public_metrics.clone())))
+                }
+                stats_ui::Feed::Timeline => (client.get_home_timeline(&user.id, 50).await?, None),
+                stats_ui::Feed::Bookmarks => (client.get_bookmarks(&user.id, 50).await?, None),
+                stats_ui::Feed::User(handle) => {
+                    let other = client.get_user_by_username(handle).await?;
+                    (client.get_user_tweets(&other.id, 20).await?, Some((other.username, other.public_metrics)))
+                }
+            };
+            // Snapshot follower counts and post metrics on every run to chart them over time. The
+            // posts are worth showing even when the snapshots can't be saved.
+            let mut warning = None;
+            let (followers, metrics) = match profile {
+                Some((username, user_metrics)) => {
+                    let followers = match user_metrics {
+                        Some(user_metrics) => followers::record_snapshot(&username, &user_metrics),
+                        None => Ok(Vec::new()),
+                    };
+                    let followers = followers.unwrap_or_else(|e| {
+                        warning = Some(tf("stats.save_followers_failed", &[&format!("{:#}", e)]));
+                        followers::load_snapshots(&username).unwrap_or_default()
+                    });
+                    if let Err(e) = metrics::record_snapshot(&username, &tweets) {
+                        warning = Some(tf("stats.save_metrics_failed", &[&format!("{:#}", e)]));
+                    }
+                    (followers, metrics::load_history(&username).unwrap_or_default())
+                }
+                None => (Vec::new(), metrics::MetricsHistory::new()),
+            };
+            let posting_days = match feed {
+                stats_ui::Feed::Mine => history::posts_per_day(&account),
+                _ => BTreeMap::new(),
+            };
+            Ok(LoadedStats {
+                account,
+                user_id: user.id,
+                tweets,
+                followers,
+                metrics,
+                posting_days,
+                warning,
+            })
+        }.await;
+        let _ = loaded_tx.send(StatsEvent::Loaded(result)).await;
+    });
+}
 
-    let twitter_client = TwitterClient::new(config.twitter.clone());
+fn stats_mouse_key(app: &mut stats_ui::StatsApp, mouse: MouseEvent) -> Option<KeyEvent> {
+    if !matches!(app.state, stats_ui::StatsState::TweetList | stats_ui::StatsState::StatsDetail) {
+        return None;
+    }
+    if clicked_in(&mouse, app.help_area) {
+        let inner = app.help_area.inner(Margin::new(1, 1));
+        return buttons::key_at(&stats_ui::help_text(app), inner, true, mouse.column, mouse.row);
+    }
 
-    let (post_tx, mut post_rx) = mpsc::channel::<PostCommand>(10);
-    let (result_tx, mut result_rx) = mpsc::channel::<PostResult>(10);
+    match mouse.kind {
+        MouseEventKind::ScrollDown => Some(KeyEvent::from(KeyCode::Down)),
+        MouseEventKind::ScrollUp => Some(KeyEvent::from(KeyCode::Up)),
+        MouseEventKind::Down(MouseButton::Left) => {
+            let i = app.item_at(mouse.column, mouse.row)?;
+            let in_list = matches!(app.state, stats_ui::StatsState::TweetList);
+            if in_list && app.selected_item() == Some(i) {
+                Some(KeyEvent::from(KeyCode::Enter))
+            } else {
+                app.select_item(i);
+                None
+            }
+        }
+        _ => None,
+    }
+}
 
-    let posting_task = tokio::spawn(async move {
-        while let Some(cmd) = post_rx.recv().await {
-            match cmd {
-                PostCommand::Post { text, image_data } => {
-                    let result = post_tweet(&twitter_client, text, image_data).await;
-                    let _ = result_tx.send(result).await;
+fn drain_stats_events(tab: &mut StatsTab) {
+    let app = &mut tab.app;
+    app.rate_limit = tab.client.read_limit(app.feed.endpoint());
+    while let Ok(stats_event) = tab.events_rx.try_recv() {
+        match stats_event {
+            StatsEvent::Loaded(Ok(loaded)) if loaded.account != app.account => {}
+            StatsEvent::Loaded(Ok(loaded)) => {
+                app.user_id = Some(loaded.user_id);
+                app.followers = loaded.followers;
+                app.metrics = loaded.metrics;
+                app.posting_days = loaded.posting_days;
+                app.set_tweets(loaded.tweets);
+                if loaded.warning.is_some() {
+                    app.status = loaded.warning;
+                }
+            }
+            StatsEvent::Loaded(Err(e)) => {
+                app.state = stats_ui::StatsState::Error(tf("stats.load_failed", &[&e]));
+            }
+            StatsEvent::Replies(tweet_id, result) => {
+                // Ignore replies for a post that is no longer open
+                if app.get_selected_tweet().map(|t| &t.id) == Some(&tweet_id) {
+                    match result {
+                        Ok(replies) => app.set_replies(replies),
+                        Err(e) => app.status = Some(tf("stats.replies_failed", &[&e])),
+                    }
+                }
+            }
+            StatsEvent::Engagement(tweet_id, engagement, undo, result) => {
+                app.status = Some(match result {
+                    Ok(()) => engaged_message(engagement, undo).to_string(),
+                    Err(e) => {
+                        // Roll back the optimistic count
+                        app.adjust_engagement(&tweet_id, engagement, if undo { 1 } else { -1 });
+                        tf("status.failed", &[&e])
+                    }
+                });
+            }
+            StatsEvent::Unbookmarked(_, _, _, Ok(())) => {
+                app.status = Some(t("stats.bookmark_removed").to_string());
+            }
+            StatsEvent::Unbookmarked(account, index, tweet, Err(e)) => {
+                if account == app.account && app.feed == stats_ui::Feed::Bookmarks {
+                    app.restore_tweet(index, tweet);
                 }
+                app.status = Some(tf("status.failed", &[&e]));
+            }
+            StatsEvent::Action(result) => {
+                app.status = Some(match result {
+                    Ok(msg) => msg,
+                    Err(e) => tf("status.failed", &[&e]),
+                });
             }
         }
-    });
-
-    let result = run_app(&mut terminal, &mut app, &mut image_data, post_tx, &mut result_rx).await;
+    }
+}
 
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
+/// Points the stats tab at the next configured account and fetches its feed.
+fn switch_stats_account(tab: &mut StatsTab) {
+    let current = tab.accounts.iter().position(|account| account.name == tab.app.account).unwrap_or(0);
+    let next = &tab.accounts[(current + 1) % tab.accounts.len()];
+    tab.client = TwitterClient::new(next.twitter.clone());
+    tab.app.switch_account(next.name.clone());
 
-    drop(posting_task);
+    if tab.client.can_read() {
+        load_stats(tab);
+    } else {
+        let message = tf("stats.free_tier", &[&next.name]);
+        tab.app.state = stats_ui::StatsState::Error(message);
+    }
+}
 
-    if let Err(err) = result {
-        eprintln!("Error: {}", err);
+/// Follows or unfollows `target_user_id`, and says which happened.
+async fn follow_or_unfollow(
+    client: &TwitterClient,
+    user_id: &str,
+    target_user_id: &str,
+    handle: &str,
+    follow: bool,
+) -> Result<String> {
+    if !follow {
+        client.unfollow_user(user_id, target_user_id).await?;
+        return Ok(tf("stats.unfollowed", &[&handle]));
     }
+    Ok(if client.follow_user(user_id, target_user_id).await? {
+        tf("stats.followed", &[&handle])
+    } else {
+        tf("stats.follow_requested", &[&handle])
+    })
+}
 
-    Ok(())
+/// The ID and @handle (or ID, when it wasn't expanded) of whoever wrote the highlighted reply,
+/// or why there's no one to act on.
+fn reply_author(app: &stats_ui::StatsApp) -> Result<(String, String), &'static str> {
+    let reply = app.get_selected_reply().ok_or(t("stats.no_reply"))?;
+    let author_id = reply.author_id.clone().ok_or(t("stats.reply_author_unknown"))?;
+    if app.user_id.as_ref() == Some(&author_id) {
+        return Err(t("stats.own_reply"));
+    }
+    let handle = match &reply.author {
+        Some(author) => format!("@{}", author),
+        None => tf("stats.user_id", &[&author_id]),
+    };
+    Ok((author_id, handle))
 }
 
-async fn run_app<'a>(
-    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
-    app: &'a mut ui::App<'a>,
-    image_data: &mut Option<Vec<u8>>,
-    post_tx: mpsc::Sender<PostCommand>,
-    result_rx: &mut mpsc::Receiver<PostResult>,
-) -> Result<()> {
-    loop {
-        terminal.draw(|f| ui::draw(f, app))?;
+/// Handles a key on the stats tab; returns true to quit.
+fn handle_stats_key(tab: &mut StatsTab, key: KeyEvent) -> bool {
+    if key.code == KeyCode::Char('a') && tab.accounts.len() > 1 && matches!(tab.app.state, stats_ui::StatsState::TweetList) {
+        switch_stats_account(tab);
+        return false;
+    }
 
-        if let Ok(result) = result_rx.try_recv() {
-            match result {
-                PostResult::Success(tweet_id) => {
-                    app.state = AppState::Success(tweet_id);
+    let StatsTab { app, client: twitter_client, events_tx, .. } = tab;
+    match &app.state {
+        stats_ui::StatsState::TweetList => {
+            match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => {
+                    return true;
                 }
-                PostResult::Error(msg) => {
-                    app.state = AppState::Error(msg);
+                KeyCode::Down => {
+                    app.next();
                 }
-            }
-        }
-
-        if event::poll(std::time::Duration::from_millis(100))? {
-            let event = event::read()?;
-            match event {
-                Event::Key(key) => {
-                match app.state {
-                    AppState::Composing => {
-                        match (key.code, key.modifiers) {
-                            (KeyCode::Esc, _) => {
-                                return Ok(());
-                            }
-                            (KeyCode::Char('c'), m) if m == KeyModifiers::CONTROL => {
-                                return Ok(());
-                            }
-                            (KeyCode::Char('c'), m) if m == (KeyModifiers::CONTROL | KeyModifiers::SHIFT) => {
-                                // Copy text - handled by TextArea
-                                app.textarea.input(key);
-                            }
-                            (KeyCode::Char('v'), m) if m == (KeyModifiers::CONTROL | KeyModifiers::SHIFT) => {
-                                // Paste text - handled by TextArea
-                                app.textarea.input(key);
-                            }
-                            (KeyCode::Char('u'), KeyModifiers::CONTROL) => {
-                                app.state = AppState::FilePrompt;
-                                app.file_path_input.clear();
-                            }
-                            (KeyCode::Char('s'), KeyModifiers::CONTROL) => {
-                                let text = app.get_text();
-                                if !text.trim().is_empty() {
-                                    let draft = if let Some(draft_id) = &app.current_draft_id {
-                                        // Update existing draft
-                                        if let Some(existing) = app.drafts.iter_mut().find(|d| &d.id == draft_id) {
-                                            existing.update_content(text.clone());
-                                            existing.clone()
-                                        } else {
-                                            drafts::Draft::new(text)
-                                        }
-                                    } else {
-                                        // Create new draft
-                                        drafts::Draft::new(text)
-                                    };
-                                    
-                                    if let Err(e) = drafts::save_draft(&draft) {
-                                        app.state = AppState::Error(format!("Failed to save draft: {}", e));
-                                    } else {
-                                        app.current_draft_id = Some(draft.id.clone());
-                                    }
-                                }
-                            }
-                            (KeyCode::Char('d'), KeyModifiers::CONTROL) => {
-                                app.load_drafts();
-                                app.state = AppState::DraftBrowser;
-                            }
-                            (KeyCode::Char('p'), KeyModifiers::CONTROL) => {
-                                let text = app.get_text();
-                                if !text.trim().is_empty() {
-                                    app.state = AppState::Posting;
-                                    let img_data = image_data.clone();
-                                    let _ = post_tx.send(PostCommand::Post {
-                                        text,
-                                        image_data: img_data,
-                                    }).await;
-                                }
-                            }
-                            _ => {
-                                // Pass all other events to TextArea
-                                app.textarea.input(key);
-                            }
-                        }
+                KeyCode::Up => {
+                    app.previous();
+                }
+                KeyCode::Enter => {
+                    if let Some(tweet) = app.get_selected_tweet() {
+                        let tweet_id = tweet.id.clone();
+                        app.set_replies(Vec::new());
+                        app.state = stats_ui::StatsState::StatsDetail;
+                        spawn_replies_fetch(twitter_client, tweet_id, events_tx);
                     }
-                    AppState::DraftBrowser => {
-                        match key.code {
-                            KeyCode::Esc => {
-                                app.state = AppState::Composing;
-                            }
-                            KeyCode::Down => {
-                                app.next_draft();
-                            }
-                            KeyCode::Up => {
-                                app.previous_draft();
-                            }
-                            KeyCode::Enter => {
-                                app.select_current_draft();
-                            }
-                            KeyCode::Delete => {
-                                app.delete_selected_draft();
-                            }
-                            _ => {}
-                        }
+                }
+                KeyCode::Char('b') if app.feed != stats_ui::Feed::Bookmarks => {
+                    if let (Some(user_id), Some(tweet)) = (app.user_id.clone(), app.get_selected_tweet()) {
+                        let tweet_id = tweet.id.clone();
+                        let client = twitter_client.clone();
+                        spawn_stats_action(events_tx, async move {
+                            client.add_bookmark(&user_id, &tweet_id).await?;
+                            Ok(t("stats.bookmarked").to_string())
+                        });
                     }
-                    AppState::FilePrompt => {
-                        match key.code {
-                            KeyCode::Esc => {
-                                app.state = AppState::Composing;
-                                app.file_path_input.clear();
-                            }
-                            KeyCode::Enter => {
-                                let path = app.file_path_input.trim();
-                                if !path.is_empty() {
-                                    match clipboard::validate_image_file(path) {
-                                        Ok(img_data) => {
-                                            *image_data = Some(img_data);
-                                            app.has_image = true;
-                                            app.state = AppState::Composing;
-                                            app.file_path_input.clear();
-                                        }
-                                        Err(e) => {
-                                            app.state = AppState::Error(format!("Image error: {}", e));
-                                        }
-                                    }
-                                } else {
-                                    app.state = AppState::Composing;
-                                }
-                            }
-                            KeyCode::Char(c) => {
-                                app.file_path_input.push(c);
-                            }
-                            KeyCode::Backspace => {
-                                app.file_path_input.pop();
-                            }
-                            _ => {}
-                        }
+                }
+                KeyCode::Char(c @ ('l' | 't' | 'L' | 'T')) => {
+                    if let Some(tweet_id) = app.get_selected_tweet().map(|t| t.id.clone()) {
+                        spawn_engagement(app, twitter_client, tweet_id, engagement_key(c), events_tx);
                     }
-                    AppState::Posting => {
+                }
+                KeyCode::Char('x') | KeyCode::Delete if app.feed == stats_ui::Feed::Bookmarks => {
+                    let Some(user_id) = app.user_id.clone() else {
+                        return false;
+                    };
+                    // Taken off the list right away, and put back if X doesn't remove it
+                    if let Some((index, tweet)) = app.remove_selected_tweet() {
+                        let client = twitter_client.clone();
+                        let account = app.account.clone();
+                        let events_tx = events_tx.clone();
+                        tokio::spawn(async move {
+                            let result = client.remove_bookmark(&user_id, &tweet.id).await;
+                            let _ = events_tx.send(StatsEvent::Unbookmarked(account, index, tweet, result)).await;
+                        });
                     }
-                    AppState::Success(_) | AppState::Error(_) => {
-                        match key.code {
-                            KeyCode::Esc => {
-                                return Ok(());
-                            }
-                            _ => {
-                                app.reset();
-                                *image_data = None;
-                            }
-                        }
+                }
+                _ => {}
+            }
+        }
+        stats_ui::StatsState::StatsDetail => {
+            let block_confirmed = app.block_warned.take();
+            match key.code {
+                KeyCode::Esc => {
+                    app.status = None;
+                    app.state = stats_ui::StatsState::TweetList;
+                }
+                KeyCode::Char('q') => {
+                    return true;
+                }
+                KeyCode::Down => {
+                    app.next_reply();
+                }
+                KeyCode::Up => {
+                    app.previous_reply();
+                }
+                KeyCode::Enter | KeyCode::Char(' ') => {
+                    app.toggle_selected_reply();
+                }
+                KeyCode::PageDown => {
+                    app.scroll_down();
+                }
+                KeyCode::PageUp => {
+                    app.scroll_up();
+                }
+                KeyCode::Char(c @ ('l' | 't' | 'L' | 'T')) => {
+                    // Act on the highlighted reply, or the post itself when there are none
+                    let tweet_id = app
+                        .get_selected_reply()
+                        .or(app.get_selected_tweet())
+                        .map(|tweet| tweet.id.clone());
+                    if let Some(tweet_id) = tweet_id {
+                        spawn_engagement(app, twitter_client, tweet_id, engagement_key(c), events_tx);
                     }
                 }
+                KeyCode::Char('b') => {
+                    // Bookmark the highlighted reply, or the post itself when there are none
+                    let tweet_id = app
+                        .get_selected_reply()
+                        .or(app.get_selected_tweet())
+                        .map(|tweet| tweet.id.clone());
+                    if let (Some(user_id), Some(tweet_id)) = (app.user_id.clone(), tweet_id) {
+                        let client = twitter_client.clone();
+                        spawn_stats_action(events_tx, async move {
+                            client.add_bookmark(&user_id, &tweet_id).await?;
+                            Ok(t("stats.bookmarked").to_string())
+                        });
+                    }
                 }
-                Event::Mouse(mouse) => {
-                    // Pass mouse events to TextArea for click-to-position and drag-to-select
-                    if app.state == AppState::Composing {
-                        app.textarea.input(crossterm::event::Event::Mouse(mouse));
+                KeyCode::Char('m') | KeyCode::Char('x') => {
+                    let Some(user_id) = app.user_id.clone() else {
+                        return false;
+                    };
+                    let (author_id, handle) = match reply_author(app) {
+                        Ok(author) => author,
+                        Err(problem) => {
+                            app.status = Some(problem.to_string());
+                            return false;
+                        }
+                    };
+                    let client = twitter_client.clone();
+                    if key.code == KeyCode::Char('m') {
+                        spawn_stats_action(events_tx, async move {
+                            client.mute_user(&user_id, &author_id).await?;
+                            Ok(tf("stats.muted", &[&handle]))
+                        });
+                    } else if block_confirmed.as_ref() == Some(&author_id) {
+                        spawn_stats_action(events_tx, async move {
+                            client.block_user(&user_id, &author_id).await?;
+                            Ok(tf("stats.blocked", &[&handle]))
+                        });
+                    } else {
+                        app.status = Some(tf("stats.block_confirm", &[&handle]));
+                        app.block_warned = Some(author_id);
                     }
                 }
+                KeyCode::Char('f') | KeyCode::Char('u') => {
+                    let Some(user_id) = app.user_id.clone() else {
+                        return false;
+                    };
+                    let (author_id, handle) = match reply_author(app) {
+                        Ok(author) => author,
+                        Err(problem) => {
+                            app.status = Some(problem.to_string());
+                            return false;
+                        }
+                    };
+                    let client = twitter_client.clone();
+                    let follow = key.code == KeyCode::Char('f');
+                    spawn_stats_action(events_tx, async move {
+                        follow_or_unfollow(&client, &user_id, &author_id, &handle, follow).await
+                    });
+                }
                 _ => {}
             }
         }
-    }
-}
-
-async fn post_tweet(
-    client: &TwitterClient,
-    text: String,
-    image_data: Option<Vec<u8>>,
-) -> PostResult {
-    let media_id = if let Some(img_data) = image_data {
-        match client.upload_media(&img_data).await {
-            Ok(id) => Some(id),
-            Err(e) => {
-                return PostResult::Error(format!("Failed to upload image: {}", e));
+        stats_ui::StatsState::Loading(_) => {
+            // Wait for loading to complete
+        }
+        stats_ui::StatsState::Error(_) => {
+            match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => {
+                    return true;
+                }
+                _ => {
+                    app.state = stats_ui::StatsState::TweetList;
+                }
             }
         }
-    } else {
-        None
-    };
-
-    match client.post_tweet(text, media_id).await {
-        Ok(tweet_data) => PostResult::Success(tweet_data.id),
-        Err(e) => PostResult::Error(format!("Failed to post: {}", e)),
     }
+    false
 }
 
-async fn run_stats_mode(config: Config) -> Result<()> {
-    let twitter_client = TwitterClient::new(config.twitter.clone());
-
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+type ProfileData = (twitter::UserProfile, Vec<twitter::Tweet>);
 
-    let mut app = stats_ui::StatsApp::new();
-
-    // Fetch user info and tweets in background
-    let client_clone = TwitterClient::new(config.twitter.clone());
-    let (data_tx, mut data_rx) = mpsc::channel::<Result<Vec<twitter::Tweet>>>(1);
-    
+fn spawn_profile_lookup(
+    client: &TwitterClient,
+    username: String,
+    data_tx: &mpsc::Sender<Result<ProfileData>>,
+) {
+    let client = client.clone();
+    let data_tx = data_tx.clone();
     tokio::spawn(async move {
         let result = async {
-            let user = client_clone.get_current_user().await?;
-            let tweets = client_clone.get_user_tweets(&user.id, 20).await?;
-            Ok(tweets)
+            let profile = client.get_user_by_username(&username).await?;
+            let tweets = client.get_user_tweets(&profile.id, 10).await?;
+            Ok((profile, tweets))
         }.await;
         let _ = data_tx.send(result).await;
     });
+}
 
-    let result = run_stats_app(&mut terminal, &mut app, &twitter_client, &mut data_rx).await;
+async fn run_profile_mode(config: &Config, account: AccountConfig, username: Option<String>) -> Result<()> {
+    let twitter_client = TwitterClient::new(account.twitter.clone());
+    let mut terminal = setup_terminal()?;
 
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
+    let mut app = profile_ui::ProfileApp::new(account.profile.accent_color());
+    app.time_format = config.display.time_format.clone();
+    let (data_tx, mut data_rx) = mpsc::channel::<Result<ProfileData>>(1);
+
+    if let Some(username) = username {
+        let username = username.trim_start_matches('@').to_string();
+        app.state = profile_ui::ProfileState::Loading(tf("profile.looking_up", &[&username]));
+        spawn_profile_lookup(&twitter_client, username, &data_tx);
+    }
+
+    let result = run_profile_app(&mut terminal, &mut app, &twitter_client, &data_tx, &mut data_rx).await;
+    restore_terminal(&mut terminal)?;
 
     if let Err(err) = result {
         eprintln!("Error: {}", err);
@@ -322,29 +2829,62 @@ async fn run_stats_mode(config: Config) -> Result<()> {
     Ok(())
 }
 
-async fn run_stats_app(
+async fn run_profile_app(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
-    app: &mut stats_ui::StatsApp,
+    app: &mut profile_ui::ProfileApp,
     twitter_client: &TwitterClient,
-    data_rx: &mut mpsc::Receiver<Result<Vec<twitter::Tweet>>>,
+    data_tx: &mpsc::Sender<Result<ProfileData>>,
+    data_rx: &mut mpsc::Receiver<Result<ProfileData>>,
 ) -> Result<()> {
+    let (action_tx, mut action_rx) = mpsc::channel::<Result<String>>(4);
     loop {
-        terminal.draw(|f| stats_ui::draw(f, app))?;
+        terminal.draw(|f| {
+            if !ui::draw_too_small(f) {
+                profile_ui::draw(f, app);
+            }
+            theme::finish(f);
+        })?;
 
-        // Check for initial data load
         if let Ok(result) = data_rx.try_recv() {
             match result {
-                Ok(tweets) => app.set_tweets(tweets),
+                Ok((profile, tweets)) => app.set_profile(profile, tweets),
                 Err(e) => {
-                    app.state = stats_ui::StatsState::Error(format!("Failed to load tweets: {}", e));
+                    app.state = profile_ui::ProfileState::Error(tf("profile.lookup_failed", &[&e]));
                 }
             }
         }
+        if let Ok(result) = action_rx.try_recv() {
+            app.status = Some(match result {
+                Ok(msg) => msg,
+                Err(e) => tf("status.failed", &[&e]),
+            });
+        }
 
         if event::poll(std::time::Duration::from_millis(100))? {
             if let Event::Key(key) = event::read()? {
                 match &app.state {
-                    stats_ui::StatsState::TweetList => {
+                    profile_ui::ProfileState::Input => {
+                        match key.code {
+                            KeyCode::Esc => {
+                                return Ok(());
+                            }
+                            KeyCode::Enter => {
+                                let username = app.input.trim().trim_start_matches('@').to_string();
+                                if !username.is_empty() {
+                                    app.state = profile_ui::ProfileState::Loading(tf("profile.looking_up", &[&username]));
+                                    spawn_profile_lookup(twitter_client, username, data_tx);
+                                }
+                            }
+                            KeyCode::Char(c) => {
+                                app.input.push(c);
+                            }
+                            KeyCode::Backspace => {
+                                app.input.pop();
+                            }
+                            _ => {}
+                        }
+                    }
+                    profile_ui::ProfileState::Profile => {
                         match key.code {
                             KeyCode::Esc | KeyCode::Char('q') => {
                                 return Ok(());
@@ -355,38 +2895,256 @@ async fn run_stats_app(
                             KeyCode::Up => {
                                 app.previous();
                             }
-                            KeyCode::Enter => {
-                                app.state = stats_ui::StatsState::StatsDetail;
+                            KeyCode::Char('/') => {
+                                app.input.clear();
+                                app.state = profile_ui::ProfileState::Input;
                             }
-                            _ => {}
-                        }
-                    }
-                    stats_ui::StatsState::StatsDetail => {
-                        match key.code {
-                            KeyCode::Esc => {
-                                app.state = stats_ui::StatsState::TweetList;
+                            KeyCode::Char('f') | KeyCode::Char('u') => {
+                                let Some(profile) = app.profile.clone() else {
+                                    continue;
+                                };
+                                let client = twitter_client.clone();
+                                let action_tx = action_tx.clone();
+                                let follow = key.code == KeyCode::Char('f');
+                                tokio::spawn(async move {
+                                    let result = async {
+                                        let me = client.get_current_user().await?;
+                                        if me.id == profile.id {
+                                            anyhow::bail!(t("profile.own_profile"));
+                                        }
+                                        let handle = format!("@{}", profile.username);
+                                        follow_or_unfollow(&client, &me.id, &profile.id, &handle, follow).await
+                                    }.await;
+                                    let _ = action_tx.send(result).await;
+                                });
                             }
-                            KeyCode::Char('q') => {
-                                return Ok(());
+                            KeyCode::Char(c @ ('l' | 't' | 'L' | 'T')) => {
+                                let Some(tweet_id) = app.selected_tweet().map(|tweet| tweet.id.clone()) else {
+                                    continue;
+                                };
+                                let (engagement, undo) = engagement_key(c);
+                                let client = twitter_client.clone();
+                                let action_tx = action_tx.clone();
+                                tokio::spawn(async move {
+                                    let result = async {
+                                        let me = client.get_current_user().await?;
+                                        engage(&client, &me.id, &tweet_id, engagement, undo).await?;
+                                        Ok(engaged_message(engagement, undo).to_string())
+                                    }.await;
+                                    let _ = action_tx.send(result).await;
+                                });
                             }
                             _ => {}
                         }
                     }
-                    stats_ui::StatsState::Loading(_) => {
-                        // Wait for loading to complete
+                    profile_ui::ProfileState::Loading(_) => {
+                        // Wait for the lookup to complete
                     }
-                    stats_ui::StatsState::Error(_) => {
+                    profile_ui::ProfileState::Error(_) => {
                         match key.code {
                             KeyCode::Esc | KeyCode::Char('q') => {
                                 return Ok(());
                             }
                             _ => {
-                                app.state = stats_ui::StatsState::TweetList;
+                                app.state = profile_ui::ProfileState::Input;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn queue_mouse_key(app: &mut queue_ui::QueueApp, mouse: MouseEvent) -> Option<KeyEvent> {
+    if app.state != queue_ui::QueueState::List {
+        return None;
+    }
+    if clicked_in(&mouse, app.help_area) {
+        let inner = app.help_area.inner(Margin::new(1, 1));
+        return buttons::key_at(queue_ui::help_text(), inner, true, mouse.column, mouse.row);
+    }
+
+    match mouse.kind {
+        MouseEventKind::ScrollDown => Some(KeyEvent::from(KeyCode::Down)),
+        MouseEventKind::ScrollUp => Some(KeyEvent::from(KeyCode::Up)),
+        MouseEventKind::Down(MouseButton::Left) => {
+            let i = app.item_at(mouse.column, mouse.row)?;
+            app.list_state.select(Some(i));
+            None
+        }
+        _ => None,
+    }
+}
+
+fn drain_queue_results(tab: &mut QueueTab) -> Result<()> {
+    let app = &mut tab.app;
+    if let Ok((post, result)) = tab.result_rx.try_recv() {
+        app.status = Some(match result {
+            Ok((url, crossposts)) => {
+                queue::published(&post)?;
+                let failed: Vec<String> = crossposts
+                    .into_iter()
+                    .filter_map(|(target, result)| Some(format!("{}: {}", target, result.err()?)))
+                    .collect();
+                match failed.as_slice() {
+                    [] => tf("queue.posted", &[&url]),
+                    _ => tf("queue.partly_posted", &[&url, &failed.join("; ")]),
+                }
+            }
+            Err(e) => {
+                queue::update_post(&post.id, |post| post.fail(&e))?;
+                tf("status.failed", &[&e])
+            }
+        });
+        app.state = queue_ui::QueueState::List;
+        app.reload();
+    }
+    Ok(())
+}
+
+/// Handles a key on the queue tab; returns true to quit.
+fn handle_queue_key(tab: &mut QueueTab, key: KeyEvent, config: &Config) -> Result<bool> {
+    let QueueTab { app, result_tx, publishing, .. } = tab;
+    match app.state {
+        queue_ui::QueueState::List => {
+            app.status = None;
+            match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => {
+                    return Ok(true);
+                }
+                KeyCode::Down => {
+                    app.next();
+                }
+                KeyCode::Up => {
+                    app.previous();
+                }
+                KeyCode::Char('r') => {
+                    let Some(post) = app.selected_post().cloned() else {
+                        return Ok(false);
+                    };
+                    let Ok(account) = config.account(Some(&post.account)) else {
+                        app.status = Some(tf("queue.unknown_account", &[&post.account]));
+                        return Ok(false);
+                    };
+                    let Some(publish_lock) = lock::try_lock(daemon::PUBLISH_LOCK)? else {
+                        app.status = Some(t("queue.daemon_busy").to_string());
+                        return Ok(false);
+                    };
+                    // Holding the publish lock, a post marked as posting was interrupted and
+                    // may already be on X
+                    let Some(post) = queue::claim(&post.id, |post| post.status != queue::QueueStatus::Posting)? else {
+                        app.status = Some(t("queue.gone").to_string());
+                        app.reload();
+                        return Ok(false);
+                    };
+                    app.state = queue_ui::QueueState::Posting;
+                    let result_tx = result_tx.clone();
+                    let config = config.clone();
+                    *publishing = Some(tokio::spawn(async move {
+                        let _publish_lock = publish_lock;
+                        let client = TwitterClient::new(account.twitter.clone());
+                        let result = posting::publish_queued(&post, &account, &client, &config).await;
+                        let _ = result_tx.send((post, result)).await;
+                    }));
+                }
+                KeyCode::Char('e') if app.selected_post().is_some() => {
+                    app.time_input.clear();
+                    // Start from the post's time, unless it's a failed one whose time has passed
+                    app.picker = match app.selected_post() {
+                        Some(post) if post.scheduled_at > chrono::Utc::now() => SchedulePicker::new(post.scheduled_at),
+                        _ => SchedulePicker::default(),
+                    };
+                    app.state = queue_ui::QueueState::EditTime;
+                }
+                KeyCode::Char('p') => {
+                    if let Some(post) = app.selected_post().cloned() {
+                        let paused = !post.paused;
+                        queue::update_post(&post.id, |post| {
+                            post.paused = paused;
+                            // A series resumes from its next occurrence rather than catching up
+                            if let Some(next) = post.recurrence.as_ref().and_then(|recurrence| recurrence.next_after(chrono::Utc::now())) {
+                                if !post.paused && post.scheduled_at < chrono::Utc::now() {
+                                    post.scheduled_at = next;
+                                }
                             }
+                        })?;
+                        app.status = Some(t(if paused { "queue.paused" } else { "queue.resumed" }).to_string());
+                        app.reload();
+                    }
+                }
+                KeyCode::Char('d') | KeyCode::Delete => {
+                    if let Some(post) = app.selected_post() {
+                        queue::delete_post(&post.id)?;
+                        app.status = Some(t("queue.deleted").to_string());
+                        app.reload();
+                    }
+                }
+                _ => {}
+            }
+        }
+        queue_ui::QueueState::EditTime => {
+            match key.code {
+                KeyCode::Esc => {
+                    app.state = queue_ui::QueueState::List;
+                }
+                _ if move_schedule_picker(&mut app.picker, key.code) => app.time_input.clear(),
+                KeyCode::Enter => {
+                    let recurrence = queue::parse_recurrence(&app.time_input);
+                    let at = match (app.time_input.as_str(), &recurrence) {
+                        (_, Some(recurrence)) => recurrence.next_after(chrono::Utc::now()),
+                        ("", None) => app.picker.to_utc(),
+                        (input, None) => queue::parse_schedule_time(input),
+                    };
+                    match (at, app.selected_post().cloned()) {
+                        (Some(at), Some(post)) => {
+                            // Rescheduling a failed post puts it back in line. A new rule
+                            // changes the whole series; a time only moves its next post.
+                            queue::update_post(&post.id, |post| {
+                                post.scheduled_at = at;
+                                post.status = queue::QueueStatus::Scheduled;
+                                if recurrence.is_some() {
+                                    post.recurrence = recurrence;
+                                }
+                            })?;
+                            app.status = Some(t("queue.rescheduled").to_string());
+                            app.state = queue_ui::QueueState::List;
+                            app.reload();
+                        }
+                        _ => {
+                            app.status = Some(t("queue.time_unreadable").to_string());
+                            app.state = queue_ui::QueueState::List;
                         }
                     }
                 }
+                KeyCode::Char(c) => {
+                    app.time_input.push(c);
+                    app.picker.follow_typed(&app.time_input);
+                }
+                KeyCode::Backspace => {
+                    app.time_input.pop();
+                    app.picker.follow_typed(&app.time_input);
+                }
+                _ => {}
             }
         }
+        queue_ui::QueueState::Posting => {
+            // Wait for the retry to complete
+        }
+    }
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_post_ids_from_links() {
+        assert_eq!(parse_tweet_id("1846623541178888412"), "1846623541178888412");
+        assert_eq!(parse_tweet_id("https://x.com/someone/status/1846623541178888412"), "1846623541178888412");
+        assert_eq!(parse_tweet_id("https://twitter.com/someone/status/123?s=20&t=abc"), "123");
+        assert_eq!(parse_tweet_id("https://x.com/someone/status/123/photo/1"), "123");
+        assert_eq!(parse_tweet_id(" x.com/i/web/status/123#reply "), "123");
     }
 }
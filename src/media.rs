@@ -0,0 +1,137 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use image::RgbImage;
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use std::cmp::Reverse;
+use std::fs;
+use std::path::PathBuf;
+
+/// How many recently attached images are kept around for re-use.
+const MAX_ITEMS: usize = 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MediaItem {
+    /// SHA-1 of the PNG bytes, so attaching the same image twice doesn't duplicate it.
+    pub id: String,
+    pub name: String,
+    pub width: u32,
+    pub height: u32,
+    pub size: usize,
+    pub last_used: DateTime<Utc>,
+}
+
+impl MediaItem {
+    pub fn label(&self) -> String {
+        format!(
+            "{} | {}x{} | {} KB",
+            self.name,
+            self.width,
+            self.height,
+            self.size.div_ceil(1024)
+        )
+    }
+
+    pub fn load(&self) -> Result<Vec<u8>> {
+        fs::read(media_dir()?.join(format!("{}.png", self.id)))
+            .context("Failed to read image from media library")
+    }
+
+    /// Downscaled copy for previewing in the terminal, fitting within `width` x `height` pixels.
+    pub fn thumbnail(&self, width: u32, height: u32) -> Result<RgbImage> {
+        let img = image::load_from_memory(&self.load()?)
+            .context("Failed to decode image from media library")?;
+        Ok(img.thumbnail(width, height).to_rgb8())
+    }
+}
+
+fn media_dir() -> Result<PathBuf> {
+    let dir = crate::config::config_dir()?.join("media");
+
+    if !dir.exists() {
+        fs::create_dir_all(&dir)
+            .context("Failed to create media directory")?;
+    }
+
+    Ok(dir)
+}
+
+fn index_path() -> Result<PathBuf> {
+    Ok(media_dir()?.join("index.json"))
+}
+
+/// Recently attached images, most recently used first.
+pub fn load_library() -> Result<Vec<MediaItem>> {
+    let path = index_path()?;
+
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(&path)
+        .context("Failed to read media library")?;
+    let mut items: Vec<MediaItem> = serde_json::from_str(&content)
+        .context("Failed to parse media library")?;
+    items.sort_by_key(|item| Reverse(item.last_used));
+
+    Ok(items)
+}
+
+fn save_library(items: &[MediaItem]) -> Result<()> {
+    let json = serde_json::to_string_pretty(items)
+        .context("Failed to serialize media library")?;
+    fs::write(index_path()?, json)
+        .context("Failed to write media library")
+}
+
+/// Adds an attached image to the library (or bumps it if already there), evicting the oldest.
+pub fn remember(name: &str, png_data: &[u8]) -> Result<MediaItem> {
+    // SHA-1 rather than std's hasher, whose output can change between Rust versions
+    let id = format!("{:x}", Sha1::digest(png_data));
+
+    let mut items = load_library()?;
+    items.retain(|item| item.id != id);
+
+    let img = image::load_from_memory(png_data)
+        .context("Failed to decode attached image")?;
+    fs::write(media_dir()?.join(format!("{}.png", id)), png_data)
+        .context("Failed to write image to media library")?;
+
+    let item = MediaItem {
+        id,
+        name: name.to_string(),
+        width: img.width(),
+        height: img.height(),
+        size: png_data.len(),
+        last_used: Utc::now(),
+    };
+    items.insert(0, item.clone());
+
+    for evicted in items.split_off(items.len().min(MAX_ITEMS)) {
+        let _ = fs::remove_file(media_dir()?.join(format!("{}.png", evicted.id)));
+    }
+
+    save_library(&items)?;
+    Ok(item)
+}
+
+pub fn touch(id: &str) -> Result<()> {
+    let mut items = load_library()?;
+    if let Some(item) = items.iter_mut().find(|item| item.id == id) {
+        item.last_used = Utc::now();
+    }
+    save_library(&items)
+}
+
+pub fn forget(id: &str) -> Result<()> {
+    let mut items = load_library()?;
+    items.retain(|item| item.id != id);
+
+    let file_path = media_dir()?.join(format!("{}.png", id));
+    if file_path.exists() {
+        fs::remove_file(&file_path)
+            .context("Failed to delete image from media library")?;
+    }
+
+    save_library(&items)
+}
@@ -0,0 +1,149 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, DurationRound, Local, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::twitter::Tweet;
+
+/// A post's public counts at one point in time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricsSnapshot {
+    pub taken_at: DateTime<Utc>,
+    pub likes: u32,
+    pub retweets: u32,
+    pub replies: u32,
+    pub quotes: u32,
+    pub impressions: u32,
+}
+
+/// Snapshots per post ID, oldest first.
+pub type MetricsHistory = BTreeMap<String, Vec<MetricsSnapshot>>;
+
+fn metrics_dir() -> Result<PathBuf> {
+    let dir = crate::config::config_dir()?.join("metrics");
+
+    if !dir.exists() {
+        fs::create_dir_all(&dir)
+            .context("Failed to create metrics directory")?;
+    }
+
+    Ok(dir)
+}
+
+fn metrics_path(username: &str) -> Result<PathBuf> {
+    Ok(metrics_dir()?.join(format!("{}.json", username)))
+}
+
+pub fn load_history(username: &str) -> Result<MetricsHistory> {
+    let path = metrics_path(username)?;
+
+    if !path.exists() {
+        return Ok(MetricsHistory::new());
+    }
+
+    let content = fs::read_to_string(&path)
+        .context("Failed to read metrics history")?;
+    serde_json::from_str(&content).context("Failed to parse metrics history")
+}
+
+/// Appends the current counts of each post and returns how many were recorded.
+/// Like follower snapshots, runs within the same clock hour replace the previous one, so an
+/// hourly job that fires a little early still gets a sample each hour.
+pub fn record_snapshot(username: &str, tweets: &[Tweet]) -> Result<usize> {
+    let mut history = load_history(username)?;
+    let now = Utc::now();
+    let mut recorded = 0;
+
+    for tweet in tweets {
+        let Some(metrics) = &tweet.public_metrics else {
+            continue;
+        };
+        let series = history.entry(tweet.id.clone()).or_default();
+
+        if series.last().is_some_and(|last| same_hour(last.taken_at, now)) {
+            series.pop();
+        }
+
+        series.push(MetricsSnapshot {
+            taken_at: now,
+            likes: metrics.like_count,
+            retweets: metrics.retweet_count,
+            replies: metrics.reply_count,
+            quotes: metrics.quote_count,
+            impressions: metrics.impression_count,
+        });
+        recorded += 1;
+    }
+
+    let json = serde_json::to_string_pretty(&history)
+        .context("Failed to serialize metrics history")?;
+    fs::write(metrics_path(username)?, json)
+        .context("Failed to write metrics history")?;
+
+    Ok(recorded)
+}
+
+/// Whether `a` and `b` fall within the same clock hour.
+pub fn same_hour(a: DateTime<Utc>, b: DateTime<Utc>) -> bool {
+    let hour = |at: DateTime<Utc>| at.duration_trunc(Duration::hours(1)).ok();
+    hour(a) == hour(b)
+}
+
+/// Every recorded snapshot of every account, one row each, for pandas or DuckDB.
+fn to_csv(histories: &[(String, MetricsHistory)]) -> String {
+    let mut csv = String::from("username,post_id,taken_at,likes,retweets,replies,quotes,impressions\n");
+
+    for (username, history) in histories {
+        for (post_id, series) in history {
+            for snapshot in series {
+                csv.push_str(&format!(
+                    "{},{},{},{},{},{},{},{}\n",
+                    username,
+                    post_id,
+                    snapshot.taken_at.to_rfc3339(),
+                    snapshot.likes,
+                    snapshot.retweets,
+                    snapshot.replies,
+                    snapshot.quotes,
+                    snapshot.impressions,
+                ));
+            }
+        }
+    }
+
+    csv
+}
+
+/// Writes the metrics history as CSV to `path`, or to `~/.config/xpost/exports/`,
+/// and returns the file's path.
+pub fn export_csv(path: Option<&Path>) -> Result<PathBuf> {
+    let mut histories = Vec::new();
+    for entry in fs::read_dir(metrics_dir()?).context("Failed to read metrics directory")? {
+        let file = entry?.path();
+        if file.extension().and_then(|s| s.to_str()) != Some("json") {
+            continue;
+        }
+        if let Some(username) = file.file_stem().and_then(|s| s.to_str()) {
+            histories.push((username.to_string(), load_history(username)?));
+        }
+    }
+    histories.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let path = match path {
+        Some(path) => path.to_path_buf(),
+        None => {
+            let dir = crate::config::config_dir()?.join("exports");
+            if !dir.exists() {
+                fs::create_dir_all(&dir)
+                    .context("Failed to create exports directory")?;
+            }
+            dir.join(format!("metrics-{}.csv", Local::now().format("%Y%m%d-%H%M%S")))
+        }
+    };
+    fs::write(&path, to_csv(&histories))
+        .context("Failed to write metrics export")?;
+
+    Ok(path)
+}
@@ -0,0 +1,28 @@
+use notify_rust::Notification;
+use std::io::Write;
+
+use crate::config::NotificationConfig;
+
+/// Shows a desktop notification if enabled. Failures (no notification daemon, ssh) are ignored.
+pub fn desktop(config: &NotificationConfig, summary: &str, body: &str) {
+    if !config.desktop {
+        return;
+    }
+
+    let _ = Notification::new()
+        .appname("xpost")
+        .summary(summary)
+        .body(body)
+        .show();
+}
+
+/// Rings the terminal bell if enabled. Terminals show it even from the alternate screen.
+pub fn bell(config: &NotificationConfig) {
+    if !config.bell {
+        return;
+    }
+
+    let mut stdout = std::io::stdout();
+    let _ = stdout.write_all(b"\x07");
+    let _ = stdout.flush();
+}
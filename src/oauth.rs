@@ -0,0 +1,168 @@
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::TwitterConfig;
+
+/// The `Authorization` header for an OAuth 1.0a request. Parameters in `url`'s query string
+/// and `form` (an `application/x-www-form-urlencoded` body) are signed along with the
+/// request; multipart bodies aren't, so pass no `form` for those.
+pub fn authorization_header(config: &TwitterConfig, method: &str, url: &str, form: &[(&str, &str)]) -> String {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default();
+    sign(config, method, url, form, &nonce(), timestamp)
+}
+
+fn sign(config: &TwitterConfig, method: &str, url: &str, form: &[(&str, &str)], nonce: &str, timestamp: u64) -> String {
+    let timestamp = timestamp.to_string();
+    let oauth_params = [
+        ("oauth_consumer_key", config.api_key.as_str()),
+        ("oauth_nonce", nonce),
+        ("oauth_signature_method", "HMAC-SHA1"),
+        ("oauth_timestamp", timestamp.as_str()),
+        ("oauth_token", config.access_token.as_str()),
+        ("oauth_version", "1.0"),
+    ];
+
+    let (base_url, query) = url.split_once('?').unwrap_or((url, ""));
+    let base_url = base_url.split('#').next().unwrap_or(base_url);
+    let mut params: Vec<(String, String)> = parse_query(query)
+        .into_iter()
+        .chain(form.iter().map(|(key, value)| (key.to_string(), value.to_string())))
+        .chain(oauth_params.iter().map(|(key, value)| (key.to_string(), value.to_string())))
+        .map(|(key, value)| (encode(&key), encode(&value)))
+        .collect();
+    params.sort();
+
+    let normalized = params
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect::<Vec<_>>()
+        .join("&");
+    let base_string = format!("{}&{}&{}", method.to_uppercase(), encode(base_url), encode(&normalized));
+    let signing_key = format!("{}&{}", encode(&config.api_secret), encode(&config.access_token_secret));
+
+    let mut mac = Hmac::<Sha1>::new_from_slice(signing_key.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(base_string.as_bytes());
+    let signature = base64::engine::general_purpose::STANDARD.encode(mac.finalize().into_bytes());
+
+    let mut fields: Vec<(&str, &str)> = oauth_params.to_vec();
+    fields.push(("oauth_signature", &signature));
+    fields.sort();
+    let fields = fields
+        .iter()
+        .map(|(key, value)| format!("{}=\"{}\"", key, encode(value)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("OAuth {}", fields)
+}
+
+/// Decoded key/value pairs of a query string or form body.
+fn parse_query(query: &str) -> Vec<(String, String)> {
+    let decode = |part: &str| {
+        let part = part.replace('+', " ");
+        urlencoding::decode(&part).map(|decoded| decoded.into_owned()).unwrap_or(part)
+    };
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (decode(key), decode(value))
+        })
+        .collect()
+}
+
+/// Percent-encoding as OAuth wants it: everything but letters, digits and `-._~`.
+fn encode(text: &str) -> String {
+    urlencoding::encode(text).into_owned()
+}
+
+fn nonce() -> String {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos());
+    let high = hasher.finish();
+    hasher.write_u64(high);
+    format!("{:016x}{:016x}", high, hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn credentials(api_key: &str, api_secret: &str, access_token: &str, access_token_secret: &str) -> TwitterConfig {
+        TwitterConfig {
+            api_key: api_key.to_string(),
+            api_secret: api_secret.to_string(),
+            access_token: access_token.to_string(),
+            access_token_secret: access_token_secret.to_string(),
+            oauth2_token: None,
+            bearer_token: None,
+            tier: None,
+        }
+    }
+
+    fn signature(header: &str) -> &str {
+        let start = header.find("oauth_signature=\"").unwrap() + "oauth_signature=\"".len();
+        let end = start + header[start..].find('"').unwrap();
+        &header[start..end]
+    }
+
+    // Appendix A.5 of the OAuth Core 1.0 spec: query parameters only
+    #[test]
+    fn signs_query_parameters() {
+        let config = credentials("dpf43f3p2l4k3l03", "kd94hf93k423kf44", "nnch734d00sl2jdk", "pfkkdhi9sl3r4s00");
+        let header = sign(
+            &config,
+            "GET",
+            "http://photos.example.net/photos?file=vacation.jpg&size=original",
+            &[],
+            "kllo9940pd9333jh",
+            1191242096,
+        );
+        assert_eq!(signature(&header), encode("tR3+Ty81lMeYAr/Fid0kMTYa/WM="));
+    }
+
+    // X's "Creating a signature" example: a query parameter plus a form body
+    #[test]
+    fn signs_query_and_form_parameters() {
+        let config = credentials(
+            "xvz1evFS4wEEPTGEFPHBog",
+            "kAcSOqF21Fu85e7zjz7ZN2U4ZRhfV3WpwPAoE3Z7kBw",
+            "370773112-GmHxMAgYyLbNEtIKZeRNFsMKPR9EyMZeS9weJAEb",
+            "LswwdoUaIvS8ltyTt5jkRh4J50vUPVVHtR2YPi5kE",
+        );
+        let header = sign(
+            &config,
+            "POST",
+            "https://api.twitter.com/1.1/statuses/update.json?include_entities=true",
+            &[("status", "Hello Ladies + Gentlemen, a signed OAuth request!")],
+            "kYjzVBB8Y0ZFabxSWbWovY3uYSQ2pTgmZeNu2VS4cg",
+            1318622958,
+        );
+        assert_eq!(signature(&header), encode("hCtSmYh+iHYCEqBWrE7C7hYmtUk="));
+        assert!(header.starts_with("OAuth oauth_consumer_key=\"xvz1evFS4wEEPTGEFPHBog\", oauth_nonce="));
+    }
+
+    #[test]
+    fn encoded_query_values_are_signed_decoded() {
+        let config = credentials("key", "secret", "token", "token_secret");
+        let raw = sign(&config, "GET", "https://api.x.com/2/tweets/search/recent?query=a b", &[], "n", 1);
+        let escaped = sign(&config, "GET", "https://api.x.com/2/tweets/search/recent?query=a%20b", &[], "n", 1);
+        assert_eq!(raw, escaped);
+    }
+
+    #[test]
+    fn percent_encodes_reserved_characters() {
+        assert_eq!(encode("Ladies + Gentlemen"), "Ladies%20%2B%20Gentlemen");
+        assert_eq!(encode("An encoded string!"), "An%20encoded%20string%21");
+        assert_eq!(encode("Dogs, Cats & Mice"), "Dogs%2C%20Cats%20%26%20Mice");
+        assert_eq!(encode("☃"), "%E2%98%83");
+        assert_eq!(encode("-._~"), "-._~");
+    }
+}
@@ -0,0 +1,214 @@
+use anyhow::Result;
+use std::io::{self, BufRead};
+
+use crate::config::{AccountConfig, Config};
+use crate::crosspost;
+use crate::posting;
+use crate::queue;
+use crate::stats_ui::Feed;
+use crate::text;
+use crate::thread;
+use crate::twitter::{PostOptions, Tweet, TwitterClient};
+use crate::ui::{PREMIUM_CHAR_LIMIT, STANDARD_CHAR_LIMIT};
+
+/// `--plain`: a line-based interface for terminal screen readers. Nothing is drawn or
+/// redrawn and nothing is conveyed by colour alone; every change of state is its own line.
+pub async fn run(config: &Config, account: &AccountConfig, command: Option<&str>, user: Option<String>) -> Result<()> {
+    let client = TwitterClient::new(account.twitter.clone());
+    match command {
+        Some("stats") => match user {
+            Some(handle) => list_posts(config, &client, Feed::User(handle)).await,
+            None => list_posts(config, &client, Feed::Mine).await,
+        },
+        Some("timeline") => list_posts(config, &client, Feed::Timeline).await,
+        Some("bookmarks") => list_posts(config, &client, Feed::Bookmarks).await,
+        Some("queue") => list_queue(),
+        _ => compose(config, account, &client).await,
+    }
+}
+
+async fn compose(config: &Config, account: &AccountConfig, client: &TwitterClient) -> Result<()> {
+    println!("Posting as {}.", account.name);
+    println!("Type your post. Put a line with just --- between posts for a thread.");
+    println!("Finish with a line with just a period to post, or Ctrl+D.");
+
+    let mut lines = Vec::new();
+    for line in io::stdin().lock().lines() {
+        let line = line?;
+        if line.trim() == "." {
+            break;
+        }
+        lines.push(line);
+    }
+    let text = lines.join("\n");
+    if text.trim().is_empty() {
+        println!("Nothing to post.");
+        return Ok(());
+    }
+
+    let char_limit = match account.profile.premium {
+        Some(true) => PREMIUM_CHAR_LIMIT,
+        Some(false) => STANDARD_CHAR_LIMIT,
+        None => match client.get_current_user().await {
+            Ok(user) if user.is_premium() => PREMIUM_CHAR_LIMIT,
+            Ok(_) => STANDARD_CHAR_LIMIT,
+            Err(_) => {
+                println!("Couldn't tell whether this account has premium; assuming {} characters.", STANDARD_CHAR_LIMIT);
+                STANDARD_CHAR_LIMIT
+            }
+        },
+    };
+    let parts = thread::split_thread(&text);
+    if let Some((i, part)) = parts.iter().enumerate().find(|(_, part)| text::weighted_len(part) > char_limit) {
+        println!(
+            "Error: post {} of {} is {} characters, over the {} limit. Nothing was posted.",
+            i + 1,
+            parts.len(),
+            text::weighted_len(part),
+            char_limit
+        );
+        std::process::exit(1);
+    }
+
+    // The same checks the composer asks about
+    let mut warnings = Vec::new();
+    let matched = posting::blocklisted(account, &text);
+    if !matched.is_empty() {
+        warnings.push(format!("This matches your blocklist ({}).", matched.join(", ")));
+    }
+    if let Some((used, cap)) = posting::over_monthly_cap(account, &text) {
+        warnings.push(format!("You have used {} of your {} posts this month.", used, cap));
+    }
+    if !warnings.is_empty() {
+        let answer = ask(&format!("Warning: {} Type y and press Enter to post anyway.", warnings.join(" ")));
+        if answer != "y" && answer != "yes" {
+            println!("Nothing was posted.");
+            return Ok(());
+        }
+    }
+    if let Some(at) = posting::quiet_until(config) {
+        let answer = ask(&format!(
+            "It's quiet hours. Type s to schedule the post for {}, or y to post now.",
+            at.format("%H:%M")
+        ));
+        match answer.as_str() {
+            "s" => {
+                let post = queue::QueuedPost::new(&account.name, text, at.with_timezone(&chrono::Utc));
+                queue::save_post(&post, &[])?;
+                println!("Scheduled for {}. See it with: xpost --plain queue", at.format("%H:%M"));
+                return Ok(());
+            }
+            "y" | "yes" => {}
+            _ => {
+                println!("Nothing was posted.");
+                return Ok(());
+            }
+        }
+    }
+
+    println!("Posting to X...");
+    let options = PostOptions {
+        reply_settings: account.profile.reply_settings.clone(),
+        community_id: None,
+        reply: None,
+        alt_texts: Vec::new(),
+    };
+    let composed = text;
+    let (text, _) = crosspost::split_overrides(&composed);
+    let text = account.profile.apply_signature(text);
+    let progress = |done, total| println!("Uploaded {} of {} images.", done, total);
+    match posting::publish(account, client, text.clone(), Vec::new(), &options, None, config, &progress, &posting::Cancel::default()).await {
+        Ok(ids) => {
+            println!("Posted. {}", client.post_url(&ids[0]).await);
+            for (target, result) in posting::crosspost(account, &composed, &[], &[], config).await {
+                match result {
+                    Ok(url) => println!("Also posted to {}. {}", target, url),
+                    Err(e) => println!("Posting to {} failed: {}", target, e),
+                }
+            }
+            Ok(())
+        }
+        Err(e) if e.vetoed => {
+            println!("Error: {}", e);
+            println!("Nothing was posted.");
+            std::process::exit(1);
+        }
+        Err(e) => {
+            println!("Error: {}", e);
+            // Like the composer, keep the post so it can be retried
+            let mut post = queue::QueuedPost::new(&account.name, text, chrono::Utc::now());
+            post.fail(&e);
+            if queue::save_post(&post, &[]).is_ok() {
+                println!("The post was saved to the outbox. Retry it with: xpost queue");
+            }
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Prints `question` and reads the answer, trimmed and lowercased; empty when input has ended.
+fn ask(question: &str) -> String {
+    println!("{}", question);
+    let mut answer = String::new();
+    let _ = io::stdin().lock().read_line(&mut answer);
+    answer.trim().to_lowercase()
+}
+
+async fn list_posts(config: &Config, client: &TwitterClient, feed: Feed) -> Result<()> {
+    println!("Fetching {}...", feed.title());
+    let user = client.get_current_user().await?;
+    let tweets = match &feed {
+        Feed::Timeline => client.get_home_timeline(&user.id, 50).await?,
+        Feed::Bookmarks => client.get_bookmarks(&user.id, 50).await?,
+        Feed::User(handle) => {
+            let other = client.get_user_by_username(handle).await?;
+            client.get_user_tweets(&other.id, 20).await?
+        }
+        Feed::Mine => client.get_user_tweets(&user.id, 20).await?,
+    };
+
+    println!("{}: {} posts.", feed.title(), tweets.len());
+    for (i, tweet) in tweets.iter().enumerate() {
+        println!("{}. {}", i + 1, describe(tweet, config.display.time_format.as_deref()));
+    }
+    Ok(())
+}
+
+/// One post as a sentence or two: who, when, what, and its counts spelled out.
+fn describe(tweet: &Tweet, time_format: Option<&str>) -> String {
+    let mut parts = Vec::new();
+    if let Some(author) = &tweet.author {
+        parts.push(format!("@{}", author));
+    }
+    if let Some(created_at) = &tweet.created_at {
+        parts.push(text::format_timestamp(created_at, time_format));
+    }
+    parts.push(text::preview_line(&tweet.text, 280));
+    if let Some(metrics) = &tweet.public_metrics {
+        parts.push(format!(
+            "{} likes, {} reposts, {} replies, {} quotes, {} impressions",
+            metrics.like_count, metrics.retweet_count, metrics.reply_count, metrics.quote_count, metrics.impression_count
+        ));
+    }
+    parts.join(". ")
+}
+
+fn list_queue() -> Result<()> {
+    let posts = queue::load_queue()?;
+    println!("Outbox: {} posts.", posts.len());
+    for (i, post) in posts.iter().enumerate() {
+        let when = text::format_time(post.scheduled_at, Some("%Y-%m-%d %H:%M"));
+        let status = match &post.status {
+            _ if post.paused => format!("Paused, next due {}", when),
+            queue::QueueStatus::Scheduled => format!("Scheduled for {}", when),
+            queue::QueueStatus::Posting => "Posting now".to_string(),
+            queue::QueueStatus::Failed(error) => format!("Failed after {} attempts: {}", post.attempts, error),
+        };
+        let status = match &post.recurrence {
+            Some(recurrence) => format!("{}, repeats {}", status, recurrence.describe()),
+            None => status,
+        };
+        println!("{}. {}. {}. {}", i + 1, status, post.account, text::preview_line(&post.text, 280));
+    }
+    Ok(())
+}
@@ -0,0 +1,346 @@
+use chrono::Duration;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{Notify, Semaphore};
+use tokio::task::JoinSet;
+
+use crate::blocklist;
+use crate::config::{AccountConfig, Config};
+use crate::crosspost;
+use crate::hooks;
+use crate::links;
+use crate::scripting;
+use crate::history::{self, PostRecord};
+use crate::queue::QueuedPost;
+use crate::thread;
+use crate::timezone;
+use crate::twitter::{AuthProblem, PostOptions, ReplyTo, TwitterClient};
+
+/// X allows up to four images on a post.
+pub const MAX_IMAGES: usize = 4;
+/// How many images upload at once.
+const PARALLEL_UPLOADS: usize = 2;
+
+/// How long posting from the composer may take, uploads included, before it gives up.
+pub const POST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(180);
+
+/// Entries of `account`'s blocklist that `text` matches. This and the checks below are what
+/// the composer and `--plain` both ask about before posting.
+pub fn blocklisted(account: &AccountConfig, text: &str) -> Vec<String> {
+    blocklist::matches(&account.profile.blocklist, text)
+}
+
+/// Posts used this month and the cap, when posting `text` would take `account` past its API
+/// tier's monthly post cap.
+pub fn over_monthly_cap(account: &AccountConfig, text: &str) -> Option<(usize, usize)> {
+    let cap = account.twitter.tier?.monthly_post_cap();
+    let used = history::posts_this_month(&account.name);
+    (used + thread::split_thread(text).len() > cap).then_some((used, cap))
+}
+
+/// When to post instead, if it's `[quiet_hours]` now.
+pub fn quiet_until(config: &Config) -> Option<chrono::DateTime<chrono_tz::Tz>> {
+    config.quiet_hours.as_ref()?.deferred_until(timezone::now())
+}
+
+/// Why a post didn't go out, or only partly did.
+#[derive(Debug, Clone)]
+pub struct PostError {
+    pub message: String,
+    /// IDs of the parts of a thread that were posted before it failed.
+    pub posted: Vec<String>,
+    /// The `pre_post` hook turned the post down. Nothing went wrong that retrying would fix,
+    /// so it isn't kept in the outbox.
+    pub vetoed: bool,
+}
+
+impl PostError {
+    fn vetoed(message: String) -> Self {
+        Self { message, posted: Vec::new(), vetoed: true }
+    }
+}
+
+impl From<String> for PostError {
+    fn from(message: String) -> Self {
+        Self { message, posted: Vec::new(), vetoed: false }
+    }
+}
+
+impl std::fmt::Display for PostError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// Called with (uploaded, total) each time an image finishes uploading.
+pub type UploadProgress<'a> = &'a (dyn Fn(usize, usize) + Sync);
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+enum CancelState {
+    #[default]
+    Open,
+    Cancelled,
+    /// Something went to X, which may have published it whatever happens next.
+    Sent,
+}
+
+/// Lets the composer call a post off while it's still safe to: until its first part (or the
+/// message) is sent. Uploads and the lookups before that leave nothing on X.
+#[derive(Debug, Clone, Default)]
+pub struct Cancel {
+    state: Arc<Mutex<CancelState>>,
+    notify: Arc<Notify>,
+}
+
+impl Cancel {
+    /// Calls the post off unless it's been sent; returns whether it was.
+    pub fn cancel(&self) -> bool {
+        let Ok(mut state) = self.state.lock() else {
+            return false;
+        };
+        if *state == CancelState::Sent {
+            return false;
+        }
+        *state = CancelState::Cancelled;
+        self.notify.notify_one();
+        true
+    }
+
+    /// Marks the post as sent, unless it was called off first; returns whether to go ahead.
+    /// Call it right before the request that publishes.
+    pub fn send(&self) -> bool {
+        let Ok(mut state) = self.state.lock() else {
+            return true;
+        };
+        if *state == CancelState::Cancelled {
+            return false;
+        }
+        *state = CancelState::Sent;
+        true
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.state.lock().is_ok_and(|state| *state == CancelState::Cancelled)
+    }
+
+    pub fn is_sent(&self) -> bool {
+        self.state.lock().is_ok_and(|state| *state == CancelState::Sent)
+    }
+
+    /// Waits until the post is called off.
+    pub async fn cancelled(&self) {
+        self.notify.notified().await
+    }
+}
+
+/// Uploads `images` a few at a time, with any alt text from `alt_texts`, and returns their
+/// media IDs in the same order.
+async fn upload_images(
+    client: &TwitterClient,
+    images: Vec<Vec<u8>>,
+    alt_texts: &[String],
+    progress: UploadProgress<'_>,
+) -> Result<Vec<String>, String> {
+    let total = images.len();
+    let permits = Arc::new(Semaphore::new(PARALLEL_UPLOADS));
+    let mut uploads = JoinSet::new();
+    for (i, data) in images.into_iter().enumerate() {
+        let client = client.clone();
+        let permits = permits.clone();
+        let alt_text = alt_texts.get(i).filter(|text| !text.trim().is_empty()).cloned();
+        uploads.spawn(async move {
+            let _permit = permits.acquire_owned().await;
+            let result = match client.upload_media(&data).await {
+                Ok(id) => match alt_text {
+                    Some(alt_text) => client.set_alt_text(&id, &alt_text).await.map(|_| id),
+                    None => Ok(id),
+                },
+                Err(e) => Err(e),
+            };
+            (i, result)
+        });
+    }
+
+    // Returning early drops the set, which cancels the uploads still running
+    let mut media_ids = vec![String::new(); total];
+    let mut done = 0;
+    while let Some(joined) = uploads.join_next().await {
+        let (i, result) = joined.map_err(|e| format!("Failed to upload image: {}", e))?;
+        match result {
+            Ok(id) => media_ids[i] = id,
+            Err(e) if total == 1 => return Err(format!("Failed to upload image: {}", e)),
+            Err(e) => return Err(format!("Failed to upload image {} of {}: {}", i + 1, total, e)),
+        }
+        done += 1;
+        progress(done, total);
+    }
+    Ok(media_ids)
+}
+
+/// Posts `text` (a thread if it has `---` separators) and returns the new post IDs.
+pub async fn post_tweet(
+    client: &TwitterClient,
+    text: String,
+    images: Vec<Vec<u8>>,
+    options: &PostOptions,
+    progress: UploadProgress<'_>,
+    cancel: &Cancel,
+) -> Result<Vec<String>, PostError> {
+    let mut media_ids = upload_images(client, images, &options.alt_texts, progress).await?;
+
+    // Each part of a thread replies to the one before; the images go on the first
+    let parts = thread::split_thread(&text);
+    let total = parts.len();
+    let mut options = options.clone();
+    let mut ids = Vec::new();
+
+    for part in parts {
+        if !cancel.send() {
+            return Err("Cancelled".to_string().into());
+        }
+        match client.post_tweet(part, std::mem::take(&mut media_ids), &options).await {
+            Ok(tweet_data) => {
+                options.community_id = None;
+                options.reply = Some(ReplyTo {
+                    in_reply_to_tweet_id: tweet_data.id.clone(),
+                    exclude_reply_user_ids: Vec::new(),
+                });
+                ids.push(tweet_data.id);
+            }
+            Err(e) if ids.is_empty() => return Err(format!("Failed to post: {}", e).into()),
+            Err(e) => {
+                return Err(PostError {
+                    message: format!("Posted {} of {} parts, then failed: {}", ids.len(), total, e),
+                    posted: ids,
+                    vetoed: false,
+                });
+            }
+        }
+    }
+
+    if ids.is_empty() {
+        return Err("Nothing to post".to_string().into());
+    }
+    Ok(ids)
+}
+
+/// Posts for `account` after the transform scripts, running the configured hooks around it
+/// and logging it to the history. `cancel` can call it off until the first part is sent.
+#[allow(clippy::too_many_arguments)]
+pub async fn publish(
+    account: &AccountConfig,
+    client: &TwitterClient,
+    text: String,
+    images: Vec<Vec<u8>>,
+    options: &PostOptions,
+    delete_after: Option<Duration>,
+    config: &Config,
+    progress: UploadProgress<'_>,
+    cancel: &Cancel,
+) -> Result<Vec<String>, PostError> {
+    let hooks = &config.hooks;
+    let text = match &config.shortener {
+        Some(shortener) => links::shorten_urls(shortener, &text).await,
+        None => text,
+    };
+    let text = scripting::apply_transforms(&config.scripts, &account.name, text).await?;
+    hooks::pre_post(hooks, &account.name, &text).await.map_err(PostError::vetoed)?;
+
+    let result = post_tweet(client, text.clone(), images, options, progress, cancel).await;
+    match &result {
+        Ok(ids) => {
+            record_history(&account.name, &text, ids, delete_after);
+            let url = client.post_url(&ids[0]).await;
+            hooks::post_success(hooks, &account.name, &text, &url).await;
+        }
+        Err(e) => {
+            // The parts that made it out are on X whatever happens to the rest
+            record_history(&account.name, &text, &e.posted, delete_after);
+            hooks::post_failure(hooks, &account.name, &text, &e.message).await;
+        }
+    }
+    result
+}
+
+/// A 401's body says little, so a post that failed because X turned down the credentials
+/// says that instead, and returns which way they were turned down.
+pub fn explain_auth_failure<T>(result: Result<T, String>, client: &TwitterClient) -> (Result<T, String>, Option<AuthProblem>) {
+    match (result, client.auth_problem()) {
+        (Err(_), Some(problem)) => (Err(problem.message().to_string()), Some(problem)),
+        (result, _) => (result, None),
+    }
+}
+
+/// Where a post was cross-posted: each target's name and the post's URL there, or the error.
+pub type Crossposts = Vec<(String, Result<String, String>)>;
+
+/// Sends composed `text` to each of `account`'s cross-post targets, adapted to fit it.
+pub async fn crosspost(
+    account: &AccountConfig,
+    text: &str,
+    images: &[Vec<u8>],
+    alt_texts: &[String],
+    config: &Config,
+) -> Crossposts {
+    let mut results = Vec::new();
+    for target in config.targets.iter().filter(|target| account.profile.targets.contains(&target.name)) {
+        let parts = crosspost::adapt(target, text);
+        let result = crosspost::publish(target, parts, images, alt_texts).await;
+        results.push((target.name.clone(), result.map_err(|e| format!("{:#}", e))));
+    }
+    results
+}
+
+/// Logs each posted part of `text` to the history, one record per post ID.
+fn record_history(account: &str, text: &str, ids: &[String], delete_after: Option<Duration>) {
+    let records = ids
+        .iter()
+        .zip(thread::split_thread(text))
+        .map(|(id, part)| PostRecord::new(id.clone(), account, part, delete_after))
+        .collect();
+    let _ = history::record_posts(records);
+}
+
+/// Publishes a post from the outbox with its account's settings, then cross-posts it. A
+/// thread that failed partway before carries on from the first part that didn't go out; the
+/// link returned is to its first part, and the IDs in an error include the parts posted earlier.
+pub async fn publish_queued(
+    post: &QueuedPost,
+    account: &AccountConfig,
+    client: &TwitterClient,
+    config: &Config,
+) -> Result<(String, Crossposts), PostError> {
+    let images = post.images().map_err(|e| e.to_string())?;
+    let mut options = PostOptions {
+        reply_settings: account.profile.reply_settings.clone(),
+        community_id: post.community_id.clone(),
+        reply: post.reply.clone(),
+        alt_texts: post.alt_texts.clone(),
+    };
+
+    let (text, _) = crosspost::split_overrides(&post.text);
+    let mut text = account.profile.apply_signature(text);
+    let mut attached = images.clone();
+    if let Some(last) = post.posted.last() {
+        // The images and the community went with the first part
+        let rest: Vec<String> = thread::split_thread(&text).into_iter().skip(post.posted.len()).collect();
+        text = rest.join(&format!("\n{}\n", thread::SEPARATOR));
+        attached.clear();
+        options.alt_texts.clear();
+        options.community_id = None;
+        options.reply = Some(ReplyTo {
+            in_reply_to_tweet_id: last.clone(),
+            exclude_reply_user_ids: Vec::new(),
+        });
+    }
+
+    let result = publish(account, client, text, attached, &options, post.delete_after(), config, &|_, _| {}, &Cancel::default()).await;
+    let ids = result.map(|ids| [post.posted.clone(), ids].concat()).map_err(|mut e| {
+        if let Some(problem) = client.auth_problem() {
+            e.message = problem.message().to_string();
+        }
+        e.posted = [post.posted.clone(), e.posted].concat();
+        e
+    })?;
+    let crossposts = crosspost(account, &post.text, &images, &post.alt_texts, config).await;
+    Ok((client.post_url(&ids[0]).await, crossposts))
+}
@@ -0,0 +1,197 @@
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::locale::t;
+use crate::stats_ui::draw_centered_message;
+use crate::text;
+use crate::twitter::{Tweet, UserProfile};
+
+#[derive(Debug, Clone)]
+pub enum ProfileState {
+    Input,
+    Loading(String),
+    Profile,
+    Error(String),
+}
+
+pub struct ProfileApp {
+    pub state: ProfileState,
+    pub input: String,
+    pub profile: Option<UserProfile>,
+    pub tweets: Vec<Tweet>,
+    pub list_state: ListState,
+    pub accent: Color,
+    /// `[display] time_format`; relative times when unset.
+    pub time_format: Option<String>,
+    /// Result of the last follow, like or repost, or of taking one back, shown in the footer.
+    pub status: Option<String>,
+}
+
+impl ProfileApp {
+    pub fn new(accent: Color) -> Self {
+        Self {
+            state: ProfileState::Input,
+            input: String::new(),
+            profile: None,
+            tweets: Vec::new(),
+            list_state: ListState::default(),
+            accent,
+            time_format: None,
+            status: None,
+        }
+    }
+
+    pub fn set_profile(&mut self, profile: UserProfile, tweets: Vec<Tweet>) {
+        self.profile = Some(profile);
+        self.status = None;
+        self.tweets = tweets;
+        self.list_state.select(if self.tweets.is_empty() { None } else { Some(0) });
+        self.state = ProfileState::Profile;
+    }
+
+    pub fn selected_tweet(&self) -> Option<&Tweet> {
+        self.tweets.get(self.list_state.selected()?)
+    }
+
+    pub fn next(&mut self) {
+        if self.tweets.is_empty() {
+            return;
+        }
+        let i = match self.list_state.selected() {
+            Some(i) if i + 1 < self.tweets.len() => i + 1,
+            _ => 0,
+        };
+        self.list_state.select(Some(i));
+    }
+
+    pub fn previous(&mut self) {
+        if self.tweets.is_empty() {
+            return;
+        }
+        let i = match self.list_state.selected() {
+            Some(0) | None => self.tweets.len() - 1,
+            Some(i) => i - 1,
+        };
+        self.list_state.select(Some(i));
+    }
+}
+
+pub fn draw(f: &mut Frame, app: &mut ProfileApp) {
+    match &app.state {
+        ProfileState::Input => draw_input(f, app),
+        ProfileState::Profile => draw_profile(f, app),
+        ProfileState::Loading(msg) => draw_centered_message(f, msg, Color::Yellow, f.area()),
+        ProfileState::Error(msg) => draw_centered_message(f, msg, Color::Red, f.area()),
+    }
+}
+
+fn draw_input(f: &mut Frame, app: &ProfileApp) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(3),
+        ])
+        .split(f.area());
+
+    let input = Paragraph::new(format!("@{}", app.input))
+        .style(Style::default().fg(Color::White))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(t("profile.lookup_title"))
+                .border_style(Style::default().fg(app.accent)),
+        );
+    f.render_widget(input, chunks[0]);
+
+    let footer = Paragraph::new(t("profile.lookup_keys"))
+        .style(Style::default().fg(Color::Gray))
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL));
+    f.render_widget(footer, chunks[2]);
+}
+
+fn draw_profile(f: &mut Frame, app: &mut ProfileApp) {
+    let Some(profile) = app.profile.clone() else {
+        return;
+    };
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Length(6),
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(3),
+        ])
+        .split(f.area());
+
+    // Header
+    let header = Paragraph::new(format!("{} (@{})", profile.name, profile.username))
+        .style(Style::default().fg(app.accent).add_modifier(Modifier::BOLD))
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL));
+    f.render_widget(header, chunks[0]);
+
+    // Bio
+    let bio = Paragraph::new(profile.description.clone().unwrap_or_default())
+        .wrap(Wrap { trim: false })
+        .block(Block::default().borders(Borders::ALL).title(t("profile.bio_title")))
+        .style(Style::default().fg(Color::White));
+    f.render_widget(bio, chunks[1]);
+
+    // Counts
+    let counts = match &profile.public_metrics {
+        Some(metrics) => Line::from(vec![
+            Span::styled(t("profile.followers"), Style::default().fg(Color::Yellow)),
+            Span::raw(format!("{}", metrics.followers_count)),
+            Span::styled(t("profile.following"), Style::default().fg(Color::Green)),
+            Span::raw(format!("{}", metrics.following_count)),
+            Span::styled(t("profile.posts"), Style::default().fg(Color::Cyan)),
+            Span::raw(format!("{}", metrics.tweet_count)),
+            Span::styled(t("profile.listed"), Style::default().fg(Color::Magenta)),
+            Span::raw(format!("{}", metrics.listed_count)),
+        ]),
+        None => Line::from(t("stats.no_metrics")),
+    };
+    let counts = Paragraph::new(counts).block(Block::default().borders(Borders::ALL));
+    f.render_widget(counts, chunks[2]);
+
+    // Recent posts
+    let items: Vec<ListItem> = app
+        .tweets
+        .iter()
+        .map(|tweet| {
+            let text_preview = text::preview_line(&tweet.text, 80);
+            let date = tweet
+                .created_at
+                .as_ref()
+                .map(|d| text::format_timestamp(d, app.time_format.as_deref()))
+                .unwrap_or_else(|| t("stats.unknown_date").to_string());
+            ListItem::new(format!("{} | {}", date, text_preview))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title(t("profile.recent_posts_title")))
+        .highlight_style(crate::theme::selected())
+        .highlight_symbol(">> ");
+    f.render_stateful_widget(list, chunks[3], &mut app.list_state);
+
+    let mut footer_block = Block::default().borders(Borders::ALL);
+    if let Some(status) = &app.status {
+        footer_block = footer_block.title(status.as_str());
+    }
+    let footer = Paragraph::new(t("profile.keys"))
+        .style(Style::default().fg(Color::Gray))
+        .alignment(Alignment::Center)
+        .block(footer_block);
+    f.render_widget(footer, chunks[4]);
+}
@@ -0,0 +1,731 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc, Weekday};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::lock;
+use crate::posting;
+use crate::text;
+use crate::timezone;
+use crate::twitter::ReplyTo;
+
+/// Held while queued posts are written or removed. Changes to a post made with it read the post
+/// afresh first, so an edit can't undo what the daemon just did to it, or bring it back once
+/// it's been published.
+pub const QUEUE_LOCK: &str = "queue";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum QueueStatus {
+    Scheduled,
+    /// Being published by the daemon or `xpost flush`. Found like this afterwards, the run was
+    /// interrupted and the post may or may not have gone out.
+    Posting,
+    Failed(String),
+}
+
+/// A post waiting in the outbox, either scheduled for later or kept after a failed attempt.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedPost {
+    pub id: String,
+    pub account: String,
+    /// Text as composed; the account's signature is added when it's published.
+    pub text: String,
+    pub scheduled_at: DateTime<Utc>,
+    pub status: QueueStatus,
+    #[serde(default)]
+    pub attempts: u32,
+    /// Images saved next to the post as `<id>.png`, `<id>-1.png` and so on.
+    #[serde(default)]
+    pub image_count: usize,
+    /// Alt text for each image, in order.
+    #[serde(default)]
+    pub alt_texts: Vec<String>,
+    /// Written by versions that allowed a single image, saved as `<id>.png`. Folded into
+    /// `image_count` when the post is read, so it survives the post being saved again.
+    #[serde(default, skip_serializing)]
+    has_image: bool,
+    #[serde(default)]
+    pub community_id: Option<String>,
+    /// The post this one answers, for a reply that failed from the composer.
+    #[serde(default)]
+    pub reply: Option<ReplyTo>,
+    /// Parts of a thread that went out before an attempt failed. A retry posts only the rest,
+    /// as replies to the last of these.
+    #[serde(default)]
+    pub posted: Vec<String>,
+    /// Lifetime in seconds once posted, for ephemeral posts.
+    #[serde(default)]
+    pub delete_after_secs: Option<i64>,
+    /// Evergreen posts go out again at each occurrence; `scheduled_at` is the next one.
+    #[serde(default)]
+    pub recurrence: Option<Recurrence>,
+    /// Held back by the daemon until resumed.
+    #[serde(default)]
+    pub paused: bool,
+}
+
+/// When a recurring post goes out: at `time` on each of `days`, or every day when empty, in
+/// the configured timezone.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Recurrence {
+    #[serde(default)]
+    pub days: Vec<Weekday>,
+    pub time: NaiveTime,
+}
+
+impl Recurrence {
+    /// The first occurrence after `after`.
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let start = timezone::from_utc(after).date_naive();
+        (0..=7)
+            .map(|ahead| start + Duration::days(ahead))
+            .filter(|day| self.days.is_empty() || self.days.contains(&day.weekday()))
+            .filter_map(|day| timezone::to_utc(day.and_time(self.time)))
+            .find(|at| *at > after)
+    }
+
+    /// "every day at 09:00", "every Mon, Thu at 10:30".
+    pub fn describe(&self) -> String {
+        let days = match self.days.as_slice() {
+            [] => "day".to_string(),
+            [Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri] => "weekday".to_string(),
+            days => days.iter().map(|day| day.to_string()).collect::<Vec<_>>().join(", "),
+        };
+        format!("every {} at {}", days, self.time.format("%H:%M"))
+    }
+}
+
+/// Parses a repeat rule: "every monday 10am", "every mon, thu at 9:30", "every weekday 9am",
+/// "every weekend noon" or "every day 18:00" (also "daily 18:00"). Without a time it's 9am.
+pub fn parse_recurrence(input: &str) -> Option<Recurrence> {
+    let input = input.trim().to_lowercase();
+    let (rule, daily) = match (input.strip_prefix("every "), input.strip_prefix("daily")) {
+        (Some(rule), _) => (rule, false),
+        (_, Some(rule)) => (rule, true),
+        _ => return None,
+    };
+
+    let mut days = Vec::new();
+    let mut every_day = daily;
+    let mut rest = Vec::new();
+    for word in rule
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|word| !word.is_empty() && !matches!(*word, "at" | "on" | "and"))
+    {
+        match word {
+            "day" | "days" => every_day = true,
+            "weekday" | "weekdays" => days.extend([Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri]),
+            "weekend" | "weekends" => days.extend([Weekday::Sat, Weekday::Sun]),
+            _ => match word.parse::<Weekday>().or_else(|_| word.trim_end_matches('s').parse()) {
+                Ok(day) => days.push(day),
+                Err(_) => rest.push(word),
+            },
+        }
+    }
+    if !every_day && days.is_empty() {
+        return None;
+    }
+    if every_day {
+        days.clear();
+    }
+    days.sort_by_key(|day| day.num_days_from_monday());
+    days.dedup();
+
+    let time = match rest.as_slice() {
+        [] => NaiveTime::from_hms_opt(DEFAULT_TIME.0, DEFAULT_TIME.1, 0)?,
+        rest => parse_clock(&rest.concat())?,
+    };
+    Some(Recurrence { days, time })
+}
+
+impl QueuedPost {
+    pub fn new(account: &str, text: String, scheduled_at: DateTime<Utc>) -> Self {
+        Self {
+            id: format!("{}-{}", Utc::now().timestamp_millis(), account),
+            account: account.to_string(),
+            text,
+            scheduled_at,
+            status: QueueStatus::Scheduled,
+            attempts: 0,
+            image_count: 0,
+            alt_texts: Vec::new(),
+            has_image: false,
+            community_id: None,
+            reply: None,
+            posted: Vec::new(),
+            delete_after_secs: None,
+            recurrence: None,
+            paused: false,
+        }
+    }
+
+    /// Records a failed attempt, keeping track of any parts of the thread it did post.
+    pub fn fail(&mut self, error: &posting::PostError) {
+        self.attempts += 1;
+        self.status = QueueStatus::Failed(error.message.clone());
+        self.posted = error.posted.clone();
+    }
+
+    /// Moves a recurring post on to its first run after `now`, starting afresh; returns
+    /// false when there is none and the post is done.
+    pub fn move_to_next_run(&mut self, now: DateTime<Utc>) -> bool {
+        let Some(next) = self.recurrence.as_ref().and_then(|recurrence| recurrence.next_after(now)) else {
+            return false;
+        };
+        self.scheduled_at = next;
+        self.status = QueueStatus::Scheduled;
+        self.attempts = 0;
+        // Parts saved from a failed run belong to that run only
+        self.posted.clear();
+        true
+    }
+
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.status == QueueStatus::Scheduled && !self.paused && self.scheduled_at <= now
+    }
+
+    pub fn delete_after(&self) -> Option<chrono::Duration> {
+        self.delete_after_secs.map(chrono::Duration::seconds)
+    }
+
+    pub fn preview(&self) -> String {
+        let preview = text::preview_line(&self.text, 60);
+        let when = timezone::from_utc(self.scheduled_at).format("%Y-%m-%d %H:%M");
+        let status = match &self.status {
+            _ if self.paused => "paused",
+            QueueStatus::Scheduled => "scheduled",
+            QueueStatus::Posting => "posting",
+            QueueStatus::Failed(_) => "failed",
+        };
+        let repeats = if self.recurrence.is_some() { "↻ " } else { "" };
+        let reply = if self.reply.is_some() { "↩ " } else { "" };
+        format!("{:<9} | {} | {} | {}{}{}", status, when, self.account, repeats, reply, preview)
+    }
+
+    pub fn images(&self) -> Result<Vec<Vec<u8>>> {
+        let dir = queue_dir()?;
+        (0..self.image_count)
+            .map(|i| fs::read(dir.join(image_file_name(&self.id, i))).context("Failed to read queued image"))
+            .collect()
+    }
+}
+
+/// Parses when to post: "30m"/"2h"/"1d" or "in 2 hours" from now, a "YYYY-MM-DD HH:MM", or a
+/// phrase like "tomorrow 9am", "friday 14:30", "next monday" or "noon". Clock times are in the
+/// configured timezone.
+pub fn parse_schedule_time(input: &str) -> Option<DateTime<Utc>> {
+    parse_schedule_time_from(input, timezone::now())
+}
+
+/// Time of day for a phrase that names a day but not a time, like "tomorrow".
+const DEFAULT_TIME: (u32, u32) = (9, 0);
+
+fn parse_schedule_time_from(input: &str, now: DateTime<Tz>) -> Option<DateTime<Utc>> {
+    let input = input.trim().to_lowercase();
+    if let Some(after) = crate::history::parse_duration(&input).or_else(|| parse_in(&input)) {
+        return Some(now.with_timezone(&Utc) + after);
+    }
+    if let Ok(naive) = NaiveDateTime::parse_from_str(&input, "%Y-%m-%d %H:%M") {
+        return timezone::to_utc_in(now.timezone(), naive);
+    }
+
+    let today = now.date_naive();
+    let mut day = None;
+    let mut time = None;
+    let mut rest = Vec::new();
+    for word in input
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|word| !word.is_empty() && !matches!(*word, "at" | "on" | "this" | "next"))
+    {
+        match parse_day(word, today) {
+            Some(_) if day.is_some() => return None,
+            Some((date, evening)) => {
+                day = Some(date);
+                time = time.or(evening);
+            }
+            None => rest.push(word),
+        }
+    }
+    if !rest.is_empty() {
+        time = Some(parse_clock(&rest.concat())?);
+    }
+
+    let at = match (day, time) {
+        (Some(day), time) => day.and_time(time.or_else(|| NaiveTime::from_hms_opt(DEFAULT_TIME.0, DEFAULT_TIME.1, 0))?),
+        // A time alone is the next time the clock shows it
+        (None, Some(time)) if today.and_time(time) > now.naive_local() => today.and_time(time),
+        (None, Some(time)) => today.succ_opt()?.and_time(time),
+        (None, None) => return None,
+    };
+    timezone::to_utc_in(now.timezone(), at)
+}
+
+/// "in 2 hours", "in 90 min", "in an hour", "in 3d".
+fn parse_in(input: &str) -> Option<Duration> {
+    let words: Vec<&str> = input.strip_prefix("in ")?.split_whitespace().collect();
+    let (amount, unit) = match words.as_slice() {
+        [single] => return crate::history::parse_duration(single),
+        [amount, unit] => (*amount, *unit),
+        _ => return None,
+    };
+    let amount: i64 = match amount {
+        "a" | "an" | "one" => 1,
+        amount => amount.parse().ok()?,
+    };
+    match unit.trim_end_matches('s') {
+        "m" | "min" | "minute" => Some(Duration::minutes(amount)),
+        "h" | "hr" | "hour" => Some(Duration::hours(amount)),
+        "d" | "day" => Some(Duration::days(amount)),
+        "w" | "wk" | "week" => Some(Duration::weeks(amount)),
+        _ => None,
+    }
+}
+
+/// A day named by `word`, with the time it implies ("tonight" is 8pm).
+fn parse_day(word: &str, today: NaiveDate) -> Option<(NaiveDate, Option<NaiveTime>)> {
+    match word {
+        "today" => Some((today, None)),
+        "tonight" => Some((today, NaiveTime::from_hms_opt(20, 0, 0))),
+        "tomorrow" | "tmrw" => Some((today.succ_opt()?, None)),
+        _ => {
+            if let Ok(date) = NaiveDate::parse_from_str(word, "%Y-%m-%d") {
+                return Some((date, None));
+            }
+            // A weekday is the next one, a week ahead when it's today's
+            let weekday: Weekday = word.parse().ok()?;
+            let ahead = (weekday.num_days_from_monday() as i64 - today.weekday().num_days_from_monday() as i64).rem_euclid(7);
+            Some((today + Duration::days(if ahead == 0 { 7 } else { ahead }), None))
+        }
+    }
+}
+
+/// "9am", "9:30pm", "21:30", "noon" and the like.
+fn parse_clock(text: &str) -> Option<NaiveTime> {
+    let named = match text {
+        "midnight" => Some((0, 0)),
+        "morning" => Some((9, 0)),
+        "noon" | "midday" => Some((12, 0)),
+        "afternoon" => Some((14, 0)),
+        "evening" => Some((18, 0)),
+        _ => None,
+    };
+    if let Some((hour, minute)) = named {
+        return NaiveTime::from_hms_opt(hour, minute, 0);
+    }
+
+    let text = text.replace("a.m.", "am").replace("p.m.", "pm").replace('.', ":");
+    let (clock, offset) = match (text.strip_suffix("am"), text.strip_suffix("pm")) {
+        (Some(clock), _) => (clock, Some(0)),
+        (_, Some(clock)) => (clock, Some(12)),
+        _ => (text.as_str(), None),
+    };
+    let (hour, minute) = match clock.split_once(':') {
+        Some((hour, minute)) if minute.len() == 2 => (hour.parse::<u32>().ok()?, minute.parse::<u32>().ok()?),
+        None if offset.is_some() => (clock.parse::<u32>().ok()?, 0),
+        _ => return None,
+    };
+    let hour = match offset {
+        Some(offset) if (1..=12).contains(&hour) => hour % 12 + offset,
+        Some(_) => return None,
+        None => hour,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+/// Call once a queued post is published: a recurring one moves on to its next occurrence and
+/// stays queued, images and all; any other is removed.
+pub fn published(post: &QueuedPost) -> Result<()> {
+    let _lock = lock::lock(QUEUE_LOCK)?;
+    let dir = queue_dir()?;
+    // Deleted while it was being posted
+    let Some(mut post) = read_post(&dir, &post.id) else {
+        return Ok(());
+    };
+    if post.move_to_next_run(Utc::now()) {
+        write_post(&dir, &post, &[])
+    } else {
+        remove_post(&dir, &post.id)
+    }
+}
+
+/// One post in a batch file for `xpost queue --file`.
+#[derive(Debug, Deserialize)]
+struct BatchRow {
+    text: String,
+    /// When to post, in any form `parse_schedule_time` takes, or a repeat rule for
+    /// `parse_recurrence`.
+    #[serde(alias = "time", alias = "scheduled_at")]
+    at: String,
+    /// Path to an image, relative to the batch file.
+    #[serde(default)]
+    image: Option<String>,
+    #[serde(default)]
+    alt: Option<String>,
+    /// Account to post as; the current one when empty.
+    #[serde(default)]
+    account: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BatchFile {
+    posts: Vec<BatchRow>,
+}
+
+/// Reads a batch of posts from a `.toml` file with `[[posts]]` tables, or a CSV file whose
+/// header names the columns (`text`, `at`, and optionally `image`, `alt` and `account`), and
+/// queues them all. Every row is checked before any is queued, so a mistake on row 40 doesn't
+/// leave the first 39 half-imported. Returns how many posts were queued.
+pub fn import_batch(path: &Path, default_account: &str, accounts: &[String]) -> Result<usize> {
+    let content = fs::read_to_string(path)
+        .context("Failed to read batch file")?;
+    let rows = if path.extension().is_some_and(|ext| ext == "toml") {
+        toml::from_str::<BatchFile>(&content).context("Failed to parse batch file")?.posts
+    } else {
+        parse_csv_rows(&content)?
+    };
+    let base = path.parent().unwrap_or(Path::new("."));
+
+    let now = Utc::now();
+    let mut posts = Vec::new();
+    for (i, row) in rows.into_iter().enumerate() {
+        let row_number = i + 1;
+        if row.text.trim().is_empty() {
+            anyhow::bail!("Row {}: no text", row_number);
+        }
+        let recurrence = parse_recurrence(&row.at);
+        let at = match &recurrence {
+            Some(recurrence) => recurrence.next_after(now),
+            None => parse_schedule_time(&row.at),
+        }
+        .with_context(|| format!("Row {}: couldn't read '{}' as a time", row_number, row.at))?;
+        if at <= now {
+            anyhow::bail!("Row {}: {} has already passed", row_number, row.at);
+        }
+        let account = row
+            .account
+            .filter(|account| !account.trim().is_empty())
+            .unwrap_or_else(|| default_account.to_string());
+        if !accounts.contains(&account) {
+            anyhow::bail!("Row {}: no account named '{}'", row_number, account);
+        }
+        let images = match row.image.as_deref().map(str::trim).filter(|image| !image.is_empty()) {
+            Some(image) => {
+                let image_path = base.join(image);
+                vec![crate::clipboard::validate_image_file(&image_path.to_string_lossy())
+                    .with_context(|| format!("Row {}: couldn't read {}", row_number, image))?]
+            }
+            None => Vec::new(),
+        };
+
+        let mut post = QueuedPost::new(&account, row.text, at);
+        // Rows share a timestamp, so the row number keeps their IDs apart
+        post.id = format!("{}-{}-{}", now.timestamp_millis(), i, account);
+        post.image_count = images.len();
+        post.alt_texts = row.alt.into_iter().filter(|alt| !images.is_empty() && !alt.trim().is_empty()).collect();
+        post.recurrence = recurrence;
+        posts.push((post, images));
+    }
+
+    let _lock = lock::lock(QUEUE_LOCK)?;
+    let dir = queue_dir()?;
+    for (post, images) in &posts {
+        write_post(&dir, post, images)?;
+    }
+    Ok(posts.len())
+}
+
+fn parse_csv_rows(content: &str) -> Result<Vec<BatchRow>> {
+    let mut records = parse_csv(content).into_iter();
+    let header: Vec<String> = records
+        .next()
+        .context("The batch file is empty")?
+        .into_iter()
+        .map(|column| column.trim().to_lowercase())
+        .collect();
+
+    records
+        .filter(|record| record.iter().any(|field| !field.trim().is_empty()))
+        .enumerate()
+        .map(|(i, record)| {
+            let fields: HashMap<String, String> = header.iter().cloned().zip(record).collect();
+            let value = serde_json::to_value(fields)?;
+            serde_json::from_value(value).with_context(|| format!("Row {}: missing text or time", i + 1))
+        })
+        .collect()
+}
+
+/// Splits CSV into records of fields, following RFC 4180: fields in double quotes may hold
+/// commas and line breaks, and `""` inside them is a literal quote.
+fn parse_csv(content: &str) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = content.trim_start_matches('\u{feff}').chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => record.push(std::mem::take(&mut field)),
+            '\r' if !quoted => {}
+            '\n' if !quoted => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records
+}
+
+fn image_file_name(post_id: &str, index: usize) -> String {
+    match index {
+        0 => format!("{}.png", post_id),
+        i => format!("{}-{}.png", post_id, i),
+    }
+}
+
+pub fn queue_dir() -> Result<PathBuf> {
+    let dir = crate::config::config_dir()?.join("queue");
+
+    if !dir.exists() {
+        fs::create_dir_all(&dir)
+            .context("Failed to create queue directory")?;
+    }
+
+    Ok(dir)
+}
+
+/// Saves the post, and any images alongside it.
+pub fn save_post(post: &QueuedPost, images: &[Vec<u8>]) -> Result<()> {
+    let _lock = lock::lock(QUEUE_LOCK)?;
+    write_post(&queue_dir()?, post, images)
+}
+
+/// Call with the queue lock held.
+fn write_post(dir: &Path, post: &QueuedPost, images: &[Vec<u8>]) -> Result<()> {
+    for (i, image) in images.iter().enumerate() {
+        fs::write(dir.join(image_file_name(&post.id, i)), image)
+            .context("Failed to write queued image")?;
+    }
+
+    let json = serde_json::to_string_pretty(post)
+        .context("Failed to serialize queued post")?;
+    crate::config::write_atomic(&dir.join(format!("{}.json", post.id)), json)
+        .context("Failed to write queued post")?;
+
+    Ok(())
+}
+
+/// All queued posts, soonest first.
+pub fn load_queue() -> Result<Vec<QueuedPost>> {
+    let dir = queue_dir()?;
+    let mut posts = Vec::new();
+
+    for entry in fs::read_dir(dir).context("Failed to read queue directory")? {
+        let path = entry?.path();
+
+        if path.extension().and_then(|s| s.to_str()) == Some("json") {
+            if let Ok(content) = fs::read_to_string(&path) {
+                if let Some(post) = parse_post(&content) {
+                    posts.push(post);
+                }
+            }
+        }
+    }
+
+    posts.sort_by_key(|post| post.scheduled_at);
+
+    Ok(posts)
+}
+
+fn read_post(dir: &Path, post_id: &str) -> Option<QueuedPost> {
+    let content = fs::read_to_string(dir.join(format!("{}.json", post_id))).ok()?;
+    parse_post(&content)
+}
+
+fn parse_post(content: &str) -> Option<QueuedPost> {
+    let mut post: QueuedPost = serde_json::from_str(content).ok()?;
+    if post.has_image {
+        post.image_count = post.image_count.max(1);
+        post.has_image = false;
+    }
+    Some(post)
+}
+
+/// Applies `update` to the post as it is on disk now and saves it. Returns the updated post, or
+/// `None` if it's no longer queued.
+pub fn update_post<F>(post_id: &str, update: F) -> Result<Option<QueuedPost>>
+where
+    F: FnOnce(&mut QueuedPost),
+{
+    let _lock = lock::lock(QUEUE_LOCK)?;
+    let dir = queue_dir()?;
+    let Some(mut post) = read_post(&dir, post_id) else {
+        return Ok(None);
+    };
+    update(&mut post);
+    write_post(&dir, &post, &[])?;
+    Ok(Some(post))
+}
+
+/// Marks the post as posting if `ready` still holds for it as it is on disk now, and returns
+/// it. `None` means it changed or went away since it was looked at, and shouldn't be posted.
+pub fn claim<F>(post_id: &str, ready: F) -> Result<Option<QueuedPost>>
+where
+    F: FnOnce(&QueuedPost) -> bool,
+{
+    let _lock = lock::lock(QUEUE_LOCK)?;
+    let dir = queue_dir()?;
+    let Some(mut post) = read_post(&dir, post_id).filter(|post| ready(post)) else {
+        return Ok(None);
+    };
+    post.status = QueueStatus::Posting;
+    write_post(&dir, &post, &[])?;
+    Ok(Some(post))
+}
+
+pub fn delete_post(post_id: &str) -> Result<()> {
+    let _lock = lock::lock(QUEUE_LOCK)?;
+    remove_post(&queue_dir()?, post_id)
+}
+
+/// Call with the queue lock held.
+fn remove_post(dir: &Path, post_id: &str) -> Result<()> {
+    let images = (0..posting::MAX_IMAGES).map(|i| dir.join(image_file_name(post_id, i)));
+    for file_path in std::iter::once(dir.join(format!("{}.json", post_id))).chain(images) {
+        if file_path.exists() {
+            fs::remove_file(&file_path)
+                .context("Failed to delete queued post")?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn splits_csv_into_records() {
+        let records = parse_csv("text,at\r\nhello,tomorrow 9am\r\nbye,in 2 hours");
+        assert_eq!(records, vec![vec!["text", "at"], vec!["hello", "tomorrow 9am"], vec!["bye", "in 2 hours"]]);
+    }
+
+    #[test]
+    fn keeps_commas_line_breaks_and_quotes_inside_quoted_fields() {
+        let records = parse_csv("\u{feff}text,at\n\"one, two\nthree \"\"four\"\"\",noon\n");
+        assert_eq!(records, vec![vec!["text", "at"], vec!["one, two\nthree \"four\"", "noon"]]);
+    }
+
+    #[test]
+    fn reads_rows_by_header_and_skips_blank_lines() {
+        let rows = parse_csv_rows("Text,Time,Image\nhello,noon,cat.png\n,,\nbye,1h,\n").unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].text, "hello");
+        assert_eq!(rows[0].at, "noon");
+        assert_eq!(rows[0].image.as_deref(), Some("cat.png"));
+        assert_eq!(rows[1].text, "bye");
+        assert_eq!(rows[1].image.as_deref(), Some(""));
+    }
+
+    #[test]
+    fn rejects_rows_without_a_time() {
+        let error = parse_csv_rows("text,image\nhello,cat.png\n").unwrap_err();
+        assert_eq!(error.to_string(), "Row 1: missing text or time");
+    }
+
+    #[test]
+    fn a_recurring_post_starts_its_next_run_afresh() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 5, 20, 0, 0).unwrap();
+        let mut post = QueuedPost::new("main", "first\n---\nsecond".to_string(), now);
+        post.recurrence = parse_recurrence("every day 18:00");
+        post.fail(&posting::PostError {
+            message: "timed out".to_string(),
+            posted: vec!["100".to_string()],
+            vetoed: false,
+        });
+
+        assert!(post.move_to_next_run(now));
+        assert!(post.scheduled_at > now);
+        assert_eq!(post.status, QueueStatus::Scheduled);
+        assert_eq!(post.attempts, 0);
+        assert!(post.posted.is_empty());
+    }
+
+    #[test]
+    fn a_one_off_post_has_no_next_run() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 5, 20, 0, 0).unwrap();
+        assert!(!QueuedPost::new("main", "once".to_string(), now).move_to_next_run(now));
+    }
+
+    fn new_york(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Tz> {
+        chrono_tz::America::New_York.with_ymd_and_hms(year, month, day, hour, minute, 0).unwrap()
+    }
+
+    fn utc(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> Option<DateTime<Utc>> {
+        Some(Utc.with_ymd_and_hms(year, month, day, hour, minute, 0).unwrap())
+    }
+
+    // Wednesday 5 March 2025, 3pm; clocks in New York go forward on the 9th
+    fn wednesday_afternoon() -> DateTime<Tz> {
+        new_york(2025, 3, 5, 15, 0)
+    }
+
+    #[test]
+    fn reads_a_day_and_a_time() {
+        let now = wednesday_afternoon();
+        assert_eq!(parse_schedule_time_from("tomorrow 9am", now), utc(2025, 3, 6, 14, 0));
+        assert_eq!(parse_schedule_time_from("Friday, 14:30", now), utc(2025, 3, 7, 19, 30));
+        assert_eq!(parse_schedule_time_from("tomorrow", now), utc(2025, 3, 6, 14, 0));
+        assert_eq!(parse_schedule_time_from("tonight", now), utc(2025, 3, 6, 1, 0));
+    }
+
+    #[test]
+    fn weekdays_are_the_next_one() {
+        let now = wednesday_afternoon();
+        // Past the clock change, so 9am is 13:00 UTC rather than 14:00
+        assert_eq!(parse_schedule_time_from("next monday", now), utc(2025, 3, 10, 13, 0));
+        assert_eq!(parse_schedule_time_from("wednesday", now), utc(2025, 3, 12, 13, 0));
+        assert_eq!(parse_schedule_time_from("friday saturday", now), None);
+    }
+
+    #[test]
+    fn a_time_alone_is_the_next_time_the_clock_shows_it() {
+        let now = wednesday_afternoon();
+        assert_eq!(parse_schedule_time_from("noon", now), utc(2025, 3, 6, 17, 0));
+        assert_eq!(parse_schedule_time_from("9:30pm", now), utc(2025, 3, 6, 2, 30));
+        assert_eq!(parse_schedule_time_from("at 3 p.m.", new_york(2025, 3, 5, 9, 0)), utc(2025, 3, 5, 20, 0));
+        assert_eq!(parse_schedule_time_from("13pm", now), None);
+    }
+
+    #[test]
+    fn offsets_count_from_now() {
+        let now = new_york(2025, 3, 9, 1, 30);
+        assert_eq!(parse_schedule_time_from("in 2 hours", now), utc(2025, 3, 9, 8, 30));
+        assert_eq!(parse_schedule_time_from("30m", now), utc(2025, 3, 9, 7, 0));
+        assert_eq!(parse_schedule_time_from("in a week", now), utc(2025, 3, 16, 6, 30));
+    }
+
+    #[test]
+    fn clock_changes_move_missing_times_and_take_the_first_of_repeated_ones() {
+        // 02:30 doesn't happen on 9 March; it's 03:30 EDT
+        assert_eq!(parse_schedule_time_from("tomorrow 2:30am", new_york(2025, 3, 8, 12, 0)), utc(2025, 3, 9, 7, 30));
+        assert_eq!(parse_schedule_time_from("2025-03-09 02:30", new_york(2025, 3, 1, 12, 0)), utc(2025, 3, 9, 7, 30));
+        // 01:30 happens twice on 2 November; the first is still EDT
+        assert_eq!(parse_schedule_time_from("sunday 1:30am", new_york(2025, 10, 31, 12, 0)), utc(2025, 11, 2, 5, 30));
+    }
+}
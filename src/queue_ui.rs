@@ -0,0 +1,188 @@
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::locale::{t, tf};
+use crate::queue::{self, QueueStatus, QueuedPost};
+use crate::schedule_picker::SchedulePicker;
+use crate::stats_ui::draw_centered_message;
+
+/// The list footer's key hints.
+pub fn help_text() -> &'static str {
+    t("queue.keys")
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueueState {
+    List,
+    EditTime,
+    Posting,
+}
+
+pub struct QueueApp {
+    pub state: QueueState,
+    pub posts: Vec<QueuedPost>,
+    pub list_state: ListState,
+    /// What's been typed for the new time; the picker is used while it's empty.
+    pub time_input: String,
+    pub picker: SchedulePicker,
+    pub status: Option<String>,
+    pub accent: Color,
+    /// Where the footer and the list were last drawn, for mouse clicks.
+    pub help_area: Rect,
+    pub list_area: Rect,
+}
+
+impl QueueApp {
+    pub fn new(accent: Color) -> Self {
+        let mut app = Self {
+            state: QueueState::List,
+            posts: Vec::new(),
+            list_state: ListState::default(),
+            time_input: String::new(),
+            picker: SchedulePicker::default(),
+            status: None,
+            accent,
+            help_area: Rect::default(),
+            list_area: Rect::default(),
+        };
+        app.reload();
+        app
+    }
+
+    pub fn reload(&mut self) {
+        match queue::load_queue() {
+            Ok(posts) => self.posts = posts,
+            Err(e) => self.status = Some(format!("✗ {}", e)),
+        }
+        let selected = self.list_state.selected().unwrap_or(0);
+        self.list_state.select(if self.posts.is_empty() {
+            None
+        } else {
+            Some(selected.min(self.posts.len() - 1))
+        });
+    }
+
+    pub fn selected_post(&self) -> Option<&QueuedPost> {
+        self.list_state.selected().and_then(|i| self.posts.get(i))
+    }
+
+    /// Index of the post drawn at (`column`, `row`), if any.
+    pub fn item_at(&self, column: u16, row: u16) -> Option<usize> {
+        let area = self.list_area;
+        if column <= area.x || column + 1 >= area.right() || row <= area.y || row + 1 >= area.bottom() {
+            return None;
+        }
+        let i = self.list_state.offset() + usize::from(row - area.y - 1);
+        (i < self.posts.len()).then_some(i)
+    }
+
+    pub fn next(&mut self) {
+        if self.posts.is_empty() {
+            return;
+        }
+        let i = match self.list_state.selected() {
+            Some(i) if i + 1 < self.posts.len() => i + 1,
+            _ => 0,
+        };
+        self.list_state.select(Some(i));
+    }
+
+    pub fn previous(&mut self) {
+        if self.posts.is_empty() {
+            return;
+        }
+        let i = match self.list_state.selected() {
+            Some(0) | None => self.posts.len() - 1,
+            Some(i) => i - 1,
+        };
+        self.list_state.select(Some(i));
+    }
+}
+
+pub fn draw(f: &mut Frame, app: &mut QueueApp, area: Rect) {
+    if app.state == QueueState::Posting {
+        draw_centered_message(f, t("queue.posting"), Color::Yellow, area);
+        return;
+    }
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(5),
+            Constraint::Length(8),
+            Constraint::Length(3),
+        ])
+        .split(area);
+
+    let items: Vec<ListItem> = app
+        .posts
+        .iter()
+        .map(|post| {
+            let color = match post.status {
+                QueueStatus::Scheduled => Color::White,
+                QueueStatus::Posting => Color::Yellow,
+                QueueStatus::Failed(_) => Color::Red,
+            };
+            ListItem::new(post.preview()).style(Style::default().fg(color))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(tf("queue.title", &[&app.posts.len()]))
+                .border_style(Style::default().fg(app.accent)),
+        )
+        .highlight_style(crate::theme::selected())
+        .highlight_symbol(">> ");
+    app.list_area = chunks[0];
+    f.render_stateful_widget(list, chunks[0], &mut app.list_state);
+
+    // Full text and error of the highlighted post
+    let detail = match app.selected_post() {
+        Some(post) => {
+            let mut text = post.text.clone();
+            if let QueueStatus::Failed(error) = &post.status {
+                text = format!("{}\n\n{}", tf("queue.failed", &[error, &post.attempts]), text);
+            }
+            if let Some(recurrence) = &post.recurrence {
+                text = format!("{}\n\n{}", tf("queue.repeats", &[&recurrence.describe()]), text);
+            }
+            if post.paused {
+                text = format!("{}\n\n{}", t("queue.paused_note"), text);
+            }
+            text
+        }
+        None => t("queue.empty").to_string(),
+    };
+    if app.state == QueueState::EditTime {
+        let title = tf("queue.edit_time_title", &[&crate::timezone::zone()]);
+        crate::ui::draw_schedule_picker(f, &app.picker, &app.time_input, &title, app.accent, chunks[1]);
+    } else {
+        let detail = Paragraph::new(detail)
+            .wrap(Wrap { trim: false })
+            .block(Block::default().borders(Borders::ALL).title(t("queue.post_title")));
+        f.render_widget(detail, chunks[1]);
+    }
+
+    app.help_area = chunks[2];
+    if app.state == QueueState::EditTime {
+        let footer = Paragraph::new(crate::ui::schedule_picker_keys(&app.picker))
+            .style(Style::default().fg(Color::Gray))
+            .alignment(Alignment::Center)
+            .block(Block::default().borders(Borders::ALL));
+        f.render_widget(footer, chunks[2]);
+    } else {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(app.status.clone().unwrap_or_default());
+        let inner = block.inner(chunks[2]);
+        f.render_widget(block, chunks[2]);
+        crate::buttons::render(f, help_text(), inner, true);
+    }
+}
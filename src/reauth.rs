@@ -0,0 +1,106 @@
+use crate::config::TwitterConfig;
+use crate::locale::t;
+use crate::twitter::AuthProblem;
+
+/// One of the credentials the re-auth prompt asks for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Field {
+    ApiKey,
+    ApiSecret,
+    AccessToken,
+    AccessTokenSecret,
+}
+
+impl Field {
+    pub fn label(self) -> &'static str {
+        match self {
+            Field::ApiKey => t("reauth.api_key"),
+            Field::ApiSecret => t("reauth.api_secret"),
+            Field::AccessToken => t("reauth.access_token"),
+            Field::AccessTokenSecret => t("reauth.access_token_secret"),
+        }
+    }
+}
+
+/// New credentials for an account X stopped accepting, typed in one at a time. A revoked
+/// token only needs a new access token and secret; keys that were never accepted are asked for
+/// again too.
+#[derive(Debug, Clone)]
+pub struct Reauth {
+    pub account: String,
+    pub problem: AuthProblem,
+    pub input: String,
+    /// Set while X is being asked whether the new credentials work.
+    pub checking: bool,
+    fields: Vec<Field>,
+    index: usize,
+    /// The credentials so far, starting from the ones that failed.
+    twitter: TwitterConfig,
+}
+
+impl Reauth {
+    pub fn new(account: String, problem: AuthProblem, twitter: TwitterConfig) -> Self {
+        let fields = match problem {
+            AuthProblem::Revoked => vec![Field::AccessToken, Field::AccessTokenSecret],
+            _ => vec![Field::ApiKey, Field::ApiSecret, Field::AccessToken, Field::AccessTokenSecret],
+        };
+        Self {
+            account,
+            problem,
+            input: String::new(),
+            checking: false,
+            fields,
+            index: 0,
+            twitter,
+        }
+    }
+
+    pub fn field(&self) -> Field {
+        self.fields[self.index]
+    }
+
+    /// Which field is being asked for and how many there are, counting from 1.
+    pub fn step(&self) -> (usize, usize) {
+        (self.index + 1, self.fields.len())
+    }
+
+    /// Keeps what was typed for the current field and moves to the next one. After the last,
+    /// returns the finished credentials to check. Blank input is ignored.
+    pub fn submit(&mut self) -> Option<TwitterConfig> {
+        let value = self.input.trim().to_string();
+        if value.is_empty() {
+            return None;
+        }
+        match self.field() {
+            Field::ApiKey => self.twitter.api_key = value,
+            Field::ApiSecret => self.twitter.api_secret = value,
+            Field::AccessToken => self.twitter.access_token = value,
+            Field::AccessTokenSecret => self.twitter.access_token_secret = value,
+        }
+        self.input.clear();
+
+        if self.index + 1 < self.fields.len() {
+            self.index += 1;
+            return None;
+        }
+        Some(self.twitter.clone())
+    }
+
+    /// Goes back to the first field, for another try after X turned the credentials down.
+    pub fn restart(&mut self) {
+        self.index = 0;
+        self.input.clear();
+        self.checking = false;
+    }
+
+    /// The input with all but its last four characters hidden, so a pasted secret can be
+    /// checked against the developer portal without showing on screen.
+    pub fn masked_input(&self) -> String {
+        let len = self.input.chars().count();
+        self.input
+            .chars()
+            .enumerate()
+            .map(|(i, c)| if i + 4 < len { '•' } else { c })
+            .collect()
+    }
+}
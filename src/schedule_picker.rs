@@ -0,0 +1,131 @@
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, NaiveDateTime, Timelike, Utc};
+
+use crate::timezone;
+
+/// Minutes the minute spinner moves by.
+const MINUTE_STEP: i64 = 5;
+
+/// The part of the picker the arrow keys change.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PickerField {
+    Date,
+    Hour,
+    Minute,
+}
+
+/// A date and time in the configured timezone picked on a month calendar and hour/minute spinners, for scheduling
+/// without typing a timestamp.
+#[derive(Debug, Clone)]
+pub struct SchedulePicker {
+    pub at: NaiveDateTime,
+    pub field: PickerField,
+}
+
+impl Default for SchedulePicker {
+    /// The next full hour.
+    fn default() -> Self {
+        let now = timezone::now().naive_local();
+        let at = now
+            .with_minute(0)
+            .and_then(|at| at.with_second(0))
+            .and_then(|at| at.with_nanosecond(0))
+            .unwrap_or(now)
+            + Duration::hours(1);
+        Self { at, field: PickerField::Date }
+    }
+}
+
+impl SchedulePicker {
+    pub fn new(at: DateTime<Utc>) -> Self {
+        Self {
+            at: timezone::from_utc(at).naive_local(),
+            field: PickerField::Date,
+        }
+    }
+
+    /// Moves to the time `input` reads as, if it reads as one, so the calendar shows what's
+    /// being typed.
+    pub fn follow_typed(&mut self, input: &str) {
+        let at = match crate::queue::parse_recurrence(input) {
+            Some(recurrence) => recurrence.next_after(Utc::now()),
+            None => crate::queue::parse_schedule_time(input),
+        };
+        if let Some(at) = at {
+            self.at = timezone::from_utc(at).naive_local();
+        }
+    }
+
+    pub fn date(&self) -> NaiveDate {
+        self.at.date()
+    }
+
+    pub fn next_field(&mut self) {
+        self.field = match self.field {
+            PickerField::Date => PickerField::Hour,
+            PickerField::Hour => PickerField::Minute,
+            PickerField::Minute => PickerField::Date,
+        };
+    }
+
+    pub fn previous_field(&mut self) {
+        self.field = match self.field {
+            PickerField::Date => PickerField::Minute,
+            PickerField::Hour => PickerField::Date,
+            PickerField::Minute => PickerField::Hour,
+        };
+    }
+
+    pub fn move_days(&mut self, days: i64) {
+        self.at += Duration::days(days);
+    }
+
+    pub fn move_months(&mut self, months: i32) {
+        let moved = match months {
+            0.. => self.at.checked_add_months(Months::new(months.unsigned_abs())),
+            _ => self.at.checked_sub_months(Months::new(months.unsigned_abs())),
+        };
+        if let Some(moved) = moved {
+            self.at = moved;
+        }
+    }
+
+    /// Turns the focused spinner up (`steps` > 0) or down. Spinning past midnight changes the
+    /// day, the way a clock would.
+    pub fn spin(&mut self, steps: i64) {
+        match self.field {
+            PickerField::Date => self.move_days(steps),
+            PickerField::Hour => self.at += Duration::hours(steps),
+            PickerField::Minute => {
+                // Snap to the step first, so 09:03 goes to 09:05 rather than 09:08
+                let offset = self.at.minute() as i64 % MINUTE_STEP;
+                let minutes = match (offset, steps > 0) {
+                    (0, _) => steps * MINUTE_STEP,
+                    (offset, true) => MINUTE_STEP - offset + (steps - 1) * MINUTE_STEP,
+                    (offset, false) => -offset + (steps + 1) * MINUTE_STEP,
+                };
+                self.at += Duration::minutes(minutes);
+            }
+        }
+    }
+
+    /// The picked time in UTC, with DST changes handled as `timezone::to_utc` does.
+    pub fn to_utc(&self) -> Option<DateTime<Utc>> {
+        timezone::to_utc(self.at)
+    }
+
+    /// The calendar weeks (Monday first) of the picked month, with `None` for days outside it.
+    pub fn weeks(&self) -> Vec<[Option<NaiveDate>; 7]> {
+        let first = self.at.date().with_day(1).unwrap_or(self.at.date());
+        let mut day = first - Duration::days(first.weekday().num_days_from_monday() as i64);
+        let mut weeks = Vec::new();
+        while day.month() == first.month() || day < first {
+            let mut week = [None; 7];
+            for slot in week.iter_mut() {
+                *slot = (day.month() == first.month()).then_some(day);
+                day += Duration::days(1);
+            }
+            weeks.push(week);
+        }
+        weeks
+    }
+}
@@ -0,0 +1,70 @@
+use rhai::{Engine, Scope};
+
+use crate::config::ScriptsConfig;
+
+/// Steps a script may take before it's stopped, so one stuck in a loop can't hold up the post.
+const MAX_OPERATIONS: u64 = 1_000_000;
+/// How deeply expressions, and those inside functions, may nest.
+const MAX_EXPR_DEPTH: usize = 64;
+const MAX_FUNCTION_EXPR_DEPTH: usize = 32;
+
+/// Runs each configured transform script in order. A script sees the post as `text` (and the
+/// account name as `account`) and its final value becomes the new text. Scripts run on a
+/// blocking thread, and one that runs past `MAX_OPERATIONS` fails.
+pub async fn apply_transforms(scripts: &ScriptsConfig, account: &str, text: String) -> Result<String, String> {
+    if scripts.transforms.is_empty() {
+        return Ok(text);
+    }
+
+    let scripts = scripts.clone();
+    let account = account.to_string();
+    tokio::task::spawn_blocking(move || run_transforms(&scripts, &account, text))
+        .await
+        .map_err(|e| format!("Transform scripts failed: {}", e))?
+}
+
+fn run_transforms(scripts: &ScriptsConfig, account: &str, text: String) -> Result<String, String> {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine.set_max_expr_depths(MAX_EXPR_DEPTH, MAX_FUNCTION_EXPR_DEPTH);
+    let mut text = text;
+
+    for script in &scripts.transforms {
+        let path = crate::config::resolve_path(script);
+        let mut scope = Scope::new();
+        scope.push("text", text.clone());
+        scope.push_constant("account", account.to_string());
+
+        text = engine
+            .eval_file_with_scope::<String>(&mut scope, path.clone())
+            .map_err(|e| format!("Transform script {} failed: {}", path.display(), e))?;
+    }
+
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn script(name: &str, source: &str) -> ScriptsConfig {
+        let path = std::env::temp_dir().join(format!("xpost-{}-{}.rhai", name, std::process::id()));
+        std::fs::write(&path, source).unwrap();
+        ScriptsConfig {
+            transforms: vec![path.to_string_lossy().into_owned()],
+        }
+    }
+
+    #[tokio::test]
+    async fn transforms_the_text() {
+        let scripts = script("suffix", r#"text + " #" + account"#);
+        assert_eq!(apply_transforms(&scripts, "rust", "hello".to_string()).await.unwrap(), "hello #rust");
+    }
+
+    #[tokio::test]
+    async fn stops_a_script_that_never_ends() {
+        let scripts = script("loop", "loop { } text");
+        let error = apply_transforms(&scripts, "main", "hello".to_string()).await.unwrap_err();
+        assert!(error.contains("failed"), "{}", error);
+    }
+}
@@ -0,0 +1,130 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::drafts;
+use crate::history;
+use crate::text;
+
+/// Where a search result was found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    Draft,
+    Posted,
+    History,
+}
+
+/// One searchable piece of writing: a draft, a posted draft, or a post from the history.
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub source: Source,
+    /// The draft ID, or the post ID for history.
+    pub id: String,
+    pub text: String,
+    pub account: Option<String>,
+    pub date: DateTime<Utc>,
+}
+
+impl Entry {
+    pub fn preview(&self, time_format: Option<&str>, max: usize) -> String {
+        let date = text::format_time(self.date, time_format);
+        let preview = text::preview_line(&self.text, max);
+        match (self.source, &self.account) {
+            (Source::Draft, _) => format!("📝 {} | {}", date, preview),
+            (Source::Posted, Some(account)) => format!("✓ {} | {} | {}", date, account, preview),
+            (_, Some(account)) => format!("↗ {} | {} | {}", date, account, preview),
+            (_, None) => format!("↗ {} | {}", date, preview),
+        }
+    }
+}
+
+/// Drafts, posted drafts and post history, indexed by the words in them.
+#[derive(Debug, Default)]
+pub struct Index {
+    pub entries: Vec<Entry>,
+    /// Each lowercased word and the entries it appears in. Sorted, so the words starting with a
+    /// prefix are one range.
+    words: BTreeMap<String, BTreeSet<usize>>,
+}
+
+impl Index {
+    /// Reads everything there is to search. Encrypted drafts need `drafts::unlock` first.
+    pub fn build() -> Result<Self> {
+        let mut entries = Vec::new();
+
+        for (draft, source) in drafts::load_drafts()?
+            .0
+            .into_iter()
+            .map(|draft| (draft, Source::Draft))
+            .chain(drafts::load_archive()?.0.into_iter().map(|draft| (draft, Source::Posted)))
+        {
+            let posted = draft.posted.as_ref();
+            entries.push(Entry {
+                source,
+                account: posted.map(|posted| posted.account.clone()),
+                date: posted.map_or(draft.updated_at, |posted| posted.posted_at),
+                id: draft.id,
+                text: draft.content,
+            });
+        }
+        for record in history::load_history()?.into_iter().rev() {
+            entries.push(Entry {
+                source: Source::History,
+                id: record.id,
+                text: record.text,
+                account: Some(record.account),
+                date: record.posted_at,
+            });
+        }
+
+        let mut index = Self { entries, words: BTreeMap::new() };
+        for (i, entry) in index.entries.iter().enumerate() {
+            for word in words(&entry.text) {
+                index.words.entry(word).or_default().insert(i);
+            }
+        }
+        Ok(index)
+    }
+
+    /// Entries containing every word of `query`, each as a word or the start of one (so "borrow
+    /// check" finds "borrow checking"), best first: whole-word matches, then the newest.
+    pub fn search(&self, query: &str) -> Vec<usize> {
+        let terms: Vec<String> = words(query).collect();
+        if terms.is_empty() {
+            return Vec::new();
+        }
+
+        let mut matches: Option<BTreeSet<usize>> = None;
+        for term in &terms {
+            let found: BTreeSet<usize> = self
+                .words
+                .range(term.clone()..)
+                .take_while(|(word, _)| word.starts_with(term.as_str()))
+                .flat_map(|(_, entries)| entries.iter().copied())
+                .collect();
+            matches = Some(match matches {
+                Some(matches) => matches.intersection(&found).copied().collect(),
+                None => found,
+            });
+        }
+
+        let mut results: Vec<usize> = matches.unwrap_or_default().into_iter().collect();
+        results.sort_by_key(|&i| {
+            let exact = terms
+                .iter()
+                .filter(|term| self.words.get(*term).is_some_and(|entries| entries.contains(&i)))
+                .count();
+            (Reverse(exact), Reverse(self.entries[i].date))
+        });
+        results
+    }
+}
+
+/// The lowercased words of `text`, split on anything that isn't a letter, digit or apostrophe.
+fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .map(|word| word.trim_matches('\''))
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+}
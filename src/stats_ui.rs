@@ -1,12 +1,75 @@
+use chrono::{Datelike, Duration, Local, NaiveDate};
 use ratatui::{
-    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Layout, Margin, Rect},
     style::{Color, Modifier, Style},
+    symbols,
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    widgets::{
+        Axis, Block, Borders, Chart, Dataset, GraphType, List, ListItem, ListState, Paragraph, Scrollbar,
+        ScrollbarOrientation, ScrollbarState, Wrap,
+    },
     Frame,
 };
 
-use crate::twitter::Tweet;
+use crate::followers::FollowerSnapshot;
+use crate::metrics::{MetricsHistory, MetricsSnapshot};
+use crate::locale::{t, tf};
+use crate::text;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use crate::twitter::{Endpoint, RateLimit, Tweet};
+
+/// Which list of tweets the stats screens are showing.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Feed {
+    Mine,
+    Timeline,
+    Bookmarks,
+    /// Someone else's public posts, by handle.
+    User(String),
+}
+
+impl Feed {
+    pub fn title(&self) -> String {
+        match self {
+            Feed::Mine => t("stats.feed_mine").to_string(),
+            Feed::Timeline => t("stats.feed_timeline").to_string(),
+            Feed::Bookmarks => t("stats.feed_bookmarks").to_string(),
+            Feed::User(handle) => tf("stats.feed_user", &[handle]),
+        }
+    }
+
+    /// The endpoint the feed is read from, whose budget the footer shows.
+    pub fn endpoint(&self) -> Endpoint {
+        match self {
+            Feed::Mine | Feed::User(_) => Endpoint::UserTweets,
+            Feed::Timeline => Endpoint::HomeTimeline,
+            Feed::Bookmarks => Endpoint::Bookmarks,
+        }
+    }
+
+    /// Feeds of one account's own posts, which get the follower dashboard.
+    fn is_profile(&self) -> bool {
+        matches!(self, Feed::Mine | Feed::User(_))
+    }
+}
+
+/// Engagement actions that update a tweet's displayed metrics optimistically.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Engagement {
+    Like,
+    Retweet,
+}
+
+/// A reply as listed in the conversation tree of the detail view.
+#[derive(Debug, Clone, Copy)]
+pub struct ReplyRow {
+    /// Position in `StatsApp::replies`.
+    pub index: usize,
+    /// How many replies deep it is below the open post.
+    pub depth: usize,
+    /// Replies to it, including the ones further down.
+    pub descendants: usize,
+}
 
 #[derive(Debug, Clone)]
 pub enum StatsState {
@@ -22,31 +85,83 @@ pub struct StatsApp {
     pub selected_index: usize,
     pub list_state: ListState,
     pub replies: Vec<Tweet>,
-    pub scroll_offset: usize,
+    /// The replies shown, in tree order, leaving out the ones under collapsed replies.
+    pub reply_rows: Vec<ReplyRow>,
+    /// IDs of the replies whose own replies are hidden.
+    pub collapsed: HashSet<String>,
+    /// First visible line of the post text in the detail view.
+    pub scroll_offset: u16,
+    /// Lines of post text visible at once, as last drawn; one PageUp/PageDown.
+    pub post_page: u16,
+    pub accent: Color,
+    pub feed: Feed,
+    pub user_id: Option<String>,
+    pub reply_list_state: ListState,
+    /// Result of the last background action, shown in the footer.
+    pub status: Option<String>,
+    /// Author of the reply `x` was pressed on once; pressing it again blocks them.
+    pub block_warned: Option<String>,
+    pub followers: Vec<FollowerSnapshot>,
+    /// Recorded metrics of the listed posts, for the impressions trend.
+    pub metrics: MetricsHistory,
+    /// Posts per local day from the post history, for the activity heatmap.
+    pub posting_days: BTreeMap<NaiveDate, usize>,
+    /// `[display] time_format`; relative times when unset.
+    pub time_format: Option<String>,
+    /// Read budget left, shown in the footer.
+    pub rate_limit: Option<RateLimit>,
+    /// The account whose feed is shown, and the configured accounts `a` cycles through.
+    pub account: String,
+    pub accounts: Vec<String>,
+    /// Post to select once the feed loads, when it's opened from the composer's success screen.
+    pub focus_tweet: Option<String>,
+    /// Where the footer and the current list were last drawn, for mouse clicks.
+    pub help_area: Rect,
+    pub list_area: Rect,
 }
 
 impl StatsApp {
-    pub fn new() -> Self {
+    pub fn new(accent: Color, feed: Feed) -> Self {
         let mut list_state = ListState::default();
         list_state.select(Some(0));
         Self {
-            state: StatsState::Loading("Fetching tweets...".to_string()),
+            state: StatsState::Loading(t("stats.loading").to_string()),
             tweets: Vec::new(),
             selected_index: 0,
             list_state,
             replies: Vec::new(),
+            reply_rows: Vec::new(),
+            collapsed: HashSet::new(),
             scroll_offset: 0,
+            post_page: 0,
+            accent,
+            feed,
+            user_id: None,
+            reply_list_state: ListState::default(),
+            status: None,
+            block_warned: None,
+            followers: Vec::new(),
+            metrics: MetricsHistory::new(),
+            posting_days: BTreeMap::new(),
+            time_format: None,
+            rate_limit: None,
+            account: String::new(),
+            accounts: Vec::new(),
+            focus_tweet: None,
+            help_area: Rect::default(),
+            list_area: Rect::default(),
         }
     }
 
     pub fn set_tweets(&mut self, tweets: Vec<Tweet>) {
         self.tweets = tweets;
         if !self.tweets.is_empty() {
+            let focus = self.focus_tweet.take();
             self.state = StatsState::TweetList;
-            self.selected_index = 0;
-            self.list_state.select(Some(0));
+            self.selected_index = self.tweets.iter().position(|tweet| Some(&tweet.id) == focus.as_ref()).unwrap_or(0);
+            self.list_state.select(Some(self.selected_index));
         } else {
-            self.state = StatsState::Error("No tweets found".to_string());
+            self.state = StatsState::Error(t("stats.no_tweets").to_string());
         }
     }
 
@@ -86,169 +201,718 @@ impl StatsApp {
         self.selected_index = i;
     }
 
+    /// Drops everything fetched for the previous account while `account`'s feed loads.
+    pub fn switch_account(&mut self, account: String) {
+        self.state = StatsState::Loading(tf("stats.loading_account", &[&account]));
+        self.account = account;
+        self.tweets.clear();
+        self.replies.clear();
+        self.reply_rows.clear();
+        self.followers.clear();
+        self.metrics.clear();
+        self.posting_days.clear();
+        self.list_state.select(None);
+        self.selected_index = 0;
+        self.user_id = None;
+        self.status = None;
+        self.block_warned = None;
+        self.rate_limit = None;
+    }
+
     pub fn get_selected_tweet(&self) -> Option<&Tweet> {
         self.tweets.get(self.selected_index)
     }
 
-    pub fn set_replies(&mut self, replies: Vec<Tweet>) {
+    /// Removes the highlighted tweet from the list and returns it, with where it was.
+    pub fn remove_selected_tweet(&mut self) -> Option<(usize, Tweet)> {
+        if self.selected_index >= self.tweets.len() {
+            return None;
+        }
+        let index = self.selected_index;
+        let tweet = self.tweets.remove(index);
+        if self.selected_index >= self.tweets.len() {
+            self.selected_index = self.tweets.len().saturating_sub(1);
+        }
+        self.list_state.select(if self.tweets.is_empty() { None } else { Some(self.selected_index) });
+        Some((index, tweet))
+    }
+
+    /// Puts back a tweet `remove_selected_tweet` took out at `index`, keeping the same one
+    /// highlighted. Does nothing if the list has it again, e.g. after a reload.
+    pub fn restore_tweet(&mut self, index: usize, tweet: Tweet) {
+        if self.tweets.iter().any(|t| t.id == tweet.id) {
+            return;
+        }
+        let index = index.min(self.tweets.len());
+        if !self.tweets.is_empty() && index <= self.selected_index {
+            self.selected_index += 1;
+        }
+        self.tweets.insert(index, tweet);
+        self.list_state.select(Some(self.selected_index));
+    }
+
+    /// Adds `delta` to the like or repost count of every displayed copy of `tweet_id`.
+    pub fn adjust_engagement(&mut self, tweet_id: &str, engagement: Engagement, delta: i32) {
+        for tweet in self.tweets.iter_mut().chain(self.replies.iter_mut()) {
+            if tweet.id != tweet_id {
+                continue;
+            }
+            if let Some(metrics) = tweet.public_metrics.as_mut() {
+                let count = match engagement {
+                    Engagement::Like => &mut metrics.like_count,
+                    Engagement::Retweet => &mut metrics.retweet_count,
+                };
+                *count = count.saturating_add_signed(delta);
+            }
+        }
+    }
+
+    pub fn set_replies(&mut self, mut replies: Vec<Tweet>) {
+        // Searching a conversation also finds the post that started it
+        replies.retain(|reply| reply.conversation_id.as_deref() != Some(reply.id.as_str()));
         self.replies = replies;
+        self.collapsed.clear();
         self.scroll_offset = 0;
+        self.build_reply_rows();
+        self.reply_list_state.select(if self.reply_rows.is_empty() { None } else { Some(0) });
     }
 
-    pub fn scroll_down(&mut self) {
-        if self.scroll_offset < self.replies.len().saturating_sub(1) {
-            self.scroll_offset += 1;
+    /// Lays the replies out as a tree, oldest first under each parent. Replies whose parent
+    /// wasn't fetched go at the top level, next to the direct replies to the open post.
+    fn build_reply_rows(&mut self) {
+        let ids: HashSet<&str> = self.replies.iter().map(|reply| reply.id.as_str()).collect();
+        let mut children: HashMap<Option<&str>, Vec<usize>> = HashMap::new();
+        for (i, reply) in self.replies.iter().enumerate() {
+            let parent = reply.in_reply_to().filter(|parent| ids.contains(parent));
+            children.entry(parent).or_default().push(i);
+        }
+        for siblings in children.values_mut() {
+            siblings.sort_by_key(|&i| self.replies[i].created_at.clone());
         }
+
+        fn walk(
+            replies: &[Tweet],
+            children: &HashMap<Option<&str>, Vec<usize>>,
+            collapsed: &HashSet<String>,
+            parent: Option<&str>,
+            depth: usize,
+            rows: &mut Vec<ReplyRow>,
+        ) -> usize {
+            let mut count = 0;
+            for &index in children.get(&parent).into_iter().flatten() {
+                let id = replies[index].id.as_str();
+                let row = rows.len();
+                rows.push(ReplyRow { index, depth, descendants: 0 });
+                let mut below = Vec::new();
+                let descendants = walk(replies, children, collapsed, Some(id), depth + 1, &mut below);
+                if !collapsed.contains(id) {
+                    rows.append(&mut below);
+                }
+                rows[row].descendants = descendants;
+                count += 1 + descendants;
+            }
+            count
+        }
+
+        let mut rows = Vec::new();
+        walk(&self.replies, &children, &self.collapsed, None, 0, &mut rows);
+        self.reply_rows = rows;
     }
 
-    pub fn scroll_up(&mut self) {
-        if self.scroll_offset > 0 {
-            self.scroll_offset -= 1;
+    pub fn get_selected_reply(&self) -> Option<&Tweet> {
+        self.reply_list_state
+            .selected()
+            .and_then(|i| self.reply_rows.get(i))
+            .map(|row| &self.replies[row.index])
+    }
+
+    /// Hides the replies under the highlighted reply, or shows them again.
+    pub fn toggle_selected_reply(&mut self) {
+        let Some(row) = self.reply_list_state.selected().and_then(|i| self.reply_rows.get(i).copied()) else {
+            return;
+        };
+        if row.descendants == 0 {
+            return;
+        }
+        let id = self.replies[row.index].id.clone();
+        if !self.collapsed.remove(&id) {
+            self.collapsed.insert(id);
+        }
+        // The highlighted reply keeps its place, since only rows below it change
+        self.build_reply_rows();
+    }
+
+    pub fn next_reply(&mut self) {
+        if self.reply_rows.is_empty() {
+            return;
+        }
+        let i = match self.reply_list_state.selected() {
+            Some(i) if i + 1 < self.reply_rows.len() => i + 1,
+            _ => 0,
+        };
+        self.reply_list_state.select(Some(i));
+    }
+
+    pub fn previous_reply(&mut self) {
+        if self.reply_rows.is_empty() {
+            return;
+        }
+        let i = match self.reply_list_state.selected() {
+            Some(0) | None => self.reply_rows.len() - 1,
+            Some(i) => i - 1,
+        };
+        self.reply_list_state.select(Some(i));
+    }
+
+    /// Index of the post (or, in the detail view, the reply) drawn at (`column`, `row`).
+    pub fn item_at(&self, column: u16, row: u16) -> Option<usize> {
+        let area = self.list_area;
+        if column <= area.x || column + 1 >= area.right() || row <= area.y || row + 1 >= area.bottom() {
+            return None;
+        }
+        let (list_state, len) = match self.state {
+            StatsState::TweetList => (&self.list_state, self.tweets.len()),
+            StatsState::StatsDetail => (&self.reply_list_state, self.reply_rows.len()),
+            _ => return None,
+        };
+        let i = list_state.offset() + usize::from(row - area.y - 1);
+        (i < len).then_some(i)
+    }
+
+    pub fn selected_item(&self) -> Option<usize> {
+        match self.state {
+            StatsState::TweetList => self.list_state.selected(),
+            StatsState::StatsDetail => self.reply_list_state.selected(),
+            _ => None,
+        }
+    }
+
+    pub fn select_item(&mut self, i: usize) {
+        match self.state {
+            StatsState::TweetList => {
+                self.list_state.select(Some(i));
+                self.selected_index = i;
+            }
+            StatsState::StatsDetail => self.reply_list_state.select(Some(i)),
+            _ => {}
         }
     }
+
+    /// Scrolls the post text a page down; drawing clamps it to the end of the text.
+    pub fn scroll_down(&mut self) {
+        self.scroll_offset = self.scroll_offset.saturating_add(self.post_page.max(1));
+    }
+
+    pub fn scroll_up(&mut self) {
+        self.scroll_offset = self.scroll_offset.saturating_sub(self.post_page.max(1));
+    }
 }
 
-pub fn draw(f: &mut Frame, app: &mut StatsApp) {
+pub fn draw(f: &mut Frame, app: &mut StatsApp, area: Rect) {
     match &app.state {
-        StatsState::TweetList => draw_tweet_list(f, app),
-        StatsState::StatsDetail => draw_stats_detail(f, app),
-        StatsState::Loading(msg) => draw_centered_message(f, msg, Color::Yellow),
-        StatsState::Error(msg) => draw_centered_message(f, msg, Color::Red),
+        StatsState::TweetList => draw_tweet_list(f, app, area),
+        StatsState::StatsDetail => draw_stats_detail(f, app, area),
+        StatsState::Loading(msg) => draw_centered_message(f, msg, Color::Yellow, area),
+        StatsState::Error(msg) => draw_centered_message(f, msg, Color::Red, area),
     }
 }
 
-fn draw_tweet_list(f: &mut Frame, app: &mut StatsApp) {
-    let chunks = Layout::default()
+fn draw_tweet_list(f: &mut Frame, app: &mut StatsApp, area: Rect) {
+    // The dashboard needs room to spare above the list, and the heatmap beside the chart
+    let show_dashboard = app.feed.is_profile()
+        && !(app.followers.is_empty() && app.posting_days.is_empty())
+        && area.height >= 24
+        && area.width >= 60;
+    let dashboard_height = if show_dashboard { 9 } else { 0 };
+    let footer_height = crate::ui::instructions_height(&help_text(app), area.width);
+
+    let outer = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(3),
+            Constraint::Length(dashboard_height),
             Constraint::Min(0),
-            Constraint::Length(3),
+            Constraint::Length(footer_height),
         ])
-        .split(f.area());
+        .split(area);
+    let chunks = [outer[0], outer[2], outer[3]];
 
     // Header
-    let header = Paragraph::new("Your Recent Posts")
-        .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+    let title = if app.accounts.len() > 1 {
+        format!("{} · {}", app.feed.title(), app.account)
+    } else {
+        app.feed.title()
+    };
+    let header = Paragraph::new(title)
+        .style(Style::default().fg(app.accent).add_modifier(Modifier::BOLD))
         .alignment(Alignment::Center)
         .block(Block::default().borders(Borders::ALL));
     f.render_widget(header, chunks[0]);
 
+    if show_dashboard {
+        let fits = outer[1].width >= HEATMAP_WIDTH + 40 || app.followers.is_empty();
+        let heatmap_width = if app.posting_days.is_empty() || !fits { 0 } else { HEATMAP_WIDTH };
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(0), Constraint::Length(heatmap_width)])
+            .split(outer[1]);
+        draw_follower_growth(f, app, columns[0]);
+        if heatmap_width > 0 {
+            draw_posting_heatmap(f, app, columns[1]);
+        }
+    }
+
     // Tweet list
     let items: Vec<ListItem> = app
         .tweets
         .iter()
         .map(|tweet| {
-            let text_preview = if tweet.text.len() > 80 {
-                format!("{}...", &tweet.text[..80])
-            } else {
-                tweet.text.clone()
-            };
+            let text_preview = text::truncate(&tweet.text, 80);
             
             let date = tweet
                 .created_at
                 .as_ref()
-                .map(|d| &d[..10])
-                .unwrap_or("Unknown date");
+                .map(|d| text::format_timestamp(d, app.time_format.as_deref()))
+                .unwrap_or_else(|| t("stats.unknown_date").to_string());
             
-            let content = format!("{} | {}", date, text_preview);
+            let counts = tweet
+                .public_metrics
+                .as_ref()
+                .map(|m| format!("♥ {} ↻ {} | ", m.like_count, m.retweet_count))
+                .unwrap_or_default();
+            
+            let content = match &tweet.author {
+                Some(author) => format!("{} | {}@{} | {}", date, counts, author, text_preview),
+                None => format!("{} | {}{}", date, counts, text_preview),
+            };
             ListItem::new(content)
         })
         .collect();
 
     let list = List::new(items)
-        .block(Block::default().borders(Borders::ALL).title("Posts"))
-        .highlight_style(
-            Style::default()
-                .bg(Color::DarkGray)
-                .add_modifier(Modifier::BOLD),
-        )
+        .block(Block::default().borders(Borders::ALL).title(t("stats.posts_title")))
+        .highlight_style(crate::theme::selected())
         .highlight_symbol(">> ");
 
+    app.list_area = chunks[1];
     f.render_stateful_widget(list, chunks[1], &mut app.list_state);
 
-    // Footer
-    let footer = Paragraph::new("↑/↓: Navigate | Enter: View Stats | Esc: Exit")
-        .style(Style::default().fg(Color::Gray))
-        .alignment(Alignment::Center)
-        .block(Block::default().borders(Borders::ALL));
-    f.render_widget(footer, chunks[2]);
+    draw_footer(f, app, chunks[2]);
+}
+
+/// The footer's key hints, as "Key: action" segments separated by " | ".
+pub fn help_text(app: &StatsApp) -> String {
+    let keys = match (&app.state, &app.feed) {
+        (StatsState::StatsDetail, _) => return t("stats.keys_detail").to_string(),
+        (_, Feed::Bookmarks) => t("stats.keys_bookmarks"),
+        _ => t("stats.keys_list"),
+    };
+    if app.accounts.len() > 1 {
+        format!("{} | {} | {}", keys, t("stats.keys_switch_account"), t("stats.keys_exit"))
+    } else {
+        format!("{} | {}", keys, t("stats.keys_exit"))
+    }
+}
+
+fn draw_footer(f: &mut Frame, app: &mut StatsApp, area: Rect) {
+    app.help_area = area;
+    let block = status_block(app);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    crate::buttons::render(f, &help_text(app), inner, true);
+}
+
+fn draw_follower_growth(f: &mut Frame, app: &StatsApp, area: Rect) {
+    let (Some(first), Some(last)) = (app.followers.first(), app.followers.last()) else {
+        return;
+    };
+
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(28), Constraint::Min(0)])
+        .split(area);
+
+    let change = last.followers as i64 - first.followers as i64;
+    let summary = vec![
+        Line::from(vec![
+            Span::styled(t("stats.followers"), Style::default().fg(Color::Yellow)),
+            Span::raw(format!("{}", last.followers)),
+        ]),
+        Line::from(vec![
+            Span::styled(t("stats.following"), Style::default().fg(Color::Green)),
+            Span::raw(format!("{}", last.following)),
+        ]),
+        Line::from(vec![
+            Span::styled(t("stats.change"), Style::default().fg(Color::Cyan)),
+            Span::raw(format!("{:+}", change)),
+        ]),
+        Line::from(vec![
+            Span::styled(t("stats.since"), Style::default().fg(Color::Gray)),
+            Span::raw(first.taken_at.format("%Y-%m-%d").to_string()),
+        ]),
+    ];
+    let summary = Paragraph::new(summary)
+        .block(Block::default().borders(Borders::ALL).title(t("stats.audience_title")));
+    f.render_widget(summary, columns[0]);
+
+    // X axis is days since the first snapshot
+    let points: Vec<(f64, f64)> = app
+        .followers
+        .iter()
+        .map(|s| {
+            let days = (s.taken_at - first.taken_at).num_minutes() as f64 / (60.0 * 24.0);
+            (days, s.followers as f64)
+        })
+        .collect();
+    let max_x = points.last().map(|(x, _)| *x).unwrap_or(0.0).max(1.0);
+    let min_y = app.followers.iter().map(|s| s.followers).min().unwrap_or(0) as f64;
+    let max_y = app.followers.iter().map(|s| s.followers).max().unwrap_or(0) as f64;
+    let (min_y, max_y) = if max_y - min_y < 1.0 {
+        (min_y - 1.0, max_y + 1.0)
+    } else {
+        (min_y, max_y)
+    };
+
+    let dataset = Dataset::default()
+        .marker(symbols::Marker::Braille)
+        .graph_type(GraphType::Line)
+        .style(Style::default().fg(app.accent))
+        .data(&points);
+    let chart = Chart::new(vec![dataset])
+        .block(Block::default().borders(Borders::ALL).title(t("stats.growth_title")))
+        .x_axis(
+            Axis::default()
+                .bounds([0.0, max_x])
+                .labels(vec![
+                    Span::raw(first.taken_at.format("%m-%d").to_string()),
+                    Span::raw(last.taken_at.format("%m-%d").to_string()),
+                ])
+                .style(Style::default().fg(Color::Gray)),
+        )
+        .y_axis(
+            Axis::default()
+                .bounds([min_y, max_y])
+                .labels(vec![
+                    Span::raw(format!("{}", min_y as i64)),
+                    Span::raw(format!("{}", max_y as i64)),
+                ])
+                .style(Style::default().fg(Color::Gray)),
+        );
+    f.render_widget(chart, columns[1]);
 }
 
-fn draw_stats_detail(f: &mut Frame, app: &StatsApp) {
+/// Weeks of posting activity in the heatmap, and the panel width that fits them.
+const HEATMAP_WEEKS: i64 = 17;
+const HEATMAP_WIDTH: u16 = 2 + 4 + HEATMAP_WEEKS as u16 * 2;
+
+/// GitHub-style grid of posts per day: a column per week, Monday at the top.
+fn draw_posting_heatmap(f: &mut Frame, app: &StatsApp, area: Rect) {
+    let today = Local::now().date_naive();
+    let first_monday =
+        today - Duration::days(today.weekday().num_days_from_monday() as i64 + (HEATMAP_WEEKS - 1) * 7);
+    let busiest = app.posting_days.range(first_monday..).map(|(_, count)| *count).max().unwrap_or(0);
+    let total: usize = app.posting_days.range(first_monday..).map(|(_, count)| *count).sum();
+
+    let lines: Vec<Line> = t("stats.weekdays")
+        .split(',')
+        .take(7)
+        .enumerate()
+        .map(|(weekday, label)| {
+            let mut spans = vec![Span::styled(format!("{:<4}", label), Style::default().fg(Color::Gray))];
+            for week in 0..HEATMAP_WEEKS {
+                let day = first_monday + Duration::days(week * 7 + weekday as i64);
+                if day > today {
+                    break;
+                }
+                let count = app.posting_days.get(&day).copied().unwrap_or(0);
+                spans.push(Span::styled("■ ", Style::default().fg(heat_color(count, busiest))));
+            }
+            Line::from(spans)
+        })
+        .collect();
+
+    let cadence = crate::history::cadence(&app.posting_days, today);
+    let per_week = format!("{:.1}", cadence.per_week);
+    let summary = tf("stats.cadence", &[&cadence.streak, &cadence.longest_streak, &per_week]);
+    let heatmap = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(tf("stats.activity_title", &[&total]))
+            .title_bottom(summary),
+    );
+    f.render_widget(heatmap, area);
+}
+
+/// Four shades of green by quartile of the busiest day, grey for none.
+fn heat_color(count: usize, busiest: usize) -> Color {
+    if count == 0 {
+        return Color::DarkGray;
+    }
+    match (count * 4).div_ceil(busiest.max(1)) {
+        1 => Color::Rgb(14, 68, 41),
+        2 => Color::Rgb(0, 109, 50),
+        3 => Color::Rgb(38, 166, 65),
+        _ => Color::Rgb(57, 211, 83),
+    }
+}
+
+fn draw_impressions_trend(f: &mut Frame, app: &StatsApp, series: &[MetricsSnapshot], area: Rect) {
+    let (Some(first), Some(last)) = (series.first(), series.last()) else {
+        return;
+    };
+
+    // X axis is hours since the first snapshot
+    let points: Vec<(f64, f64)> = series
+        .iter()
+        .map(|s| {
+            let hours = (s.taken_at - first.taken_at).num_minutes() as f64 / 60.0;
+            (hours, s.impressions as f64)
+        })
+        .collect();
+    let max_x = points.last().map(|(x, _)| *x).unwrap_or(0.0).max(1.0);
+    let max_y = (series.iter().map(|s| s.impressions).max().unwrap_or(0) as f64).max(1.0);
+
+    let dataset = Dataset::default()
+        .marker(symbols::Marker::Braille)
+        .graph_type(GraphType::Line)
+        .style(Style::default().fg(Color::Blue))
+        .data(&points);
+    let chart = Chart::new(vec![dataset])
+        .block(Block::default().borders(Borders::ALL).title(t("stats.impressions_title")))
+        .x_axis(
+            Axis::default()
+                .bounds([0.0, max_x])
+                .labels(vec![
+                    Span::raw(text::format_time(first.taken_at, app.time_format.as_deref())),
+                    Span::raw(text::format_time(last.taken_at, app.time_format.as_deref())),
+                ])
+                .style(Style::default().fg(Color::Gray)),
+        )
+        .y_axis(
+            Axis::default()
+                .bounds([0.0, max_y])
+                .labels(vec![Span::raw("0"), Span::raw(format!("{}", max_y as i64))])
+                .style(Style::default().fg(Color::Gray)),
+        );
+    f.render_widget(chart, area);
+}
+
+fn status_block(app: &StatsApp) -> Block<'_> {
+    let mut block = Block::default().borders(Borders::ALL);
+    if let Some(limit) = &app.rate_limit {
+        let color = if limit.remaining == 0 {
+            Color::Red
+        } else if limit.remaining * 5 <= limit.limit {
+            Color::Yellow
+        } else {
+            Color::DarkGray
+        };
+        let minutes = (limit.resets_at - chrono::Utc::now()).num_minutes().max(0);
+        let budget = tf("stats.reads_remaining", &[&limit.remaining, &limit.limit, &minutes]);
+        block = block.title_top(Line::styled(budget, Style::default().fg(color)).right_aligned());
+    }
+    match &app.status {
+        Some(status) => block.title(status.as_str()),
+        None => block,
+    }
+}
+
+fn draw_stats_detail(f: &mut Frame, app: &mut StatsApp, area: Rect) {
+    // Short terminals keep the post and its metrics and drop the replies pane
+    let replies_height = if area.height >= 26 { Constraint::Min(5) } else { Constraint::Length(0) };
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(3),
-            Constraint::Min(5),
-            Constraint::Length(12),
-            Constraint::Length(3),
+            Constraint::Min(3),
+            Constraint::Length(8),
+            replies_height,
+            Constraint::Length(crate::ui::instructions_height(&help_text(app), area.width)),
         ])
-        .split(f.area());
+        .split(area);
 
     // Header
-    let header = Paragraph::new("Post Statistics")
-        .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+    let header = Paragraph::new(t("stats.detail_title"))
+        .style(Style::default().fg(app.accent).add_modifier(Modifier::BOLD))
         .alignment(Alignment::Center)
         .block(Block::default().borders(Borders::ALL));
     f.render_widget(header, chunks[0]);
 
     // Tweet text
-    if let Some(tweet) = app.get_selected_tweet() {
+    if let Some(tweet) = app.get_selected_tweet().cloned() {
+        let content_title = match &tweet.author {
+            Some(author) => tf("stats.post_by", &[author]),
+            None => t("stats.post_content").to_string(),
+        };
+        let text_area = chunks[1].inner(Margin::new(1, 1));
+        let lines = wrapped_height(&tweet.text, text_area.width);
+        let max_scroll = lines.saturating_sub(text_area.height);
+        app.post_page = text_area.height;
+        app.scroll_offset = app.scroll_offset.min(max_scroll);
+
         let tweet_text = Paragraph::new(tweet.text.as_str())
             .wrap(Wrap { trim: false })
-            .block(Block::default().borders(Borders::ALL).title("Post Content"))
-            .style(Style::default().fg(Color::White));
+            .block(Block::default().borders(Borders::ALL).title(content_title))
+            .style(Style::default().fg(Color::White))
+            .scroll((app.scroll_offset, 0));
         f.render_widget(tweet_text, chunks[1]);
+        if max_scroll > 0 {
+            let mut scrollbar = ScrollbarState::new(max_scroll as usize + 1).position(app.scroll_offset as usize);
+            draw_scrollbar(f, chunks[1], &mut scrollbar);
+        }
 
-        // Stats
+        // Stats, with the impressions trend beside them once there's more than one snapshot
+        let trend = app
+            .metrics
+            .get(&tweet.id)
+            .filter(|series| series.len() > 1 && chunks[2].width >= 60);
+        let stats_area = match trend {
+            Some(series) => {
+                let columns = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Length(28), Constraint::Min(0)])
+                    .split(chunks[2]);
+                draw_impressions_trend(f, app, series, columns[1]);
+                columns[0]
+            }
+            None => chunks[2],
+        };
         if let Some(metrics) = &tweet.public_metrics {
             let stats_text = vec![
                 Line::from(""),
                 Line::from(vec![
-                    Span::styled("  Likes: ", Style::default().fg(Color::Yellow)),
+                    Span::styled(t("stats.likes"), Style::default().fg(Color::Yellow)),
                     Span::raw(format!("{}", metrics.like_count)),
                 ]),
                 Line::from(vec![
-                    Span::styled("  Retweets: ", Style::default().fg(Color::Green)),
+                    Span::styled(t("stats.retweets"), Style::default().fg(Color::Green)),
                     Span::raw(format!("{}", metrics.retweet_count)),
                 ]),
                 Line::from(vec![
-                    Span::styled("  Replies: ", Style::default().fg(Color::Cyan)),
+                    Span::styled(t("stats.replies"), Style::default().fg(Color::Cyan)),
                     Span::raw(format!("{}", metrics.reply_count)),
                 ]),
                 Line::from(vec![
-                    Span::styled("  Quotes: ", Style::default().fg(Color::Magenta)),
+                    Span::styled(t("stats.quotes"), Style::default().fg(Color::Magenta)),
                     Span::raw(format!("{}", metrics.quote_count)),
                 ]),
                 Line::from(vec![
-                    Span::styled("  Impressions: ", Style::default().fg(Color::Blue)),
+                    Span::styled(t("stats.impressions"), Style::default().fg(Color::Blue)),
                     Span::raw(format!("{}", metrics.impression_count)),
                 ]),
             ];
 
             let stats = Paragraph::new(stats_text)
-                .block(Block::default().borders(Borders::ALL).title("Metrics"))
+                .block(Block::default().borders(Borders::ALL).title(t("stats.metrics_title")))
                 .alignment(Alignment::Left);
-            f.render_widget(stats, chunks[2]);
+            f.render_widget(stats, stats_area);
         } else {
-            let no_metrics = Paragraph::new("No metrics available")
-                .block(Block::default().borders(Borders::ALL).title("Metrics"))
+            let no_metrics = Paragraph::new(t("stats.no_metrics"))
+                .block(Block::default().borders(Borders::ALL).title(t("stats.metrics_title")))
                 .style(Style::default().fg(Color::Red));
-            f.render_widget(no_metrics, chunks[2]);
+            f.render_widget(no_metrics, stats_area);
         }
     }
 
-    // Footer
-    let footer = Paragraph::new("Esc: Back to List | Q: Exit")
-        .style(Style::default().fg(Color::Gray))
-        .alignment(Alignment::Center)
-        .block(Block::default().borders(Borders::ALL));
-    f.render_widget(footer, chunks[3]);
+    // Replies
+    let reply_items: Vec<ListItem> = app
+        .reply_rows
+        .iter()
+        .map(|row| {
+            let reply = &app.replies[row.index];
+            let branch = if row.descendants == 0 {
+                "  ".to_string()
+            } else if app.collapsed.contains(&reply.id) {
+                format!("▸ (+{}) ", row.descendants)
+            } else {
+                "▾ ".to_string()
+            };
+            let text_preview = text::preview_line(&reply.text, 100);
+            let date = reply
+                .created_at
+                .as_ref()
+                .map(|d| text::format_timestamp(d, app.time_format.as_deref()))
+                .unwrap_or_else(|| t("stats.unknown_date").to_string());
+            let likes = reply
+                .public_metrics
+                .as_ref()
+                .map(|m| format!("♥ {} | ", m.like_count))
+                .unwrap_or_default();
+            let author = match (&reply.author_name, &reply.author) {
+                (Some(name), Some(handle)) => format!("{} @{} | ", name, handle),
+                (None, Some(handle)) => format!("@{} | ", handle),
+                _ => String::new(),
+            };
+            ListItem::new(format!(
+                "{}{}{}{} | {}{}",
+                "  ".repeat(row.depth),
+                branch,
+                author,
+                date,
+                likes,
+                text_preview
+            ))
+        })
+        .collect();
+    let replies = List::new(reply_items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(tf("stats.replies_title", &[&app.replies.len()])),
+        )
+        .highlight_style(crate::theme::selected())
+        .highlight_symbol(">> ");
+    app.list_area = chunks[3];
+    f.render_stateful_widget(replies, chunks[3], &mut app.reply_list_state);
+    if app.reply_rows.len() > chunks[3].height.saturating_sub(2) as usize {
+        let mut scrollbar =
+            ScrollbarState::new(app.reply_rows.len()).position(app.reply_list_state.selected().unwrap_or(0));
+        draw_scrollbar(f, chunks[3], &mut scrollbar);
+    }
+
+    draw_footer(f, app, chunks[4]);
+}
+
+/// Draws a scrollbar over the right border of a bordered pane.
+fn draw_scrollbar(f: &mut Frame, area: Rect, state: &mut ScrollbarState) {
+    let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+        .begin_symbol(None)
+        .end_symbol(None);
+    f.render_stateful_widget(scrollbar, area.inner(Margin::new(0, 1)), state);
 }
 
-fn draw_centered_message(f: &mut Frame, message: &str, color: Color) {
-    let area = centered_rect(60, 20, f.area());
+/// Rows `text` takes up when word-wrapped to `width` columns, as `Wrap { trim: false }` lays it out.
+fn wrapped_height(text: &str, width: u16) -> u16 {
+    let width = width.max(1) as usize;
+    let rows: usize = text
+        .lines()
+        .map(|line| {
+            let mut rows = 1;
+            let mut used = 0;
+            for word in line.split(' ') {
+                let word_width = Span::raw(word).width();
+                if used > 0 && used + 1 + word_width <= width {
+                    used += 1 + word_width;
+                    continue;
+                }
+                if used > 0 {
+                    rows += 1;
+                }
+                // Words longer than a row are broken across rows
+                used = word_width;
+                while used > width {
+                    rows += 1;
+                    used -= width;
+                }
+            }
+            rows
+        })
+        .sum();
+    rows.max(1) as u16
+}
+
+pub fn draw_centered_message(f: &mut Frame, message: &str, color: Color, area: Rect) {
+    let area = centered_rect(60, 20, area);
     let block = Block::default()
         .borders(Borders::ALL)
         .style(Style::default().fg(color));
@@ -259,7 +923,7 @@ fn draw_centered_message(f: &mut Frame, message: &str, color: Color) {
     f.render_widget(paragraph, area);
 }
 
-fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
+pub fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -278,3 +942,42 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
         ])
         .split(popup_layout[1])[1]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tweets(ids: &[&str]) -> Vec<Tweet> {
+        ids.iter()
+            .map(|id| serde_json::from_value(serde_json::json!({ "id": id, "text": "post" })).unwrap())
+            .collect()
+    }
+
+    fn ids(app: &StatsApp) -> Vec<&str> {
+        app.tweets.iter().map(|tweet| tweet.id.as_str()).collect()
+    }
+
+    #[test]
+    fn puts_a_removed_bookmark_back_where_it_was() {
+        let mut app = StatsApp::new(Color::Cyan, Feed::Bookmarks);
+        app.set_tweets(tweets(&["1", "2", "3"]));
+        app.next();
+        let (index, tweet) = app.remove_selected_tweet().unwrap();
+        assert_eq!(ids(&app), ["1", "3"]);
+        assert_eq!(app.get_selected_tweet().unwrap().id, "3");
+
+        app.restore_tweet(index, tweet);
+        assert_eq!(ids(&app), ["1", "2", "3"]);
+        assert_eq!(app.get_selected_tweet().unwrap().id, "3");
+    }
+
+    #[test]
+    fn doesnt_restore_a_bookmark_the_list_has_again() {
+        let mut app = StatsApp::new(Color::Cyan, Feed::Bookmarks);
+        app.set_tweets(tweets(&["1"]));
+        let (index, tweet) = app.remove_selected_tweet().unwrap();
+        app.set_tweets(tweets(&["1", "2"]));
+        app.restore_tweet(index, tweet);
+        assert_eq!(ids(&app), ["1", "2"]);
+    }
+}
@@ -0,0 +1,84 @@
+/// Letter styles from Unicode's Mathematical Alphanumeric Symbols, which X shows as bold,
+/// italic or monospace text since posts have no formatting of their own. Only A-Z, a-z and
+/// (where the style has them) 0-9 change; everything else is left as it is.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextStyle {
+    Bold,
+    Italic,
+    BoldItalic,
+    Monospace,
+    /// Back to ordinary letters.
+    Plain,
+}
+
+pub const STYLES: [TextStyle; 5] = [
+    TextStyle::Bold,
+    TextStyle::Italic,
+    TextStyle::BoldItalic,
+    TextStyle::Monospace,
+    TextStyle::Plain,
+];
+
+impl TextStyle {
+    /// Locale key of the style's name.
+    pub fn label(self) -> &'static str {
+        match self {
+            TextStyle::Bold => "format.bold",
+            TextStyle::Italic => "format.italic",
+            TextStyle::BoldItalic => "format.bold_italic",
+            TextStyle::Monospace => "format.monospace",
+            TextStyle::Plain => "format.plain",
+        }
+    }
+
+    /// Code points of this style's 'A', 'a' and '0'. The sans-serif variants are used because
+    /// they have no gaps in the alphabet, unlike serif italic.
+    fn bases(self) -> Option<(u32, u32, Option<u32>)> {
+        match self {
+            TextStyle::Bold => Some((0x1D5D4, 0x1D5EE, Some(0x1D7EC))),
+            TextStyle::Italic => Some((0x1D608, 0x1D622, None)),
+            TextStyle::BoldItalic => Some((0x1D63C, 0x1D656, None)),
+            TextStyle::Monospace => Some((0x1D670, 0x1D68A, Some(0x1D7F6))),
+            TextStyle::Plain => None,
+        }
+    }
+}
+
+/// `text` in `style`, replacing any style it already had.
+pub fn apply(text: &str, style: TextStyle) -> String {
+    let Some((upper, lower, digits)) = style.bases() else {
+        return plain(text);
+    };
+    plain(text)
+        .chars()
+        .map(|c| {
+            let styled = match c {
+                'A'..='Z' => char::from_u32(upper + (c as u32 - 'A' as u32)),
+                'a'..='z' => char::from_u32(lower + (c as u32 - 'a' as u32)),
+                '0'..='9' => digits.and_then(|digits| char::from_u32(digits + (c as u32 - '0' as u32))),
+                _ => None,
+            };
+            styled.unwrap_or(c)
+        })
+        .collect()
+}
+
+/// `text` with styled letters and digits turned back into ordinary ones.
+pub fn plain(text: &str) -> String {
+    text.chars().map(unstyle).collect()
+}
+
+fn unstyle(c: char) -> char {
+    let code = c as u32;
+    for (upper, lower, digits) in STYLES.iter().filter_map(|style| style.bases()) {
+        let ranges = [(upper, 'A', 26), (lower, 'a', 26)]
+            .into_iter()
+            .chain(digits.map(|digits| (digits, '0', 10)));
+        for (base, first, len) in ranges {
+            if (base..base + len).contains(&code) {
+                return char::from_u32(first as u32 + (code - base)).unwrap_or(c);
+            }
+        }
+    }
+    c
+}
@@ -0,0 +1,87 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::process::Command;
+
+/// Runs git in `dir` and returns its stdout, or its stderr as the error.
+fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .context("Failed to run git")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("git {} failed: {}", args[0], stderr.trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn has_remote(dir: &Path) -> bool {
+    git(dir, &["remote"]).is_ok_and(|remotes| !remotes.trim().is_empty())
+}
+
+/// Makes `dir` a git repository if it isn't one yet.
+fn ensure_repo(dir: &Path) -> Result<()> {
+    if !dir.join(".git").exists() {
+        git(dir, &["init", "-q"])?;
+    }
+    Ok(())
+}
+
+/// Merges in changes from the remote, if there is one. A merge that conflicts is undone and
+/// reported with the conflicting files, leaving the local drafts as they were.
+pub fn pull(dir: &Path) -> Result<()> {
+    ensure_repo(dir)?;
+    if !has_remote(dir) {
+        return Ok(());
+    }
+
+    if let Err(e) = git(dir, &["pull", "-q", "--no-rebase", "--no-edit"]) {
+        let conflicts = git(dir, &["diff", "--name-only", "--diff-filter=U"]).unwrap_or_default();
+        if conflicts.trim().is_empty() {
+            return Err(e);
+        }
+        let _ = git(dir, &["merge", "--abort"]);
+        anyhow::bail!(
+            "Drafts changed on both machines ({}); resolve the merge in {}",
+            conflicts.split_whitespace().collect::<Vec<_>>().join(", "),
+            dir.display()
+        );
+    }
+    Ok(())
+}
+
+/// Commits everything in `dir`, then pushes in the background so a slow remote doesn't hold up
+/// the editor; a failed push is picked up by the next pull.
+pub fn commit(dir: &Path, message: &str) -> Result<()> {
+    ensure_repo(dir)?;
+    git(dir, &["add", "-A"])?;
+    if git(dir, &["status", "--porcelain"])?.trim().is_empty() {
+        return Ok(());
+    }
+    git(dir, &["commit", "-q", "-m", message])?;
+
+    if has_remote(dir) {
+        let dir = dir.to_path_buf();
+        std::thread::spawn(move || {
+            let _ = git(&dir, &["push", "-q"]);
+        });
+    }
+    Ok(())
+}
+
+/// Copies a sync client left beside a file it couldn't reconcile, such as Dropbox's
+/// "(conflicted copy)" or Syncthing's ".sync-conflict-".
+pub fn conflicted_copies(dir: &Path) -> Result<Vec<String>> {
+    let mut copies = Vec::new();
+    for entry in std::fs::read_dir(dir).context("Failed to read drafts directory")? {
+        let name = entry?.file_name().to_string_lossy().into_owned();
+        if name.contains("conflicted copy") || name.contains(".sync-conflict-") {
+            copies.push(name);
+        }
+    }
+    copies.sort();
+    Ok(copies)
+}
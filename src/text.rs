@@ -0,0 +1,184 @@
+use chrono::{DateTime, Local, Utc};
+use std::fmt::Write;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::config::PasteCleanup;
+
+/// What every link counts as on X, however long it is, since X wraps it in a t.co link.
+pub const URL_WEIGHT: usize = 23;
+
+/// `text`'s length as X counts it against the limit: each link is `URL_WEIGHT` characters,
+/// and anything outside Latin and the common punctuation, like CJK or an emoji, counts twice.
+/// An emoji built from several code points (ZWJ sequences, skin tones, keycaps) is one emoji.
+pub fn weighted_len(text: &str) -> usize {
+    let mut len = 0;
+    let mut rest = text;
+    for url in crate::links::find_urls(text) {
+        if let Some(at) = rest.find(&url) {
+            len += unweighted_len(&rest[..at]) + URL_WEIGHT;
+            rest = &rest[at + url.len()..];
+        }
+    }
+    len + unweighted_len(rest)
+}
+
+fn unweighted_len(text: &str) -> usize {
+    let light = |c: char| matches!(c as u32, 0..=0x10FF | 0x2000..=0x200D | 0x2010..=0x201F | 0x2032..=0x2037);
+    text.graphemes(true)
+        .map(|grapheme| {
+            let is_emoji = grapheme.chars().count() > 1 && grapheme.contains(['\u{FE0F}', '\u{200D}', '\u{20E3}']);
+            match grapheme.chars().next() {
+                Some(c) if light(c) && !is_emoji => grapheme.chars().count(),
+                _ => 2,
+            }
+        })
+        .sum()
+}
+
+/// `text` cut to at most `max` graphemes, with "..." appended when anything was cut off.
+/// Counting graphemes keeps emoji, flags and combined characters whole.
+pub fn truncate(text: &str, max: usize) -> String {
+    match text.grapheme_indices(true).nth(max) {
+        Some((end, _)) => format!("{}...", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+/// The first line of `text`, truncated for a one-line list preview.
+pub fn preview_line(text: &str, max: usize) -> String {
+    truncate(text.lines().next().unwrap_or(""), max)
+}
+
+/// `text` after each of the `[paste] cleanup` steps, in order.
+pub fn clean_paste(text: &str, steps: &[PasteCleanup]) -> String {
+    let mut text = text.to_string();
+    for step in steps {
+        text = match step {
+            PasteCleanup::Quotes => text
+                .replace(['\u{201C}', '\u{201D}', '\u{201E}', '\u{201F}'], "\"")
+                .replace(['\u{2018}', '\u{2019}', '\u{201A}', '\u{201B}'], "'"),
+            PasteCleanup::Newlines => {
+                let mut collapsed = String::with_capacity(text.len());
+                let mut newlines = 0;
+                for c in text.chars() {
+                    newlines = if c == '\n' { newlines + 1 } else { 0 };
+                    if newlines <= 2 {
+                        collapsed.push(c);
+                    }
+                }
+                collapsed
+            }
+            PasteCleanup::TrailingWhitespace => {
+                text.split('\n').map(str::trim_end).collect::<Vec<_>>().join("\n")
+            }
+        };
+    }
+    text
+}
+
+/// The `YYYY-MM-DD` part of an API timestamp such as "2024-05-01T12:00:00.000Z".
+pub fn date_part(timestamp: &str) -> &str {
+    timestamp.get(..10).unwrap_or(timestamp)
+}
+
+/// A post or draft time for lists: relative ("2h ago") by default, or in the local timezone
+/// with `format` (strftime, from `[display] time_format`) when one is configured.
+pub fn format_time(time: DateTime<Utc>, format: Option<&str>) -> String {
+    if let Some(format) = format {
+        let mut formatted = String::new();
+        // An invalid format string errors instead of panicking here; fall back to relative
+        if write!(formatted, "{}", time.with_timezone(&Local).format(format)).is_ok() {
+            return formatted;
+        }
+    }
+    relative_time(time, Utc::now())
+}
+
+/// Like `format_time`, for the ISO 8601 timestamps the API returns.
+pub fn format_timestamp(timestamp: &str, format: Option<&str>) -> String {
+    match DateTime::parse_from_rfc3339(timestamp) {
+        Ok(time) => format_time(time.with_timezone(&Utc), format),
+        Err(_) => date_part(timestamp).to_string(),
+    }
+}
+
+fn relative_time(time: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let elapsed = now - time;
+    if elapsed.num_minutes() < 1 {
+        "just now".to_string()
+    } else if elapsed.num_hours() < 1 {
+        format!("{}m ago", elapsed.num_minutes())
+    } else if elapsed.num_days() < 1 {
+        format!("{}h ago", elapsed.num_hours())
+    } else if elapsed.num_days() < 7 {
+        format!("{}d ago", elapsed.num_days())
+    } else {
+        time.with_timezone(&Local).format("%Y-%m-%d").to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_latin_text_by_character() {
+        assert_eq!(weighted_len("hello, world"), 12);
+        assert_eq!(weighted_len("café — naïve"), 12);
+    }
+
+    #[test]
+    fn counts_every_link_as_23() {
+        assert_eq!(weighted_len("https://x.co"), 23);
+        assert_eq!(weighted_len("see https://example.com/a/very/long/path?with=query, ok"), 4 + 23 + 4);
+    }
+
+    #[test]
+    fn counts_cjk_and_emoji_twice() {
+        assert_eq!(weighted_len("日本語"), 6);
+        assert_eq!(weighted_len("ok 👍"), 5);
+        assert_eq!(weighted_len("👩\u{200D}💻"), 2);
+        assert_eq!(weighted_len("👍🏽"), 2);
+        assert_eq!(weighted_len("1\u{FE0F}\u{20E3}"), 2);
+    }
+
+    #[test]
+    fn truncates_by_grapheme() {
+        assert_eq!(truncate("hello", 5), "hello");
+        assert_eq!(truncate("hello world", 5), "hello...");
+        assert_eq!(truncate("🇩🇪🇫🇷🇯🇵", 2), "🇩🇪🇫🇷...");
+        assert_eq!(truncate("e\u{301}e\u{301}e\u{301}", 1), "e\u{301}...");
+        assert_eq!(truncate("", 3), "");
+    }
+
+    #[test]
+    fn previews_the_first_line() {
+        assert_eq!(preview_line("first line\nsecond", 20), "first line");
+        assert_eq!(preview_line("a long first line", 6), "a long...");
+        assert_eq!(preview_line("", 6), "");
+    }
+
+    #[test]
+    fn straightens_curly_quotes() {
+        let text = "\u{201C}It\u{2019}s fine,\u{201D} she said \u{201E}twice\u{201F}";
+        assert_eq!(clean_paste(text, &[PasteCleanup::Quotes]), "\"It's fine,\" she said \"twice\"");
+    }
+
+    #[test]
+    fn collapses_runs_of_blank_lines() {
+        assert_eq!(clean_paste("one\n\n\n\ntwo\nthree\n\nfour", &[PasteCleanup::Newlines]), "one\n\ntwo\nthree\n\nfour");
+    }
+
+    #[test]
+    fn runs_the_steps_in_order() {
+        let text = "one  \n \t\n\n\ntwo\t";
+        assert_eq!(clean_paste(text, &[PasteCleanup::TrailingWhitespace]), "one\n\n\n\ntwo");
+        // A line of spaces and tabs isn't blank until they're gone, so it splits the run
+        assert_eq!(clean_paste(text, &[PasteCleanup::Newlines]), "one  \n \t\n\ntwo\t");
+        assert_eq!(
+            clean_paste(text, &[PasteCleanup::TrailingWhitespace, PasteCleanup::Newlines]),
+            "one\n\ntwo"
+        );
+        assert_eq!(clean_paste(text, &[]), text);
+    }
+}
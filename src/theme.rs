@@ -0,0 +1,40 @@
+use ratatui::{
+    style::{Color, Modifier, Style},
+    Frame,
+};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static MONOCHROME: AtomicBool = AtomicBool::new(false);
+
+/// Turns colour off when `NO_COLOR` is set (https://no-color.org) or `[display] theme` is
+/// "monochrome", for palettes where the usual greys and highlights don't show up.
+pub fn init(theme: Option<&str>) {
+    let no_color = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+    MONOCHROME.store(no_color || theme == Some("monochrome"), Ordering::Relaxed);
+}
+
+pub fn monochrome() -> bool {
+    MONOCHROME.load(Ordering::Relaxed)
+}
+
+/// The highlighted row of a list. Without colour it's marked by bold and underline, on top of
+/// the list's `>` symbol, instead of a grey background.
+pub fn selected() -> Style {
+    if monochrome() {
+        Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+    } else {
+        Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD)
+    }
+}
+
+/// Called at the end of every draw: in monochrome mode, resets the colours of everything drawn,
+/// leaving only bold, underline and the like.
+pub fn finish(f: &mut Frame) {
+    if !monochrome() {
+        return;
+    }
+    for cell in f.buffer_mut().content.iter_mut() {
+        cell.set_fg(Color::Reset);
+        cell.set_bg(Color::Reset);
+    }
+}
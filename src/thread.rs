@@ -0,0 +1,137 @@
+use anyhow::{Context, Result};
+use chrono::Local;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::config::ThreadsConfig;
+
+/// A line containing only this splits the buffer into separate posts of a thread.
+pub const SEPARATOR: &str = "---";
+
+static SPLIT: AtomicBool = AtomicBool::new(false);
+
+/// Splits posts into threads at `SEPARATOR` lines only with `[threads] split = true`, so a post
+/// with a horizontal rule in it doesn't turn into several posts unasked.
+pub fn init(config: &ThreadsConfig) {
+    SPLIT.store(config.split, Ordering::Relaxed);
+}
+
+/// Splits composed text into thread parts, dropping empty ones. Cross-post overrides after
+/// the post aren't part of it. Unless threads are turned on, the whole text is one part.
+pub fn split_thread(text: &str) -> Vec<String> {
+    split(text, SPLIT.load(Ordering::Relaxed))
+}
+
+fn split(text: &str, threads: bool) -> Vec<String> {
+    let lines: Vec<&str> = text
+        .lines()
+        .take_while(|line| crate::crosspost::override_target(line).is_none())
+        .collect();
+    let chunks: Vec<&[&str]> = if threads {
+        lines.split(|line| line.trim() == SEPARATOR).collect()
+    } else {
+        vec![&lines]
+    };
+
+    chunks
+        .into_iter()
+        .map(|chunk| chunk.join("\n").trim().to_string())
+        .filter(|part| !part.is_empty())
+        .collect()
+}
+
+/// Renders a thread as one markdown document. With `post_ids` (one per part, once posted),
+/// each part gets a footnote linking to its post.
+pub fn to_markdown(parts: &[String], post_ids: Option<&[String]>) -> String {
+    let mut markdown = String::new();
+
+    for (i, part) in parts.iter().enumerate() {
+        markdown.push_str(part);
+        if post_ids.is_some_and(|ids| i < ids.len()) {
+            markdown.push_str(&format!("[^{}]", i + 1));
+        }
+        markdown.push_str("\n\n");
+    }
+
+    if let Some(ids) = post_ids {
+        for (i, id) in ids.iter().enumerate().take(parts.len()) {
+            markdown.push_str(&format!("[^{}]: https://x.com/user/status/{}\n", i + 1, id));
+        }
+    }
+
+    markdown
+}
+
+/// Writes the thread to `~/.config/xpost/exports/` and returns the file's path.
+/// Several drafts in one markdown document, separated by rules, for `texts` exported together.
+pub fn export_drafts_markdown(texts: &[String]) -> Result<PathBuf> {
+    let dir = crate::config::config_dir()?.join("exports");
+
+    if !dir.exists() {
+        fs::create_dir_all(&dir)
+            .context("Failed to create exports directory")?;
+    }
+
+    let markdown: Vec<String> = texts.iter().map(|text| to_markdown(&split_thread(text), None)).collect();
+    let path = dir.join(format!("drafts-{}.md", Local::now().format("%Y%m%d-%H%M%S")));
+    fs::write(&path, markdown.join("---\n\n"))
+        .context("Failed to write markdown export")?;
+
+    Ok(path)
+}
+
+pub fn export_markdown(text: &str, post_ids: Option<&[String]>) -> Result<PathBuf> {
+    let dir = crate::config::config_dir()?.join("exports");
+
+    if !dir.exists() {
+        fs::create_dir_all(&dir)
+            .context("Failed to create exports directory")?;
+    }
+
+    let path = dir.join(format!("thread-{}.md", Local::now().format("%Y%m%d-%H%M%S")));
+    fs::write(&path, to_markdown(&split_thread(text), post_ids))
+        .context("Failed to write markdown export")?;
+
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_on_separator_lines_when_threads_are_on() {
+        let parts = split("first part\n---\nsecond\npart\n  ---  \n\n---\nthird", true);
+        assert_eq!(parts, vec!["first part", "second\npart", "third"]);
+    }
+
+    #[test]
+    fn keeps_separators_in_one_post_when_threads_are_off() {
+        let parts = split("above the rule\n---\nbelow it", false);
+        assert_eq!(parts, vec!["above the rule\n---\nbelow it"]);
+    }
+
+    #[test]
+    fn leaves_out_crosspost_overrides() {
+        let parts = split("for X\n---\nstill X\n=== mastodon\nfor mastodon", true);
+        assert_eq!(parts, vec!["for X", "still X"]);
+    }
+
+    #[test]
+    fn drops_empty_text() {
+        assert!(split("  \n---\n\n", true).is_empty());
+        assert!(split("", false).is_empty());
+    }
+
+    #[test]
+    fn markdown_footnotes_link_posted_parts() {
+        let parts = vec!["one".to_string(), "two".to_string()];
+        let ids = vec!["11".to_string(), "22".to_string()];
+        assert_eq!(to_markdown(&parts, None), "one\n\ntwo\n\n");
+        assert_eq!(
+            to_markdown(&parts, Some(&ids)),
+            "one[^1]\n\ntwo[^2]\n\n[^1]: https://x.com/user/status/11\n[^2]: https://x.com/user/status/22\n"
+        );
+    }
+}
@@ -0,0 +1,57 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, LocalResult, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use std::sync::OnceLock;
+
+static ZONE: OnceLock<Tz> = OnceLock::new();
+
+/// Sets the timezone scheduled times are entered and shown in: `[display] timezone` when set,
+/// otherwise the system's. Times are still stored in UTC.
+pub fn init(name: Option<&str>) -> Result<()> {
+    let zone = match name {
+        Some(name) => name
+            .parse::<Tz>()
+            .map_err(|_| anyhow::anyhow!("Unknown timezone '{}' in [display] (use a name like \"Europe/Berlin\")", name))?,
+        None => detect(),
+    };
+    let _ = ZONE.set(zone);
+    Ok(())
+}
+
+/// The system timezone from `TZ` or the OS settings, or UTC if neither names an IANA zone.
+fn detect() -> Tz {
+    std::env::var("TZ")
+        .ok()
+        .map(|name| name.trim_start_matches(':').to_string())
+        .or_else(|| iana_time_zone::get_timezone().ok())
+        .and_then(|name| name.parse().ok())
+        .unwrap_or(Tz::UTC)
+}
+
+pub fn zone() -> Tz {
+    ZONE.get().copied().unwrap_or_else(detect)
+}
+
+pub fn now() -> DateTime<Tz> {
+    Utc::now().with_timezone(&zone())
+}
+
+pub fn from_utc(at: DateTime<Utc>) -> DateTime<Tz> {
+    at.with_timezone(&zone())
+}
+
+/// The UTC instant a wall-clock time in the zone stands for. Around DST changes, a time that
+/// happens twice (clocks going back) is its first occurrence, and one that never happens
+/// (clocks going forward) is moved past the gap, so 02:30 becomes 03:30.
+pub fn to_utc(naive: NaiveDateTime) -> Option<DateTime<Utc>> {
+    to_utc_in(zone(), naive)
+}
+
+/// `to_utc` for a wall-clock time in `zone` rather than the configured one.
+pub fn to_utc_in(zone: Tz, naive: NaiveDateTime) -> Option<DateTime<Utc>> {
+    let local = match zone.from_local_datetime(&naive) {
+        LocalResult::Single(at) | LocalResult::Ambiguous(at, _) => at,
+        LocalResult::None => zone.from_local_datetime(&(naive + Duration::hours(1))).earliest()?,
+    };
+    Some(local.with_timezone(&Utc))
+}
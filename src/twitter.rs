@@ -1,14 +1,130 @@
 use anyhow::{Context, Result};
-use oauth1_request as oauth;
 use reqwest::multipart;
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 
+use crate::clipboard;
 use crate::config::TwitterConfig;
+use crate::demo;
+use crate::fixtures;
+use crate::locale::t;
+use crate::oauth;
+use crate::uploads::{self, UploadSession};
 
+const MEDIA_UPLOAD_URL: &str = "https://upload.twitter.com/1.1/media/upload.json";
+const MEDIA_METADATA_URL: &str = "https://upload.twitter.com/1.1/media/metadata/create.json";
+/// The simple upload takes images up to 5 MB; anything bigger goes up in chunks.
+const SIMPLE_UPLOAD_LIMIT: usize = 5 * 1024 * 1024;
+const UPLOAD_CHUNK_SIZE: usize = 1024 * 1024;
+/// How far back recent search reaches; X refuses a `since_id` older than that. An hour is
+/// kept in hand for clock differences.
+const SEARCH_WINDOW: Duration = Duration::hours(7 * 24 - 1);
+/// Post IDs start with the milliseconds since this moment, X's snowflake epoch.
+const SNOWFLAKE_EPOCH_MS: i64 = 1288834974657;
+
+#[derive(Clone)]
 pub struct TwitterClient {
     config: TwitterConfig,
     client: reqwest::Client,
+    /// Each endpoint's read budget, as of its latest lookup. Shared between clones so
+    /// background lookups report back to the UI.
+    read_limits: Arc<Mutex<HashMap<Endpoint, RateLimit>>>,
+    /// Why X last answered 401, cleared by the next request it accepts.
+    auth_problem: Arc<Mutex<Option<AuthProblem>>>,
+    /// Whether X has accepted these credentials this session, which makes a later 401 a
+    /// revoked token rather than a mistyped one.
+    authenticated: Arc<AtomicBool>,
+    /// Recordings to answer from instead of X, for tests.
+    replay_from: Option<PathBuf>,
+    /// The account's @username, asked of X the first time a link needs it.
+    username: Arc<Mutex<Option<String>>>,
+}
+
+/// Why X turned a request down with 401 Unauthorized.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AuthProblem {
+    /// The access token was revoked (the app disconnected from the account) or regenerated.
+    Revoked,
+    /// The request's timestamp was too far from X's clock, so the credentials may be fine.
+    Clock,
+    /// The keys or token were never accepted: mistyped, or from another app.
+    Rejected,
+}
+
+impl AuthProblem {
+    /// Reads the error body of a 401; X's v2 endpoints only say "Unauthorized", so whether
+    /// the credentials worked `before` decides between a revoked token and wrong keys.
+    fn from_response(body: &str, before: bool) -> Self {
+        if body.contains("\"code\":135") || body.contains("Timestamp out of bounds") {
+            AuthProblem::Clock
+        } else if body.contains("\"code\":89") || body.contains("Invalid or expired token") || before {
+            AuthProblem::Revoked
+        } else {
+            AuthProblem::Rejected
+        }
+    }
+
+    /// What went wrong and what to do about it, for the user.
+    pub fn message(self) -> &'static str {
+        match self {
+            AuthProblem::Revoked => t("auth.revoked"),
+            AuthProblem::Clock => t("auth.clock"),
+            AuthProblem::Rejected => t("auth.rejected"),
+        }
+    }
+
+    /// Whether new credentials would fix it.
+    pub fn needs_credentials(self) -> bool {
+        self != AuthProblem::Clock
+    }
+}
+
+/// X won't delete a post, and asking again won't change that: it's already gone (404) or it
+/// isn't the account's to delete (403).
+#[derive(Debug)]
+pub struct DeleteRefused(pub String);
+
+impl std::fmt::Display for DeleteRefused {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Failed to delete post: {}", self.0)
+    }
+}
+
+impl std::error::Error for DeleteRefused {}
+
+/// The read endpoints, each with a rate limit of its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Endpoint {
+    Me,
+    UserLookup,
+    UserTweets,
+    HomeTimeline,
+    Bookmarks,
+    TweetLookup,
+    Search,
+}
+
+/// The read budget from the `x-rate-limit-*` headers of the last lookup.
+#[derive(Debug, Clone)]
+pub struct RateLimit {
+    pub limit: u32,
+    pub remaining: u32,
+    pub resets_at: DateTime<Utc>,
+}
+
+impl RateLimit {
+    fn from_headers(headers: &reqwest::header::HeaderMap) -> Option<Self> {
+        let header = |name: &str| headers.get(name)?.to_str().ok()?.parse::<i64>().ok();
+        Some(Self {
+            limit: header("x-rate-limit-limit")? as u32,
+            remaining: header("x-rate-limit-remaining")? as u32,
+            resets_at: DateTime::from_timestamp(header("x-rate-limit-reset")?, 0)?,
+        })
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -26,11 +142,31 @@ pub struct TweetData {
 #[derive(Debug, Deserialize)]
 pub struct MediaUploadResponse {
     pub media_id_string: String,
+    /// How long a chunked upload's media ID stays valid, from INIT.
+    pub expires_after_secs: Option<i64>,
+    /// Present after FINALIZE while the uploaded media is still being processed.
+    pub processing_info: Option<ProcessingInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProcessingInfo {
+    /// "pending", "in_progress", "failed" or "succeeded".
+    pub state: String,
+    pub check_after_secs: Option<u64>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct UserTweetsResponse {
     pub data: Option<Vec<Tweet>>,
+    #[serde(default)]
+    pub includes: Includes,
+}
+
+/// Expanded objects returned alongside `data` when `expansions` is requested.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct Includes {
+    #[serde(default)]
+    pub users: Vec<UserData>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -41,6 +177,65 @@ pub struct Tweet {
     pub created_at: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub public_metrics: Option<PublicMetrics>,
+    #[serde(default)]
+    pub author_id: Option<String>,
+    /// Author's @username, filled in from `includes.users`.
+    #[serde(skip)]
+    pub author: Option<String>,
+    /// Author's display name, filled in along with `author`.
+    #[serde(skip)]
+    pub author_name: Option<String>,
+    /// ID of the post that started the conversation this one belongs to.
+    #[serde(default)]
+    pub conversation_id: Option<String>,
+    #[serde(default)]
+    pub referenced_tweets: Vec<ReferencedTweet>,
+}
+
+/// A post this one replies to, quotes or reposts.
+#[derive(Debug, Deserialize, Clone)]
+pub struct ReferencedTweet {
+    /// `replied_to`, `quoted` or `retweeted`.
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub id: String,
+}
+
+impl Tweet {
+    /// ID of the post this one is a direct reply to.
+    pub fn in_reply_to(&self) -> Option<&str> {
+        self.referenced_tweets
+            .iter()
+            .find(|referenced| referenced.kind == "replied_to")
+            .map(|referenced| referenced.id.as_str())
+    }
+}
+
+impl UserTweetsResponse {
+    /// Returns the tweets with `author` resolved from the expanded users.
+    pub fn into_tweets(self) -> Vec<Tweet> {
+        let mut tweets = self.data.unwrap_or_default();
+        resolve_authors(&mut tweets, &self.includes.users);
+        tweets
+    }
+}
+
+/// When the post with `id` was made, read from the ID itself.
+fn posted_at(id: &str) -> Option<DateTime<Utc>> {
+    let id: u64 = id.parse().ok()?;
+    DateTime::from_timestamp_millis((id >> 22) as i64 + SNOWFLAKE_EPOCH_MS)
+}
+
+/// Fills in each tweet's `author` and `author_name` from the users expanded alongside it.
+fn resolve_authors(tweets: &mut [Tweet], users: &[UserData]) {
+    for tweet in tweets {
+        let user = tweet
+            .author_id
+            .as_ref()
+            .and_then(|author_id| users.iter().find(|user| &user.id == author_id));
+        tweet.author = user.map(|user| user.username.clone());
+        tweet.author_name = user.and_then(|user| user.name.clone());
+    }
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -56,6 +251,8 @@ pub struct PublicMetrics {
 #[derive(Debug, Deserialize)]
 pub struct TweetDetailResponse {
     pub data: Tweet,
+    #[serde(default)]
+    pub includes: Includes,
 }
 
 #[derive(Debug, Deserialize)]
@@ -63,15 +260,80 @@ pub struct UserMeResponse {
     pub data: UserData,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Clone)]
 pub struct UserData {
     pub id: String,
     pub username: String,
+    /// Display name; X returns it with every user.
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub public_metrics: Option<UserMetrics>,
+    /// "None", "Basic", "Premium" or "PremiumPlus"; only returned for the authenticated user.
+    #[serde(default)]
+    pub subscription_type: Option<String>,
+}
+
+impl UserData {
+    /// Every paid tier can publish posts longer than 280 characters.
+    pub fn is_premium(&self) -> bool {
+        matches!(self.subscription_type.as_deref(), Some(tier) if tier != "None")
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UserProfileResponse {
+    pub data: Option<UserProfile>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct UserProfile {
+    pub id: String,
+    pub name: String,
+    pub username: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub public_metrics: Option<UserMetrics>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct UserMetrics {
+    pub followers_count: u64,
+    pub following_count: u64,
+    pub tweet_count: u64,
+    #[serde(default)]
+    pub listed_count: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FollowResponse {
+    pub data: FollowData,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FollowData {
+    pub following: bool,
+    /// Set when the account is protected and has to approve the request first.
+    #[serde(default)]
+    pub pending_follow: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DmEventResponse {
+    pub data: DmEventData,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DmEventData {
+    pub dm_event_id: String,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct SearchResponse {
     pub data: Option<Vec<Tweet>>,
+    #[serde(default)]
+    pub includes: Includes,
     pub meta: SearchMeta,
 }
 
@@ -85,6 +347,30 @@ struct TweetRequest {
     text: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     media: Option<MediaIds>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reply_settings: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    community_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reply: Option<ReplyTo>,
+}
+
+/// Optional fields for a new post beyond its text and media.
+#[derive(Debug, Clone, Default)]
+pub struct PostOptions {
+    pub reply_settings: Option<String>,
+    pub community_id: Option<String>,
+    pub reply: Option<ReplyTo>,
+    /// Alt text for each image, in order; empty for none.
+    pub alt_texts: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplyTo {
+    pub in_reply_to_tweet_id: String,
+    /// Users mentioned up-thread who shouldn't be pulled into this reply.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude_reply_user_ids: Vec<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -97,14 +383,63 @@ impl TwitterClient {
         Self {
             config,
             client: reqwest::Client::new(),
+            read_limits: Arc::new(Mutex::new(HashMap::new())),
+            auth_problem: Arc::new(Mutex::new(None)),
+            authenticated: Arc::new(AtomicBool::new(false)),
+            replay_from: None,
+            username: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// This client answering every request from the recordings in `dir`.
+    #[cfg(test)]
+    pub fn replaying_from(mut self, dir: PathBuf) -> Self {
+        self.replay_from = Some(dir);
+        self
+    }
+
+    pub fn can_read(&self) -> bool {
+        self.config.can_read()
+    }
+
+    /// `wanted` results, or fewer if the configured API tier needs to ration reads.
+    fn page_size(&self, wanted: u32) -> u32 {
+        self.config.tier.map_or(wanted, |tier| wanted.min(tier.max_results()))
+    }
+
+    /// What's left of `endpoint`'s rate limit as of its most recent lookup.
+    pub fn read_limit(&self, endpoint: Endpoint) -> Option<RateLimit> {
+        self.read_limits.lock().ok()?.get(&endpoint).cloned()
+    }
+
+    /// Why X last turned these credentials down, unless it has accepted them since.
+    pub fn auth_problem(&self) -> Option<AuthProblem> {
+        *self.auth_problem.lock().ok()?
+    }
+
+    fn record_read_limit(&self, endpoint: Endpoint, response: &reqwest::Response) {
+        if let (Some(limit), Ok(mut read_limits)) = (RateLimit::from_headers(response.headers()), self.read_limits.lock()) {
+            read_limits.insert(endpoint, limit);
         }
     }
 
     pub async fn upload_media(&self, image_data: &[u8]) -> Result<String> {
-        let url = "https://upload.twitter.com/1.1/media/upload.json";
+        if demo::enabled() {
+            return Ok(demo::next_id());
+        }
+        // Videos can only go up in chunks
+        if clipboard::is_video(image_data) {
+            return self.upload_media_chunked(image_data, "video/mp4", "tweet_video").await;
+        }
+        if image_data.len() > SIMPLE_UPLOAD_LIMIT {
+            let (media_type, category) = image_media_type(image_data);
+            return self.upload_media_chunked(image_data, media_type, category).await;
+        }
+
+        let url = MEDIA_UPLOAD_URL;
         
         // Create OAuth authorization header
-        let auth_header = self.create_oauth_header("POST", url, &[]);
+        let auth_header = self.oauth_header("POST", url);
 
         let form = multipart::Form::new()
             .part(
@@ -114,11 +449,11 @@ impl TwitterClient {
                     .mime_str("image/png")?,
             );
 
-        let response = self.client
+        let request = self.client
             .post(url)
             .header("Authorization", auth_header)
-            .multipart(form)
-            .send()
+            .multipart(form);
+        let response = self.send(request)
             .await
             .context("Failed to upload media")?;
 
@@ -131,27 +466,168 @@ impl TwitterClient {
         Ok(media_response.media_id_string)
     }
 
-    pub async fn post_tweet(&self, text: String, media_id: Option<String>) -> Result<TweetData> {
+    /// Uploads with INIT/APPEND/FINALIZE, carrying on from the last confirmed chunk when an
+    /// earlier attempt at the same media was cut off.
+    async fn upload_media_chunked(&self, data: &[u8], media_type: &str, media_category: &str) -> Result<String> {
+        let fingerprint = uploads::fingerprint(data);
+        let mut session = match uploads::find(&fingerprint) {
+            Some(session) => session,
+            None => {
+                let total_bytes = data.len().to_string();
+                let init = self
+                    .media_command(&[
+                        ("command", "INIT"),
+                        ("total_bytes", &total_bytes),
+                        ("media_type", media_type),
+                        ("media_category", media_category),
+                    ])
+                    .await?;
+                let session = UploadSession {
+                    fingerprint: fingerprint.clone(),
+                    media_id: init.media_id_string,
+                    segments_done: 0,
+                    expires_at: chrono::Utc::now()
+                        + chrono::Duration::seconds(init.expires_after_secs.unwrap_or(86_400)),
+                };
+                uploads::save(&session)?;
+                session
+            }
+        };
+
+        for (index, chunk) in data.chunks(UPLOAD_CHUNK_SIZE).enumerate().skip(session.segments_done) {
+            // Multipart fields aren't part of the OAuth signature
+            let auth_header = self.oauth_header("POST", MEDIA_UPLOAD_URL);
+            let form = multipart::Form::new()
+                .text("command", "APPEND")
+                .text("media_id", session.media_id.clone())
+                .text("segment_index", index.to_string())
+                .part("media", multipart::Part::bytes(chunk.to_vec()));
+
+            // A dropped connection leaves the session saved for the next attempt
+            let request = self.client
+                .post(MEDIA_UPLOAD_URL)
+                .header("Authorization", auth_header)
+                .multipart(form);
+            let response = self.send(request)
+                .await
+                .context("Failed to upload media chunk")?;
+
+            if !response.status().is_success() {
+                // The API turned the upload down, so resuming it would fail the same way
+                uploads::remove(&fingerprint)?;
+                let error_text = response.text().await?;
+                anyhow::bail!("Media upload failed: {}", error_text);
+            }
+
+            session.segments_done = index + 1;
+            uploads::save(&session)?;
+        }
+
+        let finalized = self
+            .media_command(&[("command", "FINALIZE"), ("media_id", &session.media_id)])
+            .await;
+        uploads::remove(&fingerprint)?;
+        let mut response = finalized?;
+
+        while let Some(info) = response.processing_info.take() {
+            match info.state.as_str() {
+                "succeeded" => break,
+                "failed" => anyhow::bail!("Media processing failed"),
+                _ => {
+                    let wait = info.check_after_secs.unwrap_or(1);
+                    tokio::time::sleep(std::time::Duration::from_secs(wait)).await;
+                    response = self
+                        .media_command(&[("command", "STATUS"), ("media_id", &session.media_id)])
+                        .await?;
+                }
+            }
+        }
+
+        Ok(session.media_id)
+    }
+
+    /// Sends a media upload command: INIT and FINALIZE post their parameters as a form,
+    /// STATUS asks with a query string.
+    async fn media_command(&self, params: &[(&str, &str)]) -> Result<MediaUploadResponse> {
+        let request = if params.contains(&("command", "STATUS")) {
+            let query = params
+                .iter()
+                .map(|(key, value)| format!("{}={}", key, urlencoding::encode(value)))
+                .collect::<Vec<_>>()
+                .join("&");
+            let url = format!("{}?{}", MEDIA_UPLOAD_URL, query);
+            self.client.get(&url).header("Authorization", self.oauth_header("GET", &url))
+        } else {
+            let auth_header = oauth::authorization_header(&self.config, "POST", MEDIA_UPLOAD_URL, params);
+            self.client.post(MEDIA_UPLOAD_URL).header("Authorization", auth_header).form(params)
+        };
+
+        let response = self.send(request)
+            .await
+            .context("Failed to upload media")?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            anyhow::bail!("Media upload failed: {}", error_text);
+        }
+
+        Ok(response.json().await?)
+    }
+
+    /// Describes uploaded media for screen readers, before it's attached to a post.
+    pub async fn set_alt_text(&self, media_id: &str, alt_text: &str) -> Result<()> {
+        if demo::enabled() {
+            return Ok(());
+        }
+        let body = serde_json::json!({ "media_id": media_id, "alt_text": { "text": alt_text } }).to_string();
+        let auth_header = self.oauth_header("POST", MEDIA_METADATA_URL);
+
+        let request = self.client
+            .post(MEDIA_METADATA_URL)
+            .header("Authorization", auth_header)
+            .header("Content-Type", "application/json")
+            .body(body);
+        let response = self.send(request)
+            .await
+            .context("Failed to set alt text")?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            anyhow::bail!("Setting alt text failed: {}", error_text);
+        }
+        Ok(())
+    }
+
+    pub async fn post_tweet(
+        &self,
+        text: String,
+        media_ids: Vec<String>,
+        options: &PostOptions,
+    ) -> Result<TweetData> {
+        if demo::enabled() {
+            return Ok(demo::post(text));
+        }
         let url = "https://api.twitter.com/2/tweets";
         
         let tweet_request = TweetRequest {
             text,
-            media: media_id.map(|id| MediaIds {
-                media_ids: vec![id],
-            }),
+            media: (!media_ids.is_empty()).then_some(MediaIds { media_ids }),
+            reply_settings: options.reply_settings.clone(),
+            community_id: options.community_id.clone(),
+            reply: options.reply.clone(),
         };
 
         let body = serde_json::to_string(&tweet_request)?;
         
         // Create OAuth authorization header
-        let auth_header = self.create_oauth_header("POST", url, &[]);
+        let auth_header = self.oauth_header("POST", url);
 
-        let response = self.client
+        let request = self.client
             .post(url)
             .header("Authorization", auth_header)
             .header("Content-Type", "application/json")
-            .body(body)
-            .send()
+            .body(body);
+        let response = self.send(request)
             .await
             .context("Failed to post tweet")?;
 
@@ -165,17 +641,68 @@ impl TwitterClient {
         Ok(tweet_response.data)
     }
 
+    /// Sends a DM to `participant_id` and returns the new DM event ID.
+    pub async fn send_direct_message(&self, participant_id: &str, text: String) -> Result<String> {
+        if demo::enabled() {
+            return Ok(demo::next_id());
+        }
+        let url = format!(
+            "https://api.twitter.com/2/dm_conversations/with/{}/messages",
+            participant_id
+        );
+        let body = serde_json::json!({ "text": text }).to_string();
+        let auth_header = self.oauth_header("POST", &url);
+
+        let request = self.client
+            .post(&url)
+            .header("Authorization", auth_header)
+            .header("Content-Type", "application/json")
+            .body(body);
+        let response = self.send(request)
+            .await
+            .context("Failed to send direct message")?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response.text().await?;
+            anyhow::bail!("API Error {}: {}. DMs need an app with Read, Write and Direct Messages permission", status, error_text);
+        }
+
+        let dm_response: DmEventResponse = response.json().await?;
+        Ok(dm_response.data.dm_event_id)
+    }
+
+    /// The account's @username, asked of X the first time and remembered.
+    pub async fn username(&self) -> Option<String> {
+        if let Some(username) = self.username.lock().ok()?.clone() {
+            return Some(username);
+        }
+        let username = self.get_current_user().await.ok()?.username;
+        *self.username.lock().ok()? = Some(username.clone());
+        Some(username)
+    }
+
+    /// Link to this account's post `id`.
+    pub async fn post_url(&self, id: &str) -> String {
+        format!("https://x.com/{}/status/{}", self.username().await.as_deref().unwrap_or("user"), id)
+    }
+
     pub async fn get_current_user(&self) -> Result<UserData> {
-        let url = "https://api.twitter.com/2/users/me";
-        let auth_header = self.create_oauth_header_for_url("GET", url);
+        if demo::enabled() {
+            return Ok(demo::current_user());
+        }
+        let url = "https://api.twitter.com/2/users/me?user.fields=public_metrics,subscription_type";
+        let auth_header = self.oauth_header("GET", url);
 
-        let response = self.client
+        let request = self.client
             .get(url)
-            .header("Authorization", auth_header)
-            .send()
+            .header("Authorization", auth_header);
+        let response = self.send(request)
             .await
             .context("Failed to get current user")?;
 
+        self.record_read_limit(Endpoint::Me, &response);
+
         if !response.status().is_success() {
             let error_text = response.text().await?;
             anyhow::bail!("Failed to get user: {}", error_text);
@@ -185,27 +712,342 @@ impl TwitterClient {
         Ok(user_response.data)
     }
 
+    pub async fn get_user_by_username(&self, username: &str) -> Result<UserProfile> {
+        if demo::enabled() {
+            return Ok(demo::profile(username));
+        }
+        let url = format!(
+            "https://api.twitter.com/2/users/by/username/{}?user.fields=description,public_metrics",
+            username.trim_start_matches('@')
+        );
+        let auth_header = self.read_header(&url);
+
+        let request = self.client
+            .get(&url)
+            .header("Authorization", auth_header);
+        let response = self.send(request)
+            .await
+            .context("Failed to look up user")?;
+
+        self.record_read_limit(Endpoint::UserLookup, &response);
+
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            anyhow::bail!("Failed to look up user: {}", error_text);
+        }
+
+        // Unknown handles come back as 200 with an `errors` array and no `data`
+        let user_response: UserProfileResponse = response.json().await?;
+        user_response
+            .data
+            .with_context(|| format!("User @{} not found", username.trim_start_matches('@')))
+    }
+
     pub async fn get_user_tweets(&self, user_id: &str, max_results: u32) -> Result<Vec<Tweet>> {
+        if demo::enabled() {
+            return Ok(demo::user_tweets(user_id, max_results));
+        }
+        let max_results = self.page_size(max_results);
         let url = format!(
             "https://api.twitter.com/2/users/{}/tweets?max_results={}&tweet.fields=created_at,public_metrics",
             user_id, max_results
         );
-        let auth_header = self.create_oauth_header_for_url("GET", &url);
+        let auth_header = self.read_header(&url);
 
-        let response = self.client
+        let request = self.client
             .get(&url)
-            .header("Authorization", auth_header)
-            .send()
+            .header("Authorization", auth_header);
+        let response = self.send(request)
             .await
             .context("Failed to get user tweets")?;
 
+        self.record_read_limit(Endpoint::UserTweets, &response);
+
         if !response.status().is_success() {
             let error_text = response.text().await?;
             anyhow::bail!("Failed to get tweets: {}", error_text);
         }
 
         let tweets_response: UserTweetsResponse = response.json().await?;
-        Ok(tweets_response.data.unwrap_or_default())
+        Ok(tweets_response.into_tweets())
+    }
+
+    pub async fn get_home_timeline(&self, user_id: &str, max_results: u32) -> Result<Vec<Tweet>> {
+        if demo::enabled() {
+            return Ok(demo::home_timeline(max_results));
+        }
+        let max_results = self.page_size(max_results);
+        let url = format!(
+            "https://api.twitter.com/2/users/{}/timelines/reverse_chronological?max_results={}&tweet.fields=created_at,public_metrics,author_id&expansions=author_id&user.fields=username",
+            user_id, max_results
+        );
+        let auth_header = self.oauth_header("GET", &url);
+
+        let request = self.client
+            .get(&url)
+            .header("Authorization", auth_header);
+        let response = self.send(request)
+            .await
+            .context("Failed to get home timeline")?;
+
+        self.record_read_limit(Endpoint::HomeTimeline, &response);
+
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            anyhow::bail!("Failed to get timeline: {}", error_text);
+        }
+
+        let timeline_response: UserTweetsResponse = response.json().await?;
+        Ok(timeline_response.into_tweets())
+    }
+
+    pub async fn get_bookmarks(&self, user_id: &str, max_results: u32) -> Result<Vec<Tweet>> {
+        if demo::enabled() {
+            return Ok(demo::bookmarks(max_results));
+        }
+        let max_results = self.page_size(max_results);
+        let url = format!(
+            "https://api.twitter.com/2/users/{}/bookmarks?max_results={}&tweet.fields=created_at,public_metrics,author_id&expansions=author_id&user.fields=username",
+            user_id, max_results
+        );
+
+        let request = self.client
+            .get(&url)
+            .header("Authorization", self.oauth2_header()?);
+        let response = self.send(request)
+            .await
+            .context("Failed to get bookmarks")?;
+
+        self.record_read_limit(Endpoint::Bookmarks, &response);
+
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            anyhow::bail!("Failed to get bookmarks: {}", error_text);
+        }
+
+        let bookmarks_response: UserTweetsResponse = response.json().await?;
+        Ok(bookmarks_response.into_tweets())
+    }
+
+    pub async fn add_bookmark(&self, user_id: &str, tweet_id: &str) -> Result<()> {
+        if demo::enabled() {
+            demo::set_bookmark(tweet_id, true);
+            return Ok(());
+        }
+        let url = format!("https://api.twitter.com/2/users/{}/bookmarks", user_id);
+        let body = serde_json::json!({ "tweet_id": tweet_id }).to_string();
+
+        let request = self.client
+            .post(&url)
+            .header("Authorization", self.oauth2_header()?)
+            .header("Content-Type", "application/json")
+            .body(body);
+        let response = self.send(request)
+            .await
+            .context("Failed to add bookmark")?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            anyhow::bail!("Failed to add bookmark: {}", error_text);
+        }
+
+        Ok(())
+    }
+
+    pub async fn remove_bookmark(&self, user_id: &str, tweet_id: &str) -> Result<()> {
+        if demo::enabled() {
+            demo::set_bookmark(tweet_id, false);
+            return Ok(());
+        }
+        let url = format!("https://api.twitter.com/2/users/{}/bookmarks/{}", user_id, tweet_id);
+
+        let request = self.client
+            .delete(&url)
+            .header("Authorization", self.oauth2_header()?);
+        let response = self.send(request)
+            .await
+            .context("Failed to remove bookmark")?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            anyhow::bail!("Failed to remove bookmark: {}", error_text);
+        }
+
+        Ok(())
+    }
+
+    pub async fn like_tweet(&self, user_id: &str, tweet_id: &str) -> Result<()> {
+        if demo::enabled() {
+            return Ok(());
+        }
+        let url = format!("https://api.twitter.com/2/users/{}/likes", user_id);
+        self.post_action(&url, serde_json::json!({ "tweet_id": tweet_id }))
+            .await
+            .context("Failed to like post")
+    }
+
+    pub async fn retweet(&self, user_id: &str, tweet_id: &str) -> Result<()> {
+        if demo::enabled() {
+            return Ok(());
+        }
+        let url = format!("https://api.twitter.com/2/users/{}/retweets", user_id);
+        self.post_action(&url, serde_json::json!({ "tweet_id": tweet_id }))
+            .await
+            .context("Failed to repost")
+    }
+
+    pub async fn unlike_tweet(&self, user_id: &str, tweet_id: &str) -> Result<()> {
+        if demo::enabled() {
+            return Ok(());
+        }
+        let url = format!("https://api.twitter.com/2/users/{}/likes/{}", user_id, tweet_id);
+        self.delete_action(&url).await.context("Failed to unlike post")
+    }
+
+    pub async fn unretweet(&self, user_id: &str, tweet_id: &str) -> Result<()> {
+        if demo::enabled() {
+            return Ok(());
+        }
+        let url = format!("https://api.twitter.com/2/users/{}/retweets/{}", user_id, tweet_id);
+        self.delete_action(&url).await.context("Failed to undo repost")
+    }
+
+    /// Hides `target_user_id`'s posts from `user_id` without them knowing.
+    pub async fn mute_user(&self, user_id: &str, target_user_id: &str) -> Result<()> {
+        if demo::enabled() {
+            return Ok(());
+        }
+        let url = format!("https://api.twitter.com/2/users/{}/muting", user_id);
+        self.post_action(&url, serde_json::json!({ "target_user_id": target_user_id }))
+            .await
+            .context("Failed to mute user")
+    }
+
+    /// Stops `target_user_id` from following or replying to `user_id`.
+    pub async fn block_user(&self, user_id: &str, target_user_id: &str) -> Result<()> {
+        if demo::enabled() {
+            return Ok(());
+        }
+        let url = format!("https://api.twitter.com/2/users/{}/blocking", user_id);
+        self.post_action(&url, serde_json::json!({ "target_user_id": target_user_id }))
+            .await
+            .context("Failed to block user")
+    }
+
+    /// Follows `target_user_id` as `user_id`. Protected accounts get a follow request instead,
+    /// in which case this returns false.
+    pub async fn follow_user(&self, user_id: &str, target_user_id: &str) -> Result<bool> {
+        if demo::enabled() {
+            return Ok(true);
+        }
+        let url = format!("https://api.twitter.com/2/users/{}/following", user_id);
+        let body = serde_json::json!({ "target_user_id": target_user_id }).to_string();
+        let auth_header = self.oauth_header("POST", &url);
+
+        let request = self.client
+            .post(&url)
+            .header("Authorization", auth_header)
+            .header("Content-Type", "application/json")
+            .body(body);
+        let response = self.send(request)
+            .await
+            .context("Failed to follow user")?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            anyhow::bail!("Failed to follow user: {}", error_text);
+        }
+
+        let follow_response: FollowResponse = response.json().await?;
+        Ok(follow_response.data.following && !follow_response.data.pending_follow)
+    }
+
+    pub async fn unfollow_user(&self, user_id: &str, target_user_id: &str) -> Result<()> {
+        if demo::enabled() {
+            return Ok(());
+        }
+        let url = format!("https://api.twitter.com/2/users/{}/following/{}", user_id, target_user_id);
+        let auth_header = self.oauth_header("DELETE", &url);
+
+        let request = self.client
+            .delete(&url)
+            .header("Authorization", auth_header);
+        let response = self.send(request)
+            .await
+            .context("Failed to unfollow user")?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            anyhow::bail!("Failed to unfollow user: {}", error_text);
+        }
+
+        Ok(())
+    }
+
+    pub async fn delete_tweet(&self, tweet_id: &str) -> Result<()> {
+        if demo::enabled() {
+            demo::delete(tweet_id);
+            return Ok(());
+        }
+        let url = format!("https://api.twitter.com/2/tweets/{}", tweet_id);
+        let auth_header = self.oauth_header("DELETE", &url);
+
+        let request = self.client
+            .delete(&url)
+            .header("Authorization", auth_header);
+        let response = self.send(request)
+            .await
+            .context("Failed to delete post")?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response.text().await?;
+            if status == reqwest::StatusCode::NOT_FOUND || status == reqwest::StatusCode::FORBIDDEN {
+                return Err(DeleteRefused(error_text).into());
+            }
+            anyhow::bail!("Failed to delete post: {}", error_text);
+        }
+
+        Ok(())
+    }
+
+    /// POSTs `{"tweet_id": ...}` to a user action endpoint (likes, retweets).
+    async fn post_action(&self, url: &str, body: serde_json::Value) -> Result<()> {
+        let body = body.to_string();
+        let auth_header = self.oauth_header("POST", url);
+
+        let request = self.client
+            .post(url)
+            .header("Authorization", auth_header)
+            .header("Content-Type", "application/json")
+            .body(body);
+        let response = self.send(request)
+            .await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            anyhow::bail!("API Error: {}", error_text);
+        }
+
+        Ok(())
+    }
+
+    /// DELETEs a user action (a like or repost) by its URL.
+    async fn delete_action(&self, url: &str) -> Result<()> {
+        let auth_header = self.oauth_header("DELETE", url);
+
+        let request = self.client
+            .delete(url)
+            .header("Authorization", auth_header);
+        let response = self.send(request)
+            .await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            anyhow::bail!("API Error: {}", error_text);
+        }
+
+        Ok(())
     }
 
     pub async fn get_tweet_details(&self, tweet_id: &str) -> Result<Tweet> {
@@ -213,15 +1055,17 @@ impl TwitterClient {
             "https://api.twitter.com/2/tweets/{}?tweet.fields=created_at,public_metrics",
             tweet_id
         );
-        let auth_header = self.create_oauth_header_for_url("GET", &url);
+        let auth_header = self.read_header(&url);
 
-        let response = self.client
+        let request = self.client
             .get(&url)
-            .header("Authorization", auth_header)
-            .send()
+            .header("Authorization", auth_header);
+        let response = self.send(request)
             .await
             .context("Failed to get tweet details")?;
 
+        self.record_read_limit(Endpoint::TweetLookup, &response);
+
         if !response.status().is_success() {
             let error_text = response.text().await?;
             anyhow::bail!("Failed to get tweet details: {}", error_text);
@@ -231,98 +1075,163 @@ impl TwitterClient {
         Ok(tweet_response.data)
     }
 
-    pub async fn get_tweet_replies(&self, tweet_id: &str, max_results: u32) -> Result<Vec<Tweet>> {
+    /// Users a reply to `tweet_id` would mention, other than the post's author (who can't be excluded).
+    pub async fn get_reply_participants(&self, tweet_id: &str) -> Result<Vec<UserData>> {
+        if demo::enabled() {
+            return Ok(demo::participants());
+        }
         let url = format!(
-            "https://api.twitter.com/2/tweets/search/recent?query=conversation_id:{}&max_results={}&tweet.fields=created_at,author_id",
-            tweet_id, max_results.min(100)
+            "https://api.twitter.com/2/tweets/{}?tweet.fields=author_id&expansions=author_id,entities.mentions.username",
+            tweet_id
         );
-        let auth_header = self.create_oauth_header_for_url("GET", &url);
+        let auth_header = self.read_header(&url);
 
-        let response = self.client
+        let request = self.client
             .get(&url)
-            .header("Authorization", auth_header)
-            .send()
+            .header("Authorization", auth_header);
+        let response = self.send(request)
             .await
-            .context("Failed to get tweet replies")?;
+            .context("Failed to get conversation participants")?;
+
+        self.record_read_limit(Endpoint::TweetLookup, &response);
 
         if !response.status().is_success() {
             let error_text = response.text().await?;
-            anyhow::bail!("Failed to get replies: {}", error_text);
+            anyhow::bail!("Failed to get conversation participants: {}", error_text);
         }
 
-        let search_response: SearchResponse = response.json().await?;
-        Ok(search_response.data.unwrap_or_default())
+        let tweet_response: TweetDetailResponse = response.json().await?;
+        let author_id = tweet_response.data.author_id;
+        Ok(tweet_response
+            .includes
+            .users
+            .into_iter()
+            .filter(|user| Some(&user.id) != author_id.as_ref())
+            .collect())
     }
 
-    fn create_oauth_header_for_url(&self, method: &str, url: &str) -> String {
-        let client = oauth::Credentials::new(
-            &self.config.api_key,
-            &self.config.api_secret,
-        );
+    pub async fn get_tweet_replies(&self, tweet_id: &str, max_results: u32) -> Result<Vec<Tweet>> {
+        if demo::enabled() {
+            return Ok(demo::replies(max_results));
+        }
+        self.search_recent(&format!("conversation_id:{}", tweet_id), None, max_results)
+            .await
+            .context("Failed to get replies")
+    }
 
-        let token_creds = oauth::Credentials::new(
-            &self.config.access_token,
-            &self.config.access_token_secret,
+    /// Posts from the last week matching the X search `query`, newest first. With `since_id`,
+    /// only the ones posted after that post.
+    pub async fn search_recent(&self, query: &str, since_id: Option<&str>, max_results: u32) -> Result<Vec<Tweet>> {
+        if demo::enabled() {
+            return Ok(demo::search(query, since_id, max_results));
+        }
+        // X rejects fewer than 10 results
+        let max_results = self.page_size(max_results).clamp(10, 100);
+        let mut url = format!(
+            "https://api.twitter.com/2/tweets/search/recent?query={}&max_results={}&tweet.fields=created_at,author_id,public_metrics,conversation_id,referenced_tweets&expansions=author_id&user.fields=username",
+            urlencoding::encode(query), max_results
         );
+        // Everything the search can still find is newer than a post from before its window
+        let since_id = since_id.filter(|id| posted_at(id).is_none_or(|at| Utc::now() - at < SEARCH_WINDOW));
+        if let Some(since_id) = since_id {
+            url.push_str(&format!("&since_id={}", since_id));
+        }
+        let auth_header = self.read_header(&url);
 
-        let token = oauth::Token::new(client.clone(), token_creds);
-
-        // Parse URL to extract query parameters
-        if let Some(query_start) = url.find('?') {
-            let base_url = &url[..query_start];
-            let query_string = &url[query_start + 1..];
-            
-            // Parse query parameters into a BTreeMap (automatically sorted)
-            let params: BTreeMap<String, String> = query_string
-                .split('&')
-                .filter_map(|param| {
-                    let mut parts = param.splitn(2, '=');
-                    match (parts.next(), parts.next()) {
-                        (Some(key), Some(value)) => Some((key.to_string(), value.to_string())),
-                        _ => None,
-                    }
-                })
-                .collect();
-            
-            let request = oauth::request::AssertSorted::new(&params);
-            
-            oauth::authorize(
-                method,
-                base_url,
-                &request,
-                &token,
-                oauth::HmacSha1::new(),
-            )
-        } else {
-            oauth::authorize(
-                method,
-                url,
-                &(),
-                &token,
-                oauth::HmacSha1::new(),
-            )
+        let request = self.client
+            .get(&url)
+            .header("Authorization", auth_header);
+        let response = self.send(request)
+            .await
+            .context("Failed to search posts")?;
+
+        self.record_read_limit(Endpoint::Search, &response);
+
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            anyhow::bail!("Search failed: {}", error_text);
         }
+
+        let search_response: SearchResponse = response.json().await?;
+        let mut replies = search_response.data.unwrap_or_default();
+        resolve_authors(&mut replies, &search_response.includes.users);
+        Ok(replies)
     }
 
-    fn create_oauth_header(&self, method: &str, url: &str, _params: &[(&str, &str)]) -> String {
-        let client = oauth::Credentials::new(
-            &self.config.api_key,
-            &self.config.api_secret,
-        );
+    /// Sends a request, recording or replaying the response when `fixtures` is asked to, and
+    /// notes whether X accepted the credentials. A 401's body is read to tell why, then handed
+    /// back as it came for the caller's own error.
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response> {
+        let response = fixtures::send(&self.client, request, self.replay_from.as_deref()).await?;
+        let status = response.status();
+        if status != reqwest::StatusCode::UNAUTHORIZED {
+            if status.is_success() {
+                self.authenticated.store(true, Ordering::Relaxed);
+                if let Ok(mut problem) = self.auth_problem.lock() {
+                    *problem = None;
+                }
+            }
+            return Ok(response);
+        }
 
-        let token_creds = oauth::Credentials::new(
-            &self.config.access_token,
-            &self.config.access_token_secret,
-        );
+        let headers = response.headers().clone();
+        let body = response.bytes().await.context("Failed to read response")?;
+        let problem = AuthProblem::from_response(&String::from_utf8_lossy(&body), self.authenticated.load(Ordering::Relaxed));
+        if let Ok(mut last) = self.auth_problem.lock() {
+            *last = Some(problem);
+        }
+        Ok(fixtures::rebuild(status, headers, body))
+    }
+
+    fn oauth2_header(&self) -> Result<String> {
+        let token = self.config.oauth2_token.as_deref().context(
+            "This endpoint needs an OAuth 2.0 user token: set oauth2_token in config.toml",
+        )?;
+        Ok(format!("Bearer {}", token))
+    }
 
-        let token = oauth::Token::new(client.clone(), token_creds);
+    /// For public read endpoints: the app-only bearer token when configured, so those calls
+    /// come out of the app's rate limits rather than the user's.
+    fn read_header(&self, url: &str) -> String {
+        match &self.config.bearer_token {
+            Some(token) => format!("Bearer {}", token),
+            None => self.oauth_header("GET", url),
+        }
+    }
+
+    fn oauth_header(&self, method: &str, url: &str) -> String {
+        oauth::authorization_header(&self.config, method, url, &[])
+    }
+}
+
+/// The MIME type and media category X expects for an image, going by its bytes. GIFs have
+/// their own category so they can animate; anything unrecognised goes up as a PNG.
+fn image_media_type(data: &[u8]) -> (&'static str, &'static str) {
+    match image::guess_format(data) {
+        Ok(image::ImageFormat::Gif) => ("image/gif", "tweet_gif"),
+        Ok(image::ImageFormat::Jpeg) => ("image/jpeg", "tweet_image"),
+        Ok(image::ImageFormat::WebP) => ("image/webp", "tweet_image"),
+        _ => ("image/png", "tweet_image"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn names_an_image_by_its_format() {
+        assert_eq!(image_media_type(b"\xff\xd8\xff\xe0\0\x10JFIF"), ("image/jpeg", "tweet_image"));
+        assert_eq!(image_media_type(b"GIF89a\x01\0\x01\0"), ("image/gif", "tweet_gif"));
+        assert_eq!(image_media_type(b"RIFF\0\0\0\0WEBPVP8 "), ("image/webp", "tweet_image"));
+        assert_eq!(image_media_type(b"\x89PNG\r\n\x1a\n"), ("image/png", "tweet_image"));
+    }
 
-        oauth::authorize(
-            method,
-            url,
-            &(),
-            &token,
-            oauth::HmacSha1::new(),
-        )
+    #[test]
+    fn reads_the_time_from_a_post_id() {
+        let at = Utc.with_ymd_and_hms(2024, 10, 16, 18, 45, 35).unwrap() + Duration::milliseconds(480);
+        assert_eq!(posted_at("1846623541178888412"), Some(at));
+        assert_eq!(posted_at("not an id"), None);
     }
 }
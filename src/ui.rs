@@ -1,58 +1,814 @@
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Layout, Margin, Position, Rect},
     style::{Color, Style, Modifier},
-    widgets::{Block, Borders, Paragraph, Wrap, List, ListItem, ListState},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Gauge, Paragraph, Tabs, Wrap, List, ListItem, ListState},
     Frame,
 };
+use std::collections::{HashMap, HashSet};
 use tui_textarea::TextArea;
 
-use crate::drafts::Draft;
+use crate::config::{AltTextPolicy, CommunityConfig, PasteCleanup, TargetConfig, TargetKind};
+use crate::drafts::{DamagedDraft, Draft, DraftVersion};
+use crate::grammar::GrammarIssue;
+use crate::history::Cadence;
+use crate::links::LinkCard;
+use crate::locale::{t, tf};
+use crate::media::MediaItem;
+use crate::schedule_picker::{PickerField, SchedulePicker};
+use crate::reauth::Reauth;
+use crate::twitter::{AuthProblem, UserData};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum AppState {
     Composing,
     DraftBrowser,
+    VersionPicker,
+    TagPrompt,
+    Search,
+    MediaPicker,
+    AccountPicker,
+    CommunityPicker,
+    ParticipantPicker,
+    AssistPicker,
+    AssistPending,
+    AssistDiff,
+    GrammarPending,
+    GrammarPopup,
+    FormatPicker,
+    Preview,
     FilePrompt,
+    /// Describing the image at this index for screen readers.
+    AltTextPrompt(usize),
+    SchedulePrompt,
+    /// Typing new credentials for an account X stopped accepting.
+    ReauthPrompt,
     Posting,
-    Success(String), // Tweet URL
+    Success(Vec<PostOutcome>),
     Error(String),
 }
 
+pub const STANDARD_CHAR_LIMIT: usize = 280;
+pub const PREMIUM_CHAR_LIMIT: usize = 25_000;
+pub const DM_CHAR_LIMIT: usize = 10_000;
+pub const ALT_TEXT_LIMIT: usize = 1_000;
+
+// Thumbnail size in pixels; each terminal cell shows two pixels stacked with '▀'
+const THUMBNAIL_WIDTH: u32 = 48;
+const THUMBNAIL_HEIGHT: u32 = 32;
+pub const LINK_CARD_WIDTH: u32 = 32;
+pub const LINK_CARD_HEIGHT: u32 = 16;
+
+/// Default width of the writing column in zen mode.
+pub const ZEN_WIDTH: u16 = 72;
+
+/// Smallest terminal the screens fit in; anything smaller shows a resize notice instead.
+pub const MIN_WIDTH: u16 = 50;
+pub const MIN_HEIGHT: u16 = 20;
+
+/// Height of a bordered key-hint bar, wrapped to `width` but kept to at most three rows.
+pub fn instructions_height(instructions: &str, width: u16) -> u16 {
+    crate::buttons::rows(instructions, width.saturating_sub(2)).min(3) + 2
+}
+
+/// Result of publishing a post to one account.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PostOutcome {
+    pub account: String,
+    pub result: Result<Vec<String>, String>, // Post IDs (one per thread part) or error message
+    /// The account's cross-posts, once the post itself went out.
+    pub crossposts: crate::posting::Crossposts,
+    /// Why X turned the account's credentials down, when that's why it failed.
+    pub auth_problem: Option<AuthProblem>,
+    /// The account's @username, for links to the post; looked up once it's out.
+    pub username: Option<String>,
+}
+
+impl PostOutcome {
+    /// Link to the post (the first part of a thread), once it's out.
+    pub fn url(&self) -> Option<String> {
+        let id = self.result.as_ref().ok()?.first()?;
+        Some(format!("https://x.com/{}/status/{}", self.username.as_deref().unwrap_or("user"), id))
+    }
+}
+
+/// How long a finished background post stays in the list.
+pub const BACKGROUND_POST_LINGER: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// A post left to finish in the background (Ctrl+N while posting) so the next one can be
+/// written meanwhile.
+#[derive(Debug, Clone)]
+pub struct BackgroundPost {
+    pub id: u64,
+    /// The start of its text, to tell it apart in the list.
+    pub summary: String,
+    pub upload_progress: Option<(usize, usize)>,
+    /// The draft it was posted from (ID and text), archived once it's out.
+    pub draft: Option<(String, String)>,
+    pub outcomes: Option<Vec<PostOutcome>>,
+    pub finished_at: Option<std::time::Instant>,
+}
+
+impl BackgroundPost {
+    pub fn new(id: u64, text: &str) -> Self {
+        let first_line = text.lines().find(|line| !line.trim().is_empty()).unwrap_or("").trim();
+        let summary = match first_line.char_indices().nth(30) {
+            Some((end, _)) => format!("{}…", &first_line[..end]),
+            None => first_line.to_string(),
+        };
+        Self {
+            id,
+            summary,
+            upload_progress: None,
+            draft: None,
+            outcomes: None,
+            finished_at: None,
+        }
+    }
+}
+
 pub struct App<'a> {
     pub state: AppState,
     pub textarea: TextArea<'a>,
-    pub has_image: bool,
+    /// Images attached to the post; the data itself lives with the compose tab.
+    pub image_count: usize,
+    /// Alt text for each attached image, empty until written.
+    pub alt_texts: Vec<String>,
+    pub alt_text_input: String,
+    /// `[paste] cleanup`, applied to text pasted into the editor.
+    pub paste_cleanup: Vec<PasteCleanup>,
+    /// (uploaded, total) while images upload.
+    pub upload_progress: Option<(usize, usize)>,
+    /// ID of the post on the posting screen, which its results and progress come back with.
+    pub posting_id: u64,
+    /// Posts sent to the background, oldest first.
+    pub background_posts: Vec<BackgroundPost>,
     pub file_path_input: String,
+    /// What's been typed at the schedule prompt; the picker is used while it's empty.
+    pub schedule_input: String,
+    pub schedule_picker: SchedulePicker,
+    /// The account whose credentials the error screen offers to re-enter, and why.
+    pub reauth_offer: Option<(String, AuthProblem)>,
+    pub reauth: Option<Reauth>,
     pub drafts: Vec<Draft>,
+    /// Files in the drafts folder that couldn't be read, listed after the drafts.
+    pub damaged_drafts: Vec<DamagedDraft>,
     pub draft_list_state: ListState,
+    /// The browser lists posted drafts from the archive instead.
+    pub show_archive: bool,
+    /// Drafts marked in the browser for a bulk delete, tag or export, by ID.
+    pub marked_drafts: HashSet<String>,
+    /// Set once a bulk delete has been asked to confirm.
+    pub delete_drafts_warned: bool,
+    pub tag_input: String,
+    pub search_index: crate::search::Index,
+    pub search_input: String,
+    /// Indexes into `search_index.entries`, best match first.
+    pub search_results: Vec<usize>,
+    pub search_list_state: ListState,
     pub current_draft_id: Option<String>,
+    pub version_list_state: ListState,
+    pub media: Vec<MediaItem>,
+    pub media_list_state: ListState,
+    pub media_thumbnail: Option<image::RgbImage>,
+    pub accent: Color,
+    pub accounts: Vec<String>,
+    pub target_accounts: Vec<bool>,
+    pub account_list_state: ListState,
+    pub communities: Vec<CommunityConfig>,
+    /// Index into `communities` the next post goes to; `None` posts to the regular timeline.
+    pub selected_community: Option<usize>,
+    pub community_list_state: ListState,
+    pub char_limit: usize,
+    /// Set while it isn't known whether the account has premium, so `char_limit` is an
+    /// assumption; the status bar marks it with a "?".
+    pub char_limit_unsure: bool,
+    pub preview_scroll: u16,
+    /// Instructions offered by the AI assist picker; empty when assist isn't configured.
+    pub assist_prompts: Vec<String>,
+    pub assist_list_state: ListState,
+    pub assist_suggestion: Option<String>,
+    /// Problems from the last grammar check, underlined in the editor until the text changes.
+    pub grammar_issues: Vec<GrammarIssue>,
+    pub grammar_list_state: ListState,
+    pub grammar_enabled: bool,
+    pub format_list_state: ListState,
+    /// Monthly post caps of the accounts whose API tier is declared.
+    pub post_caps: HashMap<String, usize>,
+    /// Set once the cap warning has been shown, so the next Ctrl+P posts anyway.
+    pub cap_warned: bool,
+    /// Set once quiet hours have offered to schedule the post, so the next Ctrl+P posts now.
+    pub quiet_warned: bool,
+    /// Each account's cross-post targets, whose length limits the status bar checks the post against.
+    pub crosspost_targets: HashMap<String, Vec<TargetConfig>>,
+    /// The (account, blocklist entry) matches already confirmed past; text that matches
+    /// anything else asks again.
+    pub blocklist_confirmed: HashSet<(String, String)>,
+    /// Each account's `alt_text` policy, and whether the next Ctrl+P has been confirmed past a warning.
+    pub alt_text_policies: HashMap<String, AltTextPolicy>,
+    pub alt_text_warned: bool,
+    /// Set once posting the highlighted draft from the browser has been asked to confirm.
+    pub post_draft_warned: bool,
+    /// The draft being posted straight from the browser, which the composer's text is left alone for.
+    pub posting_draft: Option<Draft>,
+    /// Posts each capped account has made this month, from the local history.
+    pub posts_this_month: HashMap<String, usize>,
+    /// `[display] streak_reminder`, and the posting streak it reminds about.
+    pub streak_reminder: bool,
+    pub cadence: Option<Cadence>,
+    /// Zen mode hides the bars and centres a `zen_width` column, optionally typewriter-scrolled.
+    pub zen: bool,
+    pub zen_width: u16,
+    pub typewriter: bool,
+    /// `[display] time_format` for draft times; relative times when unset.
+    pub time_format: Option<String>,
+    /// Open Graph card for the first link in the buffer, shown in the preview.
+    pub link_card: Option<LinkCard>,
+    /// URL whose card is being fetched.
+    pub link_card_pending: Option<String>,
+    /// Short forms of long links in the buffer, substituted when previewing and posting.
+    pub short_urls: HashMap<String, String>,
+    pub shortening: bool,
+    /// Lifetime of the next post; the daemon deletes it once this has passed.
+    pub delete_after: Option<chrono::Duration>,
+    /// The status bar is drawn inverted until this instant, to flash on completion.
+    pub flash_until: Option<std::time::Instant>,
+    /// Whether the terminal has focus, so completions can notify when it doesn't.
+    pub focused: bool,
+    /// One-off message shown in the status bar until the next key press.
+    pub notice: Option<String>,
+    /// Username the buffer is sent to as a direct message instead of a post.
+    pub dm_recipient: Option<String>,
+    /// Post ID the buffer is posted as a reply to.
+    pub reply_to: Option<String>,
+    /// Users a reply would mention, with whether each one is excluded.
+    pub participants: Vec<UserData>,
+    pub excluded_participants: Vec<bool>,
+    pub participant_list_state: ListState,
+    /// Where the instructions and the current list were last drawn, for mouse clicks.
+    pub help_area: Rect,
+    pub list_area: Rect,
+}
+
+fn compose_textarea<'a>(lines: Vec<String>, accent: Color) -> TextArea<'a> {
+    let mut textarea = TextArea::new(lines);
+    textarea.set_block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(t("compose.title"))
+            .border_style(Style::default().fg(accent)),
+    );
+    textarea.set_cursor_line_style(Style::default());
+    textarea.set_cursor_style(Style::default().add_modifier(Modifier::REVERSED));
+    textarea
 }
 
 impl<'a> App<'a> {
-    pub fn new() -> Self {
-        let mut textarea = TextArea::default();
-        textarea.set_block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title("Compose your post")
-                .border_style(Style::default().fg(Color::Cyan)),
-        );
-        textarea.set_cursor_line_style(Style::default());
-        textarea.set_cursor_style(Style::default().add_modifier(Modifier::REVERSED));
-        
+    pub fn new(accent: Color) -> Self {
         Self {
             state: AppState::Composing,
-            textarea,
-            has_image: false,
+            textarea: compose_textarea(Vec::new(), accent),
+            accent,
+            image_count: 0,
+            alt_texts: Vec::new(),
+            alt_text_input: String::new(),
+            paste_cleanup: Vec::new(),
+            upload_progress: None,
+            posting_id: 0,
+            background_posts: Vec::new(),
             file_path_input: String::new(),
+            schedule_input: String::new(),
+            schedule_picker: SchedulePicker::default(),
+            reauth_offer: None,
+            reauth: None,
             drafts: Vec::new(),
+            damaged_drafts: Vec::new(),
             draft_list_state: ListState::default(),
+            show_archive: false,
+            marked_drafts: HashSet::new(),
+            delete_drafts_warned: false,
+            tag_input: String::new(),
+            search_index: crate::search::Index::default(),
+            search_input: String::new(),
+            search_results: Vec::new(),
+            search_list_state: ListState::default(),
             current_draft_id: None,
+            version_list_state: ListState::default(),
+            media: Vec::new(),
+            media_list_state: ListState::default(),
+            media_thumbnail: None,
+            accounts: Vec::new(),
+            target_accounts: Vec::new(),
+            account_list_state: ListState::default(),
+            communities: Vec::new(),
+            selected_community: None,
+            community_list_state: ListState::default(),
+            char_limit: STANDARD_CHAR_LIMIT,
+            char_limit_unsure: false,
+            preview_scroll: 0,
+            assist_prompts: Vec::new(),
+            assist_list_state: ListState::default(),
+            assist_suggestion: None,
+            grammar_issues: Vec::new(),
+            grammar_list_state: ListState::default(),
+            grammar_enabled: false,
+            format_list_state: ListState::default(),
+            post_caps: HashMap::new(),
+            cap_warned: false,
+            quiet_warned: false,
+            crosspost_targets: HashMap::new(),
+            blocklist_confirmed: HashSet::new(),
+            alt_text_policies: HashMap::new(),
+            alt_text_warned: false,
+            post_draft_warned: false,
+            posting_draft: None,
+            posts_this_month: HashMap::new(),
+            streak_reminder: false,
+            cadence: None,
+            zen: false,
+            zen_width: ZEN_WIDTH,
+            typewriter: false,
+            time_format: None,
+            link_card: None,
+            link_card_pending: None,
+            short_urls: HashMap::new(),
+            shortening: false,
+            delete_after: None,
+            flash_until: None,
+            focused: true,
+            notice: None,
+            dm_recipient: None,
+            reply_to: None,
+            participants: Vec::new(),
+            excluded_participants: Vec::new(),
+            participant_list_state: ListState::default(),
+            help_area: Rect::default(),
+            list_area: Rect::default(),
+        }
+    }
+
+    pub fn load_media(&mut self) {
+        self.media = crate::media::load_library().unwrap_or_default();
+        self.media_list_state.select(if self.media.is_empty() { None } else { Some(0) });
+        self.refresh_media_thumbnail();
+    }
+
+    pub fn selected_media(&self) -> Option<&MediaItem> {
+        self.media_list_state.selected().and_then(|i| self.media.get(i))
+    }
+
+    pub fn next_media(&mut self) {
+        if self.media.is_empty() {
+            return;
+        }
+        let i = match self.media_list_state.selected() {
+            Some(i) if i + 1 < self.media.len() => i + 1,
+            _ => 0,
+        };
+        self.media_list_state.select(Some(i));
+        self.refresh_media_thumbnail();
+    }
+
+    pub fn previous_media(&mut self) {
+        if self.media.is_empty() {
+            return;
+        }
+        let i = match self.media_list_state.selected() {
+            Some(0) | None => self.media.len() - 1,
+            Some(i) => i - 1,
+        };
+        self.media_list_state.select(Some(i));
+        self.refresh_media_thumbnail();
+    }
+
+    // Decoded once per selection change rather than on every frame
+    fn refresh_media_thumbnail(&mut self) {
+        self.media_thumbnail = self
+            .selected_media()
+            .and_then(|item| item.thumbnail(THUMBNAIL_WIDTH, THUMBNAIL_HEIGHT).ok());
+    }
+
+    /// Sets the configured accounts, with only `active` selected as a posting target.
+    pub fn set_accounts(&mut self, accounts: Vec<String>, active: &str) {
+        self.target_accounts = accounts.iter().map(|name| name == active).collect();
+        self.accounts = accounts;
+        self.account_list_state.select(Some(0));
+    }
+
+    pub fn selected_accounts(&self) -> Vec<String> {
+        self.accounts
+            .iter()
+            .zip(&self.target_accounts)
+            .filter(|(_, selected)| **selected)
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    pub fn next_account(&mut self) {
+        if self.accounts.is_empty() {
+            return;
+        }
+        let i = match self.account_list_state.selected() {
+            Some(i) if i + 1 < self.accounts.len() => i + 1,
+            _ => 0,
+        };
+        self.account_list_state.select(Some(i));
+    }
+
+    pub fn previous_account(&mut self) {
+        if self.accounts.is_empty() {
+            return;
+        }
+        let i = match self.account_list_state.selected() {
+            Some(0) | None => self.accounts.len() - 1,
+            Some(i) => i - 1,
+        };
+        self.account_list_state.select(Some(i));
+    }
+
+    pub fn toggle_selected_account(&mut self) {
+        if let Some(i) = self.account_list_state.selected() {
+            if let Some(selected) = self.target_accounts.get_mut(i) {
+                *selected = !*selected;
+            }
+        }
+    }
+
+    pub fn set_communities(&mut self, communities: Vec<CommunityConfig>) {
+        self.communities = communities;
+        self.selected_community = None;
+        self.community_list_state.select(Some(0));
+    }
+
+    pub fn community(&self) -> Option<&CommunityConfig> {
+        self.selected_community.and_then(|i| self.communities.get(i))
+    }
+
+    // The picker lists "no community" first, so list index i maps to community i - 1
+    pub fn next_community(&mut self) {
+        let len = self.communities.len() + 1;
+        let i = match self.community_list_state.selected() {
+            Some(i) if i + 1 < len => i + 1,
+            _ => 0,
+        };
+        self.community_list_state.select(Some(i));
+    }
+
+    pub fn previous_community(&mut self) {
+        let len = self.communities.len() + 1;
+        let i = match self.community_list_state.selected() {
+            Some(0) | None => len - 1,
+            Some(i) => i - 1,
+        };
+        self.community_list_state.select(Some(i));
+    }
+
+    pub fn select_current_community(&mut self) {
+        self.selected_community = match self.community_list_state.selected() {
+            Some(0) | None => None,
+            Some(i) => Some(i - 1),
+        };
+        self.state = AppState::Composing;
+    }
+
+    pub fn set_participants(&mut self, participants: Vec<UserData>) {
+        self.excluded_participants = vec![false; participants.len()];
+        self.participants = participants;
+        self.participant_list_state.select(Some(0));
+    }
+
+    /// Clears the composer for a reply to `tweet_id` from `account` alone, to follow up on a
+    /// post that just went out.
+    pub fn reply_from(&mut self, account: &str, tweet_id: String) {
+        self.reset();
+        self.posting_draft = None;
+        self.target_accounts = self.accounts.iter().map(|name| name == account).collect();
+        self.selected_community = None;
+        self.set_participants(Vec::new());
+        self.reply_to = Some(tweet_id);
+    }
+
+    pub fn excluded_user_ids(&self) -> Vec<String> {
+        self.participants
+            .iter()
+            .zip(&self.excluded_participants)
+            .filter(|(_, excluded)| **excluded)
+            .map(|(user, _)| user.id.clone())
+            .collect()
+    }
+
+    pub fn next_participant(&mut self) {
+        if self.participants.is_empty() {
+            return;
+        }
+        let i = match self.participant_list_state.selected() {
+            Some(i) if i + 1 < self.participants.len() => i + 1,
+            _ => 0,
+        };
+        self.participant_list_state.select(Some(i));
+    }
+
+    pub fn previous_participant(&mut self) {
+        if self.participants.is_empty() {
+            return;
+        }
+        let i = match self.participant_list_state.selected() {
+            Some(0) | None => self.participants.len() - 1,
+            Some(i) => i - 1,
+        };
+        self.participant_list_state.select(Some(i));
+    }
+
+    pub fn toggle_selected_participant(&mut self) {
+        if let Some(i) = self.participant_list_state.selected() {
+            if let Some(excluded) = self.excluded_participants.get_mut(i) {
+                *excluded = !*excluded;
+            }
+        }
+    }
+
+    pub fn selected_assist_prompt(&self) -> Option<&String> {
+        self.assist_list_state.selected().and_then(|i| self.assist_prompts.get(i))
+    }
+
+    pub fn next_assist_prompt(&mut self) {
+        if self.assist_prompts.is_empty() {
+            return;
+        }
+        let i = match self.assist_list_state.selected() {
+            Some(i) if i + 1 < self.assist_prompts.len() => i + 1,
+            _ => 0,
+        };
+        self.assist_list_state.select(Some(i));
+    }
+
+    pub fn previous_assist_prompt(&mut self) {
+        if self.assist_prompts.is_empty() {
+            return;
+        }
+        let i = match self.assist_list_state.selected() {
+            Some(0) | None => self.assist_prompts.len() - 1,
+            Some(i) => i - 1,
+        };
+        self.assist_list_state.select(Some(i));
+    }
+
+    /// Replaces the buffer with the pending suggestion.
+    pub fn accept_suggestion(&mut self) {
+        if let Some(suggestion) = self.assist_suggestion.take() {
+            self.set_text(suggestion);
+        }
+        self.state = AppState::Composing;
+    }
+
+    /// Stores the issues from a grammar check, whose text is underlined in the editor.
+    pub fn set_grammar_issues(&mut self, issues: Vec<GrammarIssue>) {
+        self.grammar_issues = issues;
+        self.grammar_list_state.select(if self.grammar_issues.is_empty() { None } else { Some(0) });
+    }
+
+    pub fn clear_grammar_issues(&mut self) {
+        if !self.grammar_issues.is_empty() {
+            self.set_grammar_issues(Vec::new());
+        }
+    }
+
+    pub fn selected_grammar_issue(&self) -> Option<&GrammarIssue> {
+        self.grammar_list_state.selected().and_then(|i| self.grammar_issues.get(i))
+    }
+
+    /// The text selected in the editor with Shift+arrows, if any.
+    pub fn selected_text(&self) -> Option<String> {
+        let ((start_row, start_col), (end_row, end_col)) = self.textarea.selection_range()?;
+        let lines = self.textarea.lines();
+        let mut text = String::new();
+        for row in start_row..=end_row {
+            let line = lines.get(row)?;
+            let from = if row == start_row { start_col } else { 0 };
+            let to = if row == end_row { end_col } else { line.chars().count() };
+            text.extend(line.chars().skip(from).take(to.saturating_sub(from)));
+            if row != end_row {
+                text.push('\n');
+            }
+        }
+        Some(text)
+    }
+
+    pub fn next_format(&mut self) {
+        let i = match self.format_list_state.selected() {
+            Some(i) if i + 1 < crate::styled::STYLES.len() => i + 1,
+            _ => 0,
+        };
+        self.format_list_state.select(Some(i));
+    }
+
+    pub fn previous_format(&mut self) {
+        let i = match self.format_list_state.selected() {
+            Some(0) | None => crate::styled::STYLES.len() - 1,
+            Some(i) => i - 1,
+        };
+        self.format_list_state.select(Some(i));
+    }
+
+    /// Restyles the selected text with the highlighted style, keeping whatever was yanked before.
+    pub fn apply_format(&mut self) {
+        let style = self.format_list_state.selected().and_then(|i| crate::styled::STYLES.get(i).copied());
+        let yank = self.textarea.yank_text();
+        if let Some(style) = style {
+            if self.textarea.cut() {
+                let styled = crate::styled::apply(&self.textarea.yank_text(), style);
+                self.textarea.insert_str(styled);
+                self.clear_grammar_issues();
+            }
+        }
+        self.textarea.set_yank_text(yank);
+        self.state = AppState::Composing;
+    }
+
+    pub fn next_grammar_issue(&mut self) {
+        if self.grammar_issues.is_empty() {
+            return;
+        }
+        let i = match self.grammar_list_state.selected() {
+            Some(i) if i + 1 < self.grammar_issues.len() => i + 1,
+            _ => 0,
+        };
+        self.grammar_list_state.select(Some(i));
+    }
+
+    pub fn previous_grammar_issue(&mut self) {
+        if self.grammar_issues.is_empty() {
+            return;
+        }
+        let i = match self.grammar_list_state.selected() {
+            Some(0) | None => self.grammar_issues.len() - 1,
+            Some(i) => i - 1,
+        };
+        self.grammar_list_state.select(Some(i));
+    }
+
+    /// Replaces the selected issue's text with its first suggestion and shifts the
+    /// remaining issues to match.
+    pub fn apply_grammar_suggestion(&mut self) {
+        let Some(i) = self.grammar_list_state.selected() else {
+            return;
+        };
+        if i >= self.grammar_issues.len() {
+            return;
+        }
+        let issue = self.grammar_issues.remove(i);
+        let Some(replacement) = issue.replacements.first() else {
+            self.grammar_issues.insert(i, issue);
+            return;
+        };
+
+        let chars: Vec<char> = self.get_text().chars().collect();
+        let end = (issue.offset + issue.length).min(chars.len());
+        let mut text: String = chars[..issue.offset.min(end)].iter().collect();
+        text.push_str(replacement);
+        text.extend(&chars[end..]);
+        let mut issues = std::mem::take(&mut self.grammar_issues);
+        self.set_text(text);
+
+        // Drop issues that overlapped the replaced text and move the later ones
+        let delta = replacement.chars().count() as isize - issue.length as isize;
+        issues.retain(|other| other.offset >= end || other.offset + other.length <= issue.offset);
+        for other in issues.iter_mut().filter(|other| other.offset >= end) {
+            other.offset = other.offset.saturating_add_signed(delta);
+        }
+        self.set_grammar_issues(issues);
+
+        if self.grammar_issues.is_empty() {
+            self.state = AppState::Composing;
+        }
+    }
+
+    /// The list the current screen shows: its state, length and lines per item.
+    fn active_list(&mut self) -> Option<(&mut ListState, usize, u16)> {
+        match self.state {
+            AppState::DraftBrowser => Some((&mut self.draft_list_state, self.drafts.len() + self.damaged_drafts.len(), 1)),
+            AppState::VersionPicker => {
+                let len = self.selected_draft().map_or(0, |draft| draft.versions.len());
+                Some((&mut self.version_list_state, len, 1))
+            }
+            AppState::Search => Some((&mut self.search_list_state, self.search_results.len(), 1)),
+            AppState::MediaPicker => Some((&mut self.media_list_state, self.media.len(), 1)),
+            AppState::AccountPicker => Some((&mut self.account_list_state, self.accounts.len(), 1)),
+            AppState::CommunityPicker => Some((&mut self.community_list_state, self.communities.len() + 1, 1)),
+            AppState::ParticipantPicker => Some((&mut self.participant_list_state, self.participants.len(), 1)),
+            AppState::AssistPicker => Some((&mut self.assist_list_state, self.assist_prompts.len(), 1)),
+            AppState::GrammarPopup => Some((&mut self.grammar_list_state, self.grammar_issues.len(), 2)),
+            AppState::FormatPicker => Some((&mut self.format_list_state, crate::styled::STYLES.len(), 1)),
+            _ => None,
+        }
+    }
+
+    /// Index of the list item drawn at (`column`, `row`), if any.
+    pub fn list_item_at(&mut self, column: u16, row: u16) -> Option<usize> {
+        let area = self.list_area;
+        let inside = column > area.x && column + 1 < area.right() && row > area.y && row + 1 < area.bottom();
+        let (list_state, len, item_height) = self.active_list()?;
+        if !inside {
+            return None;
+        }
+        let i = list_state.offset() + usize::from((row - area.y - 1) / item_height);
+        (i < len).then_some(i)
+    }
+
+    pub fn selected_list_item(&mut self) -> Option<usize> {
+        self.active_list().and_then(|(list_state, _, _)| list_state.selected())
+    }
+
+    pub fn select_list_item(&mut self, i: usize) {
+        if let Some((list_state, _, _)) = self.active_list() {
+            list_state.select(Some(i));
+        }
+        if self.state == AppState::MediaPicker {
+            self.refresh_media_thumbnail();
+        }
+    }
+
+    /// Steps through the ephemeral lifetimes: off, 1h, 24h, 7d.
+    pub fn cycle_delete_after(&mut self) {
+        let hours = self.delete_after.map(|after| after.num_hours());
+        self.delete_after = match hours {
+            None => Some(chrono::Duration::hours(1)),
+            Some(1) => Some(chrono::Duration::hours(24)),
+            Some(24) => Some(chrono::Duration::days(7)),
+            _ => None,
+        };
+    }
+
+    pub fn set_premium(&mut self, premium: bool) {
+        self.char_limit = if premium {
+            PREMIUM_CHAR_LIMIT
+        } else {
+            STANDARD_CHAR_LIMIT
+        };
+        self.char_limit_unsure = false;
+    }
+
+    /// The limit for the status bar, with a "?" while it's only assumed.
+    pub fn char_limit_label(&self) -> String {
+        if self.char_limit_unsure {
+            format!("{}?", self.char_limit)
+        } else {
+            self.char_limit.to_string()
+        }
+    }
+
+    /// `text`'s length as it counts against the limit: weighted the way X counts posts, or
+    /// plain characters for a direct message.
+    pub fn length(&self, text: &str) -> usize {
+        if self.dm_recipient.is_some() {
+            text.chars().count()
+        } else {
+            crate::text::weighted_len(text)
         }
     }
 
+    /// Length of the longest post in the buffer, which is what the limit applies to.
+    pub fn longest_part_count(&self) -> usize {
+        crate::thread::split_thread(&self.get_text())
+            .iter()
+            .map(|part| self.length(part))
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn thread_len(&self) -> usize {
+        crate::thread::split_thread(&self.get_text()).len()
+    }
+
+    /// The buffer with any already shortened links swapped in; this is what gets posted.
+    pub fn post_text(&self) -> String {
+        crate::links::substitute_urls(&self.get_text(), &self.short_urls)
+    }
+
+    /// The fetched card, if it's for the link currently in the buffer.
+    pub fn current_link_card(&self) -> Option<&LinkCard> {
+        let url = crate::links::first_url(&self.get_text())?;
+        self.link_card.as_ref().filter(|card| card.url == url)
+    }
+
+    pub fn is_over_limit(&self) -> bool {
+        self.longest_part_count() > self.char_limit
+    }
+
+    pub fn scroll_preview_down(&mut self, lines: u16) {
+        self.preview_scroll = self.preview_scroll.saturating_add(lines);
+    }
+
+    pub fn scroll_preview_up(&mut self, lines: u16) {
+        self.preview_scroll = self.preview_scroll.saturating_sub(lines);
+    }
+
     pub fn char_count(&self) -> usize {
-        self.textarea.lines().join("\n").chars().count()
+        self.length(&self.get_text())
+    }
+
+    pub fn word_count(&self) -> usize {
+        self.textarea.lines().iter().map(|line| line.split_whitespace().count()).sum()
     }
 
     pub fn get_text(&self) -> String {
@@ -61,49 +817,100 @@ impl<'a> App<'a> {
 
     pub fn set_text(&mut self, text: String) {
         let lines: Vec<String> = text.lines().map(|s| s.to_string()).collect();
-        self.textarea = TextArea::new(lines);
-        self.textarea.set_block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title("Compose your post")
-                .border_style(Style::default().fg(Color::Cyan)),
-        );
-        self.textarea.set_cursor_line_style(Style::default());
-        self.textarea.set_cursor_style(Style::default().add_modifier(Modifier::REVERSED));
+        self.textarea = compose_textarea(lines, self.accent);
+        self.clear_grammar_issues();
+    }
+
+    /// Recounts this month's posts for the accounts with a monthly cap, and the posting streak.
+    pub fn refresh_post_counts(&mut self) {
+        self.posts_this_month = self
+            .post_caps
+            .keys()
+            .map(|account| (account.clone(), crate::history::posts_this_month(account)))
+            .collect();
+        if let Some(account) = self.selected_accounts().first().filter(|_| self.streak_reminder) {
+            let days = crate::history::posts_per_day(account);
+            self.cadence = Some(crate::history::cadence(&days, chrono::Local::now().date_naive()));
+        }
+    }
+
+    /// Drops background posts that finished a while ago from the list.
+    pub fn prune_background_posts(&mut self) {
+        self.background_posts
+            .retain(|post| post.finished_at.is_none_or(|at| at.elapsed() < BACKGROUND_POST_LINGER));
     }
 
     pub fn reset(&mut self) {
-        self.textarea = TextArea::default();
-        self.textarea.set_block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title("Compose your post")
-                .border_style(Style::default().fg(Color::Cyan)),
-        );
-        self.textarea.set_cursor_line_style(Style::default());
-        self.textarea.set_cursor_style(Style::default().add_modifier(Modifier::REVERSED));
-        self.has_image = false;
+        self.textarea = compose_textarea(Vec::new(), self.accent);
+        self.image_count = 0;
+        self.alt_texts.clear();
+        self.upload_progress = None;
+        self.cap_warned = false;
+        self.quiet_warned = false;
+        self.blocklist_confirmed.clear();
+        self.alt_text_warned = false;
         self.file_path_input.clear();
         self.state = AppState::Composing;
         self.current_draft_id = None;
     }
 
+    /// Opens the alt text prompt for the image at `image`, with what it has so far.
+    pub fn edit_alt_text(&mut self, image: usize) {
+        self.alt_text_input = self.alt_texts.get(image).cloned().unwrap_or_default();
+        self.state = AppState::AltTextPrompt(image);
+    }
+
+    /// Indexes of the attached images that have no alt text yet.
+    pub fn images_without_alt_text(&self) -> Vec<usize> {
+        (0..self.image_count)
+            .filter(|&i| self.alt_texts.get(i).is_none_or(|text| text.trim().is_empty()))
+            .collect()
+    }
+
     pub fn load_drafts(&mut self) {
-        if let Ok(drafts) = crate::drafts::load_drafts() {
+        match crate::drafts::sync() {
+            Ok(0) => {}
+            Ok(copies) => self.notice = Some(tf("status.conflicting_drafts", &[&copies])),
+            Err(e) => self.notice = Some(tf("status.sync_failed", &[&e])),
+        }
+        if let Ok(renamed) = crate::drafts::migrate_ids() {
+            if let Some(id) = self.current_draft_id.as_ref().and_then(|id| renamed.get(id)) {
+                self.current_draft_id = Some(id.clone());
+            }
+        }
+        let drafts = if self.show_archive {
+            crate::drafts::load_archive()
+        } else {
+            crate::drafts::load_drafts()
+        };
+        if let Ok((drafts, damaged)) = drafts {
             self.drafts = drafts;
-            if !self.drafts.is_empty() {
-                self.draft_list_state.select(Some(0));
+            self.damaged_drafts = damaged;
+            self.draft_list_state.select((self.draft_rows() > 0).then_some(0));
+            if !self.damaged_drafts.is_empty() {
+                self.notice = Some(tf("status.damaged_drafts", &[&self.damaged_drafts.len()]));
             }
         }
+        self.marked_drafts.clear();
+    }
+
+    /// Rows in the draft browser: the drafts, then the damaged files.
+    fn draft_rows(&self) -> usize {
+        self.drafts.len() + self.damaged_drafts.len()
+    }
+
+    pub fn toggle_archive(&mut self) {
+        self.show_archive = !self.show_archive;
+        self.load_drafts();
     }
 
     pub fn next_draft(&mut self) {
-        if self.drafts.is_empty() {
+        if self.draft_rows() == 0 {
             return;
         }
         let i = match self.draft_list_state.selected() {
             Some(i) => {
-                if i >= self.drafts.len() - 1 {
+                if i >= self.draft_rows() - 1 {
                     0
                 } else {
                     i + 1
@@ -115,13 +922,13 @@ impl<'a> App<'a> {
     }
 
     pub fn previous_draft(&mut self) {
-        if self.drafts.is_empty() {
+        if self.draft_rows() == 0 {
             return;
         }
         let i = match self.draft_list_state.selected() {
             Some(i) => {
                 if i == 0 {
-                    self.drafts.len() - 1
+                    self.draft_rows() - 1
                 } else {
                     i - 1
                 }
@@ -135,163 +942,1345 @@ impl<'a> App<'a> {
         if let Some(i) = self.draft_list_state.selected() {
             if let Some(draft) = self.drafts.get(i).cloned() {
                 self.set_text(draft.content.clone());
-                self.current_draft_id = Some(draft.id.clone());
+                // A posted draft comes back as a fresh copy; the archived one stays as posted
+                self.current_draft_id = draft.posted.is_none().then(|| draft.id.clone());
                 self.state = AppState::Composing;
+            } else if let Some(damaged) = self.selected_damaged_draft().cloned() {
+                self.inspect_damaged_draft(&damaged);
             }
         }
     }
 
-    pub fn delete_selected_draft(&mut self) {
-        if let Some(i) = self.draft_list_state.selected() {
-            if let Some(draft) = self.drafts.get(i) {
-                let _ = crate::drafts::delete_draft(&draft.id);
-                self.drafts.remove(i);
-                
-                // Update selection
-                if self.drafts.is_empty() {
-                    self.draft_list_state.select(None);
-                } else if i >= self.drafts.len() {
-                    self.draft_list_state.select(Some(self.drafts.len() - 1));
-                }
+    /// The damaged draft file highlighted in the browser, if it's one of those.
+    pub fn selected_damaged_draft(&self) -> Option<&DamagedDraft> {
+        let i = self.draft_list_state.selected()?.checked_sub(self.drafts.len())?;
+        self.damaged_drafts.get(i)
+    }
+
+    /// Loads a damaged draft file into the composer as it is, so whatever survived can be
+    /// copied out or saved as a new draft.
+    fn inspect_damaged_draft(&mut self, damaged: &DamagedDraft) {
+        match damaged.raw_text() {
+            Ok(text) => {
+                self.set_text(text);
+                self.current_draft_id = None;
+                self.state = AppState::Composing;
+                self.notice = Some(tf("status.damaged_draft_inspect", &[&damaged.file_name()]));
             }
+            Err(e) => self.notice = Some(tf("status.error", &[&e])),
         }
     }
-}
 
-pub fn draw(f: &mut Frame, app: &mut App) {
-    if app.state == AppState::DraftBrowser {
-        draw_draft_browser(f, app);
-        return;
+    /// Saves what can be recovered of the highlighted damaged file as a new draft and sets the
+    /// file aside.
+    pub fn repair_selected_draft(&mut self) {
+        let Some(damaged) = self.selected_damaged_draft().cloned() else {
+            return;
+        };
+        let notice = match crate::drafts::repair(&damaged) {
+            Ok(Some(path)) => {
+                self.load_drafts();
+                tf("status.damaged_draft_repaired", &[&damaged.file_name(), &path.display()])
+            }
+            Ok(None) => tf("status.damaged_draft_unrecoverable", &[&damaged.file_name()]),
+            Err(e) => tf("status.error", &[&e]),
+        };
+        self.notice = Some(notice);
     }
 
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Min(3),
-            Constraint::Length(3),
-            Constraint::Length(3),
-        ])
-        .split(f.area());
+    /// Opens search over the drafts, posted drafts and post history, reading them afresh.
+    pub fn open_search(&mut self) {
+        match crate::search::Index::build() {
+            Ok(index) => {
+                self.search_index = index;
+                self.search_input.clear();
+                self.search_results.clear();
+                self.search_list_state.select(None);
+                self.state = AppState::Search;
+            }
+            Err(e) => self.notice = Some(tf("status.error", &[&e])),
+        }
+    }
 
-    draw_text_input(f, app, chunks[0]);
-    draw_status(f, app, chunks[1]);
-    draw_instructions(f, app, chunks[2]);
-}
+    pub fn update_search(&mut self) {
+        self.search_results = self.search_index.search(&self.search_input);
+        self.search_list_state.select((!self.search_results.is_empty()).then_some(0));
+    }
 
-fn draw_text_input(f: &mut Frame, app: &mut App, area: Rect) {
-    if app.state == AppState::FilePrompt {
+    pub fn selected_search_result(&self) -> Option<&crate::search::Entry> {
+        let i = *self.search_results.get(self.search_list_state.selected()?)?;
+        self.search_index.entries.get(i)
+    }
+
+    pub fn next_search_result(&mut self) {
+        let i = match self.search_list_state.selected() {
+            Some(i) if i + 1 < self.search_results.len() => i + 1,
+            _ => 0,
+        };
+        self.search_list_state.select((!self.search_results.is_empty()).then_some(i));
+    }
+
+    pub fn previous_search_result(&mut self) {
+        let i = match self.search_list_state.selected() {
+            Some(0) | None => self.search_results.len().saturating_sub(1),
+            Some(i) => i - 1,
+        };
+        self.search_list_state.select((!self.search_results.is_empty()).then_some(i));
+    }
+
+    /// Loads the highlighted result into the composer: a draft to keep editing, anything
+    /// already posted as a fresh copy.
+    pub fn select_search_result(&mut self) {
+        if let Some(entry) = self.selected_search_result().cloned() {
+            self.set_text(entry.text);
+            self.current_draft_id = (entry.source == crate::search::Source::Draft).then_some(entry.id);
+            self.state = AppState::Composing;
+        }
+    }
+
+    pub fn selected_draft(&self) -> Option<&Draft> {
+        self.draft_list_state.selected().and_then(|i| self.drafts.get(i))
+    }
+
+    /// Opens the earlier versions of the selected draft, newest first.
+    pub fn open_versions(&mut self) {
+        match self.selected_draft() {
+            Some(draft) if !draft.versions.is_empty() => {
+                self.version_list_state.select(Some(0));
+                self.state = AppState::VersionPicker;
+            }
+            Some(_) => self.notice = Some(t("status.no_versions").to_string()),
+            None => {}
+        }
+    }
+
+    /// The version highlighted in the picker, which lists them newest first.
+    pub fn selected_version(&self) -> Option<&DraftVersion> {
+        let versions = &self.selected_draft()?.versions;
+        let i = self.version_list_state.selected()?;
+        versions.len().checked_sub(i + 1).map(|i| &versions[i])
+    }
+
+    pub fn next_version(&mut self) {
+        let len = self.selected_draft().map_or(0, |draft| draft.versions.len());
+        let i = match self.version_list_state.selected() {
+            Some(i) if i + 1 < len => i + 1,
+            _ => 0,
+        };
+        self.version_list_state.select(Some(i));
+    }
+
+    pub fn previous_version(&mut self) {
+        let len = self.selected_draft().map_or(0, |draft| draft.versions.len());
+        let i = match self.version_list_state.selected() {
+            Some(0) | None => len.saturating_sub(1),
+            Some(i) => i - 1,
+        };
+        self.version_list_state.select(Some(i));
+    }
+
+    /// Loads the highlighted version into the composer as the draft's text; saving then keeps
+    /// the wording it replaced as a version too.
+    pub fn restore_selected_version(&mut self) {
+        let Some(content) = self.selected_version().map(|version| version.content.clone()) else {
+            return;
+        };
+        self.current_draft_id = self.selected_draft().map(|draft| draft.id.clone());
+        self.set_text(content);
+        self.state = AppState::Composing;
+    }
+
+    /// Pins or unpins the selected draft, which moves it between the groups.
+    pub fn toggle_pin_selected_draft(&mut self) {
+        let Some(draft) = self.draft_list_state.selected().and_then(|i| self.drafts.get_mut(i)) else {
+            return;
+        };
+        draft.pinned = !draft.pinned;
+        let _ = crate::drafts::save_draft(draft);
+        let id = draft.id.clone();
+        let message = if draft.pinned { "Pin draft" } else { "Unpin draft" };
+        self.commit_drafts(message);
+
+        crate::drafts::sort_drafts(&mut self.drafts);
+        let i = self.drafts.iter().position(|draft| draft.id == id);
+        self.draft_list_state.select(i);
+    }
+
+    /// Swaps the selected draft with its neighbour, staying within the pinned or unpinned group,
+    /// and saves the new order.
+    pub fn move_selected_draft(&mut self, up: bool) {
+        let Some(i) = self.draft_list_state.selected() else {
+            return;
+        };
+        let j = if up { i.checked_sub(1) } else { Some(i + 1) };
+        let Some(j) = j.filter(|&j| j < self.drafts.len()) else {
+            return;
+        };
+        if self.drafts[i].pinned != self.drafts[j].pinned {
+            return;
+        }
+
+        self.drafts.swap(i, j);
+        for (position, draft) in self.drafts.iter_mut().enumerate() {
+            if draft.sort_order != Some(position as u32) {
+                draft.sort_order = Some(position as u32);
+                let _ = crate::drafts::save_draft(draft);
+            }
+        }
+        self.commit_drafts("Reorder drafts");
+        self.draft_list_state.select(Some(j));
+    }
+
+    pub fn toggle_mark_selected_draft(&mut self) {
+        let Some(id) = self.selected_draft().map(|draft| draft.id.clone()) else {
+            return;
+        };
+        if !self.marked_drafts.remove(&id) {
+            self.marked_drafts.insert(id);
+        }
+    }
+
+    /// What a bulk action applies to: the marked drafts, or the highlighted one if none are.
+    pub fn bulk_drafts(&self) -> Vec<&Draft> {
+        if self.marked_drafts.is_empty() {
+            self.selected_draft().into_iter().collect()
+        } else {
+            self.drafts.iter().filter(|draft| self.marked_drafts.contains(&draft.id)).collect()
+        }
+    }
+
+    pub fn delete_marked_drafts(&mut self) {
+        for id in &self.marked_drafts {
+            let _ = crate::drafts::delete_draft(id);
+        }
+        let marked = std::mem::take(&mut self.marked_drafts);
+        self.drafts.retain(|draft| !marked.contains(&draft.id));
+        self.commit_drafts("Delete drafts");
+
+        let i = self.draft_list_state.selected().unwrap_or(0).min(self.drafts.len().saturating_sub(1));
+        self.draft_list_state.select((!self.drafts.is_empty()).then_some(i));
+    }
+
+    /// Adds `tag` to the marked drafts (or the highlighted one), or takes it off if they all have it.
+    pub fn tag_marked_drafts(&mut self, tag: &str) {
+        let tag = tag.trim().trim_start_matches('#');
+        if tag.is_empty() {
+            return;
+        }
+        let ids: HashSet<String> = self.bulk_drafts().iter().map(|draft| draft.id.clone()).collect();
+        let targets: Vec<&mut Draft> = self.drafts.iter_mut().filter(|draft| ids.contains(&draft.id)).collect();
+        let untag = targets.iter().all(|draft| draft.tags.iter().any(|t| t == tag));
+
+        for draft in targets {
+            if untag {
+                draft.tags.retain(|t| t != tag);
+            } else if !draft.tags.iter().any(|t| t == tag) {
+                draft.tags.push(tag.to_string());
+            }
+            let _ = crate::drafts::save_draft(draft);
+        }
+        self.commit_drafts("Tag drafts");
+    }
+
+    /// Commits a change to the drafts when they're synced with git, reporting a failure.
+    fn commit_drafts(&mut self, message: &str) {
+        if let Err(e) = crate::drafts::commit(message) {
+            self.notice = Some(tf("status.sync_failed", &[&e]));
+        }
+    }
+
+    pub fn delete_selected_draft(&mut self) {
+        // A damaged file is moved out of the way rather than deleted, in case it can be saved
+        if let Some(damaged) = self.selected_damaged_draft().cloned() {
+            let notice = match crate::drafts::set_aside(&damaged) {
+                Ok(path) => {
+                    self.load_drafts();
+                    tf("status.damaged_draft_set_aside", &[&damaged.file_name(), &path.display()])
+                }
+                Err(e) => tf("status.error", &[&e]),
+            };
+            self.notice = Some(notice);
+            return;
+        }
+        if let Some(i) = self.draft_list_state.selected() {
+            if let Some(draft) = self.drafts.get(i) {
+                let _ = crate::drafts::delete_draft(&draft.id);
+                self.drafts.remove(i);
+                self.commit_drafts("Delete draft");
+                
+                // Update selection
+                if self.draft_rows() == 0 {
+                    self.draft_list_state.select(None);
+                } else if i >= self.draft_rows() {
+                    self.draft_list_state.select(Some(self.draft_rows() - 1));
+                }
+            }
+        }
+    }
+}
+
+/// The session's one-line tab bar, with the current tab in the accent colour.
+pub fn draw_tab_bar(f: &mut Frame, titles: &[&str], selected: usize, accent: Color, area: Rect) {
+    let tabs = Tabs::new(titles.to_vec())
+        .select(selected)
+        .style(Style::default().fg(Color::DarkGray))
+        .highlight_style(Style::default().fg(accent).add_modifier(Modifier::BOLD))
+        .divider("|");
+    f.render_widget(tabs, area);
+}
+
+pub fn draw(f: &mut Frame, app: &mut App, area: Rect) {
+    match app.state {
+        AppState::DraftBrowser | AppState::TagPrompt => {
+            draw_draft_browser(f, app, area);
+            return;
+        }
+        AppState::VersionPicker => {
+            draw_version_picker(f, app, area);
+            return;
+        }
+        AppState::Search => {
+            draw_search(f, app, area);
+            return;
+        }
+        AppState::MediaPicker => {
+            draw_media_picker(f, app, area);
+            return;
+        }
+        AppState::AccountPicker => {
+            draw_account_picker(f, app, area);
+            return;
+        }
+        AppState::CommunityPicker => {
+            draw_community_picker(f, app, area);
+            return;
+        }
+        AppState::ParticipantPicker => {
+            draw_participant_picker(f, app, area);
+            return;
+        }
+        AppState::AssistPicker => {
+            draw_assist_picker(f, app, area);
+            return;
+        }
+        AppState::AssistDiff => {
+            draw_assist_diff(f, app, area);
+            return;
+        }
+        AppState::Preview => {
+            draw_preview(f, app, area);
+            return;
+        }
+        AppState::Composing if app.zen => {
+            draw_zen(f, app, area);
+            return;
+        }
+        AppState::Success(_) if app.dm_recipient.is_none() => {
+            draw_success(f, app, area);
+            return;
+        }
+        _ => {}
+    }
+
+    let background_height = match app.background_posts.len() {
+        0 => 0,
+        n => n.min(4) as u16 + 2,
+    };
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(3),
+            Constraint::Length(1),
+            Constraint::Length(background_height),
+            Constraint::Length(3),
+            Constraint::Length(instructions_height(&instructions(app), area.width)),
+        ])
+        .split(area);
+
+    draw_text_input(f, app, chunks[0]);
+    draw_length_gauge(f, app, chunks[1]);
+    draw_background_posts(f, app, chunks[2]);
+    draw_status(f, app, chunks[3]);
+    draw_instructions(f, app, chunks[4]);
+
+    if app.state == AppState::GrammarPopup {
+        draw_grammar_popup(f, app, chunks[0]);
+    }
+    if app.state == AppState::FormatPicker {
+        draw_format_picker(f, app, chunks[0]);
+    }
+}
+
+/// What went out: the text with its images and thread length, then a link per account and
+/// cross-post, above the quick actions.
+fn draw_success(f: &mut Frame, app: &mut App, area: Rect) {
+    let AppState::Success(outcomes) = &app.state else {
+        return;
+    };
+    let (text, image_count) = match &app.posting_draft {
+        Some(draft) => (draft.content.clone(), 0),
+        None => (app.get_text(), app.image_count),
+    };
+
+    let mut details = vec![t("success.title").to_string()];
+    match image_count {
+        0 => {}
+        1 => details.push(t("success.image").to_string()),
+        n => details.push(tf("success.images", &[&n])),
+    }
+    let parts = outcomes
+        .iter()
+        .filter_map(|outcome| outcome.result.as_ref().ok())
+        .map(Vec::len)
+        .max()
+        .unwrap_or(0);
+    if parts > 1 {
+        details.push(tf("success.thread", &[&parts]));
+    }
+
+    let mut links: Vec<Line> = Vec::new();
+    for outcome in outcomes {
+        let line = match (outcome.url(), &outcome.result) {
+            (Some(url), _) if outcomes.len() == 1 => Line::styled(format!("✓ {}", url), Style::default().fg(Color::Green)),
+            (Some(url), _) => Line::styled(format!("✓ {}: {}", outcome.account, url), Style::default().fg(Color::Green)),
+            (None, Err(e)) => Line::styled(format!("✗ {}: {}", outcome.account, e), Style::default().fg(Color::Red)),
+            (None, Ok(_)) => continue,
+        };
+        links.push(line);
+        for (target, result) in &outcome.crossposts {
+            links.push(match result {
+                Ok(url) => Line::styled(format!("  ✓ {}: {}", target, url), Style::default().fg(Color::Green)),
+                Err(e) => Line::styled(format!("  ✗ {}: {}", target, e), Style::default().fg(Color::Red)),
+            });
+        }
+    }
+    if let Some(notice) = &app.notice {
+        links.push(Line::styled(notice.clone(), Style::default().fg(Color::Yellow)));
+    }
+
+    let flashing = app.flash_until.is_some_and(|until| std::time::Instant::now() < until);
+    let links_style = if flashing { Style::default().bg(Color::Green).fg(Color::Black) } else { Style::default() };
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(3),
+            Constraint::Length(links.len() as u16 + 2),
+            Constraint::Length(instructions_height(&instructions(app), area.width)),
+        ])
+        .split(area);
+
+    let posted = Paragraph::new(text)
+        .style(Style::default().fg(Color::White))
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(details.join(" · "))
+                .border_style(Style::default().fg(app.accent)),
+        );
+    f.render_widget(posted, chunks[0]);
+
+    let links = Paragraph::new(links).style(links_style).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(t("success.links"))
+            .border_style(Style::default().fg(Color::Gray)),
+    );
+    f.render_widget(links, chunks[1]);
+
+    draw_instructions(f, app, chunks[2]);
+}
+
+/// Just the text in a centred column; the status bar only comes back to show a notice.
+fn draw_zen(f: &mut Frame, app: &mut App, area: Rect) {
+    app.help_area = Rect::default();
+    let status_height = if app.notice.is_some() { 3 } else { 0 };
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(1), Constraint::Length(status_height)])
+        .split(area);
+    let width = app.zen_width.min(area.width);
+    let column = Rect::new(area.x + (area.width - width) / 2, rows[1].y, width, rows[1].height);
+
+    let mut textarea = app.textarea.clone();
+    textarea.set_block(Block::default());
+    if app.typewriter {
+        // The text area scrolls just enough to keep the cursor in view, so ending it on the
+        // middle row pins the cursor's line there; the rest of the text is drawn below it
+        let (row, _) = textarea.cursor();
+        let middle = column.y + column.height / 2;
+        let top = middle.saturating_sub(row as u16).max(column.y);
+        f.render_widget(&textarea, Rect::new(column.x, top, column.width, middle + 1 - top));
+
+        let below: Vec<Line> = textarea.lines().iter().skip(row + 1).map(|line| Line::raw(line.as_str())).collect();
+        let rest = Rect::new(column.x, middle + 1, column.width, column.bottom().saturating_sub(middle + 1));
+        f.render_widget(Paragraph::new(below).style(textarea.style()), rest);
+    } else {
+        f.render_widget(&textarea, column);
+    }
+    draw_grammar_markers(f, app, column);
+
+    if status_height > 0 {
+        draw_status(f, app, rows[2]);
+    }
+}
+
+/// One line per background post, the newest at the bottom.
+fn draw_background_posts(f: &mut Frame, app: &App, area: Rect) {
+    if app.background_posts.is_empty() {
+        return;
+    }
+    let shown = app.background_posts.len().saturating_sub(4);
+    let lines: Vec<Line> = app.background_posts[shown..]
+        .iter()
+        .map(|post| {
+            let (text, color) = match &post.outcomes {
+                None => match post.upload_progress {
+                    Some((done, total)) if done < total => {
+                        (tf("background.uploading", &[&post.summary, &done, &total]), Color::Yellow)
+                    }
+                    _ => (tf("background.posting", &[&post.summary]), Color::Yellow),
+                },
+                Some(outcomes) => match outcomes.iter().find_map(PostOutcome::url) {
+                    Some(_) if app.dm_recipient.is_some() => (tf("background.sent", &[&post.summary]), Color::Green),
+                    Some(url) => (tf("background.posted", &[&post.summary, &url]), Color::Green),
+                    None => {
+                        let errors: Vec<&str> = outcomes
+                            .iter()
+                            .filter_map(|outcome| outcome.result.as_ref().err().map(String::as_str))
+                            .collect();
+                        // Failed posts are kept in the queue; messages aren't
+                        let key = if app.dm_recipient.is_some() { "background.message_failed" } else { "background.failed" };
+                        (tf(key, &[&post.summary, &errors.join("; ")]), Color::Red)
+                    }
+                },
+            };
+            Line::styled(text, Style::default().fg(color))
+        })
+        .collect();
+    let list = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(t("background.title"))
+            .border_style(Style::default().fg(app.accent)),
+    );
+    f.render_widget(list, area);
+}
+
+fn draw_length_gauge(f: &mut Frame, app: &App, area: Rect) {
+    let count = app.longest_part_count();
+    let ratio = count as f64 / app.char_limit as f64;
+    let color = if ratio > 1.0 {
+        Color::Red
+    } else if ratio > 0.9 {
+        Color::Yellow
+    } else {
+        app.accent
+    };
+
+    let gauge = Gauge::default()
+        .gauge_style(Style::default().fg(color).bg(Color::Black))
+        .ratio(ratio.min(1.0))
+        .label(format!("{} / {}", count, app.char_limit_label()));
+    f.render_widget(gauge, area);
+}
+
+fn draw_preview(f: &mut Frame, app: &mut App, area: Rect) {
+    // The card is left out when it would squeeze the post itself
+    let card_height = LINK_CARD_HEIGHT as u16 / 2 + 2;
+    let card = app.current_link_card().filter(|_| area.height >= card_height + 14);
+    let card_height = if card.is_some() { card_height } else { 0 };
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(3),
+            Constraint::Length(card_height),
+            Constraint::Length(1),
+            Constraint::Length(3),
+            Constraint::Length(3),
+        ])
+        .split(area);
+
+    let preview = Paragraph::new(app.post_text())
+        .style(Style::default().fg(Color::White))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(t("compose.preview_title"))
+                .border_style(Style::default().fg(app.accent)),
+        )
+        .wrap(Wrap { trim: false })
+        .scroll((app.preview_scroll, 0));
+    f.render_widget(preview, chunks[0]);
+
+    if let Some(card) = card {
+        draw_link_card(f, card, chunks[1]);
+    }
+    draw_length_gauge(f, app, chunks[2]);
+    draw_status(f, app, chunks[3]);
+    draw_instructions(f, app, chunks[4]);
+}
+
+fn draw_link_card(f: &mut Frame, card: &LinkCard, area: Rect) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(t("compose.link_card_title"))
+        .border_style(Style::default().fg(Color::Gray));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let image_width = if card.image.is_some() { LINK_CARD_WIDTH as u16 + 1 } else { 0 };
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(image_width), Constraint::Min(10)])
+        .split(inner);
+
+    if let Some(image) = &card.image {
+        f.render_widget(Paragraph::new(thumbnail_lines(image)), columns[0]);
+    }
+
+    let domain = card
+        .site_name
+        .clone()
+        .or_else(|| card.url.split('/').nth(2).map(str::to_string))
+        .unwrap_or_default();
+    let mut lines = vec![
+        Line::from(Span::styled(domain, Style::default().fg(Color::DarkGray))),
+        Line::from(Span::styled(
+            card.title.clone().unwrap_or_else(|| card.url.clone()),
+            Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+        )),
+    ];
+    if let Some(description) = &card.description {
+        lines.push(Line::from(Span::styled(description.clone(), Style::default().fg(Color::Gray))));
+    }
+    f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: true }), columns[1]);
+}
+
+fn draw_text_input(f: &mut Frame, app: &mut App, area: Rect) {
+    if app.state == AppState::FilePrompt {
         let input = Paragraph::new(app.file_path_input.as_str())
             .style(Style::default().fg(Color::White))
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .title("Enter image file path")
-                    .border_style(Style::default().fg(Color::Cyan)),
+                    .title(t("compose.file_prompt_title"))
+                    .border_style(Style::default().fg(app.accent)),
+            )
+            .wrap(Wrap { trim: false });
+        f.render_widget(input, area);
+    } else if let AppState::AltTextPrompt(image) = app.state {
+        let input = Paragraph::new(app.alt_text_input.as_str())
+            .style(Style::default().fg(Color::White))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(tf("compose.alt_text_title", &[&(image + 1), &app.image_count]))
+                    .border_style(Style::default().fg(app.accent)),
+            )
+            .wrap(Wrap { trim: false });
+        f.render_widget(input, area);
+    } else if app.state == AppState::SchedulePrompt {
+        let title = tf("compose.schedule_prompt_title", &[&crate::timezone::zone()]);
+        draw_schedule_picker(f, &app.schedule_picker, &app.schedule_input, &title, app.accent, area);
+    } else if let (AppState::ReauthPrompt, Some(reauth)) = (&app.state, &app.reauth) {
+        let (step, steps) = reauth.step();
+        let input = Paragraph::new(reauth.masked_input())
+            .style(Style::default().fg(Color::White))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(tf("compose.reauth_title", &[&reauth.field().label(), &reauth.account, &step, &steps]))
+                    .border_style(Style::default().fg(app.accent)),
             )
             .wrap(Wrap { trim: false });
         f.render_widget(input, area);
     } else {
-        let title = match &app.state {
-            AppState::Posting => "Posting...",
-            _ => "Compose your post",
+        let title = match (&app.state, &app.dm_recipient, &app.reply_to) {
+            (AppState::Posting, Some(_), _) => t("compose.sending").to_string(),
+            (AppState::Posting, None, _) => t("compose.posting").to_string(),
+            (_, Some(username), _) => tf("compose.message_title", &[username]),
+            (_, None, Some(tweet_id)) => tf("compose.reply_title", &[tweet_id]),
+            (_, None, None) => t("compose.title").to_string(),
         };
         
         let mut textarea = app.textarea.clone();
         textarea.set_block(
             Block::default()
                 .borders(Borders::ALL)
-                .title(title)
-                .border_style(Style::default().fg(Color::Cyan)),
-        );
-        f.render_widget(&textarea, area);
-    }
+                .title(title)
+                .border_style(Style::default().fg(app.accent)),
+        );
+        f.render_widget(&textarea, area);
+        draw_grammar_markers(f, app, area.inner(Margin { horizontal: 1, vertical: 1 }));
+    }
+}
+
+/// Underlines the text of each grammar issue in the editor drawn in `area`. The editor doesn't
+/// say how far it has scrolled, so the text is placed relative to where it drew the cursor.
+fn draw_grammar_markers(f: &mut Frame, app: &App, area: Rect) {
+    if app.grammar_issues.is_empty() {
+        return;
+    }
+    let buf = f.buffer_mut();
+    let area = area.intersection(buf.area);
+    let Some(cursor) = area.positions().find(|&position| buf[position].modifier.contains(Modifier::REVERSED)) else {
+        return;
+    };
+
+    let lines = app.textarea.lines();
+    let (cursor_row, cursor_col) = app.textarea.cursor();
+    let width = |row: usize, col: usize| Span::raw(lines[row].chars().take(col).collect::<String>()).width() as i64;
+    let style = Style::default().fg(Color::Red).add_modifier(Modifier::UNDERLINED);
+
+    for issue in &app.grammar_issues {
+        let start = text_position(lines, issue.offset);
+        let end = text_position(lines, issue.offset + issue.length);
+        // Issues that run over a line break aren't marked
+        let (Some((row, start)), Some((end_row, end))) = (start, end) else {
+            continue;
+        };
+        if row != end_row {
+            continue;
+        }
+        let y = cursor.y as i64 + row as i64 - cursor_row as i64;
+        let shift = cursor.x as i64 - width(cursor_row, cursor_col);
+        for x in width(row, start) + shift..width(row, end) + shift {
+            let (Ok(x), Ok(y)) = (u16::try_from(x), u16::try_from(y)) else {
+                continue;
+            };
+            if area.contains(Position { x, y }) {
+                buf[(x, y)].set_style(style);
+            }
+        }
+    }
+}
+
+/// Row and column in `lines` of the character `offset` characters into their text.
+fn text_position(lines: &[String], offset: usize) -> Option<(usize, usize)> {
+    let mut remaining = offset;
+    for (row, line) in lines.iter().enumerate() {
+        let len = line.chars().count();
+        if remaining <= len {
+            return Some((row, remaining));
+        }
+        remaining -= len + 1;
+    }
+    None
+}
+
+/// Draws a resize notice over the whole terminal when it's smaller than the screens need.
+/// Returns whether it did, in which case nothing else should be drawn.
+pub fn draw_too_small(f: &mut Frame) -> bool {
+    let area = f.area();
+    if area.width >= MIN_WIDTH && area.height >= MIN_HEIGHT {
+        return false;
+    }
+    let message = tf("ui.too_small", &[&area.width, &area.height, &MIN_WIDTH, &MIN_HEIGHT]);
+    let notice = Paragraph::new(message)
+        .style(Style::default().fg(Color::Yellow))
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+    let middle = Rect::new(area.x, area.y + area.height.saturating_sub(2) / 2, area.width, area.height.min(2));
+    f.render_widget(notice, middle);
+    true
+}
+
+/// How the post fits each cross-post target of the selected accounts: its length against the
+/// target's limit, or how many posts it becomes there. LinkedIn can't take a thread, so a post
+/// too long for one share is flagged.
+fn target_indicator(app: &App, accounts: &[String]) -> String {
+    let text = app.post_text();
+    let mut seen = HashSet::new();
+    let fits: Vec<String> = accounts
+        .iter()
+        .filter_map(|account| app.crosspost_targets.get(account))
+        .flatten()
+        .filter(|target| seen.insert(&target.name) && target.char_limit() != usize::MAX)
+        .map(|target| {
+            let parts = crate::crosspost::adapt(target, &text);
+            match parts.as_slice() {
+                [] => tf("status.target_fits", &[&target.name, &0, &target.char_limit()]),
+                [part] => tf("status.target_fits", &[&target.name, &part.chars().count(), &target.char_limit()]),
+                _ if target.kind == TargetKind::LinkedIn => {
+                    let length = parts.join("\n\n").chars().count();
+                    tf("status.target_too_long", &[&target.name, &length, &target.char_limit()])
+                }
+                _ => tf("status.target_thread", &[&target.name, &parts.len()]),
+            }
+        })
+        .collect();
+    if fits.is_empty() {
+        return String::new();
+    }
+    tf("status.targets", &[&fits.join(", ")])
+}
+
+/// Posts left this month for the selected accounts that have a cap, flagged once 90% is used.
+fn quota_indicator(app: &App, targets: &[String]) -> String {
+    targets
+        .iter()
+        .filter_map(|account| {
+            let cap = *app.post_caps.get(account)?;
+            let used = app.posts_this_month.get(account).copied().unwrap_or(0);
+            let warning = if used * 10 >= cap * 9 { "⚠ " } else { "" };
+            let name = if targets.len() > 1 { format!("{} ", account) } else { String::new() };
+            Some(tf("status.quota", &[&warning, &name, &cap.saturating_sub(used), &cap]))
+        })
+        .collect()
+}
+
+fn draw_status(f: &mut Frame, app: &App, area: Rect) {
+    let status_text = match &app.state {
+        AppState::Composing => {
+            let char_count = app.char_count();
+            let (row, col) = app.textarea.cursor();
+            let writing_indicator = tf(
+                "status.writing",
+                &[&app.word_count(), &app.textarea.lines().len(), &(row + 1), &(col + 1)],
+            );
+            let thread_indicator = match app.thread_len() {
+                n if n > 1 => tf("status.thread", &[&n]),
+                _ => String::new(),
+            };
+            let image_indicator = match app.image_count {
+                0 => String::new(),
+                1 => t("status.image").to_string(),
+                n => tf("status.images", &[&n]),
+            };
+            let image_indicator = match app.images_without_alt_text().len() {
+                0 => image_indicator,
+                n => format!("{}{}", image_indicator, tf("status.no_alt_text", &[&n])),
+            };
+            let draft_indicator = if app.current_draft_id.is_some() {
+                t("status.draft")
+            } else {
+                ""
+            };
+            let targets = app.selected_accounts();
+            let account_indicator = if app.accounts.len() > 1 {
+                format!(" | 👥 {}", targets.join(", "))
+            } else {
+                String::new()
+            };
+            let community_indicator = match app.community() {
+                Some(community) => format!(" | 🏘 {}", community.name),
+                None => String::new(),
+            };
+            let ephemeral_indicator = match app.delete_after {
+                Some(after) => tf("status.deletes_after", &[&crate::history::format_duration(after)]),
+                None => String::new(),
+            };
+            let quota_indicator = quota_indicator(app, &targets);
+            let target_indicator = target_indicator(app, &targets);
+            let streak_indicator = match app.cadence {
+                Some(cadence) if !cadence.posted_today && cadence.streak > 0 => {
+                    tf("status.streak", &[&cadence.streak])
+                }
+                Some(cadence) if !cadence.posted_today => t("status.no_post_today").to_string(),
+                _ => String::new(),
+            };
+            let reply_indicator = match app.excluded_user_ids().len() {
+                _ if app.reply_to.is_none() => String::new(),
+                0 => t("status.reply").to_string(),
+                n => tf("status.reply_excluded", &[&n]),
+            };
+            
+            format!(
+                "{}{}{}{}{}{}{}{}{}{}{}{}",
+                tf("status.characters", &[&char_count, &app.char_limit_label()]),
+                writing_indicator,
+                thread_indicator,
+                image_indicator,
+                draft_indicator,
+                account_indicator,
+                target_indicator,
+                community_indicator,
+                reply_indicator,
+                ephemeral_indicator,
+                quota_indicator,
+                streak_indicator
+            )
+        }
+        AppState::FilePrompt => {
+            t("status.file_prompt").to_string()
+        }
+        AppState::SchedulePrompt => {
+            t("status.schedule_prompt").to_string()
+        }
+        AppState::AltTextPrompt(_) => {
+            tf("status.alt_text_prompt", &[&app.alt_text_input.chars().count(), &ALT_TEXT_LIMIT])
+        }
+        AppState::ReauthPrompt => match &app.reauth {
+            Some(reauth) if reauth.checking => t("status.reauth_checking").to_string(),
+            Some(reauth) => tf("status.reauth_prompt", &[&reauth.problem.message()]),
+            None => String::new(),
+        },
+        AppState::AssistPending => {
+            t("status.assist_pending").to_string()
+        }
+        AppState::GrammarPending => {
+            t("status.grammar_pending").to_string()
+        }
+        AppState::GrammarPopup => {
+            match app.selected_grammar_issue() {
+                Some(issue) => issue.message.clone(),
+                None => t("status.no_grammar_issues").to_string(),
+            }
+        }
+        AppState::FormatPicker => {
+            t("status.format_picker").to_string()
+        }
+        AppState::AssistPicker => {
+            t("status.assist_picker").to_string()
+        }
+        AppState::AssistDiff => {
+            let suggestion = app.assist_suggestion.as_deref().unwrap_or("");
+            tf("status.suggestion", &[&suggestion.chars().count(), &app.char_count()])
+        }
+        AppState::Posting if app.dm_recipient.is_some() => {
+            t("status.sending_message").to_string()
+        }
+        AppState::Posting => match app.upload_progress {
+            Some((done, total)) if done < total => tf("status.uploading", &[&done, &total]),
+            _ => t("status.posting").to_string(),
+        },
+        // Posts get the success panel; this is a sent message
+        AppState::Success(outcomes) => match outcomes.first().map(|outcome| &outcome.result) {
+            Some(Err(e)) => tf("status.error", &[e]),
+            _ => tf("status.message_sent", &[&app.dm_recipient.clone().unwrap_or_default()]),
+        },
+        AppState::Error(msg) => {
+            tf("status.error", &[msg])
+        }
+        AppState::DraftBrowser | AppState::TagPrompt if !app.marked_drafts.is_empty() => {
+            tf("status.marked", &[&app.marked_drafts.len(), &app.drafts.len()])
+        }
+        AppState::DraftBrowser | AppState::TagPrompt if app.show_archive => {
+            tf("status.archived", &[&app.drafts.len()])
+        }
+        AppState::DraftBrowser | AppState::TagPrompt => {
+            tf("status.drafts", &[&app.drafts.len()])
+        }
+        AppState::VersionPicker => {
+            match app.selected_version() {
+                Some(version) => tf("status.version", &[&version.content.chars().count()]),
+                None => String::new(),
+            }
+        }
+        AppState::Search => {
+            tf("status.search", &[&app.search_index.entries.len()])
+        }
+        AppState::MediaPicker => {
+            match app.selected_media() {
+                Some(item) => item.label(),
+                None => t("status.no_media").to_string(),
+            }
+        }
+        AppState::AccountPicker => {
+            tf("status.accounts", &[&app.selected_accounts().len(), &app.accounts.len()])
+        }
+        AppState::Preview => {
+            let lines = app.textarea.lines().len();
+            let link_indicator = if app.shortening {
+                t("status.shortening")
+            } else if app.link_card_pending.is_some() {
+                t("status.loading_card")
+            } else {
+                ""
+            };
+            tf("status.lines", &[&app.char_count(), &app.char_limit_label(), &lines]) + link_indicator
+        }
+        AppState::ParticipantPicker => {
+            tf("status.excluding", &[&app.excluded_user_ids().len(), &app.participants.len()])
+        }
+        AppState::CommunityPicker => {
+            match app.community() {
+                Some(community) => tf("status.community", &[&community.name]),
+                None => t("status.timeline").to_string(),
+            }
+        }
+    };
+    let status_text = app.notice.clone().unwrap_or(status_text);
+
+    let status_color = match &app.state {
+        AppState::Success(_) => Color::Green,
+        AppState::Error(_) => Color::Red,
+        AppState::Posting | AppState::AssistPending | AppState::GrammarPending => Color::Yellow,
+        _ => Color::White,
+    };
+
+    let flashing = app.flash_until.is_some_and(|until| std::time::Instant::now() < until);
+    let status_style = if flashing {
+        Style::default().fg(Color::Black).bg(status_color)
+    } else {
+        Style::default().fg(status_color)
+    };
+
+    let status = Paragraph::new(status_text)
+        .style(status_style)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(t("status.title"))
+                .border_style(Style::default().fg(Color::Gray)),
+        );
+
+    f.render_widget(status, area);
+}
+
+/// The key hints for the current screen, as "Key: action" segments separated by " | ".
+pub fn instructions(app: &App) -> String {
+    let instructions = match &app.state {
+        AppState::Composing if app.dm_recipient.is_some() => {
+            t("keys.dm")
+        }
+        AppState::Composing => {
+            let mut keys = vec![t("keys.upload_image"), t("keys.recent_images")];
+            if app.image_count > 0 {
+                keys.extend([t("keys.alt_text"), t("keys.remove_images")]);
+            }
+            keys.extend([t("keys.save_draft"), t("keys.drafts")]);
+            if app.accounts.len() > 1 {
+                keys.push(t("keys.accounts"));
+            }
+            if !app.communities.is_empty() {
+                keys.push(t("keys.community"));
+            }
+            if !app.participants.is_empty() {
+                keys.push(t("keys.exclude"));
+            }
+            if !app.assist_prompts.is_empty() {
+                keys.push(t("keys.assist"));
+            }
+            if app.grammar_enabled {
+                keys.push(t("keys.grammar"));
+            }
+            if app.textarea.selection_range().is_some() {
+                keys.push(t("keys.format"));
+            }
+            keys.extend([
+                t("keys.schedule"),
+                t("keys.delete_after"),
+                t("keys.zen"),
+                t("keys.preview"),
+                t("keys.post"),
+                t("keys.exit"),
+            ]);
+            return keys.join(" | ");
+        }
+        AppState::SchedulePrompt => {
+            schedule_picker_keys(&app.schedule_picker)
+        }
+        AppState::FilePrompt | AppState::TagPrompt | AppState::AltTextPrompt(_) => {
+            t("keys.prompt")
+        }
+        AppState::Posting => {
+            t("keys.posting")
+        }
+        AppState::AssistPending | AppState::GrammarPending => {
+            t("keys.wait")
+        }
+        AppState::GrammarPopup => {
+            t("keys.grammar_popup")
+        }
+        AppState::FormatPicker => {
+            t("keys.format_picker")
+        }
+        AppState::AssistPicker => {
+            t("keys.assist_picker")
+        }
+        AppState::AssistDiff => {
+            t("keys.assist_diff")
+        }
+        AppState::Success(_) if app.dm_recipient.is_some() => {
+            t("keys.message_sent")
+        }
+        AppState::Success(_) => {
+            t("keys.success")
+        }
+        AppState::Error(_) if app.reauth_offer.is_some() => {
+            t("keys.error_reauth")
+        }
+        AppState::Error(_) => {
+            t("keys.error")
+        }
+        AppState::ReauthPrompt if app.reauth.as_ref().is_some_and(|reauth| reauth.checking) => {
+            t("keys.wait")
+        }
+        AppState::ReauthPrompt => {
+            t("keys.reauth_prompt")
+        }
+        AppState::DraftBrowser if app.selected_damaged_draft().is_some() => {
+            t("keys.damaged_draft")
+        }
+        AppState::DraftBrowser if app.show_archive => {
+            t("keys.archive_browser")
+        }
+        AppState::DraftBrowser => {
+            t("keys.draft_browser")
+        }
+        AppState::VersionPicker => {
+            t("keys.version_picker")
+        }
+        AppState::Search => {
+            t("keys.search")
+        }
+        AppState::MediaPicker => {
+            t("keys.media_picker")
+        }
+        AppState::AccountPicker => {
+            t("keys.account_picker")
+        }
+        AppState::CommunityPicker => {
+            t("keys.community_picker")
+        }
+        AppState::ParticipantPicker => {
+            t("keys.participant_picker")
+        }
+        AppState::Preview => {
+            t("keys.preview_scroll")
+        }
+    };
+
+    instructions.to_string()
+}
+
+fn draw_instructions(f: &mut Frame, app: &mut App, area: Rect) {
+    app.help_area = area;
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Gray));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    crate::buttons::render(f, &instructions(app), inner, false);
+}
+
+fn draw_draft_browser(f: &mut Frame, app: &mut App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(3),
+            Constraint::Length(3),
+            Constraint::Length(3),
+        ])
+        .split(area);
+
+    // Draft list, with checkboxes once some are marked for a bulk action
+    let items: Vec<ListItem> = app
+        .drafts
+        .iter()
+        .map(|draft| {
+            let mark = match app.marked_drafts.contains(&draft.id) {
+                _ if app.marked_drafts.is_empty() => "",
+                true => "[x] ",
+                false => "[ ] ",
+            };
+            ListItem::new(format!("{}{}", mark, draft.preview(app.time_format.as_deref())))
+                .style(Style::default().fg(Color::White))
+        })
+        .chain(app.damaged_drafts.iter().map(|damaged| {
+            ListItem::new(format!("⚠ {} | {}", damaged.file_name(), damaged.error))
+                .style(Style::default().fg(Color::Red))
+        }))
+        .collect();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(if app.show_archive { t("compose.archive_title") } else { t("compose.drafts_title") })
+                .border_style(Style::default().fg(app.accent)),
+        )
+        .highlight_style(crate::theme::selected())
+        .highlight_symbol("> ");
+
+    app.list_area = chunks[0];
+    f.render_stateful_widget(list, chunks[0], &mut app.draft_list_state);
+    
+    if app.state == AppState::TagPrompt {
+        let input = Paragraph::new(app.tag_input.as_str())
+            .style(Style::default().fg(Color::White))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(tf("compose.tag_prompt_title", &[&app.bulk_drafts().len()]))
+                    .border_style(Style::default().fg(app.accent)),
+            );
+        f.render_widget(input, chunks[1]);
+    } else {
+        draw_status(f, app, chunks[1]);
+    }
+    draw_instructions(f, app, chunks[2]);
+}
+
+fn draw_account_picker(f: &mut Frame, app: &mut App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(3),
+            Constraint::Length(3),
+            Constraint::Length(3),
+        ])
+        .split(area);
+
+    let items: Vec<ListItem> = app
+        .accounts
+        .iter()
+        .zip(&app.target_accounts)
+        .map(|(name, selected)| {
+            let mark = if *selected { "[x]" } else { "[ ]" };
+            ListItem::new(format!("{} {}", mark, name))
+                .style(Style::default().fg(Color::White))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(t("compose.accounts_title"))
+                .border_style(Style::default().fg(app.accent)),
+        )
+        .highlight_style(crate::theme::selected())
+        .highlight_symbol("> ");
+
+    app.list_area = chunks[0];
+    f.render_stateful_widget(list, chunks[0], &mut app.account_list_state);
+
+    draw_status(f, app, chunks[1]);
+    draw_instructions(f, app, chunks[2]);
 }
 
-fn draw_status(f: &mut Frame, app: &App, area: Rect) {
-    let status_text = match &app.state {
-        AppState::Composing => {
-            let char_count = app.char_count();
-            let image_indicator = if app.has_image {
-                " | 📎 Image attached"
-            } else {
-                ""
-            };
-            let draft_indicator = if app.current_draft_id.is_some() {
-                " | 📝 Draft loaded"
-            } else {
-                ""
-            };
-            
-            format!("Characters: {}{}{}", char_count, image_indicator, draft_indicator)
-        }
-        AppState::FilePrompt => {
-            "Enter the path to your image file".to_string()
-        }
-        AppState::Posting => {
-            "Posting to X...".to_string()
-        }
-        AppState::Success(url) => {
-            format!("✓ Posted successfully! https://x.com/user/status/{}", url)
-        }
-        AppState::Error(msg) => {
-            format!("✗ Error: {}", msg)
-        }
-        AppState::DraftBrowser => {
-            format!("Drafts: {} saved", app.drafts.len())
-        }
-    };
+fn draw_version_picker(f: &mut Frame, app: &mut App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(3),
+            Constraint::Length(3),
+            Constraint::Length(3),
+        ])
+        .split(area);
 
-    let status_color = match &app.state {
-        AppState::Success(_) => Color::Green,
-        AppState::Error(_) => Color::Red,
-        AppState::Posting => Color::Yellow,
-        _ => Color::White,
-    };
+    let versions = app.selected_draft().map(|draft| draft.versions.as_slice()).unwrap_or_default();
+    let items: Vec<ListItem> = versions
+        .iter()
+        .rev()
+        .map(|version| {
+            ListItem::new(version.preview(app.time_format.as_deref()))
+                .style(Style::default().fg(Color::White))
+        })
+        .collect();
 
-    let status = Paragraph::new(status_text)
-        .style(Style::default().fg(status_color))
+    let list = List::new(items)
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title("Status")
-                .border_style(Style::default().fg(Color::Gray)),
+                .title(t("compose.versions_title"))
+                .border_style(Style::default().fg(app.accent)),
+        )
+        .highlight_style(crate::theme::selected())
+        .highlight_symbol("> ");
+
+    app.list_area = chunks[0];
+    f.render_stateful_widget(list, chunks[0], &mut app.version_list_state);
+
+    draw_status(f, app, chunks[1]);
+    draw_instructions(f, app, chunks[2]);
+}
+
+fn draw_search(f: &mut Frame, app: &mut App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(3),
+            Constraint::Length(3),
+            Constraint::Length(3),
+        ])
+        .split(area);
+
+    let input = Paragraph::new(format!("{}_", app.search_input))
+        .style(Style::default().fg(Color::White))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(t("compose.search_title"))
+                .border_style(Style::default().fg(app.accent)),
         );
+    f.render_widget(input, chunks[0]);
 
-    f.render_widget(status, area);
+    let width = chunks[1].width.saturating_sub(30) as usize;
+    let items: Vec<ListItem> = app
+        .search_results
+        .iter()
+        .filter_map(|&i| app.search_index.entries.get(i))
+        .map(|entry| {
+            ListItem::new(entry.preview(app.time_format.as_deref(), width.max(20)))
+                .style(Style::default().fg(Color::White))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(tf("compose.results_title", &[&app.search_results.len()]))
+                .border_style(Style::default().fg(app.accent)),
+        )
+        .highlight_style(crate::theme::selected())
+        .highlight_symbol("> ");
+
+    app.list_area = chunks[1];
+    f.render_stateful_widget(list, chunks[1], &mut app.search_list_state);
+
+    draw_status(f, app, chunks[2]);
+    draw_instructions(f, app, chunks[3]);
 }
 
-fn draw_instructions(f: &mut Frame, app: &App, area: Rect) {
-    let instructions = match &app.state {
-        AppState::Composing => {
-            "Ctrl+U: upload image | Ctrl+S: save draft | Ctrl+D: drafts | Ctrl+P: post | Esc: exit"
-        }
-        AppState::FilePrompt => {
-            "Enter: confirm | Esc: cancel"
-        }
-        AppState::Posting => {
-            "Please wait..."
-        }
-        AppState::Success(_) | AppState::Error(_) => {
-            "Press any key to post again, or Esc to exit"
-        }
-        AppState::DraftBrowser => {
-            "↑/↓: navigate | Enter: load draft | Delete: remove draft | Esc: back"
-        }
-    };
+fn draw_community_picker(f: &mut Frame, app: &mut App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(3),
+            Constraint::Length(3),
+            Constraint::Length(3),
+        ])
+        .split(area);
 
-    let help = Paragraph::new(instructions)
-        .style(Style::default().fg(Color::DarkGray))
+    let current = app.selected_community.map_or(0, |i| i + 1);
+    let names = std::iter::once(t("compose.no_community"))
+        .chain(app.communities.iter().map(|community| community.name.as_str()));
+    let items: Vec<ListItem> = names
+        .enumerate()
+        .map(|(i, name)| {
+            let mark = if i == current { "(•)" } else { "( )" };
+            ListItem::new(format!("{} {}", mark, name))
+                .style(Style::default().fg(Color::White))
+        })
+        .collect();
+
+    let list = List::new(items)
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Gray)),
+                .title(t("compose.community_title"))
+                .border_style(Style::default().fg(app.accent)),
         )
-        .wrap(Wrap { trim: false });
+        .highlight_style(crate::theme::selected())
+        .highlight_symbol("> ");
+
+    app.list_area = chunks[0];
+    f.render_stateful_widget(list, chunks[0], &mut app.community_list_state);
 
-    f.render_widget(help, area);
+    draw_status(f, app, chunks[1]);
+    draw_instructions(f, app, chunks[2]);
 }
 
-fn draw_draft_browser(f: &mut Frame, app: &mut App) {
+fn draw_participant_picker(f: &mut Frame, app: &mut App, area: Rect) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -299,14 +2288,60 @@ fn draw_draft_browser(f: &mut Frame, app: &mut App) {
             Constraint::Length(3),
             Constraint::Length(3),
         ])
-        .split(f.area());
+        .split(area);
 
-    // Draft list
     let items: Vec<ListItem> = app
-        .drafts
+        .participants
         .iter()
-        .map(|draft| {
-            ListItem::new(draft.preview())
+        .zip(&app.excluded_participants)
+        .map(|(user, excluded)| {
+            let mark = if *excluded { "[ ]" } else { "[x]" };
+            ListItem::new(format!("{} @{}", mark, user.username))
+                .style(Style::default().fg(Color::White))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(t("compose.participants_title"))
+                .border_style(Style::default().fg(app.accent)),
+        )
+        .highlight_style(crate::theme::selected())
+        .highlight_symbol("> ");
+
+    app.list_area = chunks[0];
+    f.render_stateful_widget(list, chunks[0], &mut app.participant_list_state);
+
+    draw_status(f, app, chunks[1]);
+    draw_instructions(f, app, chunks[2]);
+}
+
+fn draw_media_picker(f: &mut Frame, app: &mut App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(3),
+            Constraint::Length(3),
+            Constraint::Length(3),
+        ])
+        .split(area);
+
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Min(20),
+            Constraint::Length(THUMBNAIL_WIDTH as u16 + 2),
+        ])
+        .split(chunks[0]);
+
+    let items: Vec<ListItem> = app
+        .media
+        .iter()
+        .map(|item| {
+            let date = item.last_used.format("%Y-%m-%d");
+            ListItem::new(format!("{} | {}", date, item.name))
                 .style(Style::default().fg(Color::White))
         })
         .collect();
@@ -315,18 +2350,323 @@ fn draw_draft_browser(f: &mut Frame, app: &mut App) {
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title("Saved Drafts")
-                .border_style(Style::default().fg(Color::Cyan)),
+                .title(t("compose.media_title"))
+                .border_style(Style::default().fg(app.accent)),
         )
-        .highlight_style(
-            Style::default()
-                .bg(Color::DarkGray)
-                .add_modifier(Modifier::BOLD),
+        .highlight_style(crate::theme::selected())
+        .highlight_symbol("> ");
+
+    app.list_area = columns[0];
+    f.render_stateful_widget(list, columns[0], &mut app.media_list_state);
+
+    let thumbnail_lines = app
+        .media_thumbnail
+        .as_ref()
+        .map(thumbnail_lines)
+        .unwrap_or_default();
+    let thumbnail = Paragraph::new(thumbnail_lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(t("compose.preview_title"))
+            .border_style(Style::default().fg(Color::Gray)),
+    );
+    f.render_widget(thumbnail, columns[1]);
+
+    draw_status(f, app, chunks[1]);
+    draw_instructions(f, app, chunks[2]);
+}
+
+/// Renders two pixel rows per line: the upper pixel as foreground of '▀', the lower as background.
+fn thumbnail_lines(img: &image::RgbImage) -> Vec<Line<'static>> {
+    (0..img.height())
+        .step_by(2)
+        .map(|y| {
+            let spans: Vec<Span> = (0..img.width())
+                .map(|x| {
+                    let [r, g, b] = img.get_pixel(x, y).0;
+                    let mut style = Style::default().fg(Color::Rgb(r, g, b));
+                    if y + 1 < img.height() {
+                        let [r, g, b] = img.get_pixel(x, y + 1).0;
+                        style = style.bg(Color::Rgb(r, g, b));
+                    }
+                    Span::styled("▀", style)
+                })
+                .collect();
+            Line::from(spans)
+        })
+        .collect()
+}
+
+fn draw_assist_picker(f: &mut Frame, app: &mut App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(3),
+            Constraint::Length(3),
+            Constraint::Length(3),
+        ])
+        .split(area);
+
+    let items: Vec<ListItem> = app
+        .assist_prompts
+        .iter()
+        .map(|prompt| ListItem::new(prompt.as_str()).style(Style::default().fg(Color::White)))
+        .collect();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(t("compose.assist_title"))
+                .border_style(Style::default().fg(app.accent)),
         )
+        .highlight_style(crate::theme::selected())
         .highlight_symbol("> ");
 
-    f.render_stateful_widget(list, chunks[0], &mut app.draft_list_state);
-    
+    app.list_area = chunks[0];
+    f.render_stateful_widget(list, chunks[0], &mut app.assist_list_state);
+
+    draw_status(f, app, chunks[1]);
+    draw_instructions(f, app, chunks[2]);
+}
+
+/// Line diff between the buffer and the suggestion: removed lines red, added lines green.
+fn draw_assist_diff(f: &mut Frame, app: &mut App, area: Rect) {
+    use similar::{ChangeTag, TextDiff};
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(3),
+            Constraint::Length(3),
+            Constraint::Length(3),
+        ])
+        .split(area);
+
+    let current = app.get_text();
+    let suggestion = app.assist_suggestion.clone().unwrap_or_default();
+    let diff = TextDiff::from_lines(&current, &suggestion);
+
+    let lines: Vec<Line> = diff
+        .iter_all_changes()
+        .map(|change| {
+            let (sign, style) = match change.tag() {
+                ChangeTag::Delete => ("- ", Style::default().fg(Color::Red)),
+                ChangeTag::Insert => ("+ ", Style::default().fg(Color::Green)),
+                ChangeTag::Equal => ("  ", Style::default().fg(Color::Gray)),
+            };
+            let text = change.value().trim_end_matches('\n');
+            Line::from(Span::styled(format!("{}{}", sign, text), style))
+        })
+        .collect();
+
+    let diff_view = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(t("compose.suggestion_title"))
+                .border_style(Style::default().fg(app.accent)),
+        );
+    f.render_widget(diff_view, chunks[0]);
+
     draw_status(f, app, chunks[1]);
     draw_instructions(f, app, chunks[2]);
 }
+
+/// Issues from the grammar check, drawn over the lower part of the editor.
+/// Key hints for the schedule picker's focused field.
+pub fn schedule_picker_keys(picker: &SchedulePicker) -> &'static str {
+    match picker.field {
+        PickerField::Date => t("keys.schedule_picker_date"),
+        PickerField::Hour | PickerField::Minute => t("keys.schedule_picker_time"),
+    }
+}
+
+/// A month calendar and hour/minute spinners, with whatever has been typed instead above them.
+pub fn draw_schedule_picker(f: &mut Frame, picker: &SchedulePicker, typed: &str, title: &str, accent: Color, area: Rect) {
+    let today = crate::timezone::now().date_naive();
+    let focused = |field: PickerField| {
+        if picker.field == field {
+            Style::default().fg(accent).add_modifier(Modifier::REVERSED | Modifier::BOLD)
+        } else {
+            crate::theme::selected()
+        }
+    };
+
+    let mut lines = vec![
+        match typed {
+            "" => Line::styled(t("schedule.type_hint"), Style::default().fg(Color::DarkGray)),
+            typed => Line::styled(format!("{}_", typed), Style::default().fg(Color::White)),
+        },
+        Line::default(),
+        Line::styled(
+            format!("◀ {} ▶", picker.date().format("%B %Y")),
+            Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+        ),
+        Line::styled(t("schedule.weekdays"), Style::default().fg(Color::Gray)),
+    ];
+    for week in picker.weeks() {
+        let days: Vec<Span> = week
+            .iter()
+            .map(|day| match day {
+                None => Span::raw("  "),
+                Some(day) => {
+                    let style = if *day == picker.date() {
+                        focused(PickerField::Date)
+                    } else if *day < today {
+                        Style::default().fg(Color::DarkGray)
+                    } else if *day == today {
+                        Style::default().fg(Color::White).add_modifier(Modifier::UNDERLINED)
+                    } else {
+                        Style::default().fg(Color::White)
+                    };
+                    Span::styled(format!("{:>2}", day.format("%-d")), style)
+                }
+            })
+            .flat_map(|day| [Span::raw(" "), day])
+            .skip(1)
+            .collect();
+        lines.push(Line::from(days));
+    }
+    lines.push(Line::default());
+    lines.push(Line::from(vec![
+        Span::styled(picker.at.format("%H").to_string(), focused(PickerField::Hour)),
+        Span::raw(" : "),
+        Span::styled(picker.at.format("%M").to_string(), focused(PickerField::Minute)),
+    ]));
+    lines.push(Line::default());
+    // What typing resolved to, for a last look before Enter
+    let recurrence = crate::queue::parse_recurrence(typed);
+    lines.push(match (recurrence, crate::queue::parse_schedule_time(typed)) {
+        (Some(recurrence), _) => Line::styled(
+            tf("schedule.repeats", &[&recurrence.describe(), &resolved_time(picker)]),
+            Style::default().fg(Color::Gray),
+        ),
+        (None, None) if !typed.is_empty() => Line::styled(t("schedule.unreadable"), Style::default().fg(Color::Yellow)),
+        _ => Line::styled(
+            tf("schedule.resolved", &[&resolved_time(picker), &time_until(picker.at)]),
+            Style::default().fg(Color::Gray),
+        ),
+    });
+
+    let picker = Paragraph::new(lines)
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(title)
+                .border_style(Style::default().fg(accent)),
+        );
+    f.render_widget(picker, area);
+}
+
+/// The picked time spelled out, with the zone's abbreviation for it (which tells CET from CEST).
+fn resolved_time(picker: &SchedulePicker) -> String {
+    match picker.to_utc() {
+        Some(at) => crate::timezone::from_utc(at).format("%A %-d %B %Y, %H:%M %Z").to_string(),
+        None => picker.at.format("%A %-d %B %Y, %H:%M").to_string(),
+    }
+}
+
+/// "in 2d 3h", "in 45m", or that the time has passed.
+fn time_until(at: chrono::NaiveDateTime) -> String {
+    let minutes = (at - crate::timezone::now().naive_local()).num_minutes();
+    if minutes < 0 {
+        return t("schedule.past").to_string();
+    }
+    let (days, hours, minutes) = (minutes / (24 * 60), minutes / 60 % 24, minutes % 60);
+    let parts: Vec<String> = [(days, "d"), (hours, "h"), (minutes, "m")]
+        .into_iter()
+        .skip_while(|(amount, _)| *amount == 0)
+        .take(2)
+        .filter(|(amount, _)| *amount > 0)
+        .map(|(amount, unit)| format!("{}{}", amount, unit))
+        .collect();
+    match parts.as_slice() {
+        [] => tf("schedule.in", &[&"<1m"]),
+        parts => tf("schedule.in", &[&parts.join(" ")]),
+    }
+}
+
+fn draw_format_picker(f: &mut Frame, app: &mut App, area: Rect) {
+    let height = (crate::styled::STYLES.len() as u16 + 2).min(area.height);
+    let popup = Rect {
+        x: area.x + 2,
+        y: area.y + area.height.saturating_sub(height + 1),
+        width: area.width.saturating_sub(4),
+        height,
+    };
+
+    let selected = crate::text::preview_line(&app.selected_text().unwrap_or_default(), 40);
+    let items: Vec<ListItem> = crate::styled::STYLES
+        .iter()
+        .map(|&style| {
+            ListItem::new(Line::from(vec![
+                Span::styled(crate::styled::apply(t(style.label()), style), Style::default().fg(Color::White)),
+                Span::raw("  "),
+                Span::styled(crate::styled::apply(&selected, style), Style::default().fg(Color::Gray)),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(t("compose.format_title"))
+                .border_style(Style::default().fg(app.accent)),
+        )
+        .highlight_style(crate::theme::selected())
+        .highlight_symbol("> ");
+
+    app.list_area = popup;
+    f.render_widget(Clear, popup);
+    f.render_stateful_widget(list, popup, &mut app.format_list_state);
+}
+
+fn draw_grammar_popup(f: &mut Frame, app: &mut App, area: Rect) {
+    let height = (app.grammar_issues.len() as u16 * 2 + 2).min(area.height.saturating_sub(2)).max(4);
+    let popup = Rect {
+        x: area.x + 2,
+        y: area.y + area.height.saturating_sub(height + 1),
+        width: area.width.saturating_sub(4),
+        height: height.min(area.height),
+    };
+
+    let chars: Vec<char> = app.get_text().chars().collect();
+    let items: Vec<ListItem> = app
+        .grammar_issues
+        .iter()
+        .map(|issue| {
+            let end = (issue.offset + issue.length).min(chars.len());
+            let flagged: String = chars[issue.offset.min(end)..end].iter().collect();
+            let fix = match issue.replacements.as_slice() {
+                [] => Span::styled(t("compose.no_suggestion"), Style::default().fg(Color::DarkGray)),
+                replacements => Span::styled(replacements.join(" / "), Style::default().fg(Color::Green)),
+            };
+            ListItem::new(vec![
+                Line::from(vec![
+                    Span::styled(flagged, Style::default().fg(Color::Red)),
+                    Span::raw(" → "),
+                    fix,
+                ]),
+                Line::from(Span::styled(issue.message.clone(), Style::default().fg(Color::Gray))),
+            ])
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(tf("compose.grammar_title", &[&app.grammar_issues.len()]))
+                .border_style(Style::default().fg(app.accent)),
+        )
+        .highlight_style(crate::theme::selected())
+        .highlight_symbol("> ");
+
+    app.list_area = popup;
+    f.render_widget(Clear, popup);
+    f.render_stateful_widget(list, popup, &mut app.grammar_list_state);
+}
@@ -0,0 +1,79 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Images upload in parallel, so updates to the sessions file take turns.
+static SESSIONS_LOCK: Mutex<()> = Mutex::new(());
+
+/// A chunked media upload in progress. It's saved after every chunk the API confirms, so
+/// posting the same media again after a dropped connection carries on from there.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadSession {
+    /// Identifies the media being uploaded; see `fingerprint`.
+    pub fingerprint: String,
+    pub media_id: String,
+    /// Chunks confirmed so far, which is also the index of the next one to APPEND.
+    pub segments_done: usize,
+    /// When the API forgets the media ID and the upload has to start over.
+    pub expires_at: DateTime<Utc>,
+}
+
+/// A key for `data` that's the same every time the same media is posted, by any build of xpost.
+pub fn fingerprint(data: &[u8]) -> String {
+    format!("{}-{:x}", data.len(), Sha1::digest(data))
+}
+
+fn uploads_path() -> Result<PathBuf> {
+    Ok(crate::config::config_dir()?.join("uploads.json"))
+}
+
+fn load_sessions() -> Result<Vec<UploadSession>> {
+    let path = uploads_path()?;
+
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(&path)
+        .context("Failed to read upload sessions")?;
+    let sessions: Vec<UploadSession> = serde_json::from_str(&content)
+        .context("Failed to parse upload sessions")?;
+
+    Ok(sessions)
+}
+
+fn save_sessions(sessions: &[UploadSession]) -> Result<()> {
+    let json = serde_json::to_string_pretty(sessions)
+        .context("Failed to serialize upload sessions")?;
+    fs::write(uploads_path()?, json)
+        .context("Failed to write upload sessions")
+}
+
+/// The unfinished upload of the media with this fingerprint, unless it has expired.
+pub fn find(fingerprint: &str) -> Option<UploadSession> {
+    load_sessions()
+        .ok()?
+        .into_iter()
+        .find(|session| session.fingerprint == fingerprint && session.expires_at > Utc::now())
+}
+
+/// Records progress on an upload, dropping any that have expired.
+pub fn save(session: &UploadSession) -> Result<()> {
+    let _lock = SESSIONS_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut sessions = load_sessions().unwrap_or_default();
+    sessions.retain(|s| s.fingerprint != session.fingerprint && s.expires_at > Utc::now());
+    sessions.push(session.clone());
+    save_sessions(&sessions)
+}
+
+/// Forgets an upload once it's finished or the API has rejected it.
+pub fn remove(fingerprint: &str) -> Result<()> {
+    let _lock = SESSIONS_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut sessions = load_sessions().unwrap_or_default();
+    sessions.retain(|s| s.fingerprint != fingerprint);
+    save_sessions(&sessions)
+}